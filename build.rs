@@ -1,3 +1,5 @@
+use std::process::Command;
+
 fn main() {
     if let Err(err) = system_deps::Config::new().probe() {
         eprintln!(
@@ -8,4 +10,21 @@ fn main() {
         );
         std::process::exit(1);
     }
+
+    // The schema of the preferences is compiled next to the build, so that the application
+    // also finds it when run from the source tree (see `settings::schema`). Packages install
+    // the schema itself, which is compiled along with the system's.
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=resources/com.example.DesktopFileInformation.gschema.xml");
+    let out_dir = std::env::var("OUT_DIR").expect("cargo sets OUT_DIR");
+    let compiled = Command::new("glib-compile-schemas")
+        .args(["--strict", "--targetdir", &out_dir, "resources"])
+        .status();
+    if !compiled.is_ok_and(|status| status.success()) {
+        println!(
+            "cargo:warning=failed to compile the GSettings schema; install libglib2.0-bin \
+             or the schema itself to run from the source tree"
+        );
+    }
+    println!("cargo:rustc-env=SCHEMA_DIR={out_dir}");
 }
@@ -0,0 +1,88 @@
+use adw::prelude::*;
+
+/// Name of the application, as in its desktop entry.
+const APPLICATION_NAME: &str = "File Information";
+
+/// Icon of the application, as in its desktop entry (`resources/file-information.desktop`).
+const APPLICATION_ICON: &str = "dialog-information";
+
+/// Widget name of the keyboard shortcuts window, by which it is found among the application's
+/// windows.
+const SHORTCUTS_WINDOW_NAME: &str = "shortcuts";
+
+/// Shows the About window, with the version, license and related links, above the active window.
+///
+/// # Arguments
+/// * `app` - The application instance.
+pub fn show_about(app: &adw::Application) {
+    let about = adw::AboutWindow::builder()
+        .application(app)
+        .application_name(APPLICATION_NAME)
+        .application_icon(APPLICATION_ICON)
+        .version(env!("CARGO_PKG_VERSION"))
+        .comments("Display Tracker metadata for files")
+        .developer_name("Anders Feder")
+        .copyright("© 2025 Anders Feder")
+        .license_type(gtk::License::MitX11)
+        .build();
+    about.add_link("Tracker", "https://tracker.gnome.org/");
+    if let Some(parent) = app.active_window() {
+        about.set_transient_for(Some(&parent));
+    }
+    about.present();
+}
+
+/// Shows the window listing the keyboard shortcuts (see `resources/shortcuts-window.ui`), or
+/// brings it to the front if it is already open.
+///
+/// # Arguments
+/// * `app` - The application instance.
+pub fn show_shortcuts(app: &adw::Application) {
+    if let Some(window) = app
+        .windows()
+        .into_iter()
+        .find(|w| w.widget_name() == SHORTCUTS_WINDOW_NAME)
+    {
+        window.present();
+        return;
+    }
+
+    let builder = gtk::Builder::from_string(include_str!("../resources/shortcuts-window.ui"));
+    let Some(window) = builder.object::<gtk::ShortcutsWindow>("shortcuts") else {
+        return;
+    };
+    window.set_application(Some(app));
+    window.set_widget_name(SHORTCUTS_WINDOW_NAME);
+    if let Some(parent) = app.active_window() {
+        window.set_transient_for(Some(&parent));
+    }
+    window.present();
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn shortcuts_list_the_accelerators_in_use() {
+        let ui = include_str!("../resources/shortcuts-window.ui");
+        for accel in [
+            "&lt;Ctrl&gt;L",
+            "&lt;Ctrl&gt;O",
+            "&lt;Ctrl&gt;V",
+            "&lt;Ctrl&gt;D",
+            "&lt;Ctrl&gt;H",
+            "&lt;Ctrl&gt;&lt;Shift&gt;F",
+            "F5",
+            "&lt;Ctrl&gt;W",
+            "&lt;Alt&gt;Left",
+            "&lt;Alt&gt;Right",
+            "&lt;Ctrl&gt;N",
+            "&lt;Ctrl&gt;comma",
+            "&lt;Ctrl&gt;question",
+        ] {
+            assert!(
+                ui.contains(&format!("\"accelerator\">{accel}<")),
+                "{accel} is missing"
+            );
+        }
+    }
+}
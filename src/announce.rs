@@ -0,0 +1,187 @@
+use adw::prelude::*;
+
+/// Widget name of the label through which messages are announced to assistive technologies.
+const STATUS_NAME: &str = "status-announcement";
+
+/// Widget name of the banner reporting problems (see [`report_problem`]).
+const PROBLEM_NAME: &str = "problem-banner";
+
+/// How long a status toast stays visible, in seconds.
+const TOAST_TIMEOUT: u32 = 3;
+
+/// Wraps the content of a window so that status messages can be shown in it (see [`announce`]),
+/// as well as problems the user may want to act on (see [`report_problem`]).
+///
+/// Besides the toast overlay, this adds an invisible label with the accessible role "status",
+/// i.e. a live region: screen readers announce its text whenever it changes, without moving
+/// the focus, and a banner, which is hidden until a problem is reported. The banner goes below
+/// the header bar if `content` is a toolbar view, and above `content` otherwise.
+///
+/// # Arguments
+/// * `content` - The widget that would otherwise be the content of the window.
+///
+/// # Returns
+/// * The widget to use as content of the window instead.
+pub fn wrap(content: &impl IsA<gtk::Widget>) -> adw::ToastOverlay {
+    let status = gtk::Label::builder()
+        .accessible_role(gtk::AccessibleRole::Status)
+        .opacity(0.0)
+        .can_target(false)
+        .halign(gtk::Align::Start)
+        .valign(gtk::Align::End)
+        .build();
+    status.set_widget_name(STATUS_NAME);
+    let banner = adw::Banner::new("");
+    // The button retries (through its action) or just dismisses; either way the problem is dealt
+    // with.
+    banner.connect_button_clicked(|banner| banner.set_revealed(false));
+    banner.set_widget_name(PROBLEM_NAME);
+    let overlay = gtk::Overlay::new();
+    if let Some(toolbar) = content.dynamic_cast_ref::<adw::ToolbarView>() {
+        toolbar.add_top_bar(&banner);
+        overlay.set_child(Some(content));
+    } else {
+        let column = gtk::Box::new(gtk::Orientation::Vertical, 0);
+        column.append(&banner);
+        column.append(content);
+        overlay.set_child(Some(&column));
+    }
+    overlay.add_overlay(&status);
+    let toasts = adw::ToastOverlay::new();
+    toasts.set_child(Some(&overlay));
+    toasts
+}
+
+/// The callbacks of window templates that lay out the widgets of [`wrap`] themselves: a toast
+/// overlay as the content of the window, holding an overlay whose child is the rest of the
+/// content and whose last overlay is a label named `status-announcement` with the accessible
+/// role "status", and a banner named `problem-banner` (see `resources/file-info-window.ui`).
+///
+/// The templates connect the `button-clicked` signal of the banner to `dismiss_problem`.
+pub struct TemplateCallbacks;
+
+#[gtk::template_callbacks(functions)]
+impl TemplateCallbacks {
+    /// Hides the problem banner once its button has been clicked; the button retries (through
+    /// its action) or just dismisses, and either way the problem is dealt with.
+    #[template_callback(function = false)]
+    fn dismiss_problem(banner: &adw::Banner) {
+        banner.set_revealed(false);
+    }
+}
+
+/// Reports a status change, such as completed loading, a failed query or a copied value, both
+/// visibly as a toast and audibly through the live region of the window.
+///
+/// Nothing happens if `widget` is not (in) a window whose content was set up with [`wrap`].
+///
+/// # Arguments
+/// * `widget` - The window, or any widget in it.
+/// * `message` - The message, as a short sentence.
+pub fn announce(widget: &impl IsA<gtk::Widget>, message: &str) {
+    let Some(toasts) = toast_overlay(widget.upcast_ref()) else {
+        return;
+    };
+    let toast = adw::Toast::new(message);
+    toast.set_timeout(TOAST_TIMEOUT);
+    toasts.add_toast(toast);
+    speak(&toasts, message);
+}
+
+/// Sets the text of the live region of the window content `toasts` (see [`wrap`]) to `message`,
+/// so that screen readers announce it.
+fn speak(toasts: &adw::ToastOverlay, message: &str) {
+    let status = toasts
+        .child()
+        .and_then(|overlay| overlay.last_child())
+        .and_downcast::<gtk::Label>()
+        .filter(|label| label.widget_name() == STATUS_NAME);
+    if let Some(status) = status {
+        // Screen readers only announce changes, so repeated messages are made to differ.
+        let text = if status.text() == message {
+            format!("{message}\u{200b}")
+        } else {
+            message.to_string()
+        };
+        status.set_text(&text);
+    }
+}
+
+/// Reports a problem that does not prevent using the window, such as a failed lookup of
+/// comments, in the banner at its top rather than in a dialog. Screen readers announce it, too.
+///
+/// Nothing happens if `widget` is not (in) a window whose content was set up with [`wrap`].
+///
+/// # Arguments
+/// * `widget` - The window, or any widget in it.
+/// * `message` - What went wrong, as a short sentence.
+/// * `retry` - The action (e.g. `"win.refresh"`) and its target, for a "Retry" button, if
+///   trying again may help; otherwise, the banner only has a "Dismiss" button.
+pub fn report_problem(
+    widget: &impl IsA<gtk::Widget>,
+    message: &str,
+    retry: Option<(&str, Option<glib::Variant>)>,
+) {
+    let Some(toasts) = toast_overlay(widget.upcast_ref()) else {
+        return;
+    };
+    let Some(banner) = find_named(toasts.upcast_ref(), PROBLEM_NAME).and_downcast::<adw::Banner>()
+    else {
+        return;
+    };
+    banner.set_title(message);
+    match retry {
+        Some((action, target)) => {
+            banner.set_button_label(Some("Retry"));
+            banner.set_action_name(Some(action));
+            banner.set_action_target_value(target.as_ref());
+        }
+        None => {
+            banner.set_button_label(Some("Dismiss"));
+            banner.set_action_name(None);
+        }
+    }
+    banner.set_revealed(true);
+    speak(&toasts, message);
+}
+
+/// Finds the toast overlay created by [`wrap`] for the window holding `widget`.
+fn toast_overlay(widget: &gtk::Widget) -> Option<adw::ToastOverlay> {
+    let window = widget
+        .downcast_ref::<adw::ApplicationWindow>()
+        .cloned()
+        .or_else(|| widget.root().and_downcast::<adw::ApplicationWindow>())?;
+    window.content().and_downcast::<adw::ToastOverlay>()
+}
+
+/// Finds the first widget named `name` among `widget` and its descendants, depth first.
+pub(crate) fn find_named(widget: &gtk::Widget, name: &str) -> Option<gtk::Widget> {
+    if widget.widget_name() == name {
+        return Some(widget.clone());
+    }
+    let mut child = widget.first_child();
+    while let Some(current) = child {
+        if let Some(found) = find_named(&current, name) {
+            return Some(found);
+        }
+        child = current.next_sibling();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn templates_lay_out_the_announcements() {
+        for ui in [
+            include_str!("../resources/backlinks-window.ui"),
+            include_str!("../resources/file-info-window.ui"),
+        ] {
+            assert!(ui.contains(&format!("\"name\">{STATUS_NAME}<")));
+            assert!(ui.contains(&format!("\"name\">{PROBLEM_NAME}<")));
+            assert!(ui.contains("handler=\"dismiss_problem\""));
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use crate::context::Context;
+use crate::dnd;
+use crate::geometry;
+use crate::restore_scroll;
+use crate::search;
+use crate::trace;
+use crate::{METADATA_WINDOW_CLASS, add_common_actions, announce, populate_backlinks_grid};
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use glib::subclass::Signal;
+use serde_json::json;
+use std::cell::{Cell, OnceCell, RefCell};
+use std::rc::Rc;
+use std::sync::OnceLock;
+
+mod imp {
+    use super::*;
+
+    /// The state of a [`BacklinksWindow`](super::BacklinksWindow). The widgets are laid out by
+    /// the composite template `resources/backlinks-window.ui`.
+    #[derive(Debug, Default, gtk::CompositeTemplate, glib::Properties)]
+    #[template(file = "../resources/backlinks-window.ui")]
+    #[properties(wrapper_type = super::BacklinksWindow)]
+    pub struct BacklinksWindow {
+        /// The node whose backlinks are listed.
+        #[property(get, set, construct_only)]
+        pub uri: RefCell<String>,
+        /// Print diagnostic information about loading the backlinks to stderr.
+        #[property(get, set)]
+        pub debug: Cell<bool>,
+        /// The shared settings, see [`super::BacklinksWindow::new`].
+        pub ctx: OnceCell<Rc<Context>>,
+
+        #[template_child]
+        pub toolbar: TemplateChild<adw::ToolbarView>,
+        #[template_child]
+        pub header: TemplateChild<adw::HeaderBar>,
+        #[template_child]
+        pub scroll: TemplateChild<gtk::ScrolledWindow>,
+        #[template_child]
+        pub grid: TemplateChild<gtk::Grid>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for BacklinksWindow {
+        const NAME: &'static str = "BacklinksWindow";
+        type Type = super::BacklinksWindow;
+        type ParentType = adw::ApplicationWindow;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            announce::TemplateCallbacks::bind_template_callbacks(klass);
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for BacklinksWindow {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![
+                    // A subject chosen in the search entry is to be opened.
+                    Signal::builder("open-subject")
+                        .param_types([String::static_type()])
+                        .build(),
+                    // The backlinks have been (re)loaded into the grid.
+                    Signal::builder("loaded").build(),
+                ]
+            })
+        }
+
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            // Add common actions (i.e., copy to clipboard, open URI) for context menus in this
+            // window.
+            add_common_actions(obj.upcast_ref());
+            obj.style_context().add_class(METADATA_WINDOW_CLASS);
+            geometry::remember(&*obj, "backlinks");
+
+            // The search entry asks for the chosen file to be opened in a subject window.
+            let weak = obj.downgrade();
+            self.header.pack_end(&search::entry(move |target| {
+                if let Some(window) = weak.upgrade() {
+                    window.emit_by_name::<()>("open-subject", &[&target]);
+                }
+            }));
+
+            // So do files dropped onto the window.
+            let weak = obj.downgrade();
+            dnd::accept(&*obj, move |target| {
+                if let Some(window) = weak.upgrade() {
+                    window.emit_by_name::<()>("open-subject", &[&target]);
+                }
+            });
+
+            // The "refresh" action (F5 or the header bar button) re-runs the query.
+            let refresh = gio::SimpleAction::new("refresh", None);
+            let weak = obj.downgrade();
+            refresh.connect_activate(move |_, _| {
+                if let Some(window) = weak.upgrade() {
+                    window.load();
+                }
+            });
+            obj.add_action(&refresh);
+        }
+    }
+
+    impl WidgetImpl for BacklinksWindow {}
+    impl WindowImpl for BacklinksWindow {}
+    impl ApplicationWindowImpl for BacklinksWindow {}
+    impl AdwApplicationWindowImpl for BacklinksWindow {}
+}
+
+glib::wrapper! {
+    /// A window listing the nodes that link to a given node (its backlinks), along with the
+    /// predicates they link with.
+    pub struct BacklinksWindow(ObjectSubclass<imp::BacklinksWindow>)
+        @extends adw::ApplicationWindow, gtk::ApplicationWindow, gtk::Window, gtk::Widget,
+        @implements gio::ActionGroup, gio::ActionMap, gtk::Accessible, gtk::Buildable,
+            gtk::ConstraintTarget, gtk::Native, gtk::Root, gtk::ShortcutManager;
+}
+
+impl BacklinksWindow {
+    /// Creates the window for the backlinks of `uri`, without loading them yet (see
+    /// [`load`](Self::load)).
+    ///
+    /// # Arguments
+    /// * `app` - The application instance.
+    /// * `parent` - The window the backlinks were asked for in, which this window stays above.
+    /// * `uri` - The node whose backlinks are to be listed.
+    /// * `ctx` - Shared application settings.
+    pub fn new(
+        app: &adw::Application,
+        parent: &impl IsA<gtk::Window>,
+        uri: &str,
+        ctx: &Rc<Context>,
+    ) -> Self {
+        let window: Self = glib::Object::builder()
+            .property("application", app)
+            .property("transient-for", parent)
+            .property("uri", uri)
+            .property("debug", ctx.debug)
+            .build();
+        let _ = window.imp().ctx.set(ctx.clone());
+        window
+    }
+
+    /// Queries the backlinks and fills the grid with them, replacing any shown before, then
+    /// emits `loaded`. The scroll offset is kept across reloads.
+    pub fn load(&self) {
+        let imp = self.imp();
+        let (Some(app), Some(ctx)) = (
+            self.application().and_downcast::<adw::Application>(),
+            imp.ctx.get().cloned(),
+        ) else {
+            return;
+        };
+        trace::event("backlinks_loading", json!({ "uri": self.uri() }));
+        let window = self.clone();
+        let scroll_offset = imp.scroll.vadjustment().value();
+        glib::MainContext::default().spawn_local(async move {
+            let imp = window.imp();
+            populate_backlinks_grid(&app, window.upcast_ref(), &imp.grid, &window.uri(), &ctx)
+                .await;
+            restore_scroll(&imp.scroll, scroll_offset);
+            window.emit_by_name::<()>("loaded", &[]);
+        });
+    }
+
+    /// Calls `f` with the URI of each subject chosen in the search entry.
+    pub fn connect_open_subject(
+        &self,
+        f: impl Fn(&Self, String) + 'static,
+    ) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "open-subject",
+            false,
+            glib::closure_local!(move |window: &Self, target: String| f(window, target)),
+        )
+    }
+}
@@ -0,0 +1,71 @@
+use crate::context::Context;
+use crate::ordering;
+use crate::settings::{self, Settings};
+use crate::tab_title;
+use adw::prelude::*;
+
+/// Returns whether `uri` is bookmarked.
+pub fn is_bookmarked(ctx: &Context, uri: &str) -> bool {
+    ctx.bookmarks.borrow().iter().any(|b| b == uri)
+}
+
+/// Bookmarks `uri`, or removes its bookmark if it has one, and stores the bookmarks in the
+/// user's preferences.
+///
+/// # Returns
+/// * Whether `uri` is bookmarked now.
+pub fn toggle(ctx: &Context, uri: &str) -> bool {
+    let bookmarks = ordering::toggle(&ctx.bookmarks.borrow(), uri);
+    Settings::load().set_string(settings::BOOKMARKS, &bookmarks.join("\n"));
+    ctx.bookmarks.replace(bookmarks);
+    fill_menu(&ctx.bookmarks_menu, &ctx.bookmarks.borrow());
+    is_bookmarked(ctx, uri)
+}
+
+/// Returns the menu of bookmarks, filled the first time it is asked for. All windows share it,
+/// so that it is kept up to date in each.
+pub fn menu(ctx: &Context) -> gio::Menu {
+    if ctx.bookmarks_menu.n_items() == 0 {
+        fill_menu(&ctx.bookmarks_menu, &ctx.bookmarks.borrow());
+    }
+    ctx.bookmarks_menu.clone()
+}
+
+/// Replaces the items of `menu` with one for each of `bookmarks`, in the order they were added,
+/// which opens the subject in a new tab. Without bookmarks, a disabled item says how to add one.
+fn fill_menu(menu: &gio::Menu, bookmarks: &[String]) {
+    menu.remove_all();
+    if bookmarks.is_empty() {
+        // Without an action, the item is shown insensitive.
+        menu.append(Some("No Bookmarks (Ctrl+D Adds One)"), None);
+        return;
+    }
+    for uri in bookmarks {
+        let item = gio::MenuItem::new(Some(&tab_title(uri)), None);
+        item.set_action_and_target_value(Some("win.open-in-new-tab"), Some(&uri.to_variant()));
+        menu.append_item(&item);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bookmarks_are_listed_in_the_menu() {
+        let ctx = Context::default();
+        ctx.bookmarks.replace(vec!["urn:tag:a".to_string()]);
+        assert!(is_bookmarked(&ctx, "urn:tag:a"));
+        assert!(!is_bookmarked(&ctx, "urn:tag:b"));
+
+        let menu = gio::Menu::new();
+        fill_menu(&menu, &[]);
+        assert_eq!(menu.n_items(), 1);
+        fill_menu(&menu, &ctx.bookmarks.borrow());
+        let target = menu.item_attribute_value(0, gio::MENU_ATTRIBUTE_TARGET, None);
+        assert_eq!(
+            target.and_then(|t| t.get::<String>()).as_deref(),
+            Some("urn:tag:a")
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use crate::rdf;
+use crate::store;
+use crate::{LinkHandler, tab_title};
+use adw::prelude::*;
+use tracker::prelude::*;
+
+/// The property linking a file or other item to the folder (or archive) holding it.
+pub const NFO_BELONGS_TO_CONTAINER: &str =
+    "http://tracker.api.gnome.org/ontology/v3/nfo#belongsToContainer";
+
+/// How many containers are followed upwards at most, so that cycles in the data end.
+const MAX_DEPTH: usize = 32;
+
+/// Returns the ancestors of `uri`, outermost first, not including `uri` itself.
+///
+/// The ancestors of `file:` URIs are the folders in their path. For other subjects, the chain
+/// of `nfo:belongsToContainer` is followed in the store, e.g. from a file inside an archive.
+pub async fn ancestors(uri: &str) -> Vec<String> {
+    if uri.starts_with("file:") {
+        return file_ancestors(uri);
+    }
+    let mut ancestors = Vec::new();
+    let mut current = uri.to_string();
+    while ancestors.len() < MAX_DEPTH {
+        let Some(container) = container(&current).await else {
+            break;
+        };
+        if container == uri || ancestors.contains(&container) {
+            break;
+        }
+        ancestors.push(container.clone());
+        current = container;
+    }
+    ancestors.reverse();
+    ancestors
+}
+
+/// Returns the folders containing the file `uri`, from the root of the file system down.
+fn file_ancestors(uri: &str) -> Vec<String> {
+    let mut ancestors = Vec::new();
+    let mut parent = gio::File::for_uri(uri).parent();
+    while let Some(folder) = parent {
+        ancestors.push(folder.uri().to_string());
+        parent = folder.parent();
+    }
+    ancestors.reverse();
+    ancestors
+}
+
+/// Looks up the container `uri` belongs to in the store.
+async fn container(uri: &str) -> Option<String> {
+    let conn = store::client().ok()?;
+    let sparql = format!(
+        "SELECT ?container WHERE {{ {} <{NFO_BELONGS_TO_CONTAINER}> ?container }} LIMIT 1",
+        rdf::iri_ref(uri)
+    );
+    let cursor = conn.query_async(&sparql).await.ok()?;
+    cursor
+        .next_future()
+        .await
+        .ok()
+        .filter(|found| *found)
+        .and_then(|_| cursor.string(0))
+        .map(|container| container.to_string())
+}
+
+/// Shows `ancestors` of the subject `current` as a row of buttons in `bar`, each separated by
+/// "›", ending with the subject itself. The bar is hidden if there are no ancestors.
+///
+/// # Arguments
+/// * `bar` - The box holding the breadcrumbs, whose previous children are removed.
+/// * `ancestors` - The ancestors, outermost first (see [`ancestors`]).
+/// * `current` - The subject shown.
+/// * `on_link` - Called with the URI of an ancestor whose button is clicked.
+pub fn fill(bar: &gtk::Box, ancestors: &[String], current: &str, on_link: &LinkHandler) {
+    while let Some(child) = bar.first_child() {
+        bar.remove(&child);
+    }
+    bar.set_visible(!ancestors.is_empty());
+    for ancestor in ancestors {
+        let button = gtk::Button::builder()
+            .child(&crumb_label(ancestor))
+            .tooltip_text(ancestor)
+            .build();
+        button.style_context().add_class("flat");
+        let on_link = on_link.clone();
+        let ancestor = ancestor.clone();
+        button.connect_clicked(move |_| on_link(&ancestor));
+        bar.append(&button);
+        let separator = gtk::Label::new(Some("›"));
+        separator.style_context().add_class("dim-label");
+        bar.append(&separator);
+    }
+    let label = crumb_label(current);
+    label.set_margin_start(6);
+    label.style_context().add_class("heading");
+    bar.append(&label);
+}
+
+/// Builds the label naming `uri` in the breadcrumbs: its file name, or the URI, shortened.
+fn crumb_label(uri: &str) -> gtk::Label {
+    let label = gtk::Label::new(Some(&tab_title(uri)));
+    label.set_ellipsize(gtk::pango::EllipsizeMode::Middle);
+    label.set_max_width_chars(24);
+    label
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_ancestors_from_the_root_down() {
+        assert_eq!(
+            file_ancestors("file:///home/user/notes.txt"),
+            ["file:///", "file:///home", "file:///home/user"]
+        );
+        assert!(file_ancestors("file:///").is_empty());
+    }
+}
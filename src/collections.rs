@@ -0,0 +1,208 @@
+use crate::finder::Query;
+use serde_json::json;
+use std::path::PathBuf;
+
+/// A query saved under a name, so that the resources it finds can be listed again later.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Collection {
+    pub name: String,
+    pub query: Query,
+}
+
+/// Largest number of queries kept in the history.
+const MAX_HISTORY: usize = 25;
+
+/// Returns the path of the file holding the saved collections,
+/// `$XDG_CONFIG_HOME/file-information/collections.json`.
+fn path() -> PathBuf {
+    glib::user_config_dir()
+        .join("file-information")
+        .join("collections.json")
+}
+
+/// Returns the path of the file holding the history of executed queries,
+/// `$XDG_DATA_HOME/file-information/query-history.json`.
+fn history_path() -> PathBuf {
+    glib::user_data_dir()
+        .join("file-information")
+        .join("query-history.json")
+}
+
+/// Returns the saved collections, sorted by name.
+///
+/// A missing or unreadable file is treated as empty, and entries that cannot be parsed are
+/// skipped.
+pub fn load() -> Vec<Collection> {
+    std::fs::read_to_string(path())
+        .map(|text| from_document(&text))
+        .unwrap_or_default()
+}
+
+/// Returns the saved collection called `name`, if any.
+pub fn find(name: &str) -> Option<Collection> {
+    load()
+        .into_iter()
+        .find(|collection| collection.name == name)
+}
+
+/// Saves `query` as the collection `name`, replacing any collection of the same name.
+///
+/// # Returns
+/// * An error if the file could not be written.
+pub fn save(name: &str, query: &Query) -> std::io::Result<()> {
+    let mut collections = load();
+    collections.retain(|collection| collection.name != name);
+    collections.push(Collection {
+        name: name.to_string(),
+        query: query.clone(),
+    });
+    collections.sort_by(|a, b| a.name.cmp(&b.name));
+    let path = path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, to_document(&collections))
+}
+
+/// Returns the queries run in the query builder, the most recent first.
+///
+/// A missing or unreadable file is treated as empty, and entries that cannot be parsed are
+/// skipped.
+pub fn history() -> Vec<Query> {
+    std::fs::read_to_string(history_path())
+        .map(|text| history_from_document(&text))
+        .unwrap_or_default()
+}
+
+/// Records `query` as the most recently run one. Running a query again moves it to the top
+/// instead of adding it twice, and only the latest `MAX_HISTORY` queries are kept.
+///
+/// Queries are recorded as they were entered, so `?subject` templates stay templates.
+///
+/// # Returns
+/// * An error if the file could not be written.
+pub fn record(query: &Query) -> std::io::Result<()> {
+    let history = recorded(history(), query);
+    let path = history_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, history_to_document(&history))
+}
+
+/// Returns `history` with `query` moved or added to the top, cut to `MAX_HISTORY` entries.
+fn recorded(mut history: Vec<Query>, query: &Query) -> Vec<Query> {
+    history.retain(|past| past != query);
+    history.insert(0, query.clone());
+    history.truncate(MAX_HISTORY);
+    history
+}
+
+/// Serializes `history` as a JSON array of queries.
+fn history_to_document(history: &[Query]) -> String {
+    let doc: Vec<serde_json::Value> = history.iter().map(Query::to_json).collect();
+    serde_json::to_string_pretty(&doc).unwrap_or_default()
+}
+
+/// Parses the history written by [`history_to_document`], in the order written.
+fn history_from_document(text: &str) -> Vec<Query> {
+    let Ok(serde_json::Value::Array(entries)) = serde_json::from_str(text) else {
+        return Vec::new();
+    };
+    entries.iter().filter_map(Query::from_json).collect()
+}
+
+/// Serializes `collections` as a JSON array of `{name, query}` objects.
+fn to_document(collections: &[Collection]) -> String {
+    let doc: Vec<serde_json::Value> = collections
+        .iter()
+        .map(|collection| json!({ "name": collection.name, "query": collection.query.to_json() }))
+        .collect();
+    // Serializing a `serde_json::Value` cannot fail.
+    serde_json::to_string_pretty(&doc).unwrap_or_default()
+}
+
+/// Parses the collections written by [`to_document`], sorted by name.
+fn from_document(text: &str) -> Vec<Collection> {
+    let Ok(serde_json::Value::Array(entries)) = serde_json::from_str(text) else {
+        return Vec::new();
+    };
+    let mut collections: Vec<Collection> = entries
+        .iter()
+        .filter_map(|entry| {
+            Some(Collection {
+                name: entry.get("name")?.as_str()?.to_string(),
+                query: Query::from_json(entry.get("query")?)?,
+            })
+        })
+        .collect();
+    collections.sort_by(|a, b| a.name.cmp(&b.name));
+    collections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finder::{Condition, Group, Join, Operator};
+
+    fn query(value: &str) -> Query {
+        Query {
+            join: Join::All,
+            groups: vec![Group {
+                join: Join::All,
+                conditions: vec![Condition {
+                    predicate: "urn:p".to_string(),
+                    operator: Operator::Is,
+                    value: value.to_string(),
+                    upper: String::new(),
+                }],
+            }],
+        }
+    }
+
+    #[test]
+    fn history_keeps_the_latest_queries_once() {
+        let mut history = Vec::new();
+        for value in ["a", "b", "a", "?subject"] {
+            history = recorded(history, &query(value));
+        }
+        assert_eq!(history, [query("?subject"), query("a"), query("b")]);
+        assert_eq!(
+            history_from_document(&history_to_document(&history)),
+            history
+        );
+        assert_eq!(history_from_document(r#"[{"join": "all"}]"#), Vec::new());
+
+        for n in 0..MAX_HISTORY + 5 {
+            history = recorded(history, &query(&n.to_string()));
+        }
+        assert_eq!(history.len(), MAX_HISTORY);
+        assert_eq!(history[0], query(&(MAX_HISTORY + 4).to_string()));
+    }
+
+    #[test]
+    fn document_round_trip_skips_invalid_entries() {
+        let query = Query {
+            join: Join::All,
+            groups: vec![Group {
+                join: Join::Any,
+                conditions: vec![Condition {
+                    predicate: "urn:p".to_string(),
+                    operator: Operator::Between,
+                    value: "1".to_string(),
+                    upper: "2".to_string(),
+                }],
+            }],
+        };
+        let collections = vec![Collection {
+            name: "Large".to_string(),
+            query,
+        }];
+        assert_eq!(from_document(&to_document(&collections)), collections);
+        assert_eq!(
+            from_document(r#"[{"name": "Broken"}, {"query": {}}]"#),
+            Vec::new()
+        );
+        assert_eq!(from_document("not json"), Vec::new());
+    }
+}
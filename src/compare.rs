@@ -0,0 +1,445 @@
+use crate::dnd;
+use crate::geometry;
+use crate::labels;
+use crate::pages;
+use crate::rdf;
+use crate::{METADATA_WINDOW_CLASS, friendly_label, tab_title};
+use adw::prelude::*;
+use serde_json::json;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// How the values of one property differ between two subjects.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Difference {
+    /// Both subjects have the same values.
+    Same,
+    /// Both subjects have the property, but with different values.
+    Changed,
+    /// Only the left subject has the property.
+    LeftOnly,
+    /// Only the right subject has the property.
+    RightOnly,
+}
+
+impl Difference {
+    /// Returns the name of the difference used in exports, relative to the left subject: a
+    /// property only the right subject has is `added`, one only the left subject has is
+    /// `removed`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Difference::Same => "same",
+            Difference::Changed => "changed",
+            Difference::LeftOnly => "removed",
+            Difference::RightOnly => "added",
+        }
+    }
+}
+
+/// The values of one property on both sides of a comparison.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiffRow {
+    pub predicate: String,
+    pub left: Vec<String>,
+    pub right: Vec<String>,
+    pub difference: Difference,
+}
+
+/// Compares the statements of two subjects property by property.
+///
+/// Rows are ordered like the properties of the left subject, followed by the properties only
+/// the right subject has. Multiple values of a property are compared regardless of their order.
+pub fn diff(left: &[rdf::Triple], right: &[rdf::Triple]) -> Vec<DiffRow> {
+    let mut predicates: Vec<&str> = Vec::new();
+    for triple in left.iter().chain(right) {
+        if !predicates.contains(&triple.predicate.as_str()) {
+            predicates.push(&triple.predicate);
+        }
+    }
+    let values = |triples: &[rdf::Triple], predicate: &str| -> Vec<String> {
+        let mut values: Vec<String> = triples
+            .iter()
+            .filter(|t| t.predicate == predicate)
+            .map(|t| match &t.object {
+                rdf::Object::Iri(iri) => iri.clone(),
+                rdf::Object::Literal { value, .. } => value.clone(),
+            })
+            .collect();
+        values.sort();
+        values
+    };
+    predicates
+        .into_iter()
+        .map(|predicate| {
+            let left = values(left, predicate);
+            let right = values(right, predicate);
+            let difference = if right.is_empty() {
+                Difference::LeftOnly
+            } else if left.is_empty() {
+                Difference::RightOnly
+            } else if left == right {
+                Difference::Same
+            } else {
+                Difference::Changed
+            };
+            DiffRow {
+                predicate: predicate.to_string(),
+                left,
+                right,
+                difference,
+            }
+        })
+        .collect()
+}
+
+/// Serializes the differing rows of a comparison as CSV.
+///
+/// Properties with the same values on both sides are left out. Multiple values of a property
+/// are separated by newlines within their cell.
+///
+/// # Arguments
+/// * `left` - The URI of the left subject.
+/// * `right` - The URI of the right subject.
+/// * `rows` - The rows returned by [`diff`].
+pub fn to_csv(left: &str, right: &str, rows: &[DiffRow]) -> String {
+    let mut wtr = csv::WriterBuilder::new()
+        .has_headers(true)
+        .from_writer(vec![]);
+    let _ = wtr.write_record([
+        "Left",
+        "Right",
+        "Predicate",
+        "Change",
+        "Left Values",
+        "Right Values",
+    ]);
+    for r in rows.iter().filter(|r| r.difference != Difference::Same) {
+        let _ = wtr.write_record([
+            left,
+            right,
+            &r.predicate,
+            r.difference.as_str(),
+            &r.left.join("\n"),
+            &r.right.join("\n"),
+        ]);
+    }
+    String::from_utf8(wtr.into_inner().unwrap_or_default()).unwrap_or_default()
+}
+
+/// Serializes the differing rows of a comparison as a JSON document.
+///
+/// The document has the following shape:
+///
+/// ```json
+/// {
+///   "left": "file:///home/user/a.jpg",
+///   "right": "file:///home/user/b.jpg",
+///   "differences": [
+///     {
+///       "predicate": "http://tracker.api.gnome.org/ontology/v3/nfo#width",
+///       "change": "changed",
+///       "left": ["640"],
+///       "right": ["800"]
+///     }
+///   ]
+/// }
+/// ```
+///
+/// # Arguments
+/// * `left` - The URI of the left subject.
+/// * `right` - The URI of the right subject.
+/// * `rows` - The rows returned by [`diff`].
+pub fn to_json(left: &str, right: &str, rows: &[DiffRow]) -> String {
+    let differences: Vec<_> = rows
+        .iter()
+        .filter(|r| r.difference != Difference::Same)
+        .map(|r| {
+            json!({
+                "predicate": r.predicate,
+                "change": r.difference.as_str(),
+                "left": r.left,
+                "right": r.right,
+            })
+        })
+        .collect();
+    let doc = json!({
+        "left": left,
+        "right": right,
+        "differences": differences,
+    });
+    // Serializing a `serde_json::Value` cannot fail.
+    serde_json::to_string_pretty(&doc).unwrap_or_default()
+}
+
+/// Asks for a file name and saves an export of a comparison there.
+///
+/// # Arguments
+/// * `window` - The comparison window, which the file chooser is attached to.
+/// * `suggested_name` - The file name initially proposed.
+/// * `contents` - The exported document.
+fn save_export(window: &adw::ApplicationWindow, suggested_name: &str, contents: String) {
+    let chooser = gtk::FileChooserNative::new(
+        Some("Export Differences"),
+        Some(window),
+        gtk::FileChooserAction::Save,
+        Some("Export"),
+        None,
+    );
+    chooser.set_current_name(suggested_name);
+    let window = window.clone();
+    chooser.connect_response(move |chooser, response| {
+        if response != gtk::ResponseType::Accept {
+            return;
+        }
+        let Some(path) = chooser.file().and_then(|f| f.path()) else {
+            return;
+        };
+        if let Err(err) = std::fs::write(&path, &contents) {
+            let dialog = adw::MessageDialog::new(
+                Some(&window),
+                Some("Could not export differences"),
+                Some(&err.to_string()),
+            );
+            dialog.add_response("close", "Close");
+            dialog.present();
+        }
+    });
+    chooser.show();
+}
+
+/// Opens a window comparing the metadata of two subjects side by side.
+///
+/// Values that differ are highlighted, as are properties present on one side only.
+///
+/// # Arguments
+/// * `app` - The application instance.
+/// * `parent` - The window the comparison was started from.
+/// * `left` - The URI of the subject shown in the left column.
+/// * `right` - The URI of the subject shown in the right column.
+pub fn open_window(
+    app: &adw::Application,
+    parent: &adw::ApplicationWindow,
+    left: String,
+    right: String,
+) {
+    let grid = gtk::Grid::builder()
+        .column_spacing(12)
+        .row_spacing(4)
+        .margin_start(6)
+        .margin_end(6)
+        .margin_top(6)
+        .margin_bottom(6)
+        .build();
+    grid.set_widget_name("data-grid");
+    let scroll = gtk::ScrolledWindow::builder()
+        .min_content_width(720)
+        .min_content_height(400)
+        .child(&grid)
+        .build();
+
+    // The export formats are offered from a menu once the comparison has been computed.
+    let export_menu = gio::Menu::new();
+    export_menu.append(Some("Export as CSV…"), Some("win.export-csv"));
+    export_menu.append(Some("Export as JSON…"), Some("win.export-json"));
+    let export_button = gtk::MenuButton::builder()
+        .icon_name("document-save-symbolic")
+        .tooltip_text("Export Differences")
+        .menu_model(&export_menu)
+        .sensitive(false)
+        .build();
+
+    let header = adw::HeaderBar::new();
+    header.set_title_widget(Some(&gtk::Label::new(Some("Comparison"))));
+    header.pack_end(&export_button);
+    let toolbar = adw::ToolbarView::new();
+    toolbar.add_top_bar(&header);
+    toolbar.set_content(Some(&scroll));
+
+    let window = adw::ApplicationWindow::builder()
+        .application(app)
+        .transient_for(parent)
+        .default_width(760)
+        .default_height(500)
+        .title("Comparison")
+        .content(&toolbar)
+        .build();
+    geometry::remember(&window, "comparison");
+    window.style_context().add_class(METADATA_WINDOW_CLASS);
+
+    // Files dropped onto the comparison open in new tabs of the window it was started from.
+    let parent_clone = parent.clone();
+    dnd::accept(&window, move |target| {
+        ActionGroupExt::activate_action(
+            &parent_clone,
+            "open-in-new-tab",
+            Some(&target.to_variant()),
+        );
+    });
+
+    // The rows of the comparison, filled in once both subjects have been queried.
+    let rows: Rc<RefCell<Vec<DiffRow>>> = Rc::new(RefCell::new(Vec::new()));
+    for (name, extension, export) in [
+        (
+            "export-csv",
+            "csv",
+            to_csv as fn(&str, &str, &[DiffRow]) -> String,
+        ),
+        ("export-json", "json", to_json),
+    ] {
+        let action = gio::SimpleAction::new(name, None);
+        let window_clone = window.clone();
+        let rows = rows.clone();
+        let (left, right) = (left.clone(), right.clone());
+        action.connect_activate(move |_, _| {
+            let contents = export(&left, &right, &rows.borrow());
+            save_export(&window_clone, &format!("differences.{extension}"), contents);
+        });
+        window.add_action(&action);
+    }
+    window.present();
+
+    glib::MainContext::default().spawn_local(async move {
+        let (left_triples, right_triples) = match (
+            pages::subject_triples(&left).await,
+            pages::subject_triples(&right).await,
+        ) {
+            (Ok(l), Ok(r)) => (l, r),
+            (Err(err), _) | (_, Err(err)) => {
+                grid.attach(
+                    &gtk::Label::new(Some(&format!("The store could not be queried: {err}"))),
+                    0,
+                    0,
+                    3,
+                    1,
+                );
+                return;
+            }
+        };
+
+        // The column headings name the two subjects.
+        for (column, text, tooltip) in [
+            (0, "Property".to_string(), None),
+            (1, tab_title(&left), Some(&left)),
+            (2, tab_title(&right), Some(&right)),
+        ] {
+            let label = gtk::Label::new(Some(&text));
+            label.set_halign(gtk::Align::Start);
+            label.style_context().add_class("first-col");
+            label.set_tooltip_text(tooltip.map(String::as_str));
+            grid.attach(&label, column, 0, 1, 1);
+        }
+
+        let diff_rows = diff(&left_triples, &right_triples);
+        labels::load(diff_rows.iter().map(|r| r.predicate.as_str())).await;
+        rows.replace(diff_rows);
+        export_button.set_sensitive(true);
+        for (row, diff_row) in rows.borrow().iter().enumerate() {
+            let row = row as i32 + 1;
+            let predicate = gtk::Label::new(Some(&friendly_label(&diff_row.predicate)));
+            predicate.set_halign(gtk::Align::Start);
+            predicate.set_valign(gtk::Align::Start);
+            predicate.set_tooltip_text(Some(&diff_row.predicate));
+            grid.attach(&predicate, 0, row, 1, 1);
+
+            // Changed values are shown as warnings; a property present on one side only is
+            // shown as added there and as a dimmed dash on the other side.
+            let (left_class, right_class) = match diff_row.difference {
+                Difference::Same => (None, None),
+                Difference::Changed => (Some("warning"), Some("warning")),
+                Difference::LeftOnly => (Some("success"), Some("dim-label")),
+                Difference::RightOnly => (Some("dim-label"), Some("success")),
+            };
+            for (column, values, class) in [
+                (1, &diff_row.left, left_class),
+                (2, &diff_row.right, right_class),
+            ] {
+                let text = if values.is_empty() {
+                    "—".to_string()
+                } else {
+                    values.join("\n")
+                };
+                let label = gtk::Label::new(Some(&text));
+                label.set_halign(gtk::Align::Start);
+                label.set_xalign(0.0);
+                label.set_wrap(true);
+                label.set_wrap_mode(gtk::pango::WrapMode::WordChar);
+                label.set_max_width_chars(40);
+                label.set_selectable(true);
+                if let Some(class) = class {
+                    label.style_context().add_class(class);
+                }
+                grid.attach(&label, column, row, 1, 1);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triple(predicate: &str, value: &str) -> rdf::Triple {
+        rdf::Triple {
+            subject: "urn:s".to_string(),
+            predicate: predicate.to_string(),
+            object: rdf::Object::Iri(value.to_string()),
+        }
+    }
+
+    #[test]
+    fn diff_classifies_properties() {
+        let left = [
+            triple("urn:same", "a"),
+            triple("urn:changed", "x"),
+            triple("urn:left", "l"),
+            triple("urn:multi", "1"),
+            triple("urn:multi", "2"),
+        ];
+        let right = [
+            triple("urn:multi", "2"),
+            triple("urn:multi", "1"),
+            triple("urn:changed", "y"),
+            triple("urn:same", "a"),
+            triple("urn:right", "r"),
+        ];
+        let kinds: Vec<_> = diff(&left, &right)
+            .into_iter()
+            .map(|row| (row.predicate, row.difference))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                ("urn:same".to_string(), Difference::Same),
+                ("urn:changed".to_string(), Difference::Changed),
+                ("urn:left".to_string(), Difference::LeftOnly),
+                ("urn:multi".to_string(), Difference::Same),
+                ("urn:right".to_string(), Difference::RightOnly),
+            ]
+        );
+    }
+
+    #[test]
+    fn exports_leave_out_same_rows() {
+        let rows = diff(
+            &[triple("urn:same", "a"), triple("urn:changed", "x")],
+            &[
+                triple("urn:same", "a"),
+                triple("urn:changed", "y"),
+                triple("urn:new", "n"),
+            ],
+        );
+        let csv = to_csv("urn:l", "urn:r", &rows);
+        assert_eq!(
+            csv,
+            "Left,Right,Predicate,Change,Left Values,Right Values\n\
+             urn:l,urn:r,urn:changed,changed,x,y\n\
+             urn:l,urn:r,urn:new,added,,n\n"
+        );
+        let parsed: serde_json::Value =
+            serde_json::from_str(&to_json("urn:l", "urn:r", &rows)).unwrap();
+        assert_eq!(parsed["left"], "urn:l");
+        assert_eq!(parsed["differences"].as_array().map(Vec::len), Some(2));
+        assert_eq!(parsed["differences"][1]["change"], "added");
+        assert_eq!(parsed["differences"][1]["right"][0], "n");
+    }
+}
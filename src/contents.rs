@@ -0,0 +1,184 @@
+use crate::announce;
+use crate::breadcrumbs::NFO_BELONGS_TO_CONTAINER;
+use crate::context::Context;
+use crate::pages;
+use crate::rdf;
+use crate::store;
+use crate::trace;
+use crate::{
+    FILEDATAOBJECT, NFO_FILE_NAME, NFO_FILE_SIZE, NIE_INTERPRETED_AS, NIE_MIME_TYPE,
+    open_subject_window, tab_title,
+};
+use adw::prelude::*;
+use serde_json::json;
+use std::rc::Rc;
+use tracker::prelude::*;
+
+const NFO_FOLDER: &str = "http://tracker.api.gnome.org/ontology/v3/nfo#Folder";
+
+/// A file or folder inside a folder, as indexed in the store.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    pub uri: String,
+    /// The `nfo:fileName`, if indexed.
+    pub name: Option<String>,
+    /// The `nfo:fileSize` in bytes, if indexed.
+    pub size: Option<u64>,
+    /// The `nie:mimeType` of the entry's content, if indexed.
+    pub mime_type: Option<String>,
+}
+
+impl Entry {
+    /// Returns the name shown for the entry: its file name, or else the last part of its URI.
+    pub fn title(&self) -> String {
+        self.name.clone().unwrap_or_else(|| tab_title(&self.uri))
+    }
+
+    /// Describes the size and type of the entry, e.g. "1.2 kB · text/plain", leaving out what
+    /// is not known.
+    pub fn description(&self) -> String {
+        let size = self.size.map(|size| glib::format_size(size).to_string());
+        [size, self.mime_type.clone()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" · ")
+    }
+}
+
+/// The pattern matching the entries `?child` of the folder `uri`, whether `uri` is the folder's
+/// file or the `nfo:Folder` it is interpreted as (which the entries belong to).
+fn children_pattern(uri: &str) -> String {
+    let folder = rdf::iri_ref(uri);
+    format!(
+        "{{ ?child <{NFO_BELONGS_TO_CONTAINER}> {folder} }} \
+         UNION {{ {folder} <{NIE_INTERPRETED_AS}> ?folder . \
+         ?child <{NFO_BELONGS_TO_CONTAINER}> ?folder }} \
+         ?child a <{FILEDATAOBJECT}> ."
+    )
+}
+
+/// Returns whether the subject `uri` is a folder, i.e. is or is interpreted as an `nfo:Folder`.
+/// Failed queries count as not a folder.
+pub async fn is_folder(uri: &str) -> bool {
+    let Ok(conn) = store::client() else {
+        return false;
+    };
+    let subject = rdf::iri_ref(uri);
+    let sparql = format!(
+        "SELECT ?folder WHERE {{ \
+         {{ {subject} a <{NFO_FOLDER}> . BIND({subject} AS ?folder) }} \
+         UNION {{ {subject} <{NIE_INTERPRETED_AS}> ?folder . ?folder a <{NFO_FOLDER}> }} \
+         }} LIMIT 1"
+    );
+    match conn.query_async(&sparql).await {
+        Ok(cursor) => cursor.next_future().await.unwrap_or(false),
+        Err(_) => false,
+    }
+}
+
+/// Returns the query for the files and folders directly inside the folder `uri`, as
+/// `?child ?name ?size ?mime` rows sorted by name.
+pub fn children_query(uri: &str) -> String {
+    format!(
+        "SELECT ?child ?name ?size ?mime WHERE {{ {pattern} \
+         OPTIONAL {{ ?child <{NFO_FILE_NAME}> ?name }} \
+         OPTIONAL {{ ?child <{NFO_FILE_SIZE}> ?size }} \
+         OPTIONAL {{ ?child <{NIE_INTERPRETED_AS}> ?content . ?content <{NIE_MIME_TYPE}> ?mime }} \
+         }} ORDER BY LCASE(?name) ?child",
+        pattern = children_pattern(uri)
+    )
+}
+
+/// Queries the files and folders directly inside the folder `uri`, sorted by name.
+pub async fn children(uri: &str) -> Result<Vec<Entry>, glib::Error> {
+    let conn = store::client()?;
+    let cursor = conn.query_async(&children_query(uri)).await?;
+    let mut entries: Vec<Entry> = Vec::new();
+    while cursor.next_future().await? {
+        let uri = cursor.string(0).unwrap_or_default().to_string();
+        // Content with several types yields a row for each; the first is enough.
+        if entries.last().is_some_and(|last| last.uri == uri) {
+            continue;
+        }
+        let text = |column| {
+            cursor
+                .string(column)
+                .map(|value| value.to_string())
+                .filter(|value| !value.is_empty())
+        };
+        entries.push(Entry {
+            uri,
+            name: text(1),
+            size: text(2).and_then(|size| size.parse().ok()),
+            mime_type: text(3),
+        });
+    }
+    Ok(entries)
+}
+
+/// Lists the contents of the folder `uri` in `list`, replacing the entries shown before.
+/// Activating an entry opens it in a subject window of its own.
+///
+/// A failed query is reported in the window's banner, offering to refresh the tab.
+///
+/// # Arguments
+/// * `list` - The list of the contents page of a subject tab.
+/// * `app` - The application instance, for the windows opened.
+/// * `uri` - The URI of the folder.
+/// * `ctx` - Shared application settings.
+pub async fn load(list: &gtk::ListBox, app: &adw::Application, uri: &str, ctx: &Rc<Context>) {
+    pages::clear_list(list);
+    let entries = match children(uri).await {
+        Ok(entries) => entries,
+        Err(err) => {
+            trace::event(
+                "query_failed",
+                json!({ "query": "contents", "uri": uri, "error": err.to_string() }),
+            );
+            announce::report_problem(
+                list,
+                "The contents of the folder could not be loaded.",
+                Some(("win.refresh", None)),
+            );
+            return;
+        }
+    };
+    for entry in entries {
+        let row = adw::ActionRow::builder()
+            .title(glib::markup_escape_text(&entry.title()))
+            .subtitle(glib::markup_escape_text(&entry.description()))
+            .tooltip_text(&entry.uri)
+            .activatable(true)
+            .build();
+        row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+        let app = app.clone();
+        let ctx = ctx.clone();
+        row.connect_activated(move |_| open_subject_window(&app, entry.uri.clone(), &ctx));
+        list.append(&row);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_describe_what_is_known() {
+        let entry = Entry {
+            uri: "file:///home/user/notes.txt".to_string(),
+            name: None,
+            size: Some(1200),
+            mime_type: Some("text/plain".to_string()),
+        };
+        assert_eq!(entry.title(), "notes.txt");
+        assert_eq!(entry.description(), "1.2 kB · text/plain");
+        let entry = Entry {
+            name: Some("Notes".to_string()),
+            size: None,
+            ..entry
+        };
+        assert_eq!(entry.title(), "Notes");
+        assert_eq!(entry.description(), "text/plain");
+    }
+}
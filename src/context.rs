@@ -0,0 +1,150 @@
+use crate::events::EventBus;
+use crate::export::ExportFormat;
+use crate::options::Options;
+use crate::ordering::RowOrder;
+use crate::pinboard::Pinboard;
+use crate::queue::OpenQueue;
+use crate::registry::Registry;
+use crate::settings::{self, Settings};
+use gio::prelude::*;
+use std::cell::{Cell, RefCell};
+
+/// Settings shared by every window opened by one application instance.
+///
+/// A single `Context` is created when the command line is handled and then passed (behind an
+/// `Rc`) to every window, so that windows opened by following links behave like the first one.
+#[derive(Debug, Default)]
+pub struct Context {
+    /// Print additional diagnostic information to stderr.
+    pub debug: bool,
+    /// With `debug`, write a PNG snapshot of a subject window to this file whenever its loaded
+    /// metadata has been painted, e.g. for visual regression checks.
+    pub screenshot: Option<std::path::PathBuf>,
+    /// Shell command to run after the metadata of a subject has been loaded.
+    pub post_load_hook: Option<String>,
+    /// Serve the metadata of each subject window over HTTP on a loopback port.
+    pub share: bool,
+    /// Show an inline bar placing numeric values (file size, duration, rating) within the range
+    /// of values found across the store.
+    pub value_ranges: bool,
+    /// Show how long loading a subject took (connecting, querying, rendering) in a status bar at
+    /// the bottom of its tab.
+    pub timings: bool,
+    /// Follow links in the current subject window instead of opening a new window. This can be
+    /// changed at runtime from the window menu.
+    pub same_window: Cell<bool>,
+    /// Show predicates and linked IRIs abbreviated with namespace prefixes (e.g. `nfo:fileName`)
+    /// instead of friendly labels and full IRIs. This can be changed at runtime from the window
+    /// menu.
+    pub compact_uris: Cell<bool>,
+    /// How the rows of the property table are ordered. This can be changed at runtime from the
+    /// window menu.
+    pub row_order: Cell<RowOrder>,
+    /// Show technical rows such as `rdf:type`, which are hidden by default. This can be changed
+    /// at runtime from the window menu.
+    pub show_advanced: Cell<bool>,
+    /// Follow date-times with how long ago they were, e.g. "(3 days ago)". This can be changed
+    /// at runtime from the window menu.
+    pub relative_dates: Cell<bool>,
+    /// Show the datatype of literals (e.g. `xsd:integer`) in a third column. This can be changed
+    /// at runtime from the window menu.
+    pub show_datatypes: Cell<bool>,
+    /// Show the properties of the information element a file is interpreted as
+    /// (`nie:interpretedAs`), such as its title or dimensions, along with the file's own. This
+    /// can be changed at runtime from the window menu.
+    pub merge_interpreted: Cell<bool>,
+    /// The predicates listed first, before those ordered by `row_order`, in the order they were
+    /// marked as favorites. This can be changed at runtime from the context menu of predicates.
+    pub favorites: RefCell<Vec<String>>,
+    /// Predicates whose rows are never shown. This can be changed at runtime from the
+    /// preferences window.
+    pub hidden_predicates: RefCell<Vec<String>>,
+    /// The format the "Copy" button of subject windows puts the table in. This can be changed at
+    /// runtime from the preferences window.
+    pub export_format: Cell<ExportFormat>,
+    /// The `strftime`-style pattern used to display date-times in the grid and in exports. This
+    /// can be changed at runtime from the window menu.
+    pub date_format: RefCell<String>,
+    /// Show date-times in UTC rather than in the local time zone. This can be changed at runtime
+    /// from the window menu, along with `date_format`.
+    pub utc_dates: Cell<bool>,
+    /// The tabs showing each open subject, so that opening a subject again can bring its
+    /// existing tab to the front instead of creating a duplicate.
+    pub open_subjects: RefCell<Registry<adw::TabPage>>,
+    /// Notifications about changed subjects, which every subject window listens to in order to
+    /// refresh the affected tabs.
+    pub changes: EventBus,
+    /// Reports changes made to the store by the miners and other applications, which are
+    /// forwarded to `changes`. It is created along with the first subject window.
+    pub store_notifier: RefCell<Option<tracker::Notifier>>,
+    /// Whether the Tracker miner is watched on the bus, so that `store_notifier` is renewed and
+    /// the open subjects are reloaded when it restarts. The watch is set up along with the
+    /// first subject window.
+    pub store_watched: Cell<bool>,
+    /// Subjects waiting to be opened, see `OpenQueue`.
+    pub open_queue: OpenQueue,
+    /// Values pinned from any window, see `Pinboard`.
+    pub pinboard: Pinboard,
+    /// The "Recent" submenu of every subject window, see `recent::menu`.
+    pub recent_menu: gio::Menu,
+    /// The bookmarked subjects, in the order they were bookmarked. This can be changed at
+    /// runtime with the star button of subject windows.
+    pub bookmarks: RefCell<Vec<String>>,
+    /// The "Bookmarks" submenu of every subject window, see `bookmarks::menu`.
+    pub bookmarks_menu: gio::Menu,
+}
+
+impl Context {
+    /// Builds the context from the stored preferences and state alone.
+    pub fn from_settings() -> Self {
+        let preferences = settings::preferences();
+        let state = Settings::load();
+        let date_format = preferences.string(settings::DATE_FORMAT);
+        Self {
+            same_window: Cell::new(preferences.boolean(settings::SAME_WINDOW)),
+            compact_uris: Cell::new(preferences.boolean(settings::COMPACT_URIS)),
+            row_order: Cell::new(RowOrder::from_settings()),
+            show_advanced: Cell::new(preferences.boolean(settings::SHOW_ADVANCED)),
+            relative_dates: Cell::new(preferences.boolean(settings::RELATIVE_DATES)),
+            show_datatypes: Cell::new(preferences.boolean(settings::SHOW_DATATYPES)),
+            merge_interpreted: Cell::new(preferences.boolean(settings::MERGE_INTERPRETED)),
+            favorites: RefCell::new(settings::preference_list(settings::FAVORITE_PREDICATES)),
+            hidden_predicates: RefCell::new(settings::preference_list(settings::HIDDEN_PREDICATES)),
+            bookmarks: RefCell::new(state.string_list(settings::BOOKMARKS)),
+            export_format: Cell::new(ExportFormat::from_settings()),
+            date_format: RefCell::new(if date_format.is_empty() {
+                settings::DEFAULT_DATE_FORMAT.to_string()
+            } else {
+                date_format.to_string()
+            }),
+            utc_dates: Cell::new(preferences.boolean(settings::UTC_DATES)),
+            ..Self::default()
+        }
+    }
+
+    /// Builds the context from parsed command-line options, falling back to the stored
+    /// preferences for anything not given on the command line.
+    pub fn from_options(opts: &Options) -> Self {
+        let ctx = Self {
+            debug: opts.debug,
+            screenshot: opts.screenshot.clone(),
+            post_load_hook: opts.post_load_hook.clone(),
+            share: opts.share,
+            value_ranges: opts.value_ranges,
+            timings: opts.timings,
+            ..Self::from_settings()
+        };
+        if let Some(format) = &opts.date_format {
+            ctx.date_format.replace(format.clone());
+        }
+        if opts.utc {
+            ctx.utc_dates.set(true);
+        }
+        if opts.same_window {
+            ctx.same_window.set(true);
+        } else if opts.new_window {
+            ctx.same_window.set(false);
+        }
+        ctx
+    }
+}
@@ -0,0 +1,63 @@
+use crate::location_target;
+use adw::prelude::*;
+
+/// Makes `window` accept files, and text naming a subject, dropped onto it, e.g. from the file
+/// manager or a terminal.
+///
+/// # Arguments
+/// * `window` - The window accepting drops.
+/// * `on_drop` - Called with the subject to inspect for each file, URI or path dropped (see
+///   `location_target`).
+pub fn accept(window: &impl IsA<gtk::Widget>, on_drop: impl Fn(String) + 'static) {
+    let target = gtk::DropTarget::new(glib::Type::INVALID, gdk4::DragAction::COPY);
+    target.set_types(&[gdk4::FileList::static_type(), String::static_type()]);
+    target.connect_drop(move |_, value, _, _| {
+        let targets = dropped_targets(value);
+        if targets.is_empty() {
+            return false;
+        }
+        for target in targets {
+            on_drop(target);
+        }
+        true
+    });
+    window.add_controller(target);
+}
+
+/// Returns the subjects in a dropped list of files (`text/uri-list`) or text.
+fn dropped_targets(value: &glib::Value) -> Vec<String> {
+    if let Ok(files) = value.get::<gdk4::FileList>() {
+        return files
+            .files()
+            .iter()
+            .map(|file| file.uri().to_string())
+            .collect();
+    }
+    value
+        .get::<String>()
+        .map(|text| text_targets(&text))
+        .unwrap_or_default()
+}
+
+/// Returns the subjects named in dropped text, one per line. Comment lines, as in
+/// `text/uri-list`, and lines that name no subject are skipped.
+fn text_targets(text: &str) -> Vec<String> {
+    text.lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(location_target)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_targets_one_per_line() {
+        let text = "# dropped from a terminal\r\nfile:///a.txt\r\n\r\n/tmp/b c.txt\r\nnot a subject\r\n<urn:x>";
+        assert_eq!(
+            text_targets(text),
+            ["file:///a.txt", "file:///tmp/b%20c.txt", "urn:x"]
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use crate::pages;
+use crate::rdf;
+use crate::store;
+use crate::{FILEDATAOBJECT, LinkHandler, NIE_IS_STORED_AS, tab_title};
+use adw::prelude::*;
+use tracker::prelude::*;
+
+const NFO_HAS_HASH: &str = "http://tracker.api.gnome.org/ontology/v3/nfo#hasHash";
+const NFO_HASH_VALUE: &str = "http://tracker.api.gnome.org/ontology/v3/nfo#hashValue";
+const NFO_HASH_ALGORITHM: &str = "http://tracker.api.gnome.org/ontology/v3/nfo#hashAlgorithm";
+
+/// Largest number of duplicates listed.
+const MAX_DUPLICATES: usize = 100;
+
+/// A file with the same content hash as the subject, which probably has the same content.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Duplicate {
+    pub uri: String,
+    /// The algorithm of the matching hash (e.g. "SHA-256"), if the store says.
+    pub algorithm: Option<String>,
+}
+
+impl Duplicate {
+    /// Returns where the duplicate is: its path for local files, otherwise its URI.
+    pub fn location(&self) -> String {
+        gio::File::for_uri(&self.uri)
+            .path()
+            .filter(|_| self.uri.starts_with("file:"))
+            .map_or_else(
+                || self.uri.clone(),
+                |path| path.to_string_lossy().into_owned(),
+            )
+    }
+}
+
+/// Returns the query for the duplicates of `uri` (see [`duplicates`]), as `?other ?algorithm`
+/// rows.
+pub fn duplicates_query(uri: &str) -> String {
+    let subject = rdf::iri_ref(uri);
+    format!(
+        "SELECT DISTINCT ?other ?algorithm WHERE {{ \
+         {{ BIND({subject} AS ?file) }} UNION {{ {subject} <{NIE_IS_STORED_AS}> ?file }} \
+         ?file <{NFO_HAS_HASH}> ?hash . ?hash <{NFO_HASH_VALUE}> ?value . \
+         OPTIONAL {{ ?hash <{NFO_HASH_ALGORITHM}> ?algorithm }} \
+         ?other <{NFO_HAS_HASH}> ?otherHash . ?otherHash <{NFO_HASH_VALUE}> ?value . \
+         OPTIONAL {{ ?otherHash <{NFO_HASH_ALGORITHM}> ?otherAlgorithm }} \
+         FILTER(!BOUND(?algorithm) || !BOUND(?otherAlgorithm) || ?algorithm = ?otherAlgorithm) \
+         ?other a <{FILEDATAOBJECT}> . \
+         FILTER(?other != ?file && ?other != {subject}) \
+         }} ORDER BY ?other LIMIT {MAX_DUPLICATES}"
+    )
+}
+
+/// Queries the files whose `nfo:hasHash` has the same value as a hash of the subject `uri` (or
+/// of the file storing it), with the same algorithm where both are known.
+///
+/// Subjects without a hash have no duplicates.
+pub async fn duplicates(uri: &str) -> Result<Vec<Duplicate>, glib::Error> {
+    let conn = store::client()?;
+    let cursor = conn.query_async(&duplicates_query(uri)).await?;
+    let mut duplicates: Vec<Duplicate> = Vec::new();
+    while cursor.next_future().await? {
+        let uri = cursor.string(0).unwrap_or_default().to_string();
+        // A file matching by several hashes is listed once.
+        if duplicates.iter().any(|duplicate| duplicate.uri == uri) {
+            continue;
+        }
+        duplicates.push(Duplicate {
+            uri,
+            algorithm: cursor
+                .string(1)
+                .map(|algorithm| algorithm.to_string())
+                .filter(|algorithm| !algorithm.is_empty()),
+        });
+    }
+    Ok(duplicates)
+}
+
+/// Lists `duplicates` in `list` with their paths, replacing those shown before.
+///
+/// # Arguments
+/// * `list` - The list of the duplicates page of a subject tab.
+/// * `duplicates` - The duplicates of the subject (see [`duplicates`]).
+/// * `on_link` - Called with the URI of a duplicate that is activated.
+pub fn fill(list: &gtk::ListBox, duplicates: &[Duplicate], on_link: &LinkHandler) {
+    pages::clear_list(list);
+    for duplicate in duplicates {
+        let row = adw::ActionRow::builder()
+            .title(glib::markup_escape_text(&tab_title(&duplicate.uri)))
+            .subtitle(glib::markup_escape_text(&duplicate.location()))
+            .activatable(true)
+            .build();
+        if let Some(algorithm) = &duplicate.algorithm {
+            row.set_tooltip_text(Some(&format!("Same {algorithm} hash")));
+        }
+        row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+        let on_link = on_link.clone();
+        let uri = duplicate.uri.clone();
+        row.connect_activated(move |_| on_link(&uri));
+        list.append(&row);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duplicates_are_located_by_path() {
+        let duplicate = Duplicate {
+            uri: "file:///home/user/My%20Photo.jpg".to_string(),
+            algorithm: None,
+        };
+        assert_eq!(duplicate.location(), "/home/user/My Photo.jpg");
+        let duplicate = Duplicate {
+            uri: "urn:fileid:1234".to_string(),
+            algorithm: None,
+        };
+        assert_eq!(duplicate.location(), "urn:fileid:1234");
+    }
+}
@@ -0,0 +1,78 @@
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::rc::Rc;
+
+/// Identifies a listener registered with an [`EventBus`], for unsubscribing it later.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SubscriptionId(u64);
+
+/// Callback invoked with the URI of a changed subject.
+type Listener = Rc<dyn Fn(&str)>;
+
+/// Application-wide notifications that the metadata of a subject has changed, keyed by the
+/// subject's URI.
+///
+/// Whatever changes a subject (or finds out that it has changed) publishes its URI here, and
+/// every window refreshes the views showing it. This keeps all windows and tabs showing the same
+/// subject consistent, not just the one where the change was initiated.
+#[derive(Default)]
+pub struct EventBus {
+    listeners: RefCell<Vec<(SubscriptionId, Listener)>>,
+    next_id: Cell<u64>,
+}
+
+impl fmt::Debug for EventBus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventBus")
+            .field("listeners", &self.listeners.borrow().len())
+            .finish()
+    }
+}
+
+impl EventBus {
+    /// Registers `listener` to be called with the URI of every changed subject.
+    pub fn subscribe(&self, listener: impl Fn(&str) + 'static) -> SubscriptionId {
+        let id = SubscriptionId(self.next_id.get());
+        self.next_id.set(id.0 + 1);
+        self.listeners.borrow_mut().push((id, Rc::new(listener)));
+        id
+    }
+
+    /// Removes a listener registered with [`EventBus::subscribe`].
+    pub fn unsubscribe(&self, id: SubscriptionId) {
+        self.listeners.borrow_mut().retain(|(i, _)| *i != id);
+    }
+
+    /// Notifies all listeners that the subject `uri` has changed.
+    ///
+    /// Listeners may subscribe or unsubscribe while being notified; such changes take effect
+    /// from the next notification on.
+    pub fn publish(&self, uri: &str) {
+        let listeners: Vec<_> = self
+            .listeners
+            .borrow()
+            .iter()
+            .map(|(_, listener)| listener.clone())
+            .collect();
+        for listener in listeners {
+            listener(uri);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn publish_reaches_subscribers_until_unsubscribed() {
+        let bus = EventBus::default();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let seen_clone = seen.clone();
+        let id = bus.subscribe(move |uri| seen_clone.borrow_mut().push(uri.to_string()));
+        bus.publish("a");
+        bus.unsubscribe(id);
+        bus.publish("b");
+        assert_eq!(*seen.borrow(), ["a"]);
+    }
+}
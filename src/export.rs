@@ -0,0 +1,310 @@
+use crate::TableRow;
+use crate::settings;
+use clap::ValueEnum;
+use gio::prelude::*;
+use serde_json::json;
+
+/// The format the "Copy" button of subject windows puts the table in, also used by
+/// `--export-dir`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// Comma-separated values, see [`to_csv`].
+    #[default]
+    Csv,
+    /// A JSON document, see [`to_json`].
+    Json,
+    /// An HTML page, see [`to_html`].
+    Html,
+}
+
+impl ExportFormat {
+    /// All formats, in the order they are offered.
+    pub const ALL: [ExportFormat; 3] = [ExportFormat::Csv, ExportFormat::Json, ExportFormat::Html];
+
+    /// Returns the name of the format, as used in the preferences and as file extension.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Html => "html",
+        }
+    }
+
+    /// Returns the label of the format in the preferences window.
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Json => "JSON",
+            ExportFormat::Html => "HTML",
+        }
+    }
+
+    /// Returns the format named `name` (see [`ExportFormat::as_str`]), if there is one.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|format| format.as_str() == name)
+    }
+
+    /// Returns the format chosen in the preferences, or the default one.
+    pub fn from_settings() -> Self {
+        Self::from_name(&settings::preferences().string(settings::EXPORT_FORMAT))
+            .unwrap_or_default()
+    }
+}
+
+/// Serializes the metadata shown in a subject window in `format`.
+///
+/// # Arguments
+/// * `format` - The format to use.
+/// * `uri` - The URI of the subject the rows describe.
+/// * `is_file_data_object` - Whether the subject is an `nfo:FileDataObject`.
+/// * `rows` - The table rows, in display order.
+pub fn serialize(
+    format: ExportFormat,
+    uri: &str,
+    is_file_data_object: bool,
+    rows: &[TableRow],
+) -> String {
+    match format {
+        ExportFormat::Csv => to_csv(rows),
+        ExportFormat::Json => to_json(uri, is_file_data_object, rows),
+        ExportFormat::Html => to_html(uri, is_file_data_object, rows),
+    }
+}
+
+/// Serializes the metadata shown in a subject window as CSV, with a header row naming the
+/// columns. The last column holds the source of each value, as in [`to_json`].
+pub fn to_csv(rows: &[TableRow]) -> String {
+    let mut wtr = csv::WriterBuilder::new()
+        .has_headers(true)
+        .from_writer(vec![]);
+    let _ = wtr.write_record([
+        "Display Predicate",
+        "Native Predicate",
+        "Display Value",
+        "Native Value",
+        "Language",
+        "Source",
+    ]);
+    for r in rows {
+        let _ = wtr.write_record([
+            r.display_predicate.as_str(),
+            &r.native_predicate,
+            &r.display_value,
+            &r.native_value,
+            &r.language,
+            r.source.as_str(),
+        ]);
+    }
+    // The writer only produces the UTF-8 text it was given.
+    String::from_utf8(wtr.into_inner().unwrap_or_default()).unwrap_or_default()
+}
+
+/// Serializes the metadata shown in a subject window as a JSON document.
+///
+/// The document has the following shape:
+///
+/// ```json
+/// {
+///   "uri": "file:///home/user/photo.jpg",
+///   "file_data_object": true,
+///   "rows": [
+///     {
+///       "display_predicate": "File Name",
+///       "native_predicate": "http://tracker.api.gnome.org/ontology/v3/nfo#fileName",
+///       "display_value": "photo.jpg",
+///       "native_value": "photo.jpg",
+///       "language": "",
+///       "source": "indexed"
+///     }
+///   ]
+/// }
+/// ```
+///
+/// The `language` of a row is the language tag of a literal value, e.g. `de`, and empty for
+/// values without one. The `source` of a row is `indexed` for values read from the store,
+/// `interpreted` for values of the information element a file is interpreted as, and `derived`
+/// for values this application computed itself.
+///
+/// # Arguments
+/// * `uri` - The URI of the subject the rows describe.
+/// * `is_file_data_object` - Whether the subject is an `nfo:FileDataObject`.
+/// * `rows` - The table rows, in display order.
+pub fn to_json(uri: &str, is_file_data_object: bool, rows: &[TableRow]) -> String {
+    // Serializing a `serde_json::Value` cannot fail.
+    serde_json::to_string_pretty(&to_json_value(uri, is_file_data_object, rows)).unwrap_or_default()
+}
+
+/// Returns the JSON document of [`to_json`] as a value, e.g. to combine the documents of
+/// several subjects.
+pub fn to_json_value(uri: &str, is_file_data_object: bool, rows: &[TableRow]) -> serde_json::Value {
+    let rows: Vec<_> = rows
+        .iter()
+        .map(|r| {
+            json!({
+                "display_predicate": r.display_predicate,
+                "native_predicate": r.native_predicate,
+                "display_value": r.display_value,
+                "native_value": r.native_value,
+                "language": r.language,
+                "source": r.source.as_str(),
+            })
+        })
+        .collect();
+    json!({
+        "uri": uri,
+        "file_data_object": is_file_data_object,
+        "rows": rows,
+    })
+}
+
+/// Renders the metadata shown in a subject window as a self-contained HTML page.
+///
+/// The page contains a two-column table of display predicates and display values; the native
+/// predicate and value are available as `title` attributes (i.e., as tooltips in a browser).
+/// Each table row has the source of its value as `class` (see [`to_json`]), and values in a
+/// given language are marked with a `lang` attribute.
+///
+/// # Arguments
+/// * `uri` - The URI of the subject the rows describe.
+/// * `is_file_data_object` - Whether the subject is an `nfo:FileDataObject`.
+/// * `rows` - The table rows, in display order.
+pub fn to_html(uri: &str, is_file_data_object: bool, rows: &[TableRow]) -> String {
+    let heading = if is_file_data_object {
+        "File Information"
+    } else {
+        "Node Information"
+    };
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{heading}: {uri}</title>\n</head>\n<body>\n<h1>{heading}</h1>\n<table>\n",
+        uri = html_escape(uri)
+    );
+    for r in rows {
+        let lang = if r.language.is_empty() {
+            String::new()
+        } else {
+            format!(" lang=\"{}\"", html_escape(&r.language))
+        };
+        html.push_str(&format!(
+            "<tr class=\"{}\"><th title=\"{}\">{}</th><td{lang} title=\"{}\">{}</td></tr>\n",
+            r.source.as_str(),
+            html_escape(&r.native_predicate),
+            html_escape(&r.display_predicate),
+            html_escape(&r.native_value),
+            html_escape(&r.display_value),
+        ));
+    }
+    html.push_str("</table>\n</body>\n</html>\n");
+    html
+}
+
+/// Escapes the characters that are significant in HTML text and attribute values.
+fn html_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ValueSource;
+
+    #[test]
+    fn to_json_contains_rows() {
+        let rows = vec![TableRow {
+            display_predicate: "File Name".to_string(),
+            native_predicate: "nfo:fileName".to_string(),
+            display_value: "a \"quoted\" name".to_string(),
+            native_value: "a \"quoted\" name".to_string(),
+            language: String::new(),
+            source: ValueSource::Indexed,
+        }];
+        let parsed: serde_json::Value =
+            serde_json::from_str(&to_json("file:///tmp/a", true, &rows)).unwrap();
+        assert_eq!(parsed["uri"], "file:///tmp/a");
+        assert_eq!(parsed["file_data_object"], true);
+        assert_eq!(parsed["rows"][0]["native_value"], "a \"quoted\" name");
+        assert_eq!(parsed["rows"][0]["source"], "indexed");
+    }
+
+    #[test]
+    fn export_formats_by_name() {
+        for format in ExportFormat::ALL {
+            assert_eq!(ExportFormat::from_name(format.as_str()), Some(format));
+        }
+        assert_eq!(ExportFormat::from_name("xml"), None);
+        let csv = serialize(ExportFormat::Csv, "urn:x", false, &[]);
+        assert_eq!(
+            csv,
+            "Display Predicate,Native Predicate,Display Value,Native Value,Language,Source\n"
+        );
+    }
+
+    #[test]
+    fn to_csv_contains_sources() {
+        let rows = vec![TableRow {
+            display_predicate: "Size".to_string(),
+            native_predicate: "nfo:fileSize".to_string(),
+            display_value: "1 KB".to_string(),
+            native_value: "1024".to_string(),
+            language: String::new(),
+            source: ValueSource::Derived,
+        }];
+        assert_eq!(
+            to_csv(&rows),
+            "Display Predicate,Native Predicate,Display Value,Native Value,Language,Source\n\
+             Size,nfo:fileSize,1 KB,1024,,derived\n"
+        );
+    }
+
+    #[test]
+    fn to_json_empty_rows() {
+        let parsed: serde_json::Value =
+            serde_json::from_str(&to_json("urn:x", false, &[])).unwrap();
+        assert_eq!(parsed["rows"].as_array().map(Vec::len), Some(0));
+    }
+
+    #[test]
+    fn to_html_escapes_values() {
+        let rows = vec![TableRow {
+            display_predicate: "Title".to_string(),
+            native_predicate: "nie:title".to_string(),
+            display_value: "<b>&</b>".to_string(),
+            native_value: "<b>&</b>".to_string(),
+            language: String::new(),
+            source: ValueSource::Derived,
+        }];
+        let html = to_html("urn:x", false, &rows);
+        assert!(
+            html.contains("<td title=\"&lt;b&gt;&amp;&lt;/b&gt;\">&lt;b&gt;&amp;&lt;/b&gt;</td>")
+        );
+        assert!(html.contains("<h1>Node Information</h1>"));
+        assert!(html.contains("<tr class=\"derived\">"));
+    }
+
+    #[test]
+    fn exports_keep_language_tags() {
+        let rows = vec![TableRow {
+            display_predicate: "Title".to_string(),
+            native_predicate: "nie:title".to_string(),
+            display_value: "Sommer".to_string(),
+            native_value: "Sommer".to_string(),
+            language: "de".to_string(),
+            source: ValueSource::Indexed,
+        }];
+        let parsed: serde_json::Value =
+            serde_json::from_str(&to_json("urn:x", false, &rows)).unwrap();
+        assert_eq!(parsed["rows"][0]["language"], "de");
+        assert!(to_html("urn:x", false, &rows).contains("<td lang=\"de\" title=\"Sommer\">"));
+    }
+}
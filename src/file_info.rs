@@ -0,0 +1,166 @@
+use crate::context::Context;
+use crate::dnd;
+use crate::geometry;
+use crate::search;
+use crate::{METADATA_WINDOW_CLASS, add_common_actions, announce};
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use glib::subclass::Signal;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::OnceLock;
+
+mod imp {
+    use super::*;
+
+    /// The state of a [`FileInfoWindow`](super::FileInfoWindow). The widgets are laid out by
+    /// the composite template `resources/file-info-window.ui`; the tabs are added by
+    /// `add_subject_tab`.
+    #[derive(Debug, Default, gtk::CompositeTemplate, glib::Properties)]
+    #[template(file = "../resources/file-info-window.ui")]
+    #[properties(wrapper_type = super::FileInfoWindow)]
+    pub struct FileInfoWindow {
+        /// The subject of the selected tab.
+        #[property(get, set)]
+        pub uri: RefCell<String>,
+        /// Print diagnostic information about loading subjects to stderr.
+        #[property(get, set)]
+        pub debug: Cell<bool>,
+
+        #[template_child]
+        pub header: TemplateChild<adw::HeaderBar>,
+        #[template_child]
+        pub header_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub menu_button: TemplateChild<gtk::MenuButton>,
+        #[template_child]
+        pub bookmark_button: TemplateChild<gtk::ToggleButton>,
+        #[template_child]
+        pub location_bar: TemplateChild<gtk::SearchBar>,
+        #[template_child]
+        pub tab_view: TemplateChild<adw::TabView>,
+        #[template_child]
+        pub bottom_box: TemplateChild<gtk::Box>,
+        #[template_child]
+        pub handler_box: TemplateChild<gtk::Box>,
+        #[template_child]
+        pub handler_icon: TemplateChild<gtk::Image>,
+        #[template_child]
+        pub handler_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub show_in_files_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub backlinks_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub copy_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub open_button: TemplateChild<gtk::Button>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for FileInfoWindow {
+        const NAME: &'static str = "FileInfoWindow";
+        type Type = super::FileInfoWindow;
+        type ParentType = adw::ApplicationWindow;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            announce::TemplateCallbacks::bind_template_callbacks(klass);
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for FileInfoWindow {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![
+                    // A subject chosen in the search entry or dropped onto the window is to be
+                    // opened.
+                    Signal::builder("open-subject")
+                        .param_types([String::static_type()])
+                        .build(),
+                    // A subject has been (re)loaded into one of the tabs.
+                    Signal::builder("loaded")
+                        .param_types([String::static_type()])
+                        .build(),
+                ]
+            })
+        }
+
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            // Add common actions (i.e., copy to clipboard, open URI) for context menus in this
+            // window.
+            add_common_actions(obj.upcast_ref());
+            // Subject windows get the styles of all metadata windows and their own (see
+            // `install_styles`).
+            obj.style_context().add_class(METADATA_WINDOW_CLASS);
+            obj.style_context().add_class("subject");
+            geometry::remember(&*obj, "subject");
+
+            // The search entry asks for the chosen file to be opened.
+            let weak = obj.downgrade();
+            self.header.pack_end(&search::entry(move |target| {
+                if let Some(window) = weak.upgrade() {
+                    window.emit_by_name::<()>("open-subject", &[&target]);
+                }
+            }));
+
+            // So do files dropped onto the window.
+            let weak = obj.downgrade();
+            dnd::accept(&*obj, move |target| {
+                if let Some(window) = weak.upgrade() {
+                    window.emit_by_name::<()>("open-subject", &[&target]);
+                }
+            });
+        }
+    }
+
+    impl WidgetImpl for FileInfoWindow {}
+    impl WindowImpl for FileInfoWindow {}
+    impl ApplicationWindowImpl for FileInfoWindow {}
+    impl AdwApplicationWindowImpl for FileInfoWindow {}
+}
+
+glib::wrapper! {
+    /// A window showing the properties, backlinks and related items of subjects, one subject
+    /// per tab, along with actions on the subject of the selected tab.
+    pub struct FileInfoWindow(ObjectSubclass<imp::FileInfoWindow>)
+        @extends adw::ApplicationWindow, gtk::ApplicationWindow, gtk::Window, gtk::Widget,
+        @implements gio::ActionGroup, gio::ActionMap, gtk::Accessible, gtk::Buildable,
+            gtk::ConstraintTarget, gtk::Native, gtk::Root, gtk::ShortcutManager;
+}
+
+impl FileInfoWindow {
+    /// Creates an empty subject window; its tabs are added by `add_subject_tab`.
+    ///
+    /// # Arguments
+    /// * `app` - The application instance.
+    /// * `ctx` - Shared application settings; `ctx.debug` sets the `debug` property.
+    pub fn new(app: &adw::Application, ctx: &Rc<Context>) -> Self {
+        glib::Object::builder()
+            .property("application", app)
+            .property("debug", ctx.debug)
+            .build()
+    }
+
+    /// Calls `f` with the URI of each subject chosen in the search entry or dropped onto the
+    /// window.
+    pub fn connect_open_subject(
+        &self,
+        f: impl Fn(&Self, String) + 'static,
+    ) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "open-subject",
+            false,
+            glib::closure_local!(move |window: &Self, target: String| f(window, target)),
+        )
+    }
+}
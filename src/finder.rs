@@ -0,0 +1,1242 @@
+use crate::announce;
+use crate::collections;
+use crate::context::Context;
+use crate::dnd;
+use crate::geometry;
+use crate::memory;
+use crate::prefixes;
+use crate::rdf;
+use crate::store;
+use crate::{ellipsize, looks_like_uri, open_subject_window, tab_title};
+use adw::prelude::*;
+use serde_json::json;
+use std::cell::RefCell;
+use std::rc::Rc;
+use tracker::prelude::*;
+
+const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+const RDF_PROPERTY: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#Property";
+const XSD_DATE_TIME: &str = "http://www.w3.org/2001/XMLSchema#dateTime";
+
+/// Largest number of matches listed.
+const MAX_RESULTS: usize = 200;
+
+/// Stands for the subject the query builder was opened from, in entered values: e.g. the
+/// condition "nie:isPartOf is ?subject" finds the files in the folder being looked at.
+pub const SUBJECT_TEMPLATE: &str = "?subject";
+
+/// How the values of a property are compared with the value the user entered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operator {
+    /// The value is exactly the entered one (compared as text, so it also works for IRIs).
+    Is,
+    /// The value is anything but the entered one, compared as text.
+    IsNot,
+    /// The value contains the entered text, ignoring case.
+    Contains,
+    /// The value is greater than the entered number, or later than the entered date.
+    Above,
+    /// The value is less than the entered number, or earlier than the entered date.
+    Below,
+    /// The value is at least the entered number or date.
+    AtLeast,
+    /// The value is at most the entered number or date.
+    AtMost,
+    /// The value lies between the two entered numbers or dates, inclusively.
+    Between,
+}
+
+impl Operator {
+    /// All operators, in the order they are offered.
+    pub const ALL: [Operator; 8] = [
+        Operator::Is,
+        Operator::IsNot,
+        Operator::Contains,
+        Operator::Above,
+        Operator::Below,
+        Operator::AtLeast,
+        Operator::AtMost,
+        Operator::Between,
+    ];
+
+    /// Returns the label of the operator in the user interface.
+    pub fn label(self) -> &'static str {
+        match self {
+            Operator::Is => "is",
+            Operator::IsNot => "is not",
+            Operator::Contains => "contains",
+            Operator::Above => "is more than",
+            Operator::Below => "is less than",
+            Operator::AtLeast => "is at least",
+            Operator::AtMost => "is at most",
+            Operator::Between => "is between",
+        }
+    }
+
+    /// Returns the name under which the operator is saved in collections.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Operator::Is => "is",
+            Operator::IsNot => "is-not",
+            Operator::Contains => "contains",
+            Operator::Above => "above",
+            Operator::Below => "below",
+            Operator::AtLeast => "at-least",
+            Operator::AtMost => "at-most",
+            Operator::Between => "between",
+        }
+    }
+
+    /// Parses an operator name written by [`Operator::as_str`].
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|op| op.as_str() == name)
+    }
+}
+
+/// Whether all or any of a number of conditions (or groups of them) must hold.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Join {
+    All,
+    Any,
+}
+
+impl Join {
+    /// Both joins, in the order they are offered.
+    pub const ALL: [Join; 2] = [Join::All, Join::Any];
+
+    /// Returns the label of the join in the user interface, which is also the name under
+    /// which it is saved in collections.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Join::All => "all",
+            Join::Any => "any",
+        }
+    }
+
+    /// Parses a join name written by [`Join::as_str`].
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|join| join.as_str() == name)
+    }
+}
+
+/// A condition on the values of one property.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Condition {
+    /// The IRI of the property.
+    pub predicate: String,
+    pub operator: Operator,
+    /// The entered value; the lower bound for `Operator::Between`.
+    pub value: String,
+    /// The upper bound for `Operator::Between`; unused otherwise.
+    pub upper: String,
+}
+
+impl Condition {
+    /// Returns the graph pattern matching the subjects that fulfil the condition, using the
+    /// variable `?v<index>` for the compared value.
+    pub fn pattern(&self, index: usize) -> String {
+        let var = format!("?v{index}");
+        format!(
+            "?subject {p} {var} . FILTER ({filter})",
+            p = rdf::iri_ref(&self.predicate),
+            filter = filter_expression(self, &var)
+        )
+    }
+
+    /// Returns the condition as a JSON object, as saved in collections.
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "predicate": self.predicate,
+            "operator": self.operator.as_str(),
+            "value": self.value,
+            "upper": self.upper,
+        })
+    }
+
+    /// Parses a condition written by [`Condition::to_json`].
+    pub fn from_json(value: &serde_json::Value) -> Option<Self> {
+        let text = |key: &str| value.get(key).and_then(|v| v.as_str()).map(String::from);
+        Some(Condition {
+            predicate: text("predicate")?,
+            operator: Operator::from_name(&text("operator")?)?,
+            value: text("value").unwrap_or_default(),
+            upper: text("upper").unwrap_or_default(),
+        })
+    }
+}
+
+/// A group of conditions, all or any of which must hold.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Group {
+    pub join: Join,
+    pub conditions: Vec<Condition>,
+}
+
+impl Group {
+    /// Returns the group as a JSON object, as saved in collections.
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "join": self.join.as_str(),
+            "conditions": self.conditions.iter().map(Condition::to_json).collect::<Vec<_>>(),
+        })
+    }
+
+    /// Parses a group written by [`Group::to_json`].
+    pub fn from_json(value: &serde_json::Value) -> Option<Self> {
+        Some(Group {
+            join: Join::from_name(value.get("join")?.as_str()?)?,
+            conditions: value
+                .get("conditions")?
+                .as_array()?
+                .iter()
+                .map(Condition::from_json)
+                .collect::<Option<_>>()?,
+        })
+    }
+}
+
+/// A query built from groups of conditions, all or any of which must hold.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Query {
+    pub join: Join,
+    pub groups: Vec<Group>,
+}
+
+impl Query {
+    /// Returns whether the query has no conditions at all.
+    pub fn is_empty(&self) -> bool {
+        self.groups.iter().all(|group| group.conditions.is_empty())
+    }
+
+    /// Returns the SPARQL query for the resources matching the query, as `?subject` rows.
+    ///
+    /// Every condition compares its own variable, so conditions on the same property may be
+    /// fulfilled by different values (e.g. a keyword "holiday" and a keyword "2024"). The
+    /// conditions of a group that must all hold are simply joined; alternatives become a
+    /// `UNION`. Groups without conditions are left out.
+    pub fn to_sparql(&self) -> String {
+        format!(
+            "{}\nORDER BY ?subject LIMIT {}",
+            self.select(),
+            memory::limit(MAX_RESULTS)
+        )
+    }
+
+    /// Returns the unordered and unlimited `SELECT` of [`Query::to_sparql`].
+    fn select(&self) -> String {
+        let mut index = 0;
+        let mut blocks = Vec::new();
+        for group in self.groups.iter().filter(|g| !g.conditions.is_empty()) {
+            let patterns: Vec<String> = group
+                .conditions
+                .iter()
+                .map(|condition| {
+                    index += 1;
+                    format!("    {{ {} }}", condition.pattern(index - 1))
+                })
+                .collect();
+            blocks.push(format!(
+                "  {{\n{}\n  }}",
+                patterns.join(&separator(group.join, "    "))
+            ));
+        }
+        format!(
+            "SELECT DISTINCT ?subject WHERE {{\n{}\n}}",
+            blocks.join(&separator(self.join, "  "))
+        )
+    }
+
+    /// Returns whether any entered value refers to [`SUBJECT_TEMPLATE`].
+    pub fn uses_subject(&self) -> bool {
+        self.groups
+            .iter()
+            .flat_map(|group| &group.conditions)
+            .any(|c| c.value.contains(SUBJECT_TEMPLATE) || c.upper.contains(SUBJECT_TEMPLATE))
+    }
+
+    /// Returns the query with [`SUBJECT_TEMPLATE`] replaced by `subject` in the entered values.
+    pub fn with_subject(&self, subject: &str) -> Query {
+        let mut query = self.clone();
+        for condition in query
+            .groups
+            .iter_mut()
+            .flat_map(|group| &mut group.conditions)
+        {
+            condition.value = condition.value.replace(SUBJECT_TEMPLATE, subject);
+            condition.upper = condition.upper.replace(SUBJECT_TEMPLATE, subject);
+        }
+        query
+    }
+
+    /// Returns a short description of the query for menus, such as
+    /// `nfo:fileSize is more than 1000, nie:title contains holiday`.
+    pub fn describe(&self) -> String {
+        let groups: Vec<String> = self
+            .groups
+            .iter()
+            .filter(|group| !group.conditions.is_empty())
+            .map(|group| {
+                let conditions: Vec<String> = group
+                    .conditions
+                    .iter()
+                    .map(|c| {
+                        let property =
+                            prefixes::compact(&c.predicate).unwrap_or_else(|| c.predicate.clone());
+                        match c.operator {
+                            Operator::Between => format!(
+                                "{property} {} {} and {}",
+                                c.operator.label(),
+                                c.value.trim(),
+                                c.upper.trim()
+                            ),
+                            op => format!("{property} {} {}", op.label(), c.value.trim()),
+                        }
+                    })
+                    .collect();
+                conditions.join(describe_separator(group.join))
+            })
+            .collect();
+        if groups.len() > 1 {
+            let groups: Vec<String> = groups.iter().map(|g| format!("({g})")).collect();
+            groups.join(describe_separator(self.join))
+        } else {
+            groups.concat()
+        }
+    }
+
+    /// Returns the properties the conditions compare, each once, in order of appearance.
+    pub fn predicates(&self) -> Vec<String> {
+        let mut predicates: Vec<String> = Vec::new();
+        for condition in self.groups.iter().flat_map(|group| &group.conditions) {
+            if !predicates.contains(&condition.predicate) {
+                predicates.push(condition.predicate.clone());
+            }
+        }
+        predicates
+    }
+
+    /// Returns the SPARQL query for a report on the resources matching the query, as
+    /// `?subject ?p ?value ?dtype` rows holding the values of the compared properties (see
+    /// [`Query::predicates`]).
+    ///
+    /// Unlike [`Query::to_sparql`], the number of matches is not limited.
+    pub fn report_sparql(&self) -> String {
+        let matches = self.select();
+        let predicates: Vec<String> = self.predicates().iter().map(|p| rdf::iri_ref(p)).collect();
+        format!(
+            "SELECT ?subject ?p ?value (DATATYPE(?value) AS ?dtype) WHERE {{\n{{ {matches} }}\n\
+             OPTIONAL {{ VALUES ?p {{ {} }} ?subject ?p ?value }}\n}}\nORDER BY ?subject ?p",
+            predicates.join(" ")
+        )
+    }
+
+    /// Returns the query as a JSON object, as saved in collections.
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "join": self.join.as_str(),
+            "groups": self.groups.iter().map(Group::to_json).collect::<Vec<_>>(),
+        })
+    }
+
+    /// Parses a query written by [`Query::to_json`].
+    pub fn from_json(value: &serde_json::Value) -> Option<Self> {
+        Some(Query {
+            join: Join::from_name(value.get("join")?.as_str()?)?,
+            groups: value
+                .get("groups")?
+                .as_array()?
+                .iter()
+                .map(Group::from_json)
+                .collect::<Option<_>>()?,
+        })
+    }
+}
+
+/// Returns what goes between the descriptions of conditions or groups joined by `join`.
+fn describe_separator(join: Join) -> &'static str {
+    match join {
+        Join::All => ", ",
+        Join::Any => " or ",
+    }
+}
+
+/// Returns what goes between the patterns of a block joined by `join`, indented by `indent`.
+fn separator(join: Join, indent: &str) -> String {
+    match join {
+        Join::All => "\n".to_string(),
+        Join::Any => format!("\n{indent}UNION\n"),
+    }
+}
+
+/// Writes an entered value as a SPARQL literal: numbers as numeric literals, dates (e.g.
+/// `2024-05-01` or a full ISO 8601 date-time) as `xsd:dateTime` literals and anything else as
+/// a string.
+pub fn literal(value: &str) -> String {
+    let value = value.trim();
+    // Rust also parses words such as "inf" and "NaN", which SPARQL does not have.
+    let numeric = value
+        .chars()
+        .all(|c| c.is_ascii_digit() || "+-.eE".contains(c));
+    if numeric && value.parse::<f64>().is_ok() {
+        return value.to_string();
+    }
+    if let Some(date_time) = parse_date(value) {
+        return format!("\"{date_time}\"^^<{XSD_DATE_TIME}>");
+    }
+    format!("\"{}\"", rdf::escape_string(value))
+}
+
+/// Parses a date (taken as midnight UTC) or a date-time in ISO 8601 format.
+///
+/// # Returns
+/// * The date-time in the ISO 8601 form the store uses, or `None` if `value` is not a date.
+fn parse_date(value: &str) -> Option<String> {
+    let utc = glib::TimeZone::utc();
+    let date_time = glib::DateTime::from_iso8601(value, Some(&utc))
+        .or_else(|_| glib::DateTime::from_iso8601(&format!("{value}T00:00:00"), Some(&utc)))
+        .ok()?;
+    date_time
+        .format("%Y-%m-%dT%H:%M:%SZ")
+        .ok()
+        .map(String::from)
+}
+
+/// Returns the SPARQL filter expression testing the variable `var` against `condition`.
+pub fn filter_expression(condition: &Condition, var: &str) -> String {
+    match condition.operator {
+        Operator::Is => format!(
+            "STR({var}) = \"{}\"",
+            rdf::escape_string(condition.value.trim())
+        ),
+        Operator::IsNot => format!(
+            "STR({var}) != \"{}\"",
+            rdf::escape_string(condition.value.trim())
+        ),
+        Operator::Contains => format!(
+            "CONTAINS(LCASE(STR({var})), \"{}\")",
+            rdf::escape_string(&condition.value.trim().to_lowercase())
+        ),
+        Operator::Above => format!("{var} > {}", literal(&condition.value)),
+        Operator::Below => format!("{var} < {}", literal(&condition.value)),
+        Operator::AtLeast => format!("{var} >= {}", literal(&condition.value)),
+        Operator::AtMost => format!("{var} <= {}", literal(&condition.value)),
+        Operator::Between => format!(
+            "{var} >= {} && {var} <= {}",
+            literal(&condition.value),
+            literal(&condition.upper)
+        ),
+    }
+}
+
+/// Turns the text entered for a property into its IRI. Prefixed names such as `nfo:fileName`
+/// are expanded (see `prefixes::expand`); full IRIs are taken as they are.
+pub fn resolve_predicate(text: &str) -> Option<String> {
+    let text = text.trim();
+    prefixes::expand(text).or_else(|| looks_like_uri(text).then(|| text.to_string()))
+}
+
+/// Runs `query` and returns the matching subjects.
+pub async fn run_query(query: &Query) -> Result<Vec<String>, glib::Error> {
+    let conn = store::client()?;
+    let cursor = conn.query_async(&query.to_sparql()).await?;
+    let mut subjects = Vec::new();
+    while cursor.next_future().await? {
+        subjects.push(cursor.string(0).unwrap_or_default().to_string());
+    }
+    Ok(subjects)
+}
+
+/// Fills `model` with the properties defined in the ontology, as prefixed names where possible,
+/// for completing the property entries.
+async fn load_properties(model: &gtk::ListStore) {
+    prefixes::load().await;
+    let sparql = format!("SELECT ?p WHERE {{ ?p <{RDF_TYPE}> <{RDF_PROPERTY}> }} ORDER BY ?p");
+    let Ok(conn) = store::client() else {
+        return;
+    };
+    let Ok(cursor) = conn.query_async(&sparql).await else {
+        return;
+    };
+    while cursor.next_future().await.unwrap_or(false) {
+        let property = cursor.string(0).unwrap_or_default().to_string();
+        let name = prefixes::compact(&property).unwrap_or(property);
+        model.insert_with_values(None, &[(0, &name)]);
+    }
+}
+
+/// The widgets editing one condition of the query builder.
+#[derive(Clone)]
+struct ConditionRow {
+    container: gtk::Box,
+    property: gtk::Entry,
+    operator: gtk::DropDown,
+    value: gtk::Entry,
+    upper: gtk::Entry,
+}
+
+impl ConditionRow {
+    /// Reads the condition from the widgets.
+    ///
+    /// # Returns
+    /// * The condition, `None` if the row has been left empty, or an error message if the
+    ///   property cannot be resolved.
+    fn condition(&self) -> Result<Option<Condition>, String> {
+        let property = self.property.text();
+        if property.trim().is_empty() && self.value.text().trim().is_empty() {
+            return Ok(None);
+        }
+        let predicate = resolve_predicate(&property).ok_or_else(|| {
+            format!(
+                "Enter “{}” as a prefixed name (e.g. nfo:fileSize) or an IRI.",
+                property.trim()
+            )
+        })?;
+        Ok(Some(Condition {
+            predicate,
+            operator: Operator::ALL[self.operator.selected() as usize % Operator::ALL.len()],
+            value: self.value.text().to_string(),
+            upper: self.upper.text().to_string(),
+        }))
+    }
+}
+
+/// The widgets editing one group of conditions.
+#[derive(Clone)]
+struct GroupBox {
+    frame: gtk::Frame,
+    join: gtk::DropDown,
+    rows_box: gtk::Box,
+    rows: Rc<RefCell<Vec<ConditionRow>>>,
+}
+
+/// The state of a query builder window.
+#[derive(Clone)]
+struct Builder {
+    /// Whether all or any of the groups must match.
+    join: gtk::DropDown,
+    groups_box: gtk::Box,
+    groups: Rc<RefCell<Vec<GroupBox>>>,
+    /// The property names offered for completion.
+    properties: gtk::ListStore,
+    /// The SPARQL preview.
+    preview: gtk::TextBuffer,
+    /// The subject the window was opened from, which `?subject` in values stands for.
+    subject: Option<String>,
+}
+
+impl Builder {
+    /// Reads the query from the widgets, skipping empty condition rows.
+    ///
+    /// # Returns
+    /// * The query, or an error message if a property cannot be resolved or there are no
+    ///   conditions at all.
+    fn query(&self) -> Result<Query, String> {
+        let mut groups = Vec::new();
+        for group in self.groups.borrow().iter() {
+            let mut conditions = Vec::new();
+            for row in group.rows.borrow().iter() {
+                conditions.extend(row.condition()?);
+            }
+            groups.push(Group {
+                join: selected_join(&group.join),
+                conditions,
+            });
+        }
+        let query = Query {
+            join: selected_join(&self.join),
+            groups,
+        };
+        if query.is_empty() {
+            return Err("Add a condition to build a query.".to_string());
+        }
+        Ok(query)
+    }
+
+    /// Reads the query from the widgets like [`Builder::query`], with `?subject` replaced by
+    /// the subject the window was opened from, ready to be run.
+    ///
+    /// # Returns
+    /// * The query, or an error message if it cannot be read or refers to `?subject` while
+    ///   there is no subject.
+    fn resolved_query(&self) -> Result<Query, String> {
+        let query = self.query()?;
+        if !query.uses_subject() {
+            return Ok(query);
+        }
+        match &self.subject {
+            Some(subject) => Ok(query.with_subject(subject)),
+            None => Err(format!(
+                "There is no subject for {SUBJECT_TEMPLATE} to stand for."
+            )),
+        }
+    }
+
+    /// Shows the SPARQL of the current query in the preview, or why there is none.
+    fn update_preview(&self) {
+        let text = match self.resolved_query() {
+            Ok(query) => query.to_sparql(),
+            Err(err) => format!("# {err}"),
+        };
+        self.preview.set_text(&text);
+    }
+
+    /// Replaces the groups shown with those of `query`.
+    fn set_query(&self, query: &Query) {
+        let groups: Vec<GroupBox> = self.groups.take();
+        for group in groups {
+            self.groups_box.remove(&group.frame);
+        }
+        self.join
+            .set_selected(Join::ALL.iter().position(|j| *j == query.join).unwrap_or(0) as u32);
+        for group in &query.groups {
+            self.add_group(Some(group));
+        }
+        self.update_preview();
+    }
+
+    /// Adds a group, with the conditions of `group` or else one empty condition row.
+    fn add_group(&self, group: Option<&Group>) {
+        let join = gtk::DropDown::from_strings(&Join::ALL.map(Join::as_str));
+        if let Some(group) = group {
+            join.set_selected(Join::ALL.iter().position(|j| *j == group.join).unwrap_or(0) as u32);
+        }
+        let remove_button = gtk::Button::from_icon_name("user-trash-symbolic");
+        remove_button.set_tooltip_text(Some("Remove Group"));
+        remove_button.style_context().add_class("flat");
+        let spacer = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+        spacer.set_hexpand(true);
+        let header = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        header.append(&gtk::Label::new(Some("Match")));
+        header.append(&join);
+        header.append(&gtk::Label::new(Some("of these conditions")));
+        header.append(&spacer);
+        header.append(&remove_button);
+
+        let rows_box = gtk::Box::new(gtk::Orientation::Vertical, 6);
+        let add_button = gtk::Button::with_label("Add Condition");
+        add_button.set_halign(gtk::Align::Start);
+        add_button.style_context().add_class("flat");
+
+        let content = gtk::Box::new(gtk::Orientation::Vertical, 6);
+        content.set_margin_start(6);
+        content.set_margin_end(6);
+        content.set_margin_top(6);
+        content.set_margin_bottom(6);
+        content.append(&header);
+        content.append(&rows_box);
+        content.append(&add_button);
+        let frame = gtk::Frame::new(None);
+        frame.set_child(Some(&content));
+        self.groups_box.append(&frame);
+
+        let group_box = GroupBox {
+            frame,
+            join,
+            rows_box,
+            rows: Rc::default(),
+        };
+        match group {
+            Some(group) if !group.conditions.is_empty() => {
+                for condition in &group.conditions {
+                    self.add_condition(&group_box, Some(condition));
+                }
+            }
+            _ => self.add_condition(&group_box, None),
+        }
+        self.groups.borrow_mut().push(group_box.clone());
+
+        let builder = self.clone();
+        group_box
+            .join
+            .connect_selected_notify(move |_| builder.update_preview());
+        let builder = self.clone();
+        let group_clone = group_box.clone();
+        add_button.connect_clicked(move |_| builder.add_condition(&group_clone, None));
+        let builder = self.clone();
+        remove_button.connect_clicked(move |_| {
+            builder
+                .groups
+                .borrow_mut()
+                .retain(|g| g.frame != group_box.frame);
+            builder.groups_box.remove(&group_box.frame);
+            builder.update_preview();
+        });
+        self.update_preview();
+    }
+
+    /// Adds a condition row to `group`, filled in from `condition` if given.
+    fn add_condition(&self, group: &GroupBox, condition: Option<&Condition>) {
+        // Each entry needs a completion of its own; they share the list of properties.
+        let completion = gtk::EntryCompletion::new();
+        completion.set_model(Some(&self.properties));
+        completion.set_text_column(0);
+        completion.set_minimum_key_length(2);
+        let property = gtk::Entry::builder()
+            .placeholder_text("Property, e.g. nfo:fileSize")
+            .completion(&completion)
+            .hexpand(true)
+            .build();
+        let labels: Vec<&str> = Operator::ALL.iter().map(|op| op.label()).collect();
+        let operator = gtk::DropDown::from_strings(&labels);
+        let value = gtk::Entry::builder()
+            .placeholder_text("Value, number, date or ?subject")
+            .activates_default(true)
+            .hexpand(true)
+            .build();
+        let upper = gtk::Entry::builder()
+            .placeholder_text("Upper bound")
+            .activates_default(true)
+            .hexpand(true)
+            .visible(false)
+            .build();
+        if let Some(condition) = condition {
+            property.set_text(
+                &prefixes::compact(&condition.predicate)
+                    .unwrap_or_else(|| condition.predicate.clone()),
+            );
+            let position = Operator::ALL
+                .iter()
+                .position(|op| *op == condition.operator);
+            operator.set_selected(position.unwrap_or(0) as u32);
+            value.set_text(&condition.value);
+            upper.set_text(&condition.upper);
+            upper.set_visible(condition.operator == Operator::Between);
+        }
+        let remove_button = gtk::Button::from_icon_name("list-remove-symbolic");
+        remove_button.set_tooltip_text(Some("Remove Condition"));
+        remove_button.style_context().add_class("flat");
+
+        let container = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        container.append(&property);
+        container.append(&operator);
+        container.append(&value);
+        container.append(&upper);
+        container.append(&remove_button);
+        group.rows_box.append(&container);
+        let row = ConditionRow {
+            container,
+            property,
+            operator,
+            value,
+            upper,
+        };
+        group.rows.borrow_mut().push(row.clone());
+
+        // Keep the preview in step with every edit.
+        for entry in [&row.property, &row.value, &row.upper] {
+            let builder = self.clone();
+            entry.connect_changed(move |_| builder.update_preview());
+        }
+        let builder = self.clone();
+        let upper_clone = row.upper.clone();
+        row.operator.connect_selected_notify(move |dropdown| {
+            upper_clone.set_visible(
+                Operator::ALL.get(dropdown.selected() as usize) == Some(&Operator::Between),
+            );
+            builder.update_preview();
+        });
+        let builder = self.clone();
+        let group = group.clone();
+        remove_button.connect_clicked(move |_| {
+            group
+                .rows
+                .borrow_mut()
+                .retain(|r| r.container != row.container);
+            group.rows_box.remove(&row.container);
+            builder.update_preview();
+        });
+        self.update_preview();
+    }
+}
+
+/// Returns the join selected in `dropdown`.
+fn selected_join(dropdown: &gtk::DropDown) -> Join {
+    Join::ALL[dropdown.selected() as usize % Join::ALL.len()]
+}
+
+/// Returns the menu loading the saved collections into the builder, one item per collection.
+fn collections_menu() -> gio::Menu {
+    let menu = gio::Menu::new();
+    for collection in collections::load() {
+        let item = gio::MenuItem::new(Some(&collection.name), None);
+        item.set_action_and_target_value(
+            Some("win.load-collection"),
+            Some(&collection.name.to_variant()),
+        );
+        menu.append_item(&item);
+    }
+    menu
+}
+
+/// Returns the menu loading the queries run before into the builder, the most recent first.
+/// Each item refers to its query by its position in the history.
+fn history_menu() -> gio::Menu {
+    let menu = gio::Menu::new();
+    for (index, query) in collections::history().iter().enumerate() {
+        let item = gio::MenuItem::new(Some(&ellipsize(&query.describe(), 60)), None);
+        item.set_action_and_target_value(
+            Some("win.load-history"),
+            Some(&(index as u32).to_variant()),
+        );
+        menu.append_item(&item);
+    }
+    menu
+}
+
+/// Asks for the name under which to save a collection.
+///
+/// # Arguments
+/// * `window` - The window that becomes the dialog's parent.
+/// * `on_name` - Called with the entered name, if the dialog is accepted.
+fn prompt_name(window: &adw::ApplicationWindow, on_name: impl Fn(String) + 'static) {
+    let entry = gtk::Entry::builder()
+        .placeholder_text("Name")
+        .activates_default(true)
+        .width_chars(30)
+        .build();
+    let dialog = adw::MessageDialog::new(
+        Some(window),
+        Some("Save as Collection"),
+        Some("A collection with the same name is replaced."),
+    );
+    dialog.add_responses(&[("cancel", "Cancel"), ("save", "Save")]);
+    dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
+    dialog.set_response_enabled("save", false);
+    dialog.set_default_response(Some("save"));
+    dialog.set_close_response("cancel");
+    dialog.set_extra_child(Some(&entry));
+
+    let dialog_clone = dialog.clone();
+    entry.connect_changed(move |entry| {
+        dialog_clone.set_response_enabled("save", !entry.text().trim().is_empty());
+    });
+    dialog.connect_response(Some("save"), move |_, _| {
+        let name = entry.text().trim().to_string();
+        if !name.is_empty() {
+            on_name(name);
+        }
+    });
+    dialog.present();
+}
+
+/// Opens the query builder: a window for finding the resources matching groups of conditions
+/// on their properties, such as files larger than a size that were modified after a date. The
+/// user picks the properties from the ontology, sees the SPARQL the window builds and runs it.
+///
+/// A query can be saved as a named collection and loaded again later. Every query run is also
+/// kept in a history, from which it can be loaded again.
+///
+/// Values may refer to the subject the window was opened from as `?subject`, which is replaced
+/// by its IRI when the query runs; saved and recorded queries keep the template.
+///
+/// Activating a match opens it in a subject window.
+///
+/// # Arguments
+/// * `app` - The application instance.
+/// * `parent` - The window the search was started from.
+/// * `subject` - The subject shown in `parent`, if any, which `?subject` stands for.
+/// * `ctx` - Shared application settings, passed on to the subject windows opened.
+pub fn open_window(
+    app: &adw::Application,
+    parent: &adw::ApplicationWindow,
+    subject: Option<String>,
+    ctx: &Rc<Context>,
+) {
+    // ---- Query Form ----
+
+    let join = gtk::DropDown::from_strings(&Join::ALL.map(Join::as_str));
+    let join_row = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+    join_row.append(&gtk::Label::new(Some("Match")));
+    join_row.append(&join);
+    join_row.append(&gtk::Label::new(Some("of these groups")));
+
+    let groups_box = gtk::Box::new(gtk::Orientation::Vertical, 6);
+    let add_group_button = gtk::Button::with_label("Add Group");
+    let find_button = gtk::Button::with_label("Find");
+    find_button.style_context().add_class("suggested-action");
+    let buttons = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+    let spacer = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    spacer.set_hexpand(true);
+    buttons.append(&add_group_button);
+    buttons.append(&spacer);
+    buttons.append(&find_button);
+
+    let form = gtk::Box::new(gtk::Orientation::Vertical, 6);
+    form.set_margin_start(12);
+    form.set_margin_end(12);
+    form.set_margin_top(12);
+    form.append(&join_row);
+    form.append(&groups_box);
+    form.append(&buttons);
+    let form_scroll = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .min_content_height(200)
+        .vexpand(true)
+        .child(&form)
+        .build();
+
+    // ---- SPARQL Preview ----
+
+    let preview = gtk::TextView::builder()
+        .editable(false)
+        .monospace(true)
+        .wrap_mode(gtk::WrapMode::WordChar)
+        .build();
+    let preview_scroll = gtk::ScrolledWindow::builder()
+        .min_content_height(120)
+        .child(&preview)
+        .build();
+    let expander = gtk::Expander::builder()
+        .label("SPARQL")
+        .margin_start(12)
+        .margin_end(12)
+        .child(&preview_scroll)
+        .build();
+
+    // ---- Results ----
+
+    let status = gtk::Label::new(None);
+    status.set_halign(gtk::Align::Start);
+    status.set_margin_start(12);
+    status.set_wrap(true);
+    status.style_context().add_class("dim-label");
+    let results = gtk::ListBox::builder()
+        .selection_mode(gtk::SelectionMode::None)
+        .activate_on_single_click(true)
+        .build();
+    let scroll = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .vexpand(true)
+        .child(&results)
+        .build();
+
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 6);
+    content.append(&form_scroll);
+    content.append(&expander);
+    content.append(&status);
+    content.append(&gtk::Separator::new(gtk::Orientation::Horizontal));
+    content.append(&scroll);
+
+    let save_button = gtk::Button::from_icon_name("document-save-as-symbolic");
+    save_button.set_tooltip_text(Some("Save as Collection…"));
+    let collections_button = gtk::MenuButton::builder()
+        .icon_name("view-list-symbolic")
+        .tooltip_text("Collections")
+        .build();
+    let history_button = gtk::MenuButton::builder()
+        .icon_name("document-open-recent-symbolic")
+        .tooltip_text("Recent Queries")
+        .build();
+    let header = adw::HeaderBar::new();
+    header.set_title_widget(Some(&gtk::Label::new(Some("Find by Property"))));
+    header.pack_end(&collections_button);
+    header.pack_end(&history_button);
+    header.pack_end(&save_button);
+    let toolbar = adw::ToolbarView::new();
+    toolbar.add_top_bar(&header);
+    toolbar.set_content(Some(&content));
+    let window = adw::ApplicationWindow::builder()
+        .application(app)
+        .transient_for(parent)
+        .default_width(720)
+        .default_height(640)
+        .title("Find by Property")
+        .content(&announce::wrap(&toolbar))
+        .build();
+    geometry::remember(&window, "finder");
+    // Files dropped onto the window open in subject windows, like results.
+    let app_clone = app.clone();
+    let ctx_clone = ctx.clone();
+    dnd::accept(&window, move |target| {
+        open_subject_window(&app_clone, target, &ctx_clone)
+    });
+    window.set_default_widget(Some(&find_button));
+
+    // ---- Behaviour ----
+
+    let properties = gtk::ListStore::new(&[glib::Type::STRING]);
+    let builder = Builder {
+        join,
+        groups_box,
+        groups: Rc::default(),
+        properties: properties.clone(),
+        preview: preview.buffer(),
+        subject,
+    };
+    builder.add_group(None);
+    let builder_clone = builder.clone();
+    builder
+        .join
+        .connect_selected_notify(move |_| builder_clone.update_preview());
+    let builder_clone = builder.clone();
+    add_group_button.connect_clicked(move |_| builder_clone.add_group(None));
+
+    // Saved collections are listed in the header and loaded into the form.
+    let refresh_collections = {
+        let collections_button = collections_button.clone();
+        move || {
+            let menu = collections_menu();
+            collections_button.set_sensitive(menu.n_items() > 0);
+            collections_button.set_menu_model(Some(&menu));
+        }
+    };
+    refresh_collections();
+    let load_collection =
+        gio::SimpleAction::new("load-collection", Some(&String::static_variant_type()));
+    let builder_clone = builder.clone();
+    let find_clone = find_button.clone();
+    load_collection.connect_activate(move |_, param| {
+        let Some(name) = param.and_then(|p| p.get::<String>()) else {
+            return;
+        };
+        if let Some(collection) = collections::find(&name) {
+            builder_clone.set_query(&collection.query);
+            find_clone.emit_clicked();
+        }
+    });
+    window.add_action(&load_collection);
+
+    // So are the queries run before, the most recent first.
+    let refresh_history = {
+        let history_button = history_button.clone();
+        move || {
+            let menu = history_menu();
+            history_button.set_sensitive(menu.n_items() > 0);
+            history_button.set_menu_model(Some(&menu));
+        }
+    };
+    refresh_history();
+    let load_history = gio::SimpleAction::new("load-history", Some(&u32::static_variant_type()));
+    let builder_clone = builder.clone();
+    let find_clone = find_button.clone();
+    load_history.connect_activate(move |_, param| {
+        let Some(index) = param.and_then(|p| p.get::<u32>()) else {
+            return;
+        };
+        if let Some(query) = collections::history().get(index as usize) {
+            builder_clone.set_query(query);
+            find_clone.emit_clicked();
+        }
+    });
+    window.add_action(&load_history);
+
+    let builder_clone = builder.clone();
+    let status_clone = status.clone();
+    let window_clone = window.clone();
+    save_button.connect_clicked(move |_| {
+        let query = match builder_clone.query() {
+            Ok(query) => query,
+            Err(err) => {
+                status_clone.set_text(&err);
+                return;
+            }
+        };
+        let status = status_clone.clone();
+        let window = window_clone.clone();
+        let refresh_collections = refresh_collections.clone();
+        prompt_name(&window_clone, move |name| {
+            match collections::save(&name, &query) {
+                Ok(()) => announce::announce(&window, &format!("Saved collection “{name}”")),
+                Err(err) => status.set_text(&format!("Could not save the collection: {err}")),
+            }
+            refresh_collections();
+        });
+    });
+
+    // The subjects of the listed matches, in the order of the rows.
+    let subjects: Rc<RefCell<Vec<String>>> = Rc::default();
+    let app_clone = app.clone();
+    let ctx_clone = ctx.clone();
+    let subjects_clone = subjects.clone();
+    results.connect_row_activated(move |_, row| {
+        if let Some(subject) = subjects_clone.borrow().get(row.index() as usize) {
+            open_subject_window(&app_clone, subject.clone(), &ctx_clone);
+        }
+    });
+
+    find_button.connect_clicked(move |_| {
+        let query = match builder.resolved_query() {
+            Ok(query) => query,
+            Err(err) => {
+                status.set_text(&err);
+                return;
+            }
+        };
+        // The history keeps the query as entered, with its templates.
+        if let Ok(entered) = builder.query() {
+            if let Err(err) = collections::record(&entered) {
+                announce::report_problem(
+                    &status,
+                    &format!("Could not record the query in the history: {err}"),
+                    None,
+                );
+            }
+            refresh_history();
+        }
+        status.set_text("Searching…");
+        let status = status.clone();
+        let results = results.clone();
+        let subjects = subjects.clone();
+        glib::MainContext::default().spawn_local(async move {
+            let rows = run_query(&query).await;
+            while let Some(child) = results.first_child() {
+                results.remove(&child);
+            }
+            subjects.borrow_mut().clear();
+            let rows = match rows {
+                Ok(rows) => rows,
+                Err(err) => {
+                    status.set_text(&format!("The search failed: {err}"));
+                    return;
+                }
+            };
+            status.set_text(&match rows.len() {
+                0 => "No matches".to_string(),
+                1 => "1 match".to_string(),
+                n if n >= memory::limit(MAX_RESULTS) => format!("The first {n} matches"),
+                n => format!("{n} matches"),
+            });
+            for subject in rows {
+                results.append(&result_row(&subject));
+                subjects.borrow_mut().push(subject);
+            }
+        });
+    });
+
+    // The full list of properties is not kept in memory on small machines.
+    if !memory::is_low() {
+        glib::MainContext::default().spawn_local(async move {
+            load_properties(&properties).await;
+        });
+    }
+    window.present();
+}
+
+/// Builds the row listing one match: its name and its full IRI.
+fn result_row(subject: &str) -> gtk::Widget {
+    let title = gtk::Label::new(Some(&tab_title(subject)));
+    title.set_halign(gtk::Align::Start);
+    title.set_ellipsize(gtk::pango::EllipsizeMode::End);
+    let detail = gtk::Label::new(Some(subject));
+    detail.set_halign(gtk::Align::Start);
+    detail.set_ellipsize(gtk::pango::EllipsizeMode::Middle);
+    detail.style_context().add_class("dim-label");
+    let row = gtk::Box::new(gtk::Orientation::Vertical, 2);
+    row.set_margin_start(12);
+    row.set_margin_end(12);
+    row.set_margin_top(4);
+    row.set_margin_bottom(4);
+    row.set_tooltip_text(Some(subject));
+    row.append(&title);
+    row.append(&detail);
+    row.upcast()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn condition(operator: Operator, value: &str, upper: &str) -> Condition {
+        Condition {
+            predicate: "urn:p".to_string(),
+            operator,
+            value: value.to_string(),
+            upper: upper.to_string(),
+        }
+    }
+
+    #[test]
+    fn literal_detects_numbers_and_dates() {
+        assert_eq!(literal(" 1024 "), "1024");
+        assert_eq!(
+            literal("2024-05-01"),
+            format!("\"2024-05-01T00:00:00Z\"^^<{XSD_DATE_TIME}>")
+        );
+        assert_eq!(literal("say \"hi\""), "\"say \\\"hi\\\"\"");
+        assert_eq!(literal("NaN"), "\"NaN\"");
+    }
+
+    #[test]
+    fn find_query_filters_values() {
+        assert_eq!(
+            condition(Operator::Between, "10", "20").pattern(0),
+            "?subject <urn:p> ?v0 . FILTER (?v0 >= 10 && ?v0 <= 20)"
+        );
+        assert_eq!(
+            filter_expression(&condition(Operator::Contains, "Holiday", ""), "?v"),
+            "CONTAINS(LCASE(STR(?v)), \"holiday\")"
+        );
+        assert_eq!(
+            filter_expression(&condition(Operator::Below, "5", ""), "?v"),
+            "?v < 5"
+        );
+    }
+
+    #[test]
+    fn query_joins_groups_and_conditions() {
+        let query = Query {
+            join: Join::Any,
+            groups: vec![
+                Group {
+                    join: Join::All,
+                    conditions: vec![
+                        condition(Operator::AtLeast, "10", ""),
+                        condition(Operator::Contains, "a", ""),
+                    ],
+                },
+                Group {
+                    join: Join::All,
+                    conditions: Vec::new(),
+                },
+                Group {
+                    join: Join::Any,
+                    conditions: vec![
+                        condition(Operator::Is, "x", ""),
+                        condition(Operator::Is, "y", ""),
+                    ],
+                },
+            ],
+        };
+        assert_eq!(
+            query.to_sparql(),
+            format!(
+                "SELECT DISTINCT ?subject WHERE {{\n  {{\n    \
+                 {{ ?subject <urn:p> ?v0 . FILTER (?v0 >= 10) }}\n    \
+                 {{ ?subject <urn:p> ?v1 . FILTER (CONTAINS(LCASE(STR(?v1)), \"a\")) }}\n  \
+                 }}\n  UNION\n  {{\n    \
+                 {{ ?subject <urn:p> ?v2 . FILTER (STR(?v2) = \"x\") }}\n    UNION\n    \
+                 {{ ?subject <urn:p> ?v3 . FILTER (STR(?v3) = \"y\") }}\n  \
+                 }}\n}}\nORDER BY ?subject LIMIT {MAX_RESULTS}"
+            )
+        );
+        assert_eq!(Query::from_json(&query.to_json()), Some(query));
+    }
+
+    #[test]
+    fn subject_template_is_replaced() {
+        let query = Query {
+            join: Join::Any,
+            groups: vec![
+                Group {
+                    join: Join::All,
+                    conditions: vec![condition(Operator::Is, "?subject", "")],
+                },
+                Group {
+                    join: Join::All,
+                    conditions: vec![
+                        condition(Operator::AtLeast, "10", ""),
+                        condition(Operator::Between, "1", "2"),
+                    ],
+                },
+            ],
+        };
+        assert!(query.uses_subject());
+        let resolved = query.with_subject("file:///home");
+        assert!(!resolved.uses_subject());
+        assert_eq!(resolved.groups[0].conditions[0].value, "file:///home");
+        assert_eq!(
+            query.describe(),
+            "(urn:p is ?subject) or (urn:p is at least 10, urn:p is between 1 and 2)"
+        );
+    }
+}
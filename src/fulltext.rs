@@ -0,0 +1,237 @@
+use crate::announce;
+use crate::context::Context;
+use crate::geometry;
+use crate::memory;
+use crate::open_subject_window;
+use crate::pages;
+use crate::rdf;
+use crate::search::{FTS_MATCH, fts_phrase};
+use crate::store;
+use crate::{NFO_FILE_NAME, NIE_IS_STORED_AS};
+use adw::prelude::*;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use tracker::prelude::*;
+
+const FTS_SNIPPET: &str = "http://tracker.api.gnome.org/ontology/v3/fts#snippet";
+const FTS_RANK: &str = "http://tracker.api.gnome.org/ontology/v3/fts#rank";
+const NIE_URL: &str = "http://tracker.api.gnome.org/ontology/v3/nie#url";
+
+/// Largest number of matches listed in the search window.
+const MAX_MATCHES: usize = 100;
+
+/// How many words of the content the snippet of a match shows around the matching text.
+const SNIPPET_WORDS: usize = 12;
+
+/// Characters the store puts around the matching text in snippets, which cannot occur in the
+/// indexed text. They are replaced by markup (see [`snippet_markup`]).
+const MATCH_START: char = '\u{2}';
+const MATCH_END: char = '\u{3}';
+
+/// Widget name of the search window, by which it is found among the application's windows.
+const WINDOW_NAME: &str = "full-text-search";
+
+/// Widget name of the search entry of the search window.
+const ENTRY_NAME: &str = "full-text-search-entry";
+
+/// A file whose content matches a full-text search.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Match {
+    pub url: String,
+    pub name: String,
+    /// The content around the matching text, with the matches between `MATCH_START` and
+    /// `MATCH_END`. Empty if only the file's metadata matched.
+    pub snippet: String,
+}
+
+/// Returns a query for indexed files whose content matches `text`, as `?url ?name ?snippet`
+/// rows, the best matches first.
+fn fulltext_query(text: &str) -> String {
+    format!(
+        "SELECT ?url ?name <{FTS_SNIPPET}>(?ie, \"{MATCH_START}\", \"{MATCH_END}\", \"…\", \
+         {SNIPPET_WORDS}) WHERE {{ \
+         ?ie <{FTS_MATCH}> \"{phrase}\" ; <{NIE_IS_STORED_AS}> ?file . \
+         ?file <{NIE_URL}> ?url ; <{NFO_FILE_NAME}> ?name \
+         }} ORDER BY DESC(<{FTS_RANK}>(?ie)) ?name LIMIT {limit}",
+        phrase = rdf::escape_string(&fts_phrase(text)),
+        limit = memory::limit(MAX_MATCHES),
+    )
+}
+
+/// Queries the store for the files whose content matches `text` (see [`fulltext_query`]).
+pub async fn find(text: &str) -> Result<Vec<Match>, glib::Error> {
+    let conn = store::client()?;
+    let cursor = conn.query_async(&fulltext_query(text)).await?;
+    let mut matches: Vec<Match> = Vec::new();
+    while cursor.next_future().await? {
+        let url = cursor.string(0).unwrap_or_default().to_string();
+        // Content stored in a file along with other content (e.g. an archive) yields a row
+        // for each; the best one is enough.
+        if matches.iter().any(|found| found.url == url) {
+            continue;
+        }
+        matches.push(Match {
+            url,
+            name: cursor.string(1).unwrap_or_default().to_string(),
+            snippet: cursor.string(2).unwrap_or_default().to_string(),
+        });
+    }
+    Ok(matches)
+}
+
+/// Turns a snippet into Pango markup with the matching text in bold, on a single line.
+fn snippet_markup(snippet: &str) -> String {
+    let snippet = snippet.split_whitespace().collect::<Vec<_>>().join(" ");
+    glib::markup_escape_text(&snippet)
+        .replace(MATCH_START, "<b>")
+        .replace(MATCH_END, "</b>")
+}
+
+/// Opens the window searching the content of the indexed files, or brings it to the front if
+/// it is already open, and searches for `text` in it unless that is empty.
+///
+/// Each match is listed with a snippet of its content, and activating one opens its subject
+/// window.
+///
+/// # Arguments
+/// * `app` - The application instance.
+/// * `ctx` - Shared application settings, for the subject windows opened.
+/// * `text` - The text to search for, e.g. given with `--search`.
+pub fn open_window(app: &adw::Application, ctx: &Rc<Context>, text: &str) {
+    if let Some(window) = app
+        .windows()
+        .into_iter()
+        .find(|w| w.widget_name() == WINDOW_NAME)
+    {
+        if let Some(search) = announce::find_named(window.upcast_ref(), ENTRY_NAME)
+            .and_downcast::<gtk::SearchEntry>()
+            .filter(|_| !text.is_empty())
+        {
+            search.set_text(text);
+        }
+        window.present();
+        return;
+    }
+
+    let (results, list) = pages::list_page("No matching files");
+    list.set_activate_on_single_click(true);
+    let urls: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+    let app_clone = app.clone();
+    let ctx_clone = ctx.clone();
+    let urls_clone = urls.clone();
+    list.connect_row_activated(move |_, row| {
+        if let Some(url) = urls_clone.borrow().get(row.index() as usize) {
+            open_subject_window(&app_clone, url.clone(), &ctx_clone);
+        }
+    });
+
+    let status = adw::StatusPage::builder()
+        .icon_name("system-search-symbolic")
+        .title("Search File Contents")
+        .description("Find indexed files by the text in them.")
+        .vexpand(true)
+        .build();
+    let stack = gtk::Stack::new();
+    stack.add_named(&status, Some("status"));
+    stack.add_named(&results, Some("results"));
+
+    let search = gtk::SearchEntry::builder()
+        .placeholder_text("Search File Contents")
+        .hexpand(true)
+        .build();
+    search.set_widget_name(ENTRY_NAME);
+
+    // Each change of the text starts a new search; results of searches that have been
+    // superseded in the meantime are dropped.
+    let generation = Rc::new(Cell::new(0u32));
+    let stack_clone = stack.clone();
+    search.connect_search_changed(move |search| {
+        generation.set(generation.get().wrapping_add(1));
+        let current = generation.get();
+        let text = search.text().trim().to_string();
+        if text.is_empty() {
+            stack_clone.set_visible_child_name("status");
+            return;
+        }
+        let generation = generation.clone();
+        let list = list.clone();
+        let stack = stack_clone.clone();
+        let urls = urls.clone();
+        glib::MainContext::default().spawn_local(async move {
+            let result = find(&text).await;
+            if generation.get() != current {
+                return;
+            }
+            pages::clear_list(&list);
+            urls.borrow_mut().clear();
+            match result {
+                Ok(matches) => {
+                    for found in matches {
+                        list.append(&match_row(&found));
+                        urls.borrow_mut().push(found.url);
+                    }
+                }
+                Err(err) => list.append(&gtk::Label::new(Some(&format!("Search failed: {err}")))),
+            }
+            stack.set_visible_child_name("results");
+        });
+    });
+
+    let header = adw::HeaderBar::new();
+    header.set_title_widget(Some(&search));
+    let toolbar = adw::ToolbarView::new();
+    toolbar.add_top_bar(&header);
+    toolbar.set_content(Some(&stack));
+
+    let window = adw::ApplicationWindow::builder()
+        .application(app)
+        .default_width(560)
+        .default_height(600)
+        .title("Search")
+        .content(&toolbar)
+        .build();
+    window.set_widget_name(WINDOW_NAME);
+    geometry::remember(&window, "fulltext");
+    // Typing anywhere in the window searches.
+    search.set_key_capture_widget(Some(&window));
+    search.set_text(text);
+    window.present();
+    search.grab_focus();
+}
+
+/// Builds the row listing one match: the file name above the snippet, with the file's URL as
+/// tooltip.
+fn match_row(found: &Match) -> adw::ActionRow {
+    let row = adw::ActionRow::builder()
+        .title(glib::markup_escape_text(&found.name))
+        .subtitle(snippet_markup(&found.snippet))
+        .subtitle_lines(2)
+        .tooltip_text(&found.url)
+        .activatable(true)
+        .build();
+    row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snippets_mark_the_matches() {
+        let snippet = format!("…a <tiny>\n{MATCH_START}note{MATCH_END} about  it…");
+        assert_eq!(
+            snippet_markup(&snippet),
+            "…a &lt;tiny&gt; <b>note</b> about it…"
+        );
+    }
+
+    #[test]
+    fn fulltext_query_quotes_text() {
+        let sparql = fulltext_query("a-b");
+        assert!(
+            sparql.contains(&format!("<{FTS_MATCH}> \"\\\"a-b\\\"*\"")),
+            "{sparql}"
+        );
+    }
+}
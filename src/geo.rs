@@ -0,0 +1,153 @@
+use crate::source::MetadataSource;
+use std::collections::HashMap;
+
+pub const SLO_LOCATION: &str = "http://tracker.api.gnome.org/ontology/v3/slo#location";
+pub const SLO_LATITUDE: &str = "http://tracker.api.gnome.org/ontology/v3/slo#latitude";
+pub const SLO_LONGITUDE: &str = "http://tracker.api.gnome.org/ontology/v3/slo#longitude";
+
+/// A point on the earth in decimal degrees, as found in photos and other geotagged files.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Coordinates {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl Coordinates {
+    /// Returns the point at `latitude` and `longitude`, or `None` if they are out of range.
+    pub fn new(latitude: f64, longitude: f64) -> Option<Self> {
+        ((-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude)).then_some(
+            Self {
+                latitude,
+                longitude,
+            },
+        )
+    }
+
+    /// Parses the decimal degrees of a latitude and a longitude given as text.
+    pub fn parse(latitude: &str, longitude: &str) -> Option<Self> {
+        Self::new(
+            latitude.trim().parse().ok()?,
+            longitude.trim().parse().ok()?,
+        )
+    }
+
+    /// Parses a `geo:` URI (RFC 5870), e.g. `geo:52.5200,13.4050;u=35`. An altitude and any
+    /// parameters are ignored.
+    pub fn parse_geo_uri(text: &str) -> Option<Self> {
+        let rest = text.strip_prefix("geo:")?;
+        let point = rest.split(';').next()?;
+        let mut parts = point.split(',');
+        Self::parse(parts.next()?, parts.next()?)
+    }
+
+    /// Returns the point for display, e.g. "52.520000° N, 13.405000° E".
+    pub fn display(&self) -> String {
+        let north_south = if self.latitude < 0.0 { 'S' } else { 'N' };
+        let east_west = if self.longitude < 0.0 { 'W' } else { 'E' };
+        format!(
+            "{:.6}° {north_south}, {:.6}° {east_west}",
+            self.latitude.abs(),
+            self.longitude.abs()
+        )
+    }
+
+    /// Returns the point as a `geo:` URI, which map applications handle.
+    pub fn geo_uri(&self) -> String {
+        format!("geo:{},{}", self.latitude, self.longitude)
+    }
+
+    /// Returns the address of the point on OpenStreetMap, for systems without a map
+    /// application.
+    pub fn map_url(&self) -> String {
+        let (lat, lon) = (self.latitude, self.longitude);
+        format!("https://www.openstreetmap.org/?mlat={lat}&mlon={lon}#map=15/{lat}/{lon}")
+    }
+}
+
+/// Finds coordinates among the properties of a subject: `slo:latitude` together with
+/// `slo:longitude`, as on a location node, or else a `geo:` URI given as a literal.
+///
+/// # Arguments
+/// * `map` - The values of each predicate, as `(value, datatype, language)` tuples.
+///
+/// # Returns
+/// * The coordinates and the predicates they were read from, whose rows the combined
+///   "Location" row replaces; `None` if there are no coordinates.
+pub fn find(
+    map: &HashMap<String, Vec<(String, String, String)>>,
+) -> Option<(Coordinates, Vec<String>)> {
+    let first = |pred: &str| map.get(pred).and_then(|v| v.first()).map(|v| v.0.as_str());
+    if let Some(coordinates) = first(SLO_LATITUDE)
+        .zip(first(SLO_LONGITUDE))
+        .and_then(|(lat, lon)| Coordinates::parse(lat, lon))
+    {
+        return Some((
+            coordinates,
+            vec![SLO_LATITUDE.to_string(), SLO_LONGITUDE.to_string()],
+        ));
+    }
+    // Only literals count, since a linked node named with a geo: URI has a row of its own.
+    map.iter().find_map(|(pred, values)| {
+        let coordinates = values
+            .iter()
+            .filter(|(_, dtype, _)| !dtype.is_empty())
+            .find_map(|(value, _, _)| Coordinates::parse_geo_uri(value))?;
+        // A predicate with other values keeps its row.
+        let replaced = if values.len() == 1 {
+            vec![pred.clone()]
+        } else {
+            Vec::new()
+        };
+        Some((coordinates, replaced))
+    })
+}
+
+/// Reads the coordinates of the location a subject links to with `slo:location`, which is
+/// where tracker-miner-fs stores the position of geotagged photos.
+///
+/// # Arguments
+/// * `source` - Where to read the location from.
+/// * `map` - The values of each predicate of the subject, as for [`find`].
+pub async fn linked<S: MetadataSource + ?Sized>(
+    source: &S,
+    map: &HashMap<String, Vec<(String, String, String)>>,
+) -> Option<Coordinates> {
+    let location = &map.get(SLO_LOCATION)?.first()?.0;
+    let statements = source.statements(location).await.ok()?;
+    let first = |pred: &str| {
+        statements
+            .iter()
+            .find(|(p, _, _, _)| p == pred)
+            .map(|(_, obj, _, _)| obj.as_str())
+    };
+    Coordinates::parse(first(SLO_LATITUDE)?, first(SLO_LONGITUDE)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coordinates_from_geo_uri_and_properties() {
+        let berlin = Coordinates::parse_geo_uri("geo:52.52,13.405;u=35").unwrap();
+        assert_eq!(berlin.display(), "52.520000° N, 13.405000° E");
+        assert_eq!(berlin.geo_uri(), "geo:52.52,13.405");
+        assert!(berlin.map_url().contains("mlat=52.52&mlon=13.405"));
+        assert_eq!(Coordinates::parse_geo_uri("geo:91,0"), None);
+        assert_eq!(Coordinates::parse_geo_uri("52.52,13.405"), None);
+
+        let dtype = "http://www.w3.org/2001/XMLSchema#double".to_string();
+        let mut map = HashMap::new();
+        map.insert(
+            SLO_LATITUDE.to_string(),
+            vec![("-33.8568".to_string(), dtype.clone(), String::new())],
+        );
+        map.insert(
+            SLO_LONGITUDE.to_string(),
+            vec![("-70.65".to_string(), dtype, String::new())],
+        );
+        let (santiago, replaced) = find(&map).unwrap();
+        assert_eq!(santiago.display(), "33.856800° S, 70.650000° W");
+        assert_eq!(replaced, [SLO_LATITUDE, SLO_LONGITUDE]);
+    }
+}
@@ -0,0 +1,69 @@
+use crate::settings;
+use gtk::prelude::*;
+
+/// The relocatable GSettings schema holding the state of one kind of window, kept below
+/// [`path`].
+const SCHEMA_ID: &str = "com.example.DesktopFileInformation.WindowState";
+
+/// Restores the size and maximized state last saved for windows of `kind` (e.g. "subject"),
+/// and saves them again when `window` is closed, so that each kind of window reopens the way
+/// the user left it rather than at its built-in default size.
+///
+/// The window's `default-width`, `default-height` and `maximized` properties are bound to the
+/// settings of its kind. GTK keeps the default size at the size of the unmaximized window, so
+/// unmaximizing restores the last size. Changes are held back while the window is resized and
+/// written once, when it is closed.
+///
+/// # Arguments
+/// * `window` - The window, before it is presented.
+/// * `kind` - The kind of window, naming its settings (see [`path`]).
+pub fn remember(window: &impl IsA<gtk::Window>, kind: &str) {
+    let state = gio::Settings::new_full(
+        &settings::schema(SCHEMA_ID),
+        None::<&gio::SettingsBackend>,
+        Some(&path(kind)),
+    );
+    // Sizes too small to be usable, including the 0 of a kind never saved, are ignored, so
+    // that the window keeps its built-in default size.
+    let (width, height) = (state.int("width"), state.int("height"));
+    if width >= MIN_SIZE && height >= MIN_SIZE {
+        window.set_default_size(width, height);
+    }
+    if state.boolean("maximized") {
+        window.maximize();
+    }
+
+    state.delay();
+    for (key, property) in [
+        ("width", "default-width"),
+        ("height", "default-height"),
+        ("maximized", "maximized"),
+    ] {
+        state.bind(key, window.upcast_ref(), property).set().build();
+    }
+    window.connect_close_request(move |_| {
+        state.apply();
+        glib::Propagation::Proceed
+    });
+}
+
+/// The smallest width or height restored.
+const MIN_SIZE: i32 = 100;
+
+/// Returns the path of the settings of windows of `kind`.
+fn path(kind: &str) -> String {
+    format!("/com/example/DesktopFileInformation/windows/{kind}/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_names_the_kind_of_window() {
+        assert_eq!(
+            path("subject"),
+            "/com/example/DesktopFileInformation/windows/subject/"
+        );
+    }
+}
@@ -0,0 +1,75 @@
+use gio::prelude::*;
+
+/// Where a file in the wastebasket was deleted from, and when.
+pub const NFO_ORIGINAL_LOCATION: &str =
+    "http://tracker.api.gnome.org/ontology/v3/nfo#originalLocation";
+pub const NFO_DELETION_DATE: &str = "http://tracker.api.gnome.org/ontology/v3/nfo#deletionDate";
+
+/// URI schemes of the virtual locations provided by GVfs, which the store does not index under
+/// these URIs: the wastebasket, recently used files, phones and cameras, and network shares.
+const SCHEMES: &[&str] = &[
+    "trash", "recent", "mtp", "gphoto2", "afc", "smb", "sftp", "ftp", "dav", "davs", "nfs",
+];
+
+/// Tells whether `uri` is in a virtual location of GVfs (see `SCHEMES`), e.g. `trash:///a.txt`
+/// or `smb://server/share/a.txt`.
+pub fn is_virtual(uri: &str) -> bool {
+    uri.split_once(':')
+        .is_some_and(|(scheme, _)| SCHEMES.iter().any(|s| s.eq_ignore_ascii_case(scheme)))
+}
+
+/// Returns the URI of the file a virtual location stands for, if GIO knows it: e.g. the
+/// `file:` URI of a `recent://` entry, which the store may have indexed. Other URIs, and
+/// virtual locations that stand only for themselves (such as files in the wastebasket or on
+/// network shares), are returned as they are.
+///
+/// Looking the target up may block on the location's backend, e.g. on the network, so this is
+/// only for the command line; the main loop uses [`resolve_async`].
+pub fn resolve(uri: &str) -> String {
+    if !is_virtual(uri) {
+        return uri.to_string();
+    }
+    let info = gio::File::for_uri(uri).query_info(
+        gio::FILE_ATTRIBUTE_STANDARD_TARGET_URI,
+        gio::FileQueryInfoFlags::NONE,
+        None::<&gio::Cancellable>,
+    );
+    target_uri(uri, info)
+}
+
+/// Like [`resolve`], but without blocking the main loop.
+pub async fn resolve_async(uri: &str) -> String {
+    if !is_virtual(uri) {
+        return uri.to_string();
+    }
+    let info = gio::File::for_uri(uri)
+        .query_info_future(
+            gio::FILE_ATTRIBUTE_STANDARD_TARGET_URI,
+            gio::FileQueryInfoFlags::NONE,
+            glib::Priority::DEFAULT,
+        )
+        .await;
+    target_uri(uri, info)
+}
+
+/// Returns the target URI given in `info`, or else `uri`.
+fn target_uri(uri: &str, info: Result<gio::FileInfo, glib::Error>) -> String {
+    info.ok()
+        .and_then(|info| info.attribute_string(gio::FILE_ATTRIBUTE_STANDARD_TARGET_URI))
+        .map_or_else(|| uri.to_string(), |target| target.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn virtual_locations_by_scheme() {
+        assert!(is_virtual("trash:///report.pdf"));
+        assert!(is_virtual("SMB://server/share/a.txt"));
+        assert!(is_virtual("mtp://Phone/DCIM/IMG_0001.jpg"));
+        assert!(!is_virtual("file:///home/user/a.txt"));
+        assert!(!is_virtual("urn:trash:1"));
+        assert!(!is_virtual("/trash/a.txt"));
+    }
+}
@@ -0,0 +1,175 @@
+use crate::settings::{self, Settings};
+use adw::prelude::*;
+use std::path::{Path, PathBuf};
+
+/// The desktop entry installed with the package, whose `Exec` line is adapted to the running
+/// executable.
+const DESKTOP_ENTRY: &str = include_str!("../resources/file-information.desktop");
+
+/// The D-Bus service file installed with the package (see `service`).
+const DBUS_SERVICE: &str = include_str!("../resources/com.example.DesktopFileInformation.service");
+
+/// The ID under which the desktop entry is installed.
+const DESKTOP_ID: &str = "file-information.desktop";
+
+/// The scheme of deep links, for which the application becomes the default handler.
+const DEEP_LINK_TYPE: &str = "x-scheme-handler/x-fileinfo";
+
+/// Returns whether a desktop entry for the application is installed, either by the package or
+/// by [`install`].
+pub fn is_registered() -> bool {
+    gio::DesktopAppInfo::new(DESKTOP_ID).is_some()
+}
+
+/// Registers the running executable with the desktop, as the package does: a desktop entry
+/// offering "File Information" for any file and folder and handling `x-fileinfo:` links, and a
+/// D-Bus service file for `--service`. Both are written to the user's data directory, so this
+/// works without packaging, e.g. for a build from source.
+///
+/// # Returns
+/// * The paths of the files written, or an error message.
+pub fn install() -> Result<Vec<PathBuf>, String> {
+    let exe = std::env::current_exe()
+        .map_err(|err| format!("Could not locate the running executable: {err}"))?;
+    let data_dir = glib::user_data_dir();
+    let desktop_path = data_dir.join("applications").join(DESKTOP_ID);
+    let service_path = data_dir
+        .join("dbus-1")
+        .join("services")
+        .join("com.example.DesktopFileInformation.service");
+    for (path, template) in [
+        (&desktop_path, DESKTOP_ENTRY),
+        (&service_path, DBUS_SERVICE),
+    ] {
+        write_file(path, &with_exec(template, &exe))?;
+    }
+
+    // Refresh the MIME cache of the directory, as the package does on install; desktops that
+    // watch the directory pick up the entry without it, too.
+    let _ = std::process::Command::new("update-desktop-database")
+        .arg(data_dir.join("applications"))
+        .status();
+    let app_info = gio::DesktopAppInfo::from_filename(&desktop_path)
+        .ok_or_else(|| format!("Could not read {}", desktop_path.display()))?;
+    app_info
+        .set_as_default_for_type(DEEP_LINK_TYPE)
+        .map_err(|err| format!("Could not register for x-fileinfo: links: {err}"))?;
+    Ok(vec![desktop_path, service_path])
+}
+
+/// Writes `contents` to `path`, creating the parent directories first.
+fn write_file(path: &Path, contents: &str) -> Result<(), String> {
+    path.parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|()| std::fs::write(path, contents))
+        .map_err(|err| format!("Could not write {}: {err}", path.display()))
+}
+
+/// Returns `template` with the program of its `Exec` lines replaced by `exe`, keeping the
+/// arguments.
+fn with_exec(template: &str, exe: &Path) -> String {
+    let program = quote_exec(&exe.to_string_lossy());
+    let mut result = String::new();
+    for line in template.lines() {
+        match line.strip_prefix("Exec=") {
+            Some(command) => {
+                let args = command.split_once(' ').map(|(_, args)| args);
+                result.push_str(&match args {
+                    Some(args) => format!("Exec={program} {args}"),
+                    None => format!("Exec={program}"),
+                });
+            }
+            None => result.push_str(line),
+        }
+        result.push('\n');
+    }
+    result
+}
+
+/// Quotes `program` for an `Exec` line if it contains characters with a special meaning there.
+fn quote_exec(program: &str) -> String {
+    if !program.contains(|c: char| c.is_whitespace() || "\"'\\`$;&|<>()*?#~".contains(c)) {
+        return program.to_string();
+    }
+    let mut quoted = String::from("\"");
+    for c in program.chars() {
+        if "\"`$\\".contains(c) {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Handles `--install-handlers`: registers the application (see [`install`]) and reports the
+/// outcome on the terminal.
+///
+/// # Returns
+/// * The exit status of the command line invocation.
+pub fn run_install() -> i32 {
+    match install() {
+        Ok(paths) => {
+            for path in paths {
+                println!("Wrote {}", path.display());
+            }
+            0
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            1
+        }
+    }
+}
+
+/// On the first launch of an unregistered application, offers to register it (see
+/// [`install`]), then calls `then`. Later launches, and launches of an installed package, go
+/// straight to `then`.
+///
+/// # Arguments
+/// * `app` - The application, kept running while the offer is shown.
+/// * `then` - Called once the offer has been answered, or right away.
+pub fn offer(app: &adw::Application, then: impl Fn() + 'static) {
+    let settings = Settings::load();
+    if settings.boolean(settings::HANDLERS_OFFERED, false) || is_registered() {
+        then();
+        return;
+    }
+    settings.set_boolean(settings::HANDLERS_OFFERED, true);
+
+    let dialog = adw::MessageDialog::new(
+        None::<&gtk::Window>,
+        Some("Add File Information to Your Desktop?"),
+        Some(
+            "File Information can be offered in the “Open With” menu of every file and \
+             folder, and open x-fileinfo: links. This can also be done later from the main \
+             menu.",
+        ),
+    );
+    dialog.set_application(Some(app));
+    dialog.add_responses(&[("later", "Not Now"), ("register", "Add")]);
+    dialog.set_response_appearance("register", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("register"));
+    dialog.set_close_response("later");
+    dialog.connect_response(None, move |_, response| {
+        if let Err(err) = (response == "register").then(install).transpose() {
+            log::warn!("{err}");
+        }
+        then();
+    });
+    dialog.present();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn with_exec_replaces_program_and_keeps_arguments() {
+        let entry = with_exec(DESKTOP_ENTRY, Path::new("/opt/my apps/file-information"));
+        assert!(entry.contains("\nExec=\"/opt/my apps/file-information\" %U\n"));
+        assert!(entry.contains("x-scheme-handler/x-fileinfo"));
+        let service = with_exec(DBUS_SERVICE, Path::new("/home/u/bin/file-information"));
+        assert!(service.contains("\nExec=/home/u/bin/file-information --service\n"));
+    }
+}
@@ -0,0 +1,578 @@
+use crate::collections;
+use crate::context::Context;
+use crate::export::{self, ExportFormat};
+use crate::finder::{self, Query, resolve_predicate};
+use crate::gvfs;
+use crate::labels;
+use crate::prefixes;
+use crate::source::MetadataSource;
+use crate::store;
+use crate::table;
+use crate::{TableRow, display_value, friendly_label, tab_title};
+use clap::ValueEnum;
+use gio::prelude::*;
+use serde_json::json;
+use std::cell::RefCell;
+use std::fmt;
+use std::path::Path;
+use std::rc::Rc;
+use tracker::prelude::*;
+
+/// Why the metadata of a subject could not be printed. Each reason has an exit status of its
+/// own, so that scripts can tell them apart.
+#[derive(Debug)]
+enum Failure {
+    /// The query failed for another reason.
+    Query(glib::Error),
+    /// The store (normally the Tracker miner) could not be connected to.
+    Connection(glib::Error),
+    /// There are no statements about the subject: it is not indexed, or the URI is wrong. For
+    /// virtual locations, nothing could be read from GIO either.
+    NoTriples,
+    /// The subject is a local file that does not exist.
+    MissingPath,
+}
+
+impl Failure {
+    /// Returns the exit status reporting the failure. Status 2 is left to invalid arguments,
+    /// which clap reports.
+    fn exit_status(&self) -> i32 {
+        match self {
+            Failure::Query(_) => 1,
+            Failure::Connection(_) => 3,
+            Failure::NoTriples => 4,
+            Failure::MissingPath => 5,
+        }
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::Query(err) => write!(f, "{err}"),
+            Failure::Connection(err) => write!(f, "failed to connect to Tracker: {err}"),
+            Failure::NoTriples => write!(f, "nothing is known about it"),
+            Failure::MissingPath => write!(f, "no such file"),
+        }
+    }
+}
+
+/// Prints the metadata of `uri` to stdout as JSON (see `export::to_json`), without showing a
+/// window.
+///
+/// In watch mode the application keeps running afterwards and prints a new document whenever
+/// the store reports a change to `uri` that alters its metadata, which helps debugging miners
+/// from a terminal.
+///
+/// Failures exit with a status telling what went wrong: 3 if the store cannot be connected
+/// to, 4 if nothing is known about the subject, 5 if the subject is a file that does not exist
+/// and 1 for other failures. While watching, a subject that is not indexed yet is waited for
+/// instead.
+///
+/// # Arguments
+/// * `app` - The application, held alive while watching.
+/// * `uri` - The URI of the subject to print.
+/// * `ctx` - Shared application settings.
+/// * `watch` - Whether to keep printing changes.
+/// * `predicates` - The properties to print the values of, as prefixed names or IRIs; all of
+///   them if empty. The identifier is always printed.
+///
+/// # Returns
+/// * The exit status of the command line invocation.
+pub fn run(
+    app: &adw::Application,
+    uri: &str,
+    ctx: &Rc<Context>,
+    watch: bool,
+    predicates: &[String],
+) -> i32 {
+    // Prefixes defined in the store are recognized along with the well-known ones.
+    prefixes::load_blocking();
+    let mut selected = Vec::new();
+    for name in predicates {
+        match resolve_predicate(name) {
+            Some(predicate) => selected.push(predicate),
+            None => {
+                eprintln!("Unknown predicate “{name}”: expected a prefixed name or an IRI");
+                return 2;
+            }
+        }
+    }
+
+    let json = match snapshot(uri, &selected, ctx) {
+        Ok(json) => {
+            println!("{json}");
+            json
+        }
+        Err(Failure::NoTriples) if watch => String::new(),
+        Err(err) => {
+            eprintln!("Failed to query {uri}: {err}");
+            return err.exit_status();
+        }
+    };
+    if !watch {
+        return 0;
+    }
+
+    let notifier = match store::client().map(|conn| conn.notifier()) {
+        Ok(Some(notifier)) => notifier,
+        Ok(None) => {
+            eprintln!("The store does not report changes");
+            return 1;
+        }
+        Err(err) => {
+            let err = Failure::Connection(err);
+            eprintln!("Failed to watch {uri}: {err}");
+            return err.exit_status();
+        }
+    };
+    let last = RefCell::new(json);
+    let uri = uri.to_string();
+    let ctx = ctx.clone();
+    notifier.connect_events(move |_notifier, _service, _graph, events| {
+        if !events
+            .iter()
+            .any(|e| e.urn().as_deref() == Some(uri.as_str()))
+        {
+            return;
+        }
+        match snapshot(&uri, &selected, &ctx) {
+            // Events also arrive for changes that do not alter any value, e.g. a re-extraction
+            // with the same results; only print actual changes.
+            Ok(json) if json != *last.borrow() => {
+                println!("{json}");
+                *last.borrow_mut() = json;
+            }
+            Ok(_) => {}
+            Err(err) => eprintln!("Failed to query {uri}: {err}"),
+        }
+    });
+
+    // Keep the application running without windows, and the notifier alive, until the process
+    // is interrupted.
+    let hold = app.hold();
+    app.connect_shutdown(move |_| {
+        let _ = (&hold, &notifier);
+    });
+    0
+}
+
+/// Name of the file `export_batch` writes all subjects to when they are combined.
+const COMBINED_FILE_NAME: &str = "metadata.json";
+
+/// Writes the metadata of each of `uris` to a file in `dir`, without showing a window, and
+/// prints the paths of the files written.
+///
+/// Each subject gets a file named after it (see [`export_file_name`]), or, if `combined`, the
+/// subjects are written to a single JSON array of [`export::to_json`] documents. Subjects that
+/// cannot be read are reported and skipped, so that one missing file does not spoil the export
+/// of a whole library.
+///
+/// # Arguments
+/// * `uris` - The URIs of the subjects to export.
+/// * `dir` - The directory to write to, which is created if needed.
+/// * `format` - The format of the files, unless `combined`.
+/// * `combined` - Whether to write one JSON array instead of a file per subject.
+/// * `ctx` - Shared application settings.
+///
+/// # Returns
+/// * The exit status of the command line invocation: that of the first failure (see
+///   [`run`]), if any subject could not be read, or 1 if a file could not be written.
+pub fn export_batch(
+    uris: &[String],
+    dir: &Path,
+    format: ExportFormat,
+    combined: bool,
+    ctx: &Context,
+) -> i32 {
+    if let Err(err) = std::fs::create_dir_all(dir) {
+        eprintln!("Failed to create {}: {err}", dir.display());
+        return 1;
+    }
+    let mut status = 0;
+    let mut documents = Vec::new();
+    let mut used_names = Vec::new();
+    for uri in uris {
+        let (is_file_data_object, rows) = match subject_rows(uri, ctx) {
+            Ok(subject) => subject,
+            Err(err) => {
+                eprintln!("Failed to query {uri}: {err}");
+                if status == 0 {
+                    status = err.exit_status();
+                }
+                continue;
+            }
+        };
+        if combined {
+            documents.push(export::to_json_value(uri, is_file_data_object, &rows));
+            continue;
+        }
+        let path = dir.join(export_file_name(uri, format, &mut used_names));
+        let text = export::serialize(format, uri, is_file_data_object, &rows);
+        if !write_export(&path, &text) && status == 0 {
+            status = 1;
+        }
+    }
+    if combined {
+        // Serializing a `serde_json::Value` cannot fail.
+        let text = serde_json::to_string_pretty(&documents).unwrap_or_default();
+        if !write_export(&dir.join(COMBINED_FILE_NAME), &text) && status == 0 {
+            status = 1;
+        }
+    }
+    status
+}
+
+/// Writes `text` to `path` and prints the path, or reports why it could not be written.
+///
+/// # Returns
+/// * Whether the file was written.
+fn write_export(path: &Path, text: &str) -> bool {
+    match std::fs::write(path, text) {
+        Ok(()) => {
+            println!("{}", path.display());
+            true
+        }
+        Err(err) => {
+            eprintln!("Failed to write {}: {err}", path.display());
+            false
+        }
+    }
+}
+
+/// Returns the name of the file the metadata of `uri` is exported to: the file name for files,
+/// otherwise the URI, with the extension of `format`. Names already in `used` get a number,
+/// e.g. `IMG_0001.jpg-2.json` for a second `IMG_0001.jpg`; the name returned is added to `used`.
+fn export_file_name(uri: &str, format: ExportFormat, used: &mut Vec<String>) -> String {
+    let stem = tab_title(uri).replace('/', "_");
+    let extension = format.as_str();
+    let mut name = format!("{stem}.{extension}");
+    let mut number = 1;
+    while used.contains(&name) {
+        number += 1;
+        name = format!("{stem}-{number}.{extension}");
+    }
+    used.push(name.clone());
+    name
+}
+
+/// The output formats of `--run-saved`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    /// One row per matching resource, with a column for each property the query compares.
+    #[default]
+    Csv,
+    /// An array with one object per matching resource, mapping the compared properties to
+    /// their values.
+    Json,
+}
+
+/// One matching resource in a report, with the values of the compared properties.
+#[derive(Debug, PartialEq)]
+struct ReportRow {
+    subject: String,
+    /// The `(display, native)` values of each compared property, in the order of the
+    /// properties.
+    values: Vec<Vec<(String, String)>>,
+}
+
+/// Prints a report on the resources matching the collection `name`, saved in the query
+/// builder (see `collections`), to stdout without showing a window.
+///
+/// Unlike the builder window, the report is not limited to the first matches, so that
+/// scheduled jobs can produce complete listings from the same definitions.
+///
+/// # Arguments
+/// * `name` - The name of the collection.
+/// * `format` - The output format.
+/// * `ctx` - Shared application settings.
+///
+/// # Returns
+/// * The exit status of the command line invocation.
+pub fn run_saved(name: &str, format: ReportFormat, ctx: &Context) -> i32 {
+    let Some(collection) = collections::find(name) else {
+        eprintln!("There is no saved collection named “{name}”");
+        let names: Vec<String> = collections::load().into_iter().map(|c| c.name).collect();
+        if !names.is_empty() {
+            eprintln!("Saved collections: {}", names.join(", "));
+        }
+        return 1;
+    };
+    // There is no subject here for a template to stand for.
+    if collection.query.uses_subject() {
+        eprintln!(
+            "“{name}” refers to {}, which only the query builder can fill in",
+            finder::SUBJECT_TEMPLATE
+        );
+        return 1;
+    }
+    match report(&collection.query, format, ctx) {
+        Ok(text) => {
+            print!("{text}");
+            0
+        }
+        Err(err) => {
+            eprintln!("Failed to run “{name}”: {err}");
+            err.exit_status()
+        }
+    }
+}
+
+/// Runs `query` and serializes the matches in `format`.
+fn report(query: &Query, format: ReportFormat, ctx: &Context) -> Result<String, Failure> {
+    let conn = store::client().map_err(Failure::Connection)?;
+    let statements = report_statements(conn.as_ref(), query).map_err(Failure::Query)?;
+    let predicates = query.predicates();
+    labels::load_blocking(predicates.iter().map(String::as_str));
+    let rows = report_rows(
+        &predicates,
+        statements,
+        &ctx.date_format.borrow(),
+        ctx.utc_dates.get(),
+    );
+    Ok(match format {
+        ReportFormat::Csv => report_csv(&predicates, &rows),
+        ReportFormat::Json => report_json(&predicates, &rows),
+    })
+}
+
+/// Queries the `(subject, predicate, object, datatype)` statements of a report on `query`.
+fn report_statements(
+    conn: &dyn store::StoreClient,
+    query: &Query,
+) -> Result<Vec<(String, String, String, String)>, glib::Error> {
+    let cursor = conn.query(&query.report_sparql())?;
+    let mut statements = Vec::new();
+    while cursor.next(None::<&gio::Cancellable>)? {
+        statements.push((
+            cursor.string(0).unwrap_or_default().to_string(),
+            cursor.string(1).unwrap_or_default().to_string(),
+            cursor.string(2).unwrap_or_default().to_string(),
+            cursor.string(3).unwrap_or_default().to_string(),
+        ));
+    }
+    Ok(statements)
+}
+
+/// Groups `(subject, predicate, object, datatype)` statements into one row per subject, in
+/// order of appearance. Statements without a predicate stand for subjects that have none of
+/// the compared properties.
+fn report_rows(
+    predicates: &[String],
+    statements: Vec<(String, String, String, String)>,
+    date_format: &str,
+    utc: bool,
+) -> Vec<ReportRow> {
+    let mut rows: Vec<ReportRow> = Vec::new();
+    for (subject, pred, obj, dtype) in statements {
+        if rows.last().is_none_or(|row| row.subject != subject) {
+            rows.push(ReportRow {
+                subject: subject.clone(),
+                values: vec![Vec::new(); predicates.len()],
+            });
+        }
+        let Some(index) = predicates.iter().position(|p| *p == pred) else {
+            continue;
+        };
+        let display = if dtype.is_empty() {
+            obj.clone()
+        } else {
+            display_value(&pred, &obj, &dtype, date_format, utc)
+        };
+        if let Some(row) = rows.last_mut() {
+            row.values[index].push((display, obj));
+        }
+    }
+    rows
+}
+
+/// Writes a report as CSV: the subject and one column of displayed values per property,
+/// several values being separated by "; ".
+fn report_csv(predicates: &[String], rows: &[ReportRow]) -> String {
+    let mut wtr = csv::WriterBuilder::new()
+        .has_headers(true)
+        .from_writer(vec![]);
+    let mut header = vec!["Subject".to_string()];
+    header.extend(predicates.iter().map(|p| friendly_label(p)));
+    let _ = wtr.write_record(&header);
+    for row in rows {
+        let mut record = vec![row.subject.clone()];
+        record.extend(row.values.iter().map(|values| {
+            let displayed: Vec<&str> = values.iter().map(|(d, _)| d.as_str()).collect();
+            displayed.join("; ")
+        }));
+        let _ = wtr.write_record(&record);
+    }
+    String::from_utf8(wtr.into_inner().unwrap_or_default()).unwrap_or_default()
+}
+
+/// Writes a report as JSON: an array of `{subject, values}` objects, where `values` maps each
+/// property IRI to its native values.
+fn report_json(predicates: &[String], rows: &[ReportRow]) -> String {
+    let doc: Vec<serde_json::Value> = rows
+        .iter()
+        .map(|row| {
+            let values: serde_json::Map<String, serde_json::Value> = predicates
+                .iter()
+                .zip(&row.values)
+                .map(|(pred, values)| {
+                    let native: Vec<&str> = values.iter().map(|(_, n)| n.as_str()).collect();
+                    (pred.clone(), json!(native))
+                })
+                .collect();
+            json!({ "subject": row.subject, "values": values })
+        })
+        .collect();
+    // Serializing a `serde_json::Value` cannot fail.
+    let mut text = serde_json::to_string_pretty(&doc).unwrap_or_default();
+    text.push('\n');
+    text
+}
+
+/// Reads the metadata of `uri` from the store and serializes it like the rows of a window,
+/// keeping only the values of `predicates` unless that is empty.
+fn snapshot(uri: &str, predicates: &[String], ctx: &Context) -> Result<String, Failure> {
+    let (is_file_data_object, rows) = subject_rows(uri, ctx)?;
+    let rows = select_rows(rows, predicates);
+    Ok(export::to_json(uri, is_file_data_object, &rows))
+}
+
+/// Reads the metadata of `uri` from the store as the rows of a window (see [`read_rows`]).
+fn subject_rows(uri: &str, ctx: &Context) -> Result<(bool, Vec<TableRow>), Failure> {
+    if uri.starts_with("file:") && !gio::File::for_uri(uri).query_exists(None::<&gio::Cancellable>)
+    {
+        return Err(Failure::MissingPath);
+    }
+    let conn = store::client().map_err(Failure::Connection)?;
+    glib::MainContext::default().block_on(read_rows(&*conn, uri, ctx))
+}
+
+/// Reads the metadata of `uri` from `source` as the rows of a window, computed by
+/// `table::build` like those of the window itself: ordered and filtered as chosen in the
+/// preferences, merged with the interpreted elements if asked for, and followed by the values
+/// derived from the file system.
+///
+/// # Returns
+/// * Whether the subject is an `nfo:FileDataObject`, and the rows.
+async fn read_rows<S: MetadataSource + ?Sized>(
+    source: &S,
+    uri: &str,
+    ctx: &Context,
+) -> Result<(bool, Vec<TableRow>), Failure> {
+    let statements = source.statements(uri).await.map_err(Failure::Query)?;
+    let indexed = !statements.is_empty();
+    let table = table::build(source, uri, statements, ctx).await;
+    let rows = table.rows(uri, ctx);
+    // Virtual locations are never indexed; what GIO tells about them is all there is.
+    let known = if gvfs::is_virtual(uri) {
+        rows.len() > 1
+    } else {
+        indexed
+    };
+    if !known {
+        return Err(Failure::NoTriples);
+    }
+    Ok((table.is_file_data_object, rows))
+}
+
+/// Keeps the identifier and the rows of `predicates` among `rows`, or all rows if `predicates`
+/// is empty.
+fn select_rows(rows: Vec<TableRow>, predicates: &[String]) -> Vec<TableRow> {
+    if predicates.is_empty() {
+        return rows;
+    }
+    rows.into_iter()
+        .enumerate()
+        .filter(|(index, row)| *index == 0 || predicates.contains(&row.native_predicate))
+        .map(|(_, row)| row)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ordering::RowOrder;
+    use crate::source::MockSource;
+    use crate::{FILEDATAOBJECT, RDF_TYPE};
+
+    #[test]
+    fn rows_group_values_by_predicate() {
+        let source = MockSource::default()
+            .link("urn:a", "urn:p", "urn:1")
+            .link("urn:a", RDF_TYPE, FILEDATAOBJECT)
+            .link("urn:a", "urn:p", "urn:2");
+        let ctx = Context::default();
+        ctx.row_order.set(RowOrder::Alphabetical);
+        ctx.show_advanced.set(true);
+        let context = glib::MainContext::default();
+        let (is_file, rows) = context.block_on(read_rows(&source, "urn:a", &ctx)).unwrap();
+        assert!(is_file);
+        let values: Vec<_> = rows.iter().map(|r| r.native_value.as_str()).collect();
+        assert_eq!(values, ["urn:a", "urn:1", "urn:2", FILEDATAOBJECT]);
+
+        let rows = select_rows(rows, &[RDF_TYPE.to_string()]);
+        let values: Vec<_> = rows.iter().map(|r| r.native_value.as_str()).collect();
+        assert_eq!(values, ["urn:a", FILEDATAOBJECT]);
+
+        assert!(matches!(
+            context.block_on(read_rows(&source, "urn:b", &ctx)),
+            Err(Failure::NoTriples)
+        ));
+    }
+
+    #[test]
+    fn report_rows_collect_values_per_subject() {
+        let statement = |s: &str, p: &str, o: &str| {
+            (s.to_string(), p.to_string(), o.to_string(), String::new())
+        };
+        let predicates = vec!["urn:p".to_string(), "urn:q".to_string()];
+        let rows = report_rows(
+            &predicates,
+            vec![
+                statement("urn:a", "urn:p", "1"),
+                statement("urn:a", "urn:p", "2"),
+                statement("urn:b", "", ""),
+                statement("urn:c", "urn:q", "3"),
+            ],
+            "%F",
+            false,
+        );
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[1].values, [Vec::new(), Vec::new()]);
+        let csv = report_csv(&predicates, &rows);
+        let lines: Vec<&str> = csv.lines().skip(1).collect();
+        assert_eq!(lines, ["urn:a,1; 2,", "urn:b,,", "urn:c,,3"]);
+    }
+
+    #[test]
+    fn export_file_names_are_unique() {
+        let mut used = Vec::new();
+        let names: Vec<String> = [
+            "file:///photos/IMG_0001.jpg",
+            "file:///backup/IMG_0001.jpg",
+            "urn:a/b",
+        ]
+        .iter()
+        .map(|uri| export_file_name(uri, ExportFormat::Json, &mut used))
+        .collect();
+        assert_eq!(
+            names,
+            ["IMG_0001.jpg.json", "IMG_0001.jpg-2.json", "urn:a_b.json"]
+        );
+    }
+
+    #[test]
+    fn failures_have_distinct_exit_statuses() {
+        let error = || glib::Error::new(gio::IOErrorEnum::Failed, "failed");
+        let statuses: Vec<i32> = [
+            Failure::Query(error()),
+            Failure::Connection(error()),
+            Failure::NoTriples,
+            Failure::MissingPath,
+        ]
+        .iter()
+        .map(Failure::exit_status)
+        .collect();
+        assert_eq!(statuses, [1, 3, 4, 5]);
+    }
+}
@@ -0,0 +1,72 @@
+/// Back/forward navigation history of a single window.
+///
+/// The current location is not stored here; callers pass it in when moving, so that the
+/// history only ever contains locations that can be returned to.
+#[derive(Debug, Default)]
+pub struct History {
+    back: Vec<String>,
+    forward: Vec<String>,
+}
+
+impl History {
+    /// Records that the window navigated away from `current` to a new location.
+    ///
+    /// Like in a web browser, this discards any locations that could be reached by going
+    /// forward.
+    pub fn visit(&mut self, current: String) {
+        self.back.push(current);
+        self.forward.clear();
+    }
+
+    /// Moves one step back, returning the location to show, if there is one.
+    pub fn go_back(&mut self, current: String) -> Option<String> {
+        let previous = self.back.pop()?;
+        self.forward.push(current);
+        Some(previous)
+    }
+
+    /// Moves one step forward, returning the location to show, if there is one.
+    pub fn go_forward(&mut self, current: String) -> Option<String> {
+        let next = self.forward.pop()?;
+        self.back.push(current);
+        Some(next)
+    }
+
+    /// Whether [`History::go_back`] would succeed.
+    pub fn can_go_back(&self) -> bool {
+        !self.back.is_empty()
+    }
+
+    /// Whether [`History::go_forward`] would succeed.
+    pub fn can_go_forward(&self) -> bool {
+        !self.forward.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn back_and_forward() {
+        let mut h = History::default();
+        h.visit("a".to_string());
+        h.visit("b".to_string());
+        // Currently at "c".
+        assert_eq!(h.go_back("c".to_string()), Some("b".to_string()));
+        assert_eq!(h.go_back("b".to_string()), Some("a".to_string()));
+        assert!(!h.can_go_back());
+        assert_eq!(h.go_forward("a".to_string()), Some("b".to_string()));
+        assert!(h.can_go_forward());
+    }
+
+    #[test]
+    fn visit_clears_forward() {
+        let mut h = History::default();
+        h.visit("a".to_string());
+        assert_eq!(h.go_back("b".to_string()), Some("a".to_string()));
+        h.visit("a".to_string());
+        assert!(!h.can_go_forward());
+        assert_eq!(h.go_forward("c".to_string()), None);
+    }
+}
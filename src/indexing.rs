@@ -0,0 +1,201 @@
+use crate::rdf;
+use adw::prelude::*;
+use std::path::{Path, PathBuf};
+
+/// GSettings schema holding the configuration of tracker-miner-fs.
+const MINER_FS_SCHEMA: &str = "org.freedesktop.Tracker3.Miner.Files";
+
+/// Where a file lies relative to the folders the miner is configured to index.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RootStatus {
+    /// Inside the given folder, which is indexed recursively.
+    Recursive(PathBuf),
+    /// Directly inside the given folder, which is indexed without its subfolders.
+    Single(PathBuf),
+    /// Outside all indexed folders.
+    Outside,
+    /// The miner's configuration is not installed.
+    Unknown,
+}
+
+/// Resolves an entry of the miner's `index-*-directories` settings to a path.
+///
+/// Besides absolute paths, the miner understands `$HOME` and the special user folders
+/// `&DESKTOP`, `&DOCUMENTS`, `&DOWNLOAD`, `&MUSIC`, `&PICTURES`, `&PUBLIC_SHARE`,
+/// `&TEMPLATES` and `&VIDEOS`.
+pub fn resolve_root(entry: &str) -> Option<PathBuf> {
+    let special = match entry {
+        "&DESKTOP" => glib::UserDirectory::Desktop,
+        "&DOCUMENTS" => glib::UserDirectory::Documents,
+        "&DOWNLOAD" => glib::UserDirectory::Downloads,
+        "&MUSIC" => glib::UserDirectory::Music,
+        "&PICTURES" => glib::UserDirectory::Pictures,
+        "&PUBLIC_SHARE" => glib::UserDirectory::PublicShare,
+        "&TEMPLATES" => glib::UserDirectory::Templates,
+        "&VIDEOS" => glib::UserDirectory::Videos,
+        _ => {
+            return match entry.strip_prefix("$HOME") {
+                Some(rest) => Some(glib::home_dir().join(rest.trim_start_matches('/'))),
+                None => Some(PathBuf::from(entry)).filter(|p| p.is_absolute()),
+            };
+        }
+    };
+    glib::user_special_dir(special)
+}
+
+/// Determines whether `path` lies inside one of the indexed folders.
+///
+/// # Arguments
+/// * `path` - The file to check.
+/// * `recursive` - Folders indexed along with all their subfolders.
+/// * `single` - Folders indexed without their subfolders.
+pub fn root_status(path: &Path, recursive: &[PathBuf], single: &[PathBuf]) -> RootStatus {
+    if let Some(root) = recursive.iter().find(|root| path.starts_with(root)) {
+        return RootStatus::Recursive(root.clone());
+    }
+    match single
+        .iter()
+        .find(|root| path.parent() == Some(root.as_path()))
+    {
+        Some(root) => RootStatus::Single(root.clone()),
+        None => RootStatus::Outside,
+    }
+}
+
+/// Looks up where `path` lies relative to the folders configured in the miner's settings.
+fn configured_root_status(path: &Path) -> RootStatus {
+    let Some(schema) = gio::SettingsSchemaSource::default()
+        .and_then(|source| source.lookup(MINER_FS_SCHEMA, true))
+    else {
+        return RootStatus::Unknown;
+    };
+    let settings = gio::Settings::new_full(&schema, None::<&gio::SettingsBackend>, None);
+    let roots = |key: &str| -> Vec<PathBuf> {
+        settings
+            .strv(key)
+            .iter()
+            .filter_map(|entry| resolve_root(entry.as_str()))
+            .collect()
+    };
+    root_status(
+        path,
+        &roots("index-recursive-directories"),
+        &roots("index-single-directories"),
+    )
+}
+
+/// Builds the value of the "Indexing" row of a `file:` subject.
+///
+/// For indexed files this names the data source and tells whether the file has changed since it
+/// was crawled. Otherwise it explains why the file is missing from the index: either it lies
+/// outside the configured folders, or it has not been indexed yet.
+///
+/// # Arguments
+/// * `uri` - The `file:` URI of the subject.
+/// * `indexed` - Whether the store describes the subject as a file.
+/// * `data_source` - The subject's `nie:dataSource`, if any.
+/// * `indexed_mtime` - The subject's `nfo:fileLastModified` in the store, i.e. the modification
+///   time the miner saw when it last crawled the file.
+pub async fn status_widget(
+    uri: &str,
+    indexed: bool,
+    data_source: Option<&str>,
+    indexed_mtime: Option<&str>,
+) -> gtk::Widget {
+    let file = gio::File::for_uri(uri);
+    if !indexed {
+        let description = match file.path().map(|path| configured_root_status(&path)) {
+            Some(RootStatus::Recursive(root) | RootStatus::Single(root)) => format!(
+                "The file is inside the indexed folder {}, but has not been indexed yet. \
+                 Choose \u{201c}Re-index\u{201d} from the context menu of the identifier to \
+                 index it now.",
+                root.display()
+            ),
+            Some(RootStatus::Outside) => "The file is outside the folders that are indexed. \
+                 Add its folder to the search locations in the system settings to index it."
+                .to_string(),
+            Some(RootStatus::Unknown) | None => {
+                "The Tracker file miner does not seem to be installed.".to_string()
+            }
+        };
+        let page = adw::StatusPage::builder()
+            .icon_name("edit-find-symbolic")
+            .title("Not Indexed")
+            .description(description)
+            .build();
+        page.style_context().add_class("compact");
+        return page.upcast();
+    }
+
+    let mut status = match data_source {
+        Some(source) => format!("Indexed from {}", compact(source)),
+        None => "Indexed".to_string(),
+    };
+    // Compare the modification time the miner saw with the current one.
+    let current = file
+        .query_info_future(
+            "time::modified",
+            gio::FileQueryInfoFlags::NONE,
+            glib::Priority::DEFAULT,
+        )
+        .await
+        .ok()
+        .and_then(|info| info.modification_date_time());
+    let crawled = indexed_mtime.and_then(|t| glib::DateTime::from_iso8601(t, None).ok());
+    if let (Some(current), Some(crawled)) = (current, crawled) {
+        // The store keeps whole seconds only.
+        if current.difference(&crawled).as_seconds() >= 1 {
+            status.push_str("; the file has changed since it was crawled");
+        } else {
+            status.push_str("; up to date");
+        }
+    }
+    let label = gtk::Label::new(Some(&status));
+    label.set_halign(gtk::Align::Start);
+    label.set_wrap(true);
+    label.set_margin_start(6);
+    label.set_margin_top(4);
+    label.set_margin_bottom(4);
+    label.upcast()
+}
+
+/// Abbreviates an IRI with a well-known prefix, if possible.
+fn compact(iri: &str) -> String {
+    match rdf::compact(iri) {
+        Some((prefix, local)) => format!("{prefix}:{local}"),
+        None => iri.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_root_expands_home() {
+        assert_eq!(resolve_root("$HOME"), Some(glib::home_dir()));
+        assert_eq!(
+            resolve_root("/srv/media"),
+            Some(PathBuf::from("/srv/media"))
+        );
+        assert_eq!(resolve_root("relative"), None);
+    }
+
+    #[test]
+    fn root_status_distinguishes_single_directories() {
+        let recursive = [PathBuf::from("/home/u/Music")];
+        let single = [PathBuf::from("/home/u")];
+        assert_eq!(
+            root_status(Path::new("/home/u/Music/a/b.ogg"), &recursive, &single),
+            RootStatus::Recursive(PathBuf::from("/home/u/Music"))
+        );
+        assert_eq!(
+            root_status(Path::new("/home/u/notes.txt"), &recursive, &single),
+            RootStatus::Single(PathBuf::from("/home/u"))
+        );
+        assert_eq!(
+            root_status(Path::new("/home/u/Code/x.rs"), &recursive, &single),
+            RootStatus::Outside
+        );
+    }
+}
@@ -0,0 +1,355 @@
+use crate::memory;
+use crate::rdf;
+use crate::source::{Description, MetadataSource, SourceFuture, Text};
+use crate::store;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::thread::LocalKey;
+
+const RDFS_LABEL: &str = "http://www.w3.org/2000/01/rdf-schema#label";
+const RDFS_COMMENT: &str = "http://www.w3.org/2000/01/rdf-schema#comment";
+const RDFS_DOMAIN: &str = "http://www.w3.org/2000/01/rdf-schema#domain";
+const RDFS_RANGE: &str = "http://www.w3.org/2000/01/rdf-schema#range";
+
+/// Largest number of labels (or comments) kept in low-memory mode; beyond it, the cache starts
+/// over.
+const LOW_MEMORY_MAX_LABELS: usize = 256;
+
+/// Texts looked up from the ontology, by IRI. `None` records that the ontology has no text for
+/// the IRI, so that it is not looked up again.
+type Cache = RefCell<HashMap<String, Option<String>>>;
+
+/// The classes a property applies to (`rdfs:domain`) and the classes or datatypes of its values
+/// (`rdfs:range`), as `(domains, ranges)`.
+pub type Signature = (Vec<String>, Vec<String>);
+
+thread_local! {
+    /// The labels looked up so far. They are shared by all windows, so that following links
+    /// does not look up the same predicates again.
+    static LABELS: Cache = RefCell::new(HashMap::new());
+    /// The comments looked up so far, like `LABELS`.
+    static COMMENTS: Cache = RefCell::new(HashMap::new());
+    /// The signatures looked up along with the comments.
+    static SIGNATURES: RefCell<HashMap<String, Signature>> = RefCell::new(HashMap::new());
+}
+
+/// Returns the `rdfs:label` the ontology gives `iri` (a property or class), if it has been
+/// loaded with [`load`] or [`load_blocking`].
+pub fn lookup(iri: &str) -> Option<String> {
+    LABELS.with(|labels| labels.borrow().get(iri).cloned().flatten())
+}
+
+/// Returns the `rdfs:comment` the ontology gives `iri`, if it has been loaded with
+/// [`load_comments`].
+pub fn comment(iri: &str) -> Option<String> {
+    COMMENTS.with(|comments| comments.borrow().get(iri).cloned().flatten())
+}
+
+/// Returns the domains and ranges the ontology gives the property `iri`, if they have been
+/// loaded with [`load_comments`]; both are empty otherwise.
+pub fn signature(iri: &str) -> Signature {
+    SIGNATURES.with(|signatures| signatures.borrow().get(iri).cloned().unwrap_or_default())
+}
+
+/// Returns a query for the labels of `iris`, as `?iri ?label ?lang` rows, where `?lang` is
+/// the language tag of the label (empty for labels without one).
+pub fn labels_query(iris: &[String]) -> String {
+    texts_query(RDFS_LABEL, iris)
+}
+
+/// Returns a query for the comments, domains and ranges of `iris`, as
+/// `?iri ?property ?value ?lang` rows (see [`Description`]).
+pub fn comments_query(iris: &[String]) -> String {
+    let values: Vec<String> = iris.iter().map(|iri| rdf::iri_ref(iri)).collect();
+    format!(
+        "SELECT ?iri ?property ?value (LANG(?value) AS ?lang) WHERE {{ VALUES ?iri {{ {values} }} \
+         VALUES ?property {{ <{RDFS_COMMENT}> <{RDFS_DOMAIN}> <{RDFS_RANGE}> }} \
+         ?iri ?property ?value }}",
+        values = values.join(" ")
+    )
+}
+
+/// Returns a query for the values of `property` (a label or comment) of `iris`.
+fn texts_query(property: &str, iris: &[String]) -> String {
+    let values: Vec<String> = iris.iter().map(|iri| rdf::iri_ref(iri)).collect();
+    format!(
+        "SELECT ?iri ?label (LANG(?label) AS ?lang) WHERE {{ VALUES ?iri {{ {values} }} \
+         ?iri <{property}> ?label }}",
+        values = values.join(" ")
+    )
+}
+
+/// Returns the user's preferred languages as lowercase language tags, most preferred first,
+/// e.g. `["de-ch", "de"]` for the locale `de_CH.UTF-8`.
+pub fn preferred_languages() -> Vec<String> {
+    let mut languages: Vec<String> = Vec::new();
+    for name in glib::language_names() {
+        // Locale names look like `de_CH.UTF-8@euro`; "C" and "POSIX" name no language.
+        let name = name.split(['.', '@']).next().unwrap_or_default();
+        if name.is_empty() || name == "C" || name == "POSIX" {
+            continue;
+        }
+        let tag = name.replace('_', "-").to_lowercase();
+        if !languages.contains(&tag) {
+            languages.push(tag);
+        }
+    }
+    languages
+}
+
+/// Picks the text matching the user's languages among texts in several languages, e.g. the
+/// labels or comments of one property.
+///
+/// The first of `languages` with a text in that language wins; a text in exactly the language
+/// is preferred to one in a regional variant of it (e.g. `de-at` for `de`). Failing that, a text
+/// without a language tag is taken, then an English one, and finally whichever comes first.
+///
+/// # Arguments
+/// * `texts` - `(text, language tag)` pairs; the tag is empty for texts without one.
+/// * `languages` - The preferred languages, as returned by [`preferred_languages`].
+pub fn pick_localized(texts: &[(String, String)], languages: &[String]) -> Option<String> {
+    let primary = |tag: &str| tag.split('-').next().unwrap_or_default().to_lowercase();
+    languages
+        .iter()
+        .find_map(|language| {
+            texts
+                .iter()
+                .find(|(_, tag)| tag.eq_ignore_ascii_case(language))
+                .or_else(|| {
+                    texts
+                        .iter()
+                        .find(|(_, tag)| !tag.is_empty() && primary(tag) == *language)
+                })
+        })
+        .or_else(|| texts.iter().find(|(_, tag)| tag.is_empty()))
+        .or_else(|| texts.iter().find(|(_, tag)| primary(tag) == "en"))
+        .or_else(|| texts.first())
+        .map(|(text, _)| text.clone())
+}
+
+/// Looks up the labels of all `iris` not looked up before with a single query, so that
+/// [`lookup`] (and thereby `friendly_label`) can find them.
+///
+/// Failures are ignored: the heuristic labels are used instead.
+pub async fn load<'a>(iris: impl IntoIterator<Item = &'a str>) {
+    let _ = load_into(
+        &LABELS,
+        |iris| store::client().map(|conn| conn.labels(iris)),
+        iris,
+    )
+    .await;
+}
+
+/// Like [`load`], but looks the labels up in `source` rather than in the store.
+pub async fn load_from<'a, S: MetadataSource + ?Sized>(
+    source: &S,
+    iris: impl IntoIterator<Item = &'a str>,
+) {
+    let _ = load_into(&LABELS, |iris| Ok(source.labels(iris)), iris).await;
+}
+
+/// Looks up the comments, domains and ranges of all `iris` not looked up before with a single
+/// query, so that [`comment`] and [`signature`] can find them.
+///
+/// # Returns
+/// * The error if the store could not be queried, in which case [`comment`] and [`signature`]
+///   find nothing for the IRIs that were missing, and they are looked up again next time.
+pub async fn load_comments<'a>(iris: impl IntoIterator<Item = &'a str>) -> Result<(), glib::Error> {
+    let missing = missing(&COMMENTS, iris);
+    if missing.is_empty() {
+        return Ok(());
+    }
+    let found = store::client()?.descriptions(&missing).await?;
+    record_descriptions(&missing, found);
+    Ok(())
+}
+
+/// Looks up the texts of the `iris` missing from `cache` with `fetch`, and records them in
+/// `cache`. Nothing is recorded if the lookup fails.
+///
+/// `fetch` is only called if some IRIs are missing, so that no connection to the store is made
+/// for nothing.
+async fn load_into<'a>(
+    cache: &'static LocalKey<Cache>,
+    fetch: impl FnOnce(&[String]) -> Result<SourceFuture<Vec<Text>>, glib::Error>,
+    iris: impl IntoIterator<Item = &'a str>,
+) -> Result<(), glib::Error> {
+    let missing = missing(cache, iris);
+    if missing.is_empty() {
+        return Ok(());
+    }
+    let found = fetch(&missing)?.await?;
+    record(cache, &missing, found);
+    Ok(())
+}
+
+/// Stores the comments and signatures found for `looked_up` (see [`Description`]), and that the
+/// other IRIs have none.
+fn record_descriptions(looked_up: &[String], found: Vec<Description>) {
+    let mut comments = Vec::new();
+    let mut signatures: HashMap<String, Signature> = HashMap::new();
+    for (iri, property, value, lang) in found {
+        match property.as_str() {
+            RDFS_COMMENT => comments.push((iri, value, lang)),
+            RDFS_DOMAIN => signatures.entry(iri).or_default().0.push(value),
+            RDFS_RANGE => signatures.entry(iri).or_default().1.push(value),
+            _ => {}
+        }
+    }
+    record(&COMMENTS, looked_up, comments);
+    SIGNATURES.with(|cached| {
+        let mut cached = cached.borrow_mut();
+        // Signatures are dropped along with the comments in low-memory mode (see `record`).
+        if memory::is_low() && cached.len() + looked_up.len() > LOW_MEMORY_MAX_LABELS {
+            cached.clear();
+        }
+        for iri in looked_up {
+            cached.insert(iri.clone(), signatures.remove(iri).unwrap_or_default());
+        }
+    });
+}
+
+/// Like [`load`], but blocks until the labels have been looked up.
+pub fn load_blocking<'a>(iris: impl IntoIterator<Item = &'a str>) {
+    glib::MainContext::default().block_on(load(iris));
+}
+
+/// Returns the IRIs among `iris` that have not been looked up in `cache` yet, without
+/// duplicates.
+fn missing<'a>(
+    cache: &'static LocalKey<Cache>,
+    iris: impl IntoIterator<Item = &'a str>,
+) -> Vec<String> {
+    cache.with(|labels| {
+        let labels = labels.borrow();
+        let mut missing: Vec<String> = Vec::new();
+        for iri in iris {
+            if !iri.is_empty() && !labels.contains_key(iri) && !missing.iter().any(|m| m == iri) {
+                missing.push(iri.to_string());
+            }
+        }
+        missing
+    })
+}
+
+/// Stores the labels (or comments) found for `looked_up` in `cache`, as
+/// `(iri, label, language tag)` triples, and that the other IRIs have none.
+///
+/// An IRI with labels in several languages keeps the one matching the user's languages (see
+/// [`pick_localized`]).
+fn record(
+    cache: &'static LocalKey<Cache>,
+    looked_up: &[String],
+    found: Vec<(String, String, String)>,
+) {
+    let languages = preferred_languages();
+    cache.with(|labels| {
+        let mut labels = labels.borrow_mut();
+        // The labels just looked up are about to be shown, so only older ones are dropped.
+        if memory::is_low() && labels.len() + looked_up.len() > LOW_MEMORY_MAX_LABELS {
+            labels.clear();
+        }
+        for iri in looked_up {
+            let texts: Vec<(String, String)> = found
+                .iter()
+                .filter(|(i, label, _)| i == iri && !label.is_empty())
+                .map(|(_, label, lang)| (label.clone(), lang.clone()))
+                .collect();
+            labels.insert(iri.clone(), pick_localized(&texts, &languages));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_query_lists_iris() {
+        assert_eq!(
+            labels_query(&["urn:a".to_string(), "urn:b".to_string()]),
+            format!(
+                "SELECT ?iri ?label (LANG(?label) AS ?lang) WHERE {{ VALUES ?iri {{ <urn:a> <urn:b> }} \
+                 ?iri <{RDFS_LABEL}> ?label }}"
+            )
+        );
+    }
+
+    #[test]
+    fn recorded_labels_are_not_looked_up_again() {
+        record(
+            &LABELS,
+            &["urn:a".to_string(), "urn:b".to_string()],
+            vec![
+                ("urn:a".to_string(), "First".to_string(), String::new()),
+                ("urn:a".to_string(), "Second".to_string(), String::new()),
+            ],
+        );
+        assert_eq!(lookup("urn:a").as_deref(), Some("First"));
+        assert_eq!(lookup("urn:b"), None);
+        assert_eq!(
+            missing(&LABELS, ["urn:a", "urn:b", "urn:c", "urn:c", ""]),
+            ["urn:c"]
+        );
+        // Comments are cached separately.
+        assert_eq!(missing(&COMMENTS, ["urn:a"]), ["urn:a"]);
+        record(
+            &COMMENTS,
+            &["urn:a".to_string()],
+            vec![("urn:a".to_string(), "The first".to_string(), String::new())],
+        );
+        assert_eq!(comment("urn:a").as_deref(), Some("The first"));
+        assert_eq!(lookup("urn:a").as_deref(), Some("First"));
+    }
+
+    #[test]
+    fn descriptions_are_split_into_comments_and_signatures() {
+        let description = |iri: &str, property: &str, value: &str| {
+            (
+                iri.to_string(),
+                property.to_string(),
+                value.to_string(),
+                String::new(),
+            )
+        };
+        record_descriptions(
+            &["urn:p".to_string(), "urn:q".to_string()],
+            vec![
+                description("urn:p", RDFS_COMMENT, "A property"),
+                description("urn:p", RDFS_DOMAIN, "urn:Thing"),
+                description("urn:p", RDFS_RANGE, "urn:Value"),
+                description("urn:p", RDFS_RANGE, "urn:Other"),
+            ],
+        );
+        assert_eq!(comment("urn:p").as_deref(), Some("A property"));
+        assert_eq!(
+            signature("urn:p"),
+            (
+                vec!["urn:Thing".to_string()],
+                vec!["urn:Value".to_string(), "urn:Other".to_string()]
+            )
+        );
+        assert_eq!(comment("urn:q"), None);
+        assert_eq!(signature("urn:q"), (Vec::new(), Vec::new()));
+        assert!(missing(&COMMENTS, ["urn:p", "urn:q"]).is_empty());
+    }
+
+    #[test]
+    fn pick_localized_prefers_user_languages() {
+        let texts: Vec<(String, String)> = [("Size", "en"), ("Größe", "de-AT"), ("Taille", "fr")]
+            .iter()
+            .map(|(text, tag)| (text.to_string(), tag.to_string()))
+            .collect();
+        let pick = |languages: &[&str]| {
+            let languages: Vec<String> = languages.iter().map(|l| l.to_string()).collect();
+            pick_localized(&texts, &languages)
+        };
+        assert_eq!(pick(&["fr-ch", "fr"]).as_deref(), Some("Taille"));
+        assert_eq!(pick(&["de-at", "de"]).as_deref(), Some("Größe"));
+        // A regional variant of a preferred language beats any less preferred language.
+        assert_eq!(pick(&["de-ch", "de", "fr"]).as_deref(), Some("Größe"));
+        assert_eq!(pick(&["de"]).as_deref(), Some("Größe"));
+        assert_eq!(pick(&["nl"]).as_deref(), Some("Size"));
+        assert_eq!(pick_localized(&[], &[]), None);
+    }
+}
@@ -1,30 +1,190 @@
 use adw::prelude::*;
+use adw::subclass::prelude::ObjectSubclassIsExt;
 use clap::Parser;
-use std::cell::RefCell;
+use serde_json::json;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
+use std::path::Path;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 use tracker::prelude::*;
 
+mod about;
+mod announce;
+mod backlinks;
+mod bookmarks;
+mod breadcrumbs;
+mod collections;
+mod compare;
+mod contents;
+mod context;
+mod dnd;
+mod duplicates;
+mod events;
+mod export;
+mod file_info;
+mod finder;
+mod fulltext;
+mod geo;
+mod geometry;
+mod gvfs;
+mod handlers;
+mod headless;
+mod history;
+mod indexing;
+mod labels;
+mod media;
+mod memory;
+mod notes;
 mod options;
+mod ordering;
+mod pages;
+mod pdf;
+mod pinboard;
+mod preferences;
+mod prefixes;
+mod queue;
+mod rdf;
+mod recent;
+mod registry;
+mod related;
+mod search;
+mod service;
+mod settings;
+mod share;
+mod source;
+mod start;
+mod store;
+mod table;
+mod tags;
+mod trace;
+mod tree;
+mod visits;
+
+use context::Context;
+use history::History;
+use source::MetadataSource;
+use store::StoreClient;
 
 const APP_ID: &str = "com.example.DesktopFileInformation";
 
+/// URI scheme for deep links to a node view, e.g. `x-fileinfo:file:///etc/hostname`. The
+/// desktop entry registers the application as handler for this scheme.
+const DEEP_LINK_SCHEME: &str = "x-fileinfo:";
+
+/// Interval between opening two queued subjects (see `queue_open`).
+const OPEN_INTERVAL: Duration = Duration::from_millis(150);
+
+/// How long changes reported by the store are collected before the subjects are reloaded.
+const STORE_CHANGE_DELAY: Duration = Duration::from_millis(250);
+
 const TOOLTIP_MAX_CHARS: usize = 80;
 const COMMENT_TOOLTIP_MAX_CHARS: usize = TOOLTIP_MAX_CHARS * 3;
 
+/// Largest number of handler checks remembered in low-memory mode (see `uri_has_handler`).
+const HANDLER_CHECKS_LOW_MEMORY: usize = 256;
+
 const XSD_DATETYPE: &str = "http://www.w3.org/2001/XMLSchema#dateType";
 const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
-const RDFS_COMMENT: &str = "http://www.w3.org/2000/01/rdf-schema#comment";
 const NIE_INTERPRETED_AS: &str = "http://tracker.api.gnome.org/ontology/v3/nie#interpretedAs";
+const NIE_IS_STORED_AS: &str = "http://tracker.api.gnome.org/ontology/v3/nie#isStoredAs";
+const NIE_DATA_SOURCE: &str = "http://tracker.api.gnome.org/ontology/v3/nie#dataSource";
 const NIE_MIME_TYPE: &str = "http://tracker.api.gnome.org/ontology/v3/nie#mimeType";
+const NIE_TITLE: &str = "http://tracker.api.gnome.org/ontology/v3/nie#title";
+const NFO_FILE_NAME: &str = "http://tracker.api.gnome.org/ontology/v3/nfo#fileName";
+const NFO_FILE_LAST_MODIFIED: &str =
+    "http://tracker.api.gnome.org/ontology/v3/nfo#fileLastModified";
+const NFO_FILE_SIZE: &str = "http://tracker.api.gnome.org/ontology/v3/nfo#fileSize";
+const NFO_FILE_CREATED: &str = "http://tracker.api.gnome.org/ontology/v3/nfo#fileCreated";
+const NFO_FILE_LAST_ACCESSED: &str =
+    "http://tracker.api.gnome.org/ontology/v3/nfo#fileLastAccessed";
+/// When the file's inode last changed (its `ctime`), e.g. by renaming it or changing its
+/// permissions. The ontologies of the store have no property for it, so this is our own.
+const FILE_STATUS_CHANGED: &str = "urn:x-fileinfo:stat#fileStatusChanged";
+const XSD_INTEGER: &str = "http://www.w3.org/2001/XMLSchema#integer";
+const XSD_STRING: &str = "http://www.w3.org/2001/XMLSchema#string";
+const RDFS_SUB_CLASS_OF: &str = "http://www.w3.org/2000/01/rdf-schema#subClassOf";
 const FILEDATAOBJECT: &str = "http://tracker.api.gnome.org/ontology/v3/nfo#FileDataObject";
 
+/// Numeric properties whose values are placed within their store-wide range when
+/// `--value-ranges` is given.
+const RANGED_PREDICATES: &[&str] = &[
+    "http://tracker.api.gnome.org/ontology/v3/nfo#fileSize",
+    "http://tracker.api.gnome.org/ontology/v3/nfo#duration",
+    "http://tracker.api.gnome.org/ontology/v3/nao#numericRating",
+];
+
+/// Predicates with more values than this show only the first `COLLAPSED_VALUES` of them, with
+/// an expander for the rest, instead of a grid row for each.
+const COLLAPSE_THRESHOLD: usize = 10;
+
+/// The number of values shown of a predicate with more than `COLLAPSE_THRESHOLD` values.
+const COLLAPSED_VALUES: usize = 5;
+
+/// Values with more characters than this are clamped to `CLAMPED_LINES` lines in the grid.
+const LONG_VALUE_CHARS: usize = 240;
+
+/// The number of lines long values are clamped to (see `clamp_long_value`).
+const CLAMPED_LINES: i32 = 3;
+
+/// Date-time patterns offered in the "Date Format" dialog, as `(label, pattern)` pairs.
+const DATE_FORMAT_PRESETS: &[(&str, &str)] = &[
+    ("Default", settings::DEFAULT_DATE_FORMAT),
+    ("Locale", "%c"),
+    ("ISO 8601", "%FT%T%:z"),
+];
+
+/// Properties whose values are durations in seconds, displayed as e.g. "1 h 23 m 45 s".
+const DURATION_PREDICATES: &[&str] = &["http://tracker.api.gnome.org/ontology/v3/nfo#duration"];
+
+/// Largest number of backlinks listed in low-memory mode (see `memory::init`).
+const BACKLINKS_LOW_MEMORY_LIMIT: usize = 100;
+
+/// The style class of windows listing metadata in a grid, to which the grid styles apply (see
+/// `install_styles`).
+const METADATA_WINDOW_CLASS: &str = "metadata";
+
+/// Technical properties whose rows are hidden unless "Show Advanced Properties" is on.
+const ADVANCED_PREDICATES: &[&str] = &[
+    RDF_TYPE,
+    NIE_DATA_SOURCE,
+    "http://tracker.api.gnome.org/ontology/v3/nrl#added",
+    "http://tracker.api.gnome.org/ontology/v3/nrl#modified",
+];
+
 #[derive(Clone, Default)]
 struct TableRow {
     display_predicate: String,
     native_predicate: String,
     display_value: String,
     native_value: String,
+    /// The language tag of a literal value, e.g. "de", or empty.
+    language: String,
+    source: ValueSource,
+}
+
+/// Where the value of a table row comes from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum ValueSource {
+    /// Read from the store, i.e. indexed by a miner or written by an application.
+    #[default]
+    Indexed,
+    /// Computed by this application while loading the view, e.g. from the file system.
+    Derived,
+    /// Read from the store, but about the information element the subject is interpreted as
+    /// (see `Context::merge_interpreted`).
+    Interpreted,
+}
+
+impl ValueSource {
+    /// Returns the name of the source as used in exports.
+    fn as_str(self) -> &'static str {
+        match self {
+            ValueSource::Indexed => "indexed",
+            ValueSource::Derived => "derived",
+            ValueSource::Interpreted => "interpreted",
+        }
+    }
 }
 
 /// Entry point. Parses command-line arguments and sets up the main `adw::Application` instance.
@@ -32,7 +192,53 @@ struct TableRow {
 /// Supported command-line flags:
 /// * `-h` / `--help` - only print usage help string and exit.
 /// * `-u` / `--uri`  - interpret the provided argument as a URI rather than a filesystem path.
+///   Without it, arguments starting with a scheme are taken as URIs (see `item_uri`).
+/// * `--path` - interpret the provided argument as a filesystem path even if it looks like a URI.
 /// * `-d` / `--debug` - print additional diagnostic information to stderr.
+/// * `--debug-format <text|json>` - write the diagnostic events (queries started and finished,
+///   row counts, durations) as text or as JSON lines (see `trace::event`).
+/// * `--screenshot <PNG_FILE>` - with `--debug`, save a snapshot of the window once the loaded
+///   metadata has been painted (see `save_screenshot`).
+/// * `--post-load-hook <COMMAND>` - run a shell command after the metadata has loaded, passing
+///   it the metadata as JSON on stdin.
+/// * `--share` - serve the metadata of each window as HTML and JSON on a localhost port.
+/// * `--same-window` / `--new-window` - follow links in the current window or in new windows,
+///   overriding the stored preference.
+/// * `--date-format <FORMAT>` - format date-times with a custom `strftime` pattern, overriding
+///   the stored preference.
+/// * `--value-ranges` - show where numeric values lie within the range found across the store.
+/// * `--timings` - show how long connecting, querying and rendering took below each subject.
+/// * `--low-memory` - turn off previews, caches and value ranges and ask for fewer results, as
+///   is done automatically on machines with little memory (see `memory::init`).
+/// * `--fixture <TURTLE_FILE>` - serve all queries from an in-memory store holding the triples
+///   of a Turtle file instead of from Tracker.
+/// * `--service` - keep running and open windows requested over D-Bus (see `service`). The
+///   item argument is optional in this mode.
+///   Requests are opened through a rate-limited queue (see `queue_open`).
+/// * `--file-manager` - with `--service`, also provide `org.freedesktop.FileManager1`, so that
+///   "show properties" requests from desktop components open here (see
+///   `service::export_file_manager`).
+/// * `--start-page <PAGE>` - what to show when no item is given: `file-chooser`, `recent`,
+///   `search` or `none`, overriding the stored preference (see `start::StartPage`).
+/// * `--json` - print the metadata as JSON instead of showing a window, exiting with a status
+///   telling why if that fails (see `headless::run`).
+/// * `--watch` - with `--json`, keep running and print the metadata again whenever it changes.
+/// * `--predicate <PREDICATE>` - with `--json`, only print the values of the given properties;
+///   may be repeated.
+/// * `--run-saved <NAME>` - print the resources matching a collection saved in the query
+///   builder instead of showing a window (see `headless::run_saved`).
+/// * `--format <FORMAT>` - with `--run-saved`, print `csv` (the default) or `json`.
+/// * `--export-dir <DIR>` - write the metadata of each item given (any number of them) to a
+///   file in `DIR` instead of showing a window (see `headless::export_batch`).
+/// * `--export-format <FORMAT>` - with `--export-dir`, write `csv`, `json` or `html` files,
+///   overriding the stored preference.
+/// * `--combined` - with `--export-dir`, write a single JSON array holding every item.
+/// * `--search <TERM>` - open a window searching the content of the indexed files for `TERM`
+///   (see `fulltext::open_window`).
+/// * `--install-handlers` - register the executable with the desktop and exit (see
+///   `handlers::install`).
+///
+/// Items using the `x-fileinfo:` scheme are deep links and open the node they point to.
 fn main() {
     // Create a new `adw::Application` instance with a specific application ID and set its launch flags.
     let app = adw::Application::builder()
@@ -58,34 +264,134 @@ fn main() {
             }
         };
 
-        env_logger::Builder::new()
-            .filter_level(if opts.debug {
-                log::LevelFilter::Debug
-            } else {
-                log::LevelFilter::Warn
-            })
-            .init();
+        trace::init(opts.debug, opts.debug_format);
+        // Everything after this, including the fixture and the actions, may depend on
+        // low-memory mode; it is decided once per process, here, so that the files opened later
+        // through `open` do not turn off a mode forced with `--low-memory`.
+        memory::init(opts.low_memory);
+
+        if let Some(path) = &opts.fixture {
+            match store::MemoryStore::new().and_then(|s| s.load_turtle(path).map(|()| s)) {
+                Ok(fixture) => store::install(Rc::new(fixture)),
+                Err(err) => {
+                    eprintln!("Failed to load fixture {}: {err}", path.display());
+                    return 1;
+                }
+            }
+            trace::event(
+                "fixture_loaded",
+                json!({ "path": path.display().to_string() }),
+            );
+        }
 
-        let uri = if opts.uri {
-            opts.item.clone()
-        } else {
-            gio::File::for_path(&opts.item).uri().to_string()
-        };
+        let ctx = Rc::new(Context::from_options(&opts));
+        add_app_actions(app, &ctx);
+
+        if opts.install_handlers {
+            return handlers::run_install();
+        }
+        if let Some(name) = &opts.run_saved {
+            return headless::run_saved(name, opts.format, &ctx);
+        }
+        if let Some(dir) = &opts.export_dir {
+            let uris: Vec<String> = opts
+                .item
+                .iter()
+                .chain(&opts.more_items)
+                .map(|item| item_uri(item, &opts))
+                .collect();
+            let format = opts.export_format.unwrap_or(ctx.export_format.get());
+            return headless::export_batch(&uris, dir, format, opts.combined, &ctx);
+        }
 
         app.activate();
-        open_subject_window(app, uri, opts.debug);
+
+        // In service mode, keep the application alive without windows for as long as the bus
+        // name is owned, and open a window for each request.
+        if opts.service {
+            let hold = Rc::new(RefCell::new(Some(app.hold())));
+            let app_clone = app.clone();
+            let ctx_clone = ctx.clone();
+            let on_show: Rc<dyn Fn(&str)> = Rc::new(move |requested| {
+                let requested = requested.to_string();
+                let app = app_clone.clone();
+                let ctx = ctx_clone.clone();
+                // Virtual locations are resolved without blocking the windows already open.
+                glib::MainContext::default().spawn_local(async move {
+                    let uri = if let Some(target) = deep_link_target(&requested) {
+                        target
+                    } else if requested.starts_with('/') {
+                        gio::File::for_path(&requested).uri().to_string()
+                    } else {
+                        gvfs::resolve_async(&requested).await
+                    };
+                    queue_open(&app, vec![uri], &ctx);
+                });
+            });
+            // The names stay owned until the process exits.
+            if opts.file_manager {
+                let _owner = service::export_file_manager(on_show.clone());
+            }
+            let _owner = service::export(on_show, move || {
+                hold.borrow_mut().take();
+            });
+        }
+
+        if let Some(item) = &opts.item {
+            let uri = item_uri(item, &opts);
+            if opts.json {
+                return headless::run(app, &uri, &ctx, opts.watch, &opts.predicates);
+            }
+            open_subject_window(app, uri, &ctx);
+        } else if let Some(text) = &opts.search {
+            fulltext::open_window(app, &ctx, text);
+        } else if !opts.service {
+            let page = opts
+                .start_page
+                .unwrap_or_else(start::StartPage::from_settings);
+            // A launch without a file is the usual first run of a build from source, so that
+            // is when registering with the desktop is offered.
+            let app_clone = app.clone();
+            let ctx_clone = ctx.clone();
+            handlers::offer(app, move || start::show(&app_clone, page, &ctx_clone));
+        }
         0
     });
 
     // Register a handler for when files are opened by the system with the app (e.g., double-click
     // in file manager).
     app.connect_open(|app, files, _| {
-        // If at least one file is present, build the UI for it.
-        if let Some(file) = files.first() {
-            open_subject_window(app, file.uri().to_string(), false);
+        // Build the UI for each file. A large selection is opened through the rate-limited
+        // queue, after asking for confirmation.
+        if !files.is_empty() {
+            let ctx = Rc::new(Context::from_settings());
+            add_app_actions(app, &ctx);
+            let requested: Vec<String> = files.iter().map(|file| file.uri().to_string()).collect();
+            // Virtual locations are resolved without blocking the windows already open; the
+            // application is held until the subjects have been queued.
+            let hold = app.hold();
+            let app = app.clone();
+            glib::MainContext::default().spawn_local(async move {
+                let mut uris = Vec::new();
+                for uri in requested {
+                    uris.push(match deep_link_target(&uri) {
+                        Some(target) => target,
+                        None => gvfs::resolve_async(&uri).await,
+                    });
+                }
+                queue_open(&app, uris, &ctx);
+                drop(hold);
+            });
         }
     });
 
+    // Install the style sheet and follow the preferences once, for all windows the application
+    // will open.
+    app.connect_startup(|app| {
+        install_styles();
+        preferences::connect_changes(app);
+    });
+
     // Register a no-op handler for application activation (to satisfy GTK's requirements).
     app.connect_activate(|_| {});
 
@@ -93,47 +399,40 @@ fn main() {
     app.run();
 }
 
-/// Builds and presents the main window UI for a given URI.
-///
-/// This function creates and configures the main GTK application window, sets up styling,
-/// assembles all the widgets, registers callbacks, and asynchronously populates the UI
-/// with information about the file or node referenced by the `uri` argument.
+/// Installs the application's style sheet for the default display. This is done once, on
+/// startup, rather than for every window.
 ///
-/// # Arguments
-/// * `app` - The application instance, used for context and for spawning additional windows.
-/// * `uri` - The URI (can be a file path or another type) to display information about.
-/// * `debug` - If true, prints additional diagnostic info to stderr.
-fn open_subject_window(app: &adw::Application, uri: String, debug: bool) {
-    // Create the main application window with specified size and title.
-    let window = adw::ApplicationWindow::builder()
-        .application(app)
-        .default_width(590)
-        .default_height(400)
-        .title("File Information")
-        .build();
-
-    // Add common actions (i.e., copy to clipboard, open URI) for context menus in this window.
-    add_common_actions(&window);
-
-    // Prepare a CSS provider and style the grid and its children.
+/// The rules are scoped by window class: windows listing metadata in a grid carry
+/// `METADATA_WINDOW_CLASS`, and the styles of rows only subject windows have apply to windows
+/// with the class `subject`. GTK therefore only matches the rules against the widgets of those
+/// windows.
+fn install_styles() {
     let provider = gtk::CssProvider::new();
     let css = r#"
-        grid#data-grid {
+        window.metadata grid#data-grid {
             background-color: transparent;
             margin: 0;
             padding: 0;
         }
-        label.first-col {
+        window.metadata label.first-col {
             font-weight: bold;
         }
-        textview.bordered {
+        window.subject label.favorite {
+            color: @accent_color;
+        }
+        window.subject label.badge {
+            font-size: smaller;
+            padding: 0 4px;
+            border-radius: 4px;
+            background-color: alpha(@accent_bg_color, 0.2);
+        }
+        window.subject textview.bordered {
             border: 1px solid @separator_color;
             padding: 4px;
             margin-right: 6px;
         }
     "#;
     provider.load_from_data(css);
-    // Apply CSS styling globally to all GTK widgets for the current display.
     if let Some(display) = gdk4::Display::default() {
         gtk::style_context_add_provider_for_display(
             &display,
@@ -141,181 +440,2125 @@ fn open_subject_window(app: &adw::Application, uri: String, debug: bool) {
             gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
         );
     }
+}
 
-    // Create the header bar (top bar) with a label that displays the title or loading state.
-    let header = adw::HeaderBar::new();
-    header.set_show_end_title_buttons(true);
+/// Adds application-wide actions that are shared by all windows.
+///
+/// These are the stateful `app.same-window` action, which toggles whether links are followed
+/// in the current window, the stateful `app.compact-uris` action, which toggles prefixed names
+/// (see `predicate_label`), the stateful `app.show-advanced` action, which toggles technical rows
+/// (see `is_advanced`), the stateful `app.relative-dates` action, which toggles "3 days ago"
+/// after dates (see `relative_date`), the stateful `app.show-datatypes` action, which toggles a
+/// column with the datatype of literals, the stateful `app.merge-interpreted` action, which
+/// toggles merging in the properties of `nie:interpretedAs` elements, the stateful
+/// `app.row-order` action, which chooses how the rows of
+/// the property table are ordered, `app.toggle-favorite`, which marks a predicate as a favorite
+/// listed first, `app.date-format`, which prompts for the date-time pattern,
+/// and the stateful `app.start-page` action, which chooses what a launch without a file shows.
+/// They persist the choice in the user's preferences. `app.preferences` opens the preferences
+/// window (see `preferences::open_window`), `app.install-handlers` registers the application
+/// with the desktop (see `handlers::install`), `app.new-window` shows the start page in a new
+/// window, `app.history` lists the subjects shown before (see `visits::open_window`),
+/// `app.search` opens the window searching the content of files (see `fulltext::open_window`),
+/// `app.clear-recent` empties the "Recent" menu (see `recent::clear`), and
+/// `app.shortcuts` and `app.about` show the keyboard shortcuts and the About window (see the
+/// `about` module).
+///
+/// # Arguments
+/// * `app` - The application to add the actions to.
+/// * `ctx` - The shared context whose settings the actions control.
+fn add_app_actions(app: &adw::Application, ctx: &Rc<Context>) {
+    let same_window =
+        gio::SimpleAction::new_stateful("same-window", None, &ctx.same_window.get().to_variant());
+    let ctx_clone = ctx.clone();
+    same_window.connect_change_state(move |action, state| {
+        if let Some(enabled) = state.and_then(|s| s.get::<bool>()) {
+            ctx_clone.same_window.set(enabled);
+            settings::set_preference(settings::SAME_WINDOW, enabled);
+            action.set_state(&enabled.to_variant());
+        }
+    });
+    app.add_action(&same_window);
+
+    // Switching between friendly labels and prefixed names reloads every open subject.
+    let compact_uris =
+        gio::SimpleAction::new_stateful("compact-uris", None, &ctx.compact_uris.get().to_variant());
+    let ctx_clone = ctx.clone();
+    compact_uris.connect_change_state(move |action, state| {
+        if let Some(enabled) = state.and_then(|s| s.get::<bool>()) {
+            ctx_clone.compact_uris.set(enabled);
+            settings::set_preference(settings::COMPACT_URIS, enabled);
+            action.set_state(&enabled.to_variant());
+            let uris = ctx_clone.open_subjects.borrow().uris();
+            for uri in uris {
+                ctx_clone.changes.publish(&uri);
+            }
+        }
+    });
+    app.add_action(&compact_uris);
 
-    let header_label = gtk::Label::new(Some("Loading…"));
-    header.set_title_widget(Some(&header_label));
+    // Showing or hiding technical rows (see `is_advanced`) reloads every open subject, too.
+    let show_advanced = gio::SimpleAction::new_stateful(
+        "show-advanced",
+        None,
+        &ctx.show_advanced.get().to_variant(),
+    );
+    let ctx_clone = ctx.clone();
+    show_advanced.connect_change_state(move |action, state| {
+        if let Some(enabled) = state.and_then(|s| s.get::<bool>()) {
+            ctx_clone.show_advanced.set(enabled);
+            settings::set_preference(settings::SHOW_ADVANCED, enabled);
+            action.set_state(&enabled.to_variant());
+            let uris = ctx_clone.open_subjects.borrow().uris();
+            for uri in uris {
+                ctx_clone.changes.publish(&uri);
+            }
+        }
+    });
+    app.add_action(&show_advanced);
 
-    // Construct a grid that will display all the file/node information in two columns.
-    let grid = gtk::Grid::builder()
-        .column_homogeneous(false)
-        .hexpand(true)
-        .vexpand(true)
-        .halign(gtk::Align::Fill)
-        .valign(gtk::Align::Fill)
-        .build();
-    grid.set_widget_name("data-grid");
+    // Showing how long ago dates were reloads every open subject, too.
+    let relative_dates = gio::SimpleAction::new_stateful(
+        "relative-dates",
+        None,
+        &ctx.relative_dates.get().to_variant(),
+    );
+    let ctx_clone = ctx.clone();
+    relative_dates.connect_change_state(move |action, state| {
+        if let Some(enabled) = state.and_then(|s| s.get::<bool>()) {
+            ctx_clone.relative_dates.set(enabled);
+            settings::set_preference(settings::RELATIVE_DATES, enabled);
+            action.set_state(&enabled.to_variant());
+            let uris = ctx_clone.open_subjects.borrow().uris();
+            for uri in uris {
+                ctx_clone.changes.publish(&uri);
+            }
+        }
+    });
+    app.add_action(&relative_dates);
 
-    // Add the grid inside a viewport, which allows for scrolling if content is large.
-    let viewport = gtk::Viewport::builder()
-        .scroll_to_focus(false)
-        .child(&grid)
-        .build();
+    // Showing the datatype column reloads every open subject, too.
+    let show_datatypes = gio::SimpleAction::new_stateful(
+        "show-datatypes",
+        None,
+        &ctx.show_datatypes.get().to_variant(),
+    );
+    let ctx_clone = ctx.clone();
+    show_datatypes.connect_change_state(move |action, state| {
+        if let Some(enabled) = state.and_then(|s| s.get::<bool>()) {
+            ctx_clone.show_datatypes.set(enabled);
+            settings::set_preference(settings::SHOW_DATATYPES, enabled);
+            action.set_state(&enabled.to_variant());
+            let uris = ctx_clone.open_subjects.borrow().uris();
+            for uri in uris {
+                ctx_clone.changes.publish(&uri);
+            }
+        }
+    });
+    app.add_action(&show_datatypes);
 
-    // Put the viewport into a scrollable window with minimum dimensions.
-    let scroll = gtk::ScrolledWindow::builder()
-        .min_content_width(590)
-        .min_content_height(400)
-        .child(&viewport)
-        .build();
+    // Merging in the interpreted content of files reloads every open subject, too.
+    let merge_interpreted = gio::SimpleAction::new_stateful(
+        "merge-interpreted",
+        None,
+        &ctx.merge_interpreted.get().to_variant(),
+    );
+    let ctx_clone = ctx.clone();
+    merge_interpreted.connect_change_state(move |action, state| {
+        if let Some(enabled) = state.and_then(|s| s.get::<bool>()) {
+            ctx_clone.merge_interpreted.set(enabled);
+            settings::set_preference(settings::MERGE_INTERPRETED, enabled);
+            action.set_state(&enabled.to_variant());
+            let uris = ctx_clone.open_subjects.borrow().uris();
+            for uri in uris {
+                ctx_clone.changes.publish(&uri);
+            }
+        }
+    });
+    app.add_action(&merge_interpreted);
 
-    // Create a custom toolbar to host header and bottom bar.
-    let toolbar = adw::ToolbarView::new();
-    toolbar.add_top_bar(&header);
+    // Choosing another row order reloads every open subject as well.
+    let row_order = gio::SimpleAction::new_stateful(
+        "row-order",
+        Some(glib::VariantTy::STRING),
+        &ctx.row_order.get().as_str().to_variant(),
+    );
+    let ctx_clone = ctx.clone();
+    row_order.connect_change_state(move |action, state| {
+        let Some(order) = state
+            .and_then(|s| s.str())
+            .and_then(ordering::RowOrder::from_name)
+        else {
+            return;
+        };
+        ctx_clone.row_order.set(order);
+        settings::set_preference(settings::ROW_ORDER, order.as_str());
+        action.set_state(&order.as_str().to_variant());
+        let uris = ctx_clone.open_subjects.borrow().uris();
+        for uri in uris {
+            ctx_clone.changes.publish(&uri);
+        }
+    });
+    app.add_action(&row_order);
+
+    // Marking or unmarking a favorite predicate (see `add_copy_menu`) reorders every open
+    // subject.
+    let toggle_favorite = gio::SimpleAction::new("toggle-favorite", Some(glib::VariantTy::STRING));
+    let ctx_clone = ctx.clone();
+    toggle_favorite.connect_activate(move |_, param| {
+        let Some(predicate) = param.and_then(|p| p.get::<String>()) else {
+            return;
+        };
+        let favorites = ordering::toggle(&ctx_clone.favorites.borrow(), &predicate);
+        settings::set_preference(settings::FAVORITE_PREDICATES, favorites.clone());
+        ctx_clone.favorites.replace(favorites);
+        let uris = ctx_clone.open_subjects.borrow().uris();
+        for uri in uris {
+            ctx_clone.changes.publish(&uri);
+        }
+    });
+    app.add_action(&toggle_favorite);
 
-    // Store table data (file/node attributes) in a shared, mutable reference for use by callbacks.
-    let table_data: Rc<RefCell<Vec<TableRow>>> = Rc::new(RefCell::new(Vec::new()));
-
-    // ----- Bottom bar with buttons -----
-
-    // "Close" button: closes the window when clicked.
-    let close_button = gtk::Button::with_label("Close");
-    let win_clone = window.clone();
-    close_button.connect_clicked(move |_| {
-        win_clone.close();
-    });
-
-    // "Copy" button: copies the displayed table as CSV to the clipboard.
-    let copy_button = gtk::Button::with_label("Copy");
-    let data_clone = table_data.clone();
-    copy_button.connect_clicked(move |_| {
-        let rows = data_clone.borrow();
-        // Prepare a CSV writer and add headers.
-        let mut wtr = csv::WriterBuilder::new()
-            .has_headers(true)
-            .from_writer(vec![]);
-        let _ = wtr.write_record([
-            "Display Predicate",
-            "Native Predicate",
-            "Display Value",
-            "Native Value",
-        ]);
-        // Write each row from the table to CSV.
-        for r in rows.iter() {
-            let _ = wtr.write_record([
-                &r.display_predicate,
-                &r.native_predicate,
-                &r.display_value,
-                &r.native_value,
-            ]);
-        }
-        // Convert CSV to UTF-8 string and copy to clipboard if successful.
-        if let Ok(data) = String::from_utf8(wtr.into_inner().unwrap_or_default()) {
-            if let Some(display) = gdk4::Display::default() {
-                display.clipboard().set_text(&data);
-            }
+    let date_format = gio::SimpleAction::new("date-format", None);
+    let ctx_clone = ctx.clone();
+    let app_clone = app.clone();
+    date_format.connect_activate(move |_, _| {
+        prompt_date_format(&app_clone, &ctx_clone);
+    });
+    app.add_action(&date_format);
+
+    let start_page = gio::SimpleAction::new_stateful(
+        "start-page",
+        Some(glib::VariantTy::STRING),
+        &start::StartPage::from_settings().as_str().to_variant(),
+    );
+    start_page.connect_change_state(|action, state| {
+        let Some(page) = state
+            .and_then(|s| s.str())
+            .and_then(start::StartPage::from_name)
+        else {
+            return;
+        };
+        settings::set_preference(settings::START_PAGE, page.as_str());
+        action.set_state(&page.as_str().to_variant());
+    });
+    app.add_action(&start_page);
+
+    // "Add to Desktop" registers the application like `--install-handlers`, reporting the
+    // outcome in the window it was chosen from.
+    let install_handlers = gio::SimpleAction::new("install-handlers", None);
+    let app_clone = app.clone();
+    install_handlers.connect_activate(move |_, _| {
+        let message = match handlers::install() {
+            Ok(_) => "Added File Information to the desktop".to_string(),
+            Err(err) => err,
+        };
+        if let Some(window) = app_clone.active_window() {
+            announce::announce(&window, &message);
         }
     });
+    app.add_action(&install_handlers);
 
-    // "Open" button: triggers the open-uri action using the window and the current URI.
-    let open_button = gtk::Button::with_label("Open");
-    let win_for_action = window.clone();
-    let uri_clone = uri.clone();
-    open_button.connect_clicked(move |_| {
-        gio::prelude::ActionGroupExt::activate_action(
-            &win_for_action,
-            "open-uri",
-            Some(&glib::Variant::from(uri_clone.as_str())),
-        );
+    let preferences = gio::SimpleAction::new("preferences", None);
+    let app_clone = app.clone();
+    preferences.connect_activate(move |_, _| {
+        preferences::open_window(&app_clone);
     });
+    app.add_action(&preferences);
+    app.set_accels_for_action("app.preferences", &["<Ctrl>comma"]);
+    // The context follows what is changed in the preferences window.
+    preferences::follow(ctx);
+
+    // A new window shows the chosen start page; with none chosen, it shows the file chooser,
+    // since the user asked for a window.
+    let new_window = gio::SimpleAction::new("new-window", None);
+    let ctx_clone = ctx.clone();
+    let app_clone = app.clone();
+    new_window.connect_activate(move |_, _| {
+        let page = match start::StartPage::from_settings() {
+            start::StartPage::Nothing => start::StartPage::FileChooser,
+            page => page,
+        };
+        start::show(&app_clone, page, &ctx_clone);
+    });
+    app.add_action(&new_window);
+    app.set_accels_for_action("app.new-window", &["<Ctrl>N"]);
 
-    // "Backlinks" button: opens a window showing referencing nodes.
-    let backlinks_button = gtk::Button::with_label("Backlinks");
+    let shortcuts = gio::SimpleAction::new("shortcuts", None);
     let app_clone = app.clone();
-    let win_parent = window.clone();
-    let uri_bl = uri.clone();
-    let debug_clone = debug;
-    backlinks_button.connect_clicked(move |_| {
-        open_object_window(&app_clone, &win_parent, uri_bl.clone(), debug_clone);
-    });
-
-    // Arrange all bottom bar buttons in a horizontal box, aligned to the end.
-    let bottom_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
-    bottom_box.set_spacing(5);
-    bottom_box.set_halign(gtk::Align::End);
-    bottom_box.set_margin_start(6);
-    bottom_box.set_margin_end(6);
-    bottom_box.set_margin_top(6);
-    bottom_box.set_margin_bottom(6);
-    bottom_box.append(&backlinks_button);
-    bottom_box.append(&copy_button);
-    // Only show the "Open" button if the URI has a registered external handler.
-    if uri_has_handler(&uri).is_ok() {
-        bottom_box.append(&open_button);
-    }
-    bottom_box.append(&close_button);
-    toolbar.add_bottom_bar(&bottom_box);
-
-    // Insert the scrollable grid as the main content in the window via the toolbar.
-    toolbar.set_content(Some(&scroll));
-    window.set_content(Some(&toolbar));
-    // Present the window (show it on screen).
-    window.present();
+    shortcuts.connect_activate(move |_, _| about::show_shortcuts(&app_clone));
+    app.add_action(&shortcuts);
+    app.set_accels_for_action("app.shortcuts", &["<Ctrl>question"]);
 
-    // ---- Asynchronous section to populate the grid with file/node info ----
+    let show_history = gio::SimpleAction::new("history", None);
+    let ctx_clone = ctx.clone();
     let app_clone = app.clone();
-    let window_clone = window.clone();
-    let grid_clone = grid.clone();
-    let header_clone = header_label.clone();
-    let data_clone = table_data.clone();
-    let uri_clone = uri.clone();
+    show_history.connect_activate(move |_, _| visits::open_window(&app_clone, &ctx_clone));
+    app.add_action(&show_history);
+    app.set_accels_for_action("app.history", &["<Ctrl>H"]);
 
-    // Spawn an async block on the GTK main context.
-    glib::MainContext::default().spawn_local(async move {
-        // Query data and fill the grid; returns type info and the rows.
-        let (is_file_data_object, rows) =
-            populate_grid(&app_clone, &window_clone, &grid_clone, &uri_clone, debug).await;
-        let row_count = rows.len().saturating_sub(1);
-        // Update the table data for other parts of the UI (e.g., copy button).
-        data_clone.borrow_mut().clear();
-        data_clone.borrow_mut().extend(rows);
+    let search = gio::SimpleAction::new("search", None);
+    let ctx_clone = ctx.clone();
+    let app_clone = app.clone();
+    search.connect_activate(move |_, _| fulltext::open_window(&app_clone, &ctx_clone, ""));
+    app.add_action(&search);
+    app.set_accels_for_action("app.search", &["<Ctrl><Shift>F"]);
 
-        // Set the header label to reflect the object type.
-        header_clone.set_text(if is_file_data_object {
-            "File Information"
-        } else {
-            "Node Information"
+    let clear_recent = gio::SimpleAction::new("clear-recent", None);
+    let ctx_clone = ctx.clone();
+    clear_recent.connect_activate(move |_, _| recent::clear(&ctx_clone));
+    app.add_action(&clear_recent);
+
+    let show_about = gio::SimpleAction::new("about", None);
+    let app_clone = app.clone();
+    show_about.connect_activate(move |_, _| about::show_about(&app_clone));
+    app.add_action(&show_about);
+
+    let show_pinboard = gio::SimpleAction::new("pinboard", None);
+    let ctx_clone = ctx.clone();
+    let app_clone = app.clone();
+    show_pinboard.connect_activate(move |_, _| {
+        pinboard::open_window(&app_clone, &ctx_clone);
+    });
+    app.add_action(&show_pinboard);
+
+    // "Pin Value" from value context menus (see `add_copy_menu`), with the subject, the
+    // predicate and the displayed value as parameter.
+    let pin_value =
+        gio::SimpleAction::new("pin-value", Some(glib::VariantTy::new("(sss)").unwrap()));
+    let ctx_clone = ctx.clone();
+    let app_clone = app.clone();
+    pin_value.connect_activate(move |_, param| {
+        let Some((subject, predicate, value)) =
+            param.and_then(|p| p.get::<(String, String, String)>())
+        else {
+            return;
+        };
+        ctx_clone.pinboard.pin(pinboard::PinnedValue {
+            subject,
+            property: friendly_label(&predicate),
+            value,
         });
+        pinboard::open_window(&app_clone, &ctx_clone);
+    });
+    app.add_action(&pin_value);
+}
 
-        // If debug is enabled, print diagnostics about results, but only immediately after the
-        // grid has been fully painted, and therefore is ready for a screen capture.
-        if debug {
-            if let Some(clock) = grid_clone.frame_clock() {
-                let handler: Rc<RefCell<Option<glib::SignalHandlerId>>> =
-                    Rc::new(RefCell::new(None));
-                let handler_clone = handler.clone();
-                let id = clock.connect_after_paint(move |clk| {
-                    if let Some(h) = handler_clone.borrow_mut().take() {
-                        clk.disconnect(h);
-                    }
-                    log::debug!(
-                        "DEBUG: results displayed rows={} file_data={}",
-                        row_count,
-                        is_file_data_object
-                    );
-                });
-                *handler.borrow_mut() = Some(id);
-                clock.request_phase(gdk4::FrameClockPhase::AFTER_PAINT);
+/// Shows a dialog for changing the date-time display pattern and time zone.
+///
+/// The pattern can be entered or picked from `DATE_FORMAT_PRESETS`. Saving is only possible
+/// while the entered pattern is valid (see `is_valid_date_format`). Accepted settings apply to
+/// subjects loaded from then on and are stored in the user's preferences.
+///
+/// # Arguments
+/// * `app` - The application, whose active window becomes the dialog's parent.
+/// * `ctx` - The shared context holding the current pattern and time zone.
+fn prompt_date_format(app: &adw::Application, ctx: &Rc<Context>) {
+    let entry = gtk::Entry::builder()
+        .text(ctx.date_format.borrow().as_str())
+        .activates_default(true)
+        .build();
+    let presets = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    presets.style_context().add_class("linked");
+    for (label, pattern) in DATE_FORMAT_PRESETS {
+        let button = gtk::Button::with_label(label);
+        button.set_tooltip_text(Some(pattern));
+        let entry_clone = entry.clone();
+        button.connect_clicked(move |_| entry_clone.set_text(pattern));
+        presets.append(&button);
+    }
+    let utc = gtk::CheckButton::with_label("Show in UTC");
+    utc.set_active(ctx.utc_dates.get());
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+    content.append(&presets);
+    content.append(&entry);
+    content.append(&utc);
+
+    let dialog = adw::MessageDialog::new(
+        app.active_window().as_ref(),
+        Some("Date Format"),
+        Some(
+            "A strftime-style pattern, e.g. \"%F %T\" or \"%x %X\". Times are shown in the \
+             local time zone unless UTC is chosen, which makes them comparable across machines.",
+        ),
+    );
+    dialog.add_responses(&[("cancel", "Cancel"), ("save", "Save")]);
+    dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("save"));
+    dialog.set_close_response("cancel");
+    dialog.set_extra_child(Some(&content));
+
+    // Only allow saving patterns that can actually be used.
+    let dialog_clone = dialog.clone();
+    entry.connect_changed(move |entry| {
+        dialog_clone.set_response_enabled("save", is_valid_date_format(&entry.text()));
+    });
+
+    // The context follows the stored preferences (see `preferences::follow`).
+    dialog.connect_response(Some("save"), move |_, _| {
+        let format = entry.text().to_string();
+        settings::set_preference(settings::DATE_FORMAT, format.as_str());
+        settings::set_preference(settings::UTC_DATES, utc.is_active());
+    });
+    dialog.present();
+}
+
+/// Returns whether `format` is a usable date-time pattern.
+///
+/// `glib::DateTime::format` fails on malformed patterns, so the pattern is checked by formatting
+/// the current time with it.
+fn is_valid_date_format(format: &str) -> bool {
+    !format.is_empty()
+        && glib::DateTime::now_local()
+            .and_then(|now| now.format(format))
+            .is_ok()
+}
+
+/// Opens a window for each subject in `uris`, through the shared queue in `ctx.open_queue`.
+///
+/// The queue is drained at one subject per `OPEN_INTERVAL`, so that a huge selection in a file
+/// manager or a script sending many requests does not spawn unlimited windows and store
+/// connections at once. Batches larger than `queue::CONFIRM_THRESHOLD` are only opened after
+/// the user has confirmed them, and show their progress in a single window.
+///
+/// # Arguments
+/// * `app` - The application, kept alive while subjects are pending.
+/// * `uris` - The subjects to open.
+/// * `ctx` - Shared application settings.
+fn queue_open(app: &adw::Application, uris: Vec<String>, ctx: &Rc<Context>) {
+    ctx.open_queue.push(uris);
+    if ctx.open_queue.start() {
+        drain_open_queue(app, ctx);
+    }
+}
+
+/// Opens the queued subjects one by one until the queue is empty, the user cancels, or the batch
+/// grows large enough to need confirmation.
+fn drain_open_queue(app: &adw::Application, ctx: &Rc<Context>) {
+    if ctx.open_queue.needs_confirmation() {
+        confirm_open_queue(app, ctx);
+        return;
+    }
+
+    // Confirmed batches report their progress.
+    let progress = ctx
+        .open_queue
+        .confirmed()
+        .then(|| open_progress_window(app, ctx));
+
+    // Without windows, the application would quit while subjects are still pending.
+    let hold = app.hold();
+    let app = app.clone();
+    let ctx = ctx.clone();
+    glib::timeout_add_local(OPEN_INTERVAL, move || {
+        let _ = &hold;
+        let close_progress = || {
+            if let Some((window, _)) = &progress {
+                window.close();
             }
+        };
+        if ctx.open_queue.needs_confirmation() {
+            close_progress();
+            confirm_open_queue(&app, &ctx);
+            return glib::ControlFlow::Break;
+        }
+        let Some(uri) = ctx.open_queue.pop() else {
+            close_progress();
+            return glib::ControlFlow::Break;
+        };
+        open_subject_window(&app, uri, &ctx);
+        if let Some((_, bar)) = &progress {
+            let (done, total) = ctx.open_queue.progress();
+            bar.set_fraction(done as f64 / total.max(1) as f64);
+            bar.set_text(Some(&format!("{done} of {total}")));
+        }
+        glib::ControlFlow::Continue
+    });
+}
+
+/// Asks whether to open the large batch of subjects in the queue, and continues draining the
+/// queue or drops it depending on the answer.
+fn confirm_open_queue(app: &adw::Application, ctx: &Rc<Context>) {
+    let (_, total) = ctx.open_queue.progress();
+    let dialog = adw::MessageDialog::new(
+        app.active_window().as_ref(),
+        Some(&format!("Open {total} Items?")),
+        Some("Each item is shown in its own window."),
+    );
+    dialog.add_responses(&[("cancel", "Cancel"), ("open", "Open All")]);
+    dialog.set_response_appearance("open", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("open"));
+    dialog.set_close_response("cancel");
+
+    // Keep the application alive while the question is pending.
+    let hold = RefCell::new(Some(app.hold()));
+    let app = app.clone();
+    let ctx = ctx.clone();
+    dialog.connect_response(None, move |_, response| {
+        if response == "open" {
+            ctx.open_queue.confirm();
+            drain_open_queue(&app, &ctx);
+        } else {
+            ctx.open_queue.clear();
+        }
+        hold.borrow_mut().take();
+    });
+    dialog.present();
+}
+
+/// Shows a small window reporting how many subjects of the queued batch have been opened, with a
+/// button for cancelling the rest.
+///
+/// # Returns
+/// * The window and its progress bar, which the caller updates.
+fn open_progress_window(
+    app: &adw::Application,
+    ctx: &Rc<Context>,
+) -> (adw::Window, gtk::ProgressBar) {
+    let bar = gtk::ProgressBar::builder().show_text(true).build();
+    let cancel_button = gtk::Button::with_label("Cancel");
+    cancel_button.set_halign(gtk::Align::End);
+
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+    content.append(&gtk::Label::new(Some("Opening items…")));
+    content.append(&bar);
+    content.append(&cancel_button);
+
+    let window = adw::Window::builder()
+        .application(app)
+        .title("Opening Items")
+        .default_width(320)
+        .content(&content)
+        .build();
+
+    // Cancelling drops the pending subjects; the draining timer then finds the queue empty and
+    // stops.
+    let ctx = ctx.clone();
+    let window_clone = window.clone();
+    cancel_button.connect_clicked(move |_| {
+        ctx.open_queue.clear();
+        window_clone.close();
+    });
+    window.present();
+    (window, bar)
+}
+
+/// Builds and presents the main window UI for a given URI.
+///
+/// This function creates and configures the main GTK application window, sets up styling,
+/// assembles all the widgets, registers callbacks, and asynchronously populates the UI
+/// with information about the file or node referenced by the `uri` argument. The window can
+/// hold further subjects as tabs (see `add_subject_tab`); the header bar and the bottom bar
+/// always act on the selected tab. If the subject is already open, its existing tab is brought
+/// to the front instead.
+///
+/// # Arguments
+/// * `app` - The application instance, used for context and for spawning additional windows.
+/// * `uri` - The URI (can be a file path or another type) to display information about.
+/// * `ctx` - Shared application settings (debug output, post-load hook, etc.).
+fn open_subject_window(app: &adw::Application, uri: String, ctx: &Rc<Context>) {
+    if focus_open_subject(ctx, &uri) {
+        return;
+    }
+    watch_store(ctx);
+
+    // The window is laid out by its template (see `file_info`); the tabs, menus and actions
+    // are added below.
+    let window = file_info::FileInfoWindow::new(app, ctx);
+    let imp = window.imp();
+    let tab_view = imp.tab_view.get();
+
+    // The header bar menu holds window-independent preferences.
+    let menu = gio::Menu::new();
+    menu.append(Some("Open Location…"), Some("win.open-location"));
+    menu.append(Some("Location Bar"), Some("win.location-bar"));
+    menu.append_submenu(Some("Recent"), &recent::menu(ctx));
+    menu.append(Some("History"), Some("app.history"));
+    menu.append_submenu(Some("Bookmarks"), &bookmarks::menu(ctx));
+    menu.append(Some("Paste and Inspect"), Some("win.paste-and-inspect"));
+    menu.append(Some("Compare With…"), Some("win.compare-with"));
+    menu.append(Some("Find by Property…"), Some("win.find-by-property"));
+    menu.append(Some("Search File Contents…"), Some("app.search"));
+    menu.append(Some("Copy SPARQL Query"), Some("win.copy-sparql"));
+    menu.append(Some("Export as PDF…"), Some("win.export-pdf"));
+    menu.append(Some("Pinboard"), Some("app.pinboard"));
+    menu.append(Some("Open Links in Same Window"), Some("app.same-window"));
+    menu.append(Some("Show Prefixed Names"), Some("app.compact-uris"));
+    menu.append(Some("Show Advanced Properties"), Some("app.show-advanced"));
+    menu.append(Some("Show Relative Dates"), Some("app.relative-dates"));
+    menu.append(Some("Show Datatypes"), Some("app.show-datatypes"));
+    menu.append(
+        Some("Include Interpreted Content"),
+        Some("app.merge-interpreted"),
+    );
+    let order_menu = gio::Menu::new();
+    for order in ordering::RowOrder::ALL {
+        let item = gio::MenuItem::new(Some(order.label()), None);
+        item.set_action_and_target_value(Some("app.row-order"), Some(&order.as_str().to_variant()));
+        order_menu.append_item(&item);
+    }
+    menu.append_submenu(Some("Sort Properties"), &order_menu);
+    menu.append(Some("Date Format…"), Some("app.date-format"));
+    let start_menu = gio::Menu::new();
+    for (label, page) in [
+        ("File Chooser", start::StartPage::FileChooser),
+        ("Recent Subjects", start::StartPage::Recent),
+        ("Search", start::StartPage::Search),
+        ("Nothing", start::StartPage::Nothing),
+    ] {
+        let item = gio::MenuItem::new(Some(label), None);
+        item.set_action_and_target_value(Some("app.start-page"), Some(&page.as_str().to_variant()));
+        start_menu.append_item(&item);
+    }
+    menu.append_submenu(Some("Start Page"), &start_menu);
+    menu.append(Some("Add to Desktop"), Some("app.install-handlers"));
+    // The application-wide entries every GNOME application has come last, in their own section.
+    let app_section = gio::Menu::new();
+    app_section.append(Some("New Window"), Some("app.new-window"));
+    app_section.append(Some("Preferences"), Some("app.preferences"));
+    app_section.append(Some("Keyboard Shortcuts"), Some("app.shortcuts"));
+    app_section.append(Some("About File Information"), Some("app.about"));
+    menu.append_section(None, &app_section);
+    imp.menu_button.set_menu_model(Some(&menu));
+
+    // The location bar below the header completes the locations opened before.
+    let location_entry = location_entry();
+    let location_bar = imp.location_bar.get();
+    location_bar.set_child(Some(&location_entry));
+    location_bar.connect_entry(&location_entry);
+
+    // Actions for moving through the selected tab's history, reachable through header bar
+    // buttons and Alt+Left/Alt+Right. The history only grows when links are followed in the
+    // same window.
+    let back_action = gio::SimpleAction::new("go-back", None);
+    let forward_action = gio::SimpleAction::new("go-forward", None);
+    back_action.set_enabled(false);
+    forward_action.set_enabled(false);
+    window.add_action(&back_action);
+    window.add_action(&forward_action);
+
+    // The star button in the header bar bookmarks the selected tab's subject (see `bookmarks`),
+    // as does Ctrl+D. It follows the selected tab, see `update_window_chrome`.
+    let bookmark_action = gio::SimpleAction::new_stateful("bookmark", None, &false.to_variant());
+    window.add_action(&bookmark_action);
+    app.set_accels_for_action("win.bookmark", &["<Ctrl>D"]);
+
+    let win = SubjectWindow {
+        app: app.clone(),
+        window: window.clone(),
+        tab_view: tab_view.clone(),
+        header_label: imp.header_label.get(),
+        open_button: imp.open_button.get(),
+        show_in_files_button: imp.show_in_files_button.get(),
+        handler_box: imp.handler_box.get(),
+        handler_icon: imp.handler_icon.get(),
+        handler_label: imp.handler_label.get(),
+        back_action: back_action.clone(),
+        forward_action: forward_action.clone(),
+        bookmark_action: bookmark_action.clone(),
+        bookmark_button: imp.bookmark_button.get(),
+        views: Rc::new(RefCell::new(Vec::new())),
+        ctx: ctx.clone(),
+    };
+
+    let win_clone = win.clone();
+    bookmark_action.connect_change_state(move |_, _| {
+        let Some(view) = current_view(&win_clone) else {
+            return;
+        };
+        let bookmarked = bookmarks::toggle(&win_clone.ctx, &view.uri.borrow());
+        show_bookmarked(&win_clone, bookmarked);
+    });
+    // The subject may have been bookmarked (or not) in another window in the meantime.
+    let win_clone = win.clone();
+    window.connect_is_active_notify(move |window| {
+        if window.is_active()
+            && let Some(view) = current_view(&win_clone)
+        {
+            let bookmarked = bookmarks::is_bookmarked(&win_clone.ctx, &view.uri.borrow());
+            show_bookmarked(&win_clone, bookmarked);
+        }
+    });
+
+    // The buttons of the bottom bar act on the selected tab.
+    let win_clone = win.clone();
+    imp.copy_button.connect_clicked(move |_| {
+        let Some(view) = current_view(&win_clone) else {
+            return;
+        };
+        let rows = view.table_data.borrow();
+        // Serialize the table in the format chosen in the preferences and copy it to the
+        // clipboard.
+        let data = export::serialize(
+            win_clone.ctx.export_format.get(),
+            &view.uri.borrow(),
+            view.file_data_flag.get(),
+            &rows,
+        );
+        if let Some(display) = gdk4::Display::default() {
+            display.clipboard().set_text(&data);
+            announce::announce(
+                &win_clone.window,
+                &format!(
+                    "Copied {} {}",
+                    rows.len(),
+                    if rows.len() == 1 { "row" } else { "rows" }
+                ),
+            );
+        }
+    });
+
+    let win_clone = win.clone();
+    imp.open_button.connect_clicked(move |_| {
+        if let Some(view) = current_view(&win_clone) {
+            gio::prelude::ActionGroupExt::activate_action(
+                &win_clone.window,
+                "open-uri",
+                Some(&glib::Variant::from(view.uri.borrow().as_str())),
+            );
+        }
+    });
+
+    let win_clone = win.clone();
+    imp.show_in_files_button.connect_clicked(move |_| {
+        if let Some(view) = current_view(&win_clone) {
+            gio::prelude::ActionGroupExt::activate_action(
+                &win_clone.window,
+                "show-in-files",
+                Some(&glib::Variant::from(view.uri.borrow().as_str())),
+            );
+        }
+    });
+
+    // "Backlinks" button: opens a window showing referencing nodes.
+    let win_clone = win.clone();
+    imp.backlinks_button.connect_clicked(move |_| {
+        if let Some(view) = current_view(&win_clone) {
+            let uri = view.uri.borrow().clone();
+            open_object_window(
+                &win_clone.app,
+                win_clone.window.upcast_ref(),
+                uri,
+                &win_clone.ctx,
+            );
         }
     });
+
+    // If HTTP sharing was requested, serve this window's metadata and show a link to it, ahead
+    // of the buttons.
+    let share_url = if ctx.share { share_window(&win) } else { None };
+    if let Some(url) = share_url {
+        trace::event("window_shared", json!({ "uri": uri, "url": url }));
+        let link = gtk::LinkButton::with_label(&url, &url);
+        link.set_tooltip_text(Some("Open the shared view in a web browser"));
+        imp.bottom_box.prepend(&link);
+    }
+
+    // ---- In-window navigation ----
+
+    // Files chosen in the search entry or dropped onto the window open in new tabs (or bring up
+    // their existing tabs), like pasted ones.
+    let win_clone = win.clone();
+    window.connect_open_subject(move |_, target| {
+        if !focus_open_subject(&win_clone.ctx, &target) {
+            add_subject_tab(&win_clone, target);
+        }
+    });
+
+    app.set_accels_for_action("win.go-back", &["<Alt>Left"]);
+    app.set_accels_for_action("win.go-forward", &["<Alt>Right"]);
+
+    let win_clone = win.clone();
+    back_action.connect_activate(move |_, _| {
+        let Some(view) = current_view(&win_clone) else {
+            return;
+        };
+        let current = view.uri.borrow().clone();
+        let target = view.history.borrow_mut().go_back(current);
+        if let Some(target) = target {
+            show_in_view(&view, target);
+        }
+    });
+    let win_clone = win.clone();
+    forward_action.connect_activate(move |_, _| {
+        let Some(view) = current_view(&win_clone) else {
+            return;
+        };
+        let current = view.uri.borrow().clone();
+        let target = view.history.borrow_mut().go_forward(current);
+        if let Some(target) = target {
+            show_in_view(&view, target);
+        }
+    });
+
+    // ---- Tabs ----
+
+    // "Open in New Tab" from link context menus (see `add_copy_menu`).
+    let open_in_new_tab = gio::SimpleAction::new("open-in-new-tab", Some(glib::VariantTy::STRING));
+    let win_clone = win.clone();
+    open_in_new_tab.connect_activate(move |_, param| {
+        let Some(target) = param.and_then(|v| v.str()) else {
+            return;
+        };
+        if !focus_open_subject(&win_clone.ctx, target) {
+            add_subject_tab(&win_clone, target.to_string());
+        }
+    });
+    window.add_action(&open_in_new_tab);
+
+    // Ctrl+V opens the URI or path on the clipboard in a new tab. This is a window action so
+    // that it does not interfere with pasting into text entries of other windows.
+    let paste_and_inspect = gio::SimpleAction::new("paste-and-inspect", None);
+    let win_clone = win.clone();
+    paste_and_inspect.connect_activate(move |_, _| {
+        // In text fields, such as the location bar, Ctrl+V pastes as usual.
+        if let Some(text) = GtkWindowExt::focus(&win_clone.window).and_downcast::<gtk::Text>() {
+            text.emit_by_name::<()>("paste-clipboard", &[]);
+            return;
+        }
+        let win = win_clone.clone();
+        glib::MainContext::default().spawn_local(async move {
+            let text = win.window.clipboard().read_text_future().await;
+            let target = text.ok().flatten().and_then(|t| location_target(&t));
+            match target {
+                Some(target) => {
+                    if !focus_open_subject(&win.ctx, &target) {
+                        add_subject_tab(&win, target);
+                    }
+                }
+                None => {
+                    show_message(
+                        Some(win.window.upcast_ref()),
+                        "Nothing to inspect",
+                        "The clipboard does not contain a URI or an absolute path.",
+                    );
+                }
+            }
+        });
+    });
+    window.add_action(&paste_and_inspect);
+    app.set_accels_for_action("win.paste-and-inspect", &["<Ctrl>V"]);
+
+    // "Open Location…" (Ctrl+O) asks for a location to open in a new tab.
+    let open_location = gio::SimpleAction::new("open-location", None);
+    let win_clone = win.clone();
+    open_location.connect_activate(move |_, _| {
+        let win = win_clone.clone();
+        prompt_location(&win_clone, "Open Location", "Open", move |target| {
+            if !focus_open_subject(&win.ctx, &target) {
+                add_subject_tab(&win, target);
+            }
+        });
+    });
+    window.add_action(&open_location);
+    app.set_accels_for_action("win.open-location", &["<Ctrl>O"]);
+
+    // Ctrl+L shows or hides the location bar, which starts out holding the selected tab's
+    // subject, ready to be replaced.
+    let show_location_bar =
+        gio::SimpleAction::new_stateful("location-bar", None, &false.to_variant());
+    let win_clone = win.clone();
+    let bar = location_bar.clone();
+    let entry = location_entry.clone();
+    show_location_bar.connect_change_state(move |action, state| {
+        let Some(shown) = state.and_then(|s| s.get::<bool>()) else {
+            return;
+        };
+        action.set_state(&shown.to_variant());
+        bar.set_search_mode(shown);
+        if shown {
+            if let Some(view) = current_view(&win_clone) {
+                entry.set_text(&view.uri.borrow());
+            }
+            entry.grab_focus();
+            entry.select_region(0, -1);
+        }
+    });
+    window.add_action(&show_location_bar);
+    app.set_accels_for_action("win.location-bar", &["<Ctrl>L"]);
+    // Closing the bar with Escape or its close button is reflected in the action's state.
+    location_bar.connect_search_mode_enabled_notify(move |bar| {
+        show_location_bar.set_state(&bar.is_search_mode().to_variant());
+    });
+
+    // A location entered in the bar is shown in the selected tab, as if a link had been
+    // followed, so that Back returns to the previous subject.
+    let win_clone = win.clone();
+    let bar = location_bar.clone();
+    location_entry.connect_activate(move |entry| {
+        let Some(target) = location_target(&entry.text()) else {
+            entry.style_context().add_class("error");
+            return;
+        };
+        settings::Settings::load().push_recent(settings::RECENT_LOCATIONS, &target);
+        bar.set_search_mode(false);
+        match current_view(&win_clone) {
+            Some(view) => {
+                let previous = view.uri.borrow().clone();
+                view.history.borrow_mut().visit(previous);
+                show_in_view(&view, target);
+            }
+            None => add_subject_tab(&win_clone, target),
+        }
+    });
+    location_entry.connect_changed(|entry| entry.style_context().remove_class("error"));
+
+    // "Compare With…" asks for a second subject and compares the selected tab's subject with it.
+    let compare_with = gio::SimpleAction::new("compare-with", None);
+    let win_clone = win.clone();
+    compare_with.connect_activate(move |_, _| {
+        let Some(view) = current_view(&win_clone) else {
+            return;
+        };
+        let win = win_clone.clone();
+        let left = view.uri.borrow().clone();
+        prompt_location(&win_clone, "Compare With", "Compare", move |right| {
+            compare::open_window(&win.app, win.window.upcast_ref(), left.clone(), right);
+        });
+    });
+    window.add_action(&compare_with);
+
+    // "Find by Property…" opens the guided search for resources with matching values.
+    let find_by_property = gio::SimpleAction::new("find-by-property", None);
+    let win_clone = win.clone();
+    find_by_property.connect_activate(move |_, _| {
+        let subject = current_view(&win_clone).map(|view| view.uri.borrow().clone());
+        finder::open_window(
+            &win_clone.app,
+            win_clone.window.upcast_ref(),
+            subject,
+            &win_clone.ctx,
+        );
+    });
+    window.add_action(&find_by_property);
+
+    // F5 reloads the selected tab's subject. The reload is broadcast, so that every other tab
+    // and window showing the same subject is brought up to date as well.
+    let refresh = gio::SimpleAction::new("refresh", None);
+    let win_clone = win.clone();
+    refresh.connect_activate(move |_, _| {
+        if let Some(view) = current_view(&win_clone) {
+            let uri = view.uri.borrow().clone();
+            win_clone.ctx.changes.publish(&uri);
+        }
+    });
+    window.add_action(&refresh);
+    app.set_accels_for_action("win.refresh", &["F5"]);
+
+    // Copies the query behind the page shown in the selected tab, e.g. to run it with
+    // `tracker3 sparql` after tweaking it.
+    let copy_sparql = gio::SimpleAction::new("copy-sparql", None);
+    let win_clone = win.clone();
+    copy_sparql.connect_activate(move |_, _| {
+        let Some(view) = current_view(&win_clone) else {
+            return;
+        };
+        let page = view.stack.visible_child_name().unwrap_or_default();
+        let sparql = page_query(&page, &view.uri.borrow());
+        ActionGroupExt::activate_action(
+            &win_clone.window,
+            "copy-value",
+            Some(&sparql.to_variant()),
+        );
+    });
+    window.add_action(&copy_sparql);
+
+    // Renders the table of the selected tab to a PDF file, e.g. to attach to a ticket.
+    let export_pdf = gio::SimpleAction::new("export-pdf", None);
+    let win_clone = win.clone();
+    export_pdf.connect_activate(move |_, _| {
+        if let Some(view) = current_view(&win_clone) {
+            export_pdf_file(&view);
+        }
+    });
+    window.add_action(&export_pdf);
+
+    // Reload the tabs showing a subject whenever it is reported to have changed.
+    let win_clone = win.clone();
+    let subscription = ctx.changes.subscribe(move |changed| {
+        let affected: Vec<SubjectView> = win_clone
+            .views
+            .borrow()
+            .iter()
+            .filter(|v| *v.uri.borrow() == changed)
+            .cloned()
+            .collect();
+        for view in affected {
+            load_subject(&view);
+        }
+    });
+
+    // Ctrl+W closes the selected tab (and with it the window, if it was the last one).
+    let close_tab = gio::SimpleAction::new("close-tab", None);
+    let tab_view_clone = tab_view.clone();
+    close_tab.connect_activate(move |_, _| {
+        if let Some(page) = tab_view_clone.selected_page() {
+            tab_view_clone.close_page(&page);
+        }
+    });
+    window.add_action(&close_tab);
+    app.set_accels_for_action("win.close-tab", &["<Ctrl>W"]);
+
+    // Keep the header bar and bottom bar in sync with the selected tab.
+    let win_clone = win.clone();
+    tab_view.connect_selected_page_notify(move |_| update_window_chrome(&win_clone));
+
+    // Forget the state of closed tabs, and close the window along with its last tab. Closing
+    // is deferred until the tab view has finished removing the page.
+    let win_clone = win.clone();
+    tab_view.connect_close_page(move |tab_view, page| {
+        win_clone.views.borrow_mut().retain(|v| {
+            let closing = &v.page == page;
+            if closing {
+                cancel_tasks(v);
+            }
+            !closing
+        });
+        win_clone.ctx.open_subjects.borrow_mut().unregister(page);
+        if tab_view.n_pages() <= 1 {
+            let window = win_clone.window.clone();
+            glib::idle_add_local_once(move || window.close());
+        }
+        glib::Propagation::Proceed
+    });
+
+    // The window state and the tab views refer to each other; break the cycle once the window
+    // is gone. Its tabs no longer show anything either.
+    let views = win.views.clone();
+    let ctx_clone = ctx.clone();
+    window.connect_destroy(move |_| {
+        ctx_clone.changes.unsubscribe(subscription);
+        for view in views.borrow_mut().drain(..) {
+            cancel_tasks(&view);
+            ctx_clone.open_subjects.borrow_mut().unregister(&view.page);
+        }
+    });
+
+    // Present the window (show it on screen).
+    window.present();
+
+    // ---- Asynchronous section to populate the grid with file/node info ----
+    add_subject_tab(&win, uri);
+}
+
+/// Window-level widgets and state of a subject window, shared by all of its tabs.
+///
+/// All fields are reference-counted, so the struct is cheap to clone into callbacks.
+#[derive(Clone)]
+struct SubjectWindow {
+    app: adw::Application,
+    window: file_info::FileInfoWindow,
+    tab_view: adw::TabView,
+    header_label: gtk::Label,
+    open_button: gtk::Button,
+    show_in_files_button: gtk::Button,
+    /// Shows the icon and name of the application the "Open" button launches.
+    handler_box: gtk::Box,
+    handler_icon: gtk::Image,
+    handler_label: gtk::Label,
+    back_action: gio::SimpleAction,
+    forward_action: gio::SimpleAction,
+    /// Whether the selected tab's subject is bookmarked, shown by the star button.
+    bookmark_action: gio::SimpleAction,
+    bookmark_button: gtk::ToggleButton,
+    /// The views of all tabs of the window, in no particular order.
+    views: Rc<RefCell<Vec<SubjectView>>>,
+    ctx: Rc<Context>,
+}
+
+/// Widgets and state of a single subject tab that are needed to (re)load its contents.
+///
+/// All fields are reference-counted, so the struct is cheap to clone into callbacks.
+#[derive(Clone)]
+struct SubjectView {
+    win: SubjectWindow,
+    page: adw::TabPage,
+    grid: gtk::Grid,
+    scroll: gtk::ScrolledWindow,
+    /// The URI of the subject currently shown.
+    uri: Rc<RefCell<String>>,
+    history: Rc<RefCell<History>>,
+    /// The header bar title to show while this tab is selected.
+    title: Rc<RefCell<String>>,
+    /// The rows currently shown, for copying and sharing.
+    table_data: Rc<RefCell<Vec<TableRow>>>,
+    /// Whether the current subject is a file data object.
+    file_data_flag: Rc<Cell<bool>>,
+    /// The folders containing the subject, each leading to its own information.
+    breadcrumbs: gtk::Box,
+    /// Announces that the file shown has changed on disk since it was loaded.
+    banner: adw::Banner,
+    /// Watches the file shown, for `file:` subjects.
+    monitor: Rc<RefCell<Option<gio::FileMonitor>>>,
+    /// The new URI of the file shown, if it has been renamed or moved since it was loaded.
+    moved_to: Rc<RefCell<Option<String>>>,
+    /// The status bar telling how long loading the subject took, shown with `--timings`.
+    timings: gtk::Label,
+    /// The properties page, which shows either the table (`"table"`) or, if nothing is indexed
+    /// about the subject, a placeholder (`"empty"`, see `empty_properties_page`).
+    properties: gtk::Stack,
+    /// Re-indexes the subject from the placeholder of the properties page.
+    reindex_button: gtk::Button,
+    /// The pages of the tab: properties, preview, backlinks, related items, raw statements and,
+    /// for folders, their contents, or for files with duplicates, those.
+    stack: adw::ViewStack,
+    preview: adw::Bin,
+    backlinks_grid: gtk::Grid,
+    raw_buffer: gtk::TextBuffer,
+    /// The subjects sharing a tag, creator or album with the subject (see `related`).
+    related_list: gtk::ListBox,
+    /// The contents page, which is only shown for folders (see `contents::is_folder`).
+    contents_page: adw::ViewStackPage,
+    contents_list: gtk::ListBox,
+    /// The duplicates page, which is only shown if files with the same content hash are found
+    /// (see `duplicates`).
+    duplicates_page: adw::ViewStackPage,
+    duplicates_list: gtk::ListBox,
+    /// The names of the secondary pages that have been loaded for the current subject. Pages
+    /// are only loaded when they are first shown.
+    loaded_pages: Rc<RefCell<Vec<String>>>,
+    /// The tasks loading secondary pages and the rest of the tab besides the grid (breadcrumbs,
+    /// the contents and duplicates pages, the handler), which are aborted when the subject
+    /// changes or the tab is closed.
+    page_tasks: Rc<RefCell<Vec<glib::JoinHandle<()>>>>,
+    /// The task loading the subject into the grid, which is aborted when the tab is reloaded or
+    /// closed.
+    load_task: Rc<RefCell<Option<glib::JoinHandle<()>>>>,
+}
+
+/// Adds a new tab showing `uri` to a subject window, selects it and starts loading it.
+///
+/// # Arguments
+/// * `win` - The window to add the tab to.
+/// * `uri` - The URI of the subject to show in the new tab.
+fn add_subject_tab(win: &SubjectWindow, uri: String) {
+    recent::record(&win.ctx, &uri);
+    visits::record(&uri);
+    // Construct a grid that will display all the file/node information in two columns.
+    let grid = gtk::Grid::builder()
+        .column_homogeneous(false)
+        .hexpand(true)
+        .vexpand(true)
+        .halign(gtk::Align::Fill)
+        .valign(gtk::Align::Fill)
+        .build();
+    grid.set_widget_name("data-grid");
+
+    // Add the grid inside a viewport, which allows for scrolling if content is large.
+    let viewport = gtk::Viewport::builder()
+        .scroll_to_focus(false)
+        .child(&grid)
+        .build();
+
+    // Put the viewport into a scrollable window with minimum dimensions.
+    let scroll = gtk::ScrolledWindow::builder()
+        .min_content_width(590)
+        .min_content_height(400)
+        .child(&viewport)
+        .build();
+
+    // A banner above the grid reports changes to the file on disk (see `watch_file`).
+    let banner = adw::Banner::new("");
+
+    // Besides the properties, each tab has pages for a preview, the backlinks and the raw
+    // statements of its subject, which are switched between below the banner.
+    let preview = adw::Bin::new();
+    let backlinks_grid = gtk::Grid::builder()
+        .column_homogeneous(false)
+        .hexpand(true)
+        .build();
+    backlinks_grid.set_widget_name("data-grid");
+    let backlinks_scroll = gtk::ScrolledWindow::builder()
+        .child(&backlinks_grid)
+        .build();
+    let raw_buffer = gtk::TextBuffer::new(None);
+
+    // The properties page switches to a placeholder when nothing is indexed about the subject.
+    let properties = gtk::Stack::new();
+    properties.add_named(&scroll, Some("table"));
+    let (empty_page, reindex_button) = empty_properties_page(&properties);
+    properties.add_named(&empty_page, Some("empty"));
+
+    let stack = adw::ViewStack::new();
+    stack.add_titled_with_icon(
+        &properties,
+        Some("properties"),
+        "Properties",
+        "document-properties-symbolic",
+    );
+    stack.add_titled_with_icon(
+        &preview,
+        Some("preview"),
+        "Preview",
+        "image-x-generic-symbolic",
+    );
+    stack.add_titled_with_icon(
+        &backlinks_scroll,
+        Some("backlinks"),
+        "Backlinks",
+        "go-previous-symbolic",
+    );
+    let (related, related_list) = pages::list_page("No items share a tag, creator or album");
+    stack.add_titled_with_icon(
+        &related,
+        Some("related"),
+        "Related",
+        "emblem-shared-symbolic",
+    );
+    stack.add_titled_with_icon(
+        &pages::text_view(&raw_buffer),
+        Some("raw"),
+        "Raw",
+        "text-x-generic-symbolic",
+    );
+    let (contents, contents_list) = pages::list_page("The folder is empty");
+    let contents_page =
+        stack.add_titled_with_icon(&contents, Some("contents"), "Contents", "folder-symbolic");
+    contents_page.set_visible(false);
+    let (duplicates, duplicates_list) = pages::list_page("No duplicates");
+    let duplicates_page = stack.add_titled_with_icon(
+        &duplicates,
+        Some("duplicates"),
+        "Duplicates",
+        "edit-copy-symbolic",
+    );
+    duplicates_page.set_visible(false);
+    stack.set_vexpand(true);
+    let switcher = adw::ViewSwitcher::builder()
+        .stack(&stack)
+        .policy(adw::ViewSwitcherPolicy::Wide)
+        .halign(gtk::Align::Center)
+        .margin_top(4)
+        .margin_bottom(4)
+        .build();
+
+    // The folders containing the subject are shown above everything else (see `breadcrumbs`),
+    // once they are known.
+    let breadcrumbs = gtk::Box::new(gtk::Orientation::Horizontal, 2);
+    breadcrumbs.set_margin_start(6);
+    breadcrumbs.set_margin_end(6);
+    breadcrumbs.set_visible(false);
+
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 0);
+    content.append(&breadcrumbs);
+    content.append(&banner);
+    content.append(&switcher);
+    content.append(&stack);
+
+    // With `--timings`, a status bar tells how long loading took (see `load_subject`).
+    let timings = gtk::Label::builder()
+        .halign(gtk::Align::Start)
+        .margin_start(6)
+        .margin_end(6)
+        .margin_top(2)
+        .margin_bottom(2)
+        .visible(win.ctx.timings)
+        .build();
+    timings.add_css_class("dim-label");
+    timings.add_css_class("caption");
+    content.append(&timings);
+
+    let page = win.tab_view.append(&content);
+    let view = SubjectView {
+        win: win.clone(),
+        page: page.clone(),
+        grid,
+        scroll,
+        uri: Rc::new(RefCell::new(uri)),
+        history: Rc::new(RefCell::new(History::default())),
+        title: Rc::new(RefCell::new("Loading…".to_string())),
+        table_data: Rc::new(RefCell::new(Vec::new())),
+        file_data_flag: Rc::new(Cell::new(false)),
+        breadcrumbs,
+        banner: banner.clone(),
+        monitor: Rc::new(RefCell::new(None)),
+        moved_to: Rc::new(RefCell::new(None)),
+        timings,
+        properties,
+        reindex_button,
+        stack: stack.clone(),
+        preview,
+        backlinks_grid,
+        raw_buffer,
+        related_list,
+        contents_page,
+        contents_list,
+        duplicates_page,
+        duplicates_list,
+        loaded_pages: Rc::new(RefCell::new(Vec::new())),
+        page_tasks: Rc::new(RefCell::new(Vec::new())),
+        load_task: Rc::new(RefCell::new(None)),
+    };
+
+    // Load the secondary pages when they are first shown.
+    let view_clone = view.clone();
+    stack.connect_visible_child_name_notify(move |_| load_visible_page(&view_clone));
+
+    // The banner's button reloads the subject, or shows the file at its new location instead.
+    let view_clone = view.clone();
+    banner.connect_button_clicked(move |_| {
+        let moved_to = view_clone.moved_to.borrow_mut().take();
+        match moved_to {
+            Some(target) => show_in_view(&view_clone, target),
+            None => load_subject(&view_clone),
+        }
+    });
+
+    win.views.borrow_mut().push(view.clone());
+    win.tab_view.set_selected_page(&page);
+    load_subject(&view);
+}
+
+/// Brings the tab showing `uri` (and its window) to the front, if the subject is already open.
+///
+/// # Returns
+/// * `true` if an existing tab was focused, `false` if the subject is not open anywhere.
+fn focus_open_subject(ctx: &Context, uri: &str) -> bool {
+    let Some(page) = ctx.open_subjects.borrow().find(uri) else {
+        return false;
+    };
+    let child = page.child();
+    if let Some(tab_view) = child
+        .ancestor(adw::TabView::static_type())
+        .and_downcast::<adw::TabView>()
+    {
+        tab_view.set_selected_page(&page);
+    }
+    if let Some(window) = child.root().and_downcast::<gtk::Window>() {
+        window.present();
+    }
+    true
+}
+
+/// Shows a dialog asking for a location (a path, a file URI or any IRI), e.g. to open in a new
+/// tab.
+///
+/// Accepting is only possible while the text is a valid location (see `location_target`). The
+/// entry completes previously entered locations, which are stored in the user's preferences.
+///
+/// # Arguments
+/// * `win` - The subject window that becomes the dialog's parent.
+/// * `heading` - The heading of the dialog.
+/// * `accept` - The label of the button accepting the location.
+/// * `on_location` - Called with the subject URI of the accepted location.
+fn prompt_location(
+    win: &SubjectWindow,
+    heading: &str,
+    accept: &str,
+    on_location: impl Fn(String) + 'static,
+) {
+    let entry = location_entry();
+    entry.set_activates_default(true);
+    let dialog = adw::MessageDialog::new(Some(&win.window), Some(heading), None);
+    dialog.add_responses(&[("cancel", "Cancel"), ("open", accept)]);
+    dialog.set_response_appearance("open", adw::ResponseAppearance::Suggested);
+    dialog.set_response_enabled("open", false);
+    dialog.set_default_response(Some("open"));
+    dialog.set_close_response("cancel");
+    dialog.set_extra_child(Some(&entry));
+
+    // Only allow opening text that can be turned into a subject URI.
+    let dialog_clone = dialog.clone();
+    entry.connect_changed(move |entry| {
+        dialog_clone.set_response_enabled("open", location_target(&entry.text()).is_some());
+    });
+
+    dialog.connect_response(Some("open"), move |_, _| {
+        let Some(target) = location_target(&entry.text()) else {
+            return;
+        };
+        settings::Settings::load().push_recent(settings::RECENT_LOCATIONS, &target);
+        on_location(target);
+    });
+    dialog.present();
+}
+
+/// Builds an entry for a location (see `location_target`), which completes the locations opened
+/// before, as stored in the user's preferences.
+fn location_entry() -> gtk::Entry {
+    let recent = gtk::ListStore::new(&[glib::Type::STRING]);
+    for location in settings::Settings::load().string_list(settings::RECENT_LOCATIONS) {
+        recent.insert_with_values(None, &[(0, &location)]);
+    }
+    let completion = gtk::EntryCompletion::new();
+    completion.set_model(Some(&recent));
+    completion.set_text_column(0);
+
+    gtk::Entry::builder()
+        .placeholder_text("Path, file URI or IRI")
+        .completion(&completion)
+        .width_chars(40)
+        .build()
+}
+
+/// Returns whether the row of `pred` is technical, i.e. only of interest to users who know the
+/// data model: it is one of `ADVANCED_PREDICATES`, or all of its values are internal
+/// identifiers such as blank nodes.
+///
+/// # Arguments
+/// * `pred` - The predicate IRI.
+/// * `values` - The `(object, datatype, language)` values of the predicate.
+fn is_advanced(pred: &str, values: &[(String, String, String)]) -> bool {
+    let internal = |obj: &str| obj.starts_with("urn:bnode:") || obj.starts_with("_:");
+    ADVANCED_PREDICATES.contains(&pred)
+        || values
+            .iter()
+            .all(|(obj, dtype, _)| dtype.is_empty() && internal(obj))
+}
+
+/// Returns the view of the selected tab of a subject window, if any.
+fn current_view(win: &SubjectWindow) -> Option<SubjectView> {
+    let page = win.tab_view.selected_page()?;
+    win.views.borrow().iter().find(|v| v.page == page).cloned()
+}
+
+/// Updates the window-level widgets (title, "Open" and "Show in Files" buttons, the handler
+/// application, history actions) and the `uri` property of the window to reflect the selected
+/// tab.
+fn update_window_chrome(win: &SubjectWindow) {
+    let Some(view) = current_view(win) else {
+        return;
+    };
+    win.window.set_uri(view.uri.borrow().as_str());
+    win.header_label.set_text(&view.title.borrow());
+    // The handler is resolved while the subject loads (see `load_subject`); until then, the
+    // "Open" button stays hidden.
+    let handler = cached_handler(&view.uri.borrow());
+    win.open_button
+        .set_visible(handler.as_ref().is_some_and(Result::is_ok));
+    let handler = handler.and_then(Result::ok).flatten();
+    win.handler_box.set_visible(handler.is_some());
+    if let Some(app_info) = handler {
+        win.handler_label
+            .set_text(&format!("Opens with: {}", app_info.display_name()));
+        win.handler_icon.set_from_gicon(
+            &app_info
+                .icon()
+                .unwrap_or_else(|| gio::ThemedIcon::new("application-x-executable").upcast()),
+        );
+    }
+    win.show_in_files_button
+        .set_visible(view.uri.borrow().starts_with("file:"));
+    let history = view.history.borrow();
+    win.back_action.set_enabled(history.can_go_back());
+    win.forward_action.set_enabled(history.can_go_forward());
+    show_bookmarked(win, bookmarks::is_bookmarked(&win.ctx, &view.uri.borrow()));
+}
+
+/// Shows whether the selected tab's subject is bookmarked in the star button of `win`.
+fn show_bookmarked(win: &SubjectWindow, bookmarked: bool) {
+    win.bookmark_action.set_state(&bookmarked.to_variant());
+    win.bookmark_button.set_icon_name(if bookmarked {
+        "starred-symbolic"
+    } else {
+        "non-starred-symbolic"
+    });
+    win.bookmark_button.set_tooltip_text(Some(if bookmarked {
+        "Remove Bookmark"
+    } else {
+        "Bookmark"
+    }));
+}
+
+/// Returns a short title for a tab showing `uri`: the file name for file URIs and virtual
+/// locations (see `gvfs::is_virtual`), and the URI itself otherwise.
+fn tab_title(uri: &str) -> String {
+    Some(uri)
+        .filter(|uri| uri.starts_with("file:") || gvfs::is_virtual(uri))
+        .and_then(|uri| gio::File::for_uri(uri).basename())
+        .map_or_else(
+            || uri.to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        )
+}
+
+/// Callback invoked with the target URI when a link to another node is activated.
+type LinkHandler = Rc<dyn Fn(&str)>;
+
+/// Handles activation of a link in a subject tab.
+///
+/// Depending on `Context::same_window`, the target either replaces the current subject (with
+/// the current one pushed onto the tab's history) or is opened in a new subject window.
+/// Holding Ctrl while activating the link always opens a new window.
+///
+/// # Arguments
+/// * `view` - The subject tab in which the link was activated.
+/// * `target` - The URI the link points to.
+fn follow_link(view: &SubjectView, target: &str) {
+    if view.win.ctx.same_window.get() && !control_pressed() {
+        let previous = view.uri.borrow().clone();
+        view.history.borrow_mut().visit(previous);
+        show_in_view(view, target.to_string());
+    } else {
+        open_subject_window(&view.win.app, target.to_string(), &view.win.ctx);
+    }
+}
+
+/// Forwards the store's change notifications to `ctx.changes`, so that open tabs follow
+/// changes made by the miners (e.g. when an edited file has been re-extracted) or by other
+/// applications in real time.
+///
+/// The notifier is only created once, and again after the miner restarted (see
+/// `watch_service`); other calls do nothing.
+fn watch_store(ctx: &Rc<Context>) {
+    watch_service(ctx);
+    if ctx.store_notifier.borrow().is_some() {
+        return;
+    }
+    let Some(notifier) = store::client().ok().and_then(|conn| conn.notifier()) else {
+        // Windows will not update by themselves.
+        trace::event("store_changes_unavailable", json!({}));
+        return;
+    };
+    // The context owns the notifier, so the handler must not keep the context alive.
+    let ctx_weak = Rc::downgrade(ctx);
+    // The miners report changes in many small batches, e.g. while a file is being extracted.
+    // The resources changed are collected for `STORE_CHANGE_DELAY`, and each is then reloaded
+    // only once.
+    let pending: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+    notifier.connect_events(move |_notifier, _service, _graph, events| {
+        let scheduled = !pending.borrow().is_empty();
+        for urn in events.iter().filter_map(|event| event.urn()) {
+            if !pending.borrow().iter().any(|c| *c == urn) {
+                pending.borrow_mut().push(urn.to_string());
+            }
+        }
+        if scheduled || pending.borrow().is_empty() {
+            return;
+        }
+        let ctx_weak = ctx_weak.clone();
+        let pending = pending.clone();
+        glib::timeout_add_local_once(STORE_CHANGE_DELAY, move || {
+            let changed = pending.take();
+            let Some(ctx) = ctx_weak.upgrade() else {
+                return;
+            };
+            for uri in changed {
+                trace::event("store_changed", json!({ "uri": uri }));
+                ctx.changes.publish(&uri);
+            }
+        });
+    });
+    ctx.store_notifier.replace(Some(notifier));
+}
+
+/// Starts watching the Tracker miner for restarts, unless that has been done already.
+///
+/// A restarted miner does not send events to the notifier created for the previous instance,
+/// and windows opened while it was gone show errors. On a restart the notifier is therefore
+/// replaced and every open subject reloaded, so that the windows recover without restarting
+/// the application.
+fn watch_service(ctx: &Rc<Context>) {
+    if ctx.store_watched.get() {
+        return;
+    }
+    // The watch outlives the context, so the handler must not keep the context alive.
+    let ctx_weak = Rc::downgrade(ctx);
+    let watched = store::watch_service(move || {
+        let Some(ctx) = ctx_weak.upgrade() else {
+            return;
+        };
+        ctx.store_notifier.replace(None);
+        watch_store(&ctx);
+        let uris = ctx.open_subjects.borrow().uris();
+        for uri in uris {
+            ctx.changes.publish(&uri);
+        }
+    });
+    ctx.store_watched.set(watched);
+}
+
+/// Returns whether the Ctrl key is currently held down on the default keyboard.
+fn control_pressed() -> bool {
+    gdk4::Display::default()
+        .and_then(|display| display.default_seat())
+        .and_then(|seat| seat.keyboard())
+        .is_some_and(|keyboard| {
+            keyboard
+                .modifier_state()
+                .contains(gdk4::ModifierType::CONTROL_MASK)
+        })
+}
+
+/// Replaces the subject shown in a tab without touching its history, then reloads it.
+fn show_in_view(view: &SubjectView, target: String) {
+    recent::record(&view.win.ctx, &target);
+    visits::record(&target);
+    *view.uri.borrow_mut() = target;
+    // Start the new subject at the top rather than at the previous scroll offset.
+    view.scroll.vadjustment().set_value(0.0);
+    load_subject(view);
+}
+
+/// Starts watching the file shown in `view` for changes on disk, replacing any previous watch,
+/// and hides the banner reporting them.
+///
+/// When the file is modified, renamed, moved or deleted while it is shown, the banner is
+/// revealed with a button for refreshing the view (or, for renamed and moved files, for showing
+/// the new location). Subjects that are not `file:` URIs are not watched.
+///
+/// # Arguments
+/// * `view` - The tab showing the file.
+/// * `uri` - The URI of the subject being loaded.
+fn watch_file(view: &SubjectView, uri: &str) {
+    view.banner.set_revealed(false);
+    view.moved_to.replace(None);
+    if let Some(old) = view.monitor.take() {
+        old.cancel();
+    }
+    if !uri.starts_with("file:") {
+        return;
+    }
+    let monitor = match gio::File::for_uri(uri).monitor(
+        gio::FileMonitorFlags::WATCH_MOVES,
+        None::<&gio::Cancellable>,
+    ) {
+        Ok(monitor) => monitor,
+        Err(err) => {
+            trace::event(
+                "watch_failed",
+                json!({ "uri": uri, "error": err.to_string() }),
+            );
+            return;
+        }
+    };
+
+    // The handler only refers to the banner and the new location, not to the view that owns
+    // the monitor, so that no reference cycle keeps the view alive.
+    let banner = view.banner.clone();
+    let moved_to = view.moved_to.clone();
+    monitor.connect_changed(move |_, _file, other, event| {
+        let title = match event {
+            gio::FileMonitorEvent::ChangesDoneHint | gio::FileMonitorEvent::AttributeChanged => {
+                "File changed on disk"
+            }
+            gio::FileMonitorEvent::Renamed | gio::FileMonitorEvent::MovedOut => {
+                moved_to.replace(other.map(|f| f.uri().to_string()));
+                "File was renamed or moved"
+            }
+            gio::FileMonitorEvent::Deleted => "File was deleted",
+            _ => return,
+        };
+        // The button follows a moved file to its new location, if that is known.
+        let button = if moved_to.borrow().is_some() {
+            "Show New Location"
+        } else {
+            "Refresh"
+        };
+        banner.set_title(title);
+        banner.set_button_label(Some(button));
+        banner.set_revealed(true);
+    });
+    view.monitor.replace(Some(monitor));
+}
+
+/// Loads the visible page of `view`, unless it is the properties page (which is loaded along
+/// with the subject) or has already been loaded for the current subject.
+///
+/// Secondary pages thus run their queries and build their widgets only once they are first
+/// shown, which keeps opening a subject fast.
+fn load_visible_page(view: &SubjectView) {
+    let Some(name) = view.stack.visible_child_name().map(|n| n.to_string()) else {
+        return;
+    };
+    if name == "properties" || view.loaded_pages.borrow().contains(&name) {
+        return;
+    }
+    view.loaded_pages.borrow_mut().push(name.clone());
+
+    let uri = view.uri.borrow().clone();
+    let view_clone = view.clone();
+    let task = glib::MainContext::default().spawn_local(async move {
+        let view = view_clone;
+        match name.as_str() {
+            "preview" => pages::load_preview(&view.preview, &uri).await,
+            "backlinks" => {
+                populate_backlinks_grid(
+                    &view.win.app,
+                    view.win.window.upcast_ref(),
+                    &view.backlinks_grid,
+                    &uri,
+                    &view.win.ctx,
+                )
+                .await
+            }
+            "related" => {
+                // Related items are shown in this tab, like links in the grid.
+                let view_clone = view.clone();
+                let on_link: LinkHandler = Rc::new(move |target| follow_link(&view_clone, target));
+                related::load(&view.related_list, &uri, &on_link).await
+            }
+            "raw" => pages::load_raw(&view.raw_buffer, &uri).await,
+            "contents" => {
+                contents::load(&view.contents_list, &view.win.app, &uri, &view.win.ctx).await
+            }
+            _ => {}
+        }
+    });
+    view.page_tasks.borrow_mut().push(task);
+}
+
+/// Asks for a file name and writes the table of `view` there as a PDF document (see
+/// `pdf::write`).
+fn export_pdf_file(view: &SubjectView) {
+    let chooser = gtk::FileChooserNative::new(
+        Some("Export as PDF"),
+        Some(&view.win.window),
+        gtk::FileChooserAction::Save,
+        Some("Export"),
+        None,
+    );
+    let uri = view.uri.borrow().clone();
+    chooser.set_current_name(&format!("{}.pdf", tab_title(&uri)));
+    // The rows are taken when the file is chosen, so that they match what is shown then.
+    let view = view.clone();
+    chooser.connect_response(move |chooser, response| {
+        if response != gtk::ResponseType::Accept {
+            return;
+        }
+        let Some(path) = chooser.file().and_then(|f| f.path()) else {
+            return;
+        };
+        let uri = view.uri.borrow().clone();
+        match pdf::write(&path, &uri, &view.table_data.borrow()) {
+            Ok(()) => announce::announce(&view.win.window, "Exported as PDF"),
+            Err(err) => show_message(
+                Some(view.win.window.upcast_ref()),
+                "Could not export as PDF",
+                &err.to_string(),
+            ),
+        }
+    });
+    chooser.show();
+}
+
+/// Returns the SPARQL query that fills the page named `page` of a subject tab showing `uri`.
+/// The preview, which does not come from the store, is described by the properties' query.
+fn page_query(page: &str, uri: &str) -> String {
+    match page {
+        "backlinks" => source::backlinks_query(uri),
+        "related" => related::related_query(uri),
+        "raw" => pages::raw_query(uri),
+        "contents" => contents::children_query(uri),
+        "duplicates" => duplicates::duplicates_query(uri),
+        _ => source::statements_query(uri),
+    }
+}
+
+/// Aborts the tasks loading the subject or secondary pages of `view` that are still running,
+/// so that they neither fill in results for a previous load nor keep querying after the tab has
+/// closed.
+fn cancel_tasks(view: &SubjectView) {
+    if let Some(task) = view.load_task.borrow_mut().take() {
+        task.abort();
+    }
+    for task in view.page_tasks.borrow_mut().drain(..) {
+        task.abort();
+    }
+}
+
+/// Scrolls `scroll` back to the vertical offset `value` once its content has been laid out
+/// again, e.g. after a reload has replaced the rows of its grid.
+fn restore_scroll(scroll: &gtk::ScrolledWindow, value: f64) {
+    let adjustment = scroll.vadjustment();
+    glib::idle_add_local_once(move || adjustment.set_value(value));
+}
+
+/// Asynchronously queries and displays the subject currently set in `view.uri`.
+///
+/// This updates the grid, the tab, and (if the tab is selected) the window's header label,
+/// "Open" button and history actions, and runs the post-load hook once the metadata is
+/// available.
+///
+/// # Arguments
+/// * `view` - The subject tab to (re)load.
+fn load_subject(view: &SubjectView) {
+    let uri = view.uri.borrow().clone();
+    // Reloading the same subject keeps the scroll offset; `show_in_view` resets it beforehand
+    // when a different subject is shown.
+    let scroll_offset = view.scroll.vadjustment().value();
+    view.win
+        .ctx
+        .open_subjects
+        .borrow_mut()
+        .register(&uri, &view.page);
+    view.page.set_title(&tab_title(&uri));
+    view.page.set_tooltip(&glib::markup_escape_text(&uri));
+    view.page.set_loading(true);
+    *view.title.borrow_mut() = "Loading…".to_string();
+    watch_file(view, &uri);
+    // A load still running would fill the grid along with this one, and the secondary pages
+    // show the previous subject (or outdated data); reload them lazily.
+    cancel_tasks(view);
+    view.loaded_pages.borrow_mut().clear();
+    load_visible_page(view);
+    if view.page.is_selected() {
+        update_window_chrome(&view.win);
+    }
+
+    // The application opening the subject is looked up once, without blocking, for the "Open"
+    // button and the handler shown next to it.
+    let view_clone = view.clone();
+    let uri_clone = uri.clone();
+    let task = glib::MainContext::default().spawn_local(async move {
+        // The outcome is read back by `update_window_chrome`.
+        let _ = resolve_handler(&uri_clone).await;
+        if view_clone.page.is_selected() {
+            update_window_chrome(&view_clone.win);
+        }
+    });
+    view.page_tasks.borrow_mut().push(task);
+
+    // Links in the grid are routed back through this tab.
+    let view_clone = view.clone();
+    let on_link: LinkHandler = Rc::new(move |target| follow_link(&view_clone, target));
+
+    // Look up the folders containing the subject alongside its properties. Their links are
+    // routed through this tab, too. The lookup is aborted if another subject is shown in the
+    // meantime (see `cancel_tasks`).
+    let view_clone = view.clone();
+    let on_link_clone = on_link.clone();
+    let uri_clone = uri.clone();
+    let task = glib::MainContext::default().spawn_local(async move {
+        let ancestors = breadcrumbs::ancestors(&uri_clone).await;
+        breadcrumbs::fill(
+            &view_clone.breadcrumbs,
+            &ancestors,
+            &uri_clone,
+            &on_link_clone,
+        );
+    });
+    view.page_tasks.borrow_mut().push(task);
+
+    // Folders get a page listing their contents. Like the breadcrumbs, the check is aborted if
+    // another subject is shown in the meantime.
+    let view_clone = view.clone();
+    let uri_clone = uri.clone();
+    let task = glib::MainContext::default().spawn_local(async move {
+        let is_folder = contents::is_folder(&uri_clone).await;
+        view_clone.contents_page.set_visible(is_folder);
+        if !is_folder && view_clone.stack.visible_child_name().as_deref() == Some("contents") {
+            view_clone.stack.set_visible_child_name("properties");
+        }
+    });
+    view.page_tasks.borrow_mut().push(task);
+
+    // Files with the same content hash are listed as probable duplicates, on a page that only
+    // appears if there are any, so they are looked up right away rather than when the page is
+    // first shown. Failures merely leave it hidden. Like the secondary pages, the lookup is
+    // aborted when the tab is reloaded or closed (see `cancel_tasks`).
+    let view_clone = view.clone();
+    let on_link_clone = on_link.clone();
+    let uri_clone = uri.clone();
+    let task = glib::MainContext::default().spawn_local(async move {
+        let duplicates = match duplicates::duplicates(&uri_clone).await {
+            Ok(duplicates) => duplicates,
+            Err(err) => {
+                trace::event(
+                    "query_failed",
+                    json!({ "query": "duplicates", "uri": uri_clone, "error": err.to_string() }),
+                );
+                Vec::new()
+            }
+        };
+        duplicates::fill(&view_clone.duplicates_list, &duplicates, &on_link_clone);
+        let page = &view_clone.duplicates_page;
+        page.set_visible(!duplicates.is_empty());
+        page.set_badge_number(duplicates.len() as u32);
+        page.set_needs_attention(!duplicates.is_empty());
+        if duplicates.is_empty()
+            && view_clone.stack.visible_child_name().as_deref() == Some("duplicates")
+        {
+            view_clone.stack.set_visible_child_name("properties");
+        }
+    });
+    view.page_tasks.borrow_mut().push(task);
+
+    let task_view = view.clone();
+    // Spawn an async block on the GTK main context.
+    let task = glib::MainContext::default().spawn_local(async move {
+        let view = task_view;
+        let ctx = view.win.ctx.clone();
+        // Query data and fill the grid; returns type info and the rows.
+        let started = Instant::now();
+        let mut timings = trace::Timings::default();
+        let (is_file_data_object, rows) = populate_grid(
+            &on_link,
+            view.win.window.upcast_ref(),
+            &view.grid,
+            &uri,
+            &ctx,
+            &mut timings,
+        )
+        .await;
+        let row_count = rows.len().saturating_sub(1);
+
+        // Hand the loaded metadata to the user's post-load hook, if one was configured. Nothing
+        // is run if the query failed and no rows were produced.
+        if let Some(command) = ctx.post_load_hook.as_ref().filter(|_| !rows.is_empty()) {
+            let json = export::to_json(&uri, is_file_data_object, &rows);
+            run_post_load_hook(command, json);
+        }
+
+        // Update the table data for other parts of the UI (e.g., copy button).
+        view.table_data.borrow_mut().clear();
+        view.table_data.borrow_mut().extend(rows);
+        view.file_data_flag.set(is_file_data_object);
+        restore_scroll(&view.scroll, scroll_offset);
+
+        // Instead of a table holding little more than the identifier, say that nothing is known
+        // about the subject, offering to index it (for files).
+        let is_file = uri.starts_with("file:");
+        view.reindex_button.set_visible(is_file);
+        view.reindex_button
+            .set_action_target_value(Some(&uri.to_variant()));
+        // Virtual locations are never indexed; what GIO tells about them is all there is.
+        let empty = nothing_indexed(&view.table_data.borrow()) && !gvfs::is_virtual(&uri);
+        view.properties
+            .set_visible_child_name(if empty { "empty" } else { "table" });
+
+        // Set the header label to reflect the object type.
+        *view.title.borrow_mut() = if is_file_data_object {
+            "File Information".to_string()
+        } else {
+            "Node Information".to_string()
+        };
+        view.page.set_loading(false);
+        if view.page.is_selected() {
+            update_window_chrome(&view.win);
+            // Tell how loading went; errors have been reported in detail by `populate_grid`.
+            let message = if view.table_data.borrow().is_empty() {
+                "Loading failed".to_string()
+            } else {
+                format!(
+                    "Loaded {row_count} {}",
+                    if row_count == 1 {
+                        "property"
+                    } else {
+                        "properties"
+                    }
+                )
+            };
+            announce::announce(&view.win.window, &message);
+        }
+        view.win
+            .window
+            .emit_by_name::<()>("loaded", &[&uri.to_string()]);
+
+        // If debug or timings are enabled, print diagnostics about results, but only immediately
+        // after the grid has been fully painted, and therefore is ready for a screen capture.
+        // Rendering is what remains of the time taken once connecting and querying are
+        // accounted for.
+        if (view.win.window.debug() || ctx.timings)
+            && let Some(clock) = view.properties.frame_clock()
+        {
+            let handler: Rc<RefCell<Option<glib::SignalHandlerId>>> = Rc::new(RefCell::new(None));
+            let handler_clone = handler.clone();
+            let ctx_clone = ctx.clone();
+            let window_clone = view.win.window.clone();
+            let label = view.timings.clone();
+            let uri = uri.clone();
+            let id = clock.connect_after_paint(move |clk| {
+                if let Some(h) = handler_clone.borrow_mut().take() {
+                    clk.disconnect(h);
+                }
+                let timings = trace::Timings {
+                    rendering_ms: trace::millis_since(started)
+                        .saturating_sub(timings.connection_ms + timings.query_ms),
+                    ..timings
+                };
+                trace::event(
+                    "subject_rendered",
+                    json!({
+                        "uri": uri,
+                        "connection_ms": timings.connection_ms,
+                        "query_ms": timings.query_ms,
+                        "rendering_ms": timings.rendering_ms,
+                    }),
+                );
+                label.set_text(&timings.summary());
+                trace::event(
+                    "results_displayed",
+                    json!({ "rows": row_count, "file_data": is_file_data_object }),
+                );
+                if let Some(path) = &ctx_clone.screenshot {
+                    match save_screenshot(window_clone.upcast_ref(), path) {
+                        Ok(()) => trace::event(
+                            "screenshot_saved",
+                            json!({ "path": path.display().to_string() }),
+                        ),
+                        Err(err) => {
+                            log::warn!("Could not save a screenshot to {}: {err}", path.display())
+                        }
+                    }
+                }
+            });
+            *handler.borrow_mut() = Some(id);
+            clock.request_phase(gdk4::FrameClockPhase::AFTER_PAINT);
+        }
+    });
+    *view.load_task.borrow_mut() = Some(task);
+}
+
+/// Renders `window` as it is currently shown and writes the image to `path` as PNG.
+fn save_screenshot(window: &gtk::Window, path: &Path) -> Result<(), String> {
+    let paintable = gtk::WidgetPaintable::new(Some(window));
+    let snapshot = gtk::Snapshot::new();
+    paintable.snapshot(&snapshot, window.width() as f64, window.height() as f64);
+    let node = snapshot
+        .to_node()
+        .ok_or_else(|| "the window has not been painted".to_string())?;
+    let renderer = window
+        .renderer()
+        .ok_or_else(|| "the window has no renderer".to_string())?;
+    renderer
+        .render_texture(node, None)
+        .save_to_png(path)
+        .map_err(|err| err.to_string())
+}
+
+/// Builds the placeholder shown on the properties page of `properties` when nothing is indexed
+/// about the subject (see `nothing_indexed`).
+///
+/// It offers to re-index the subject and to show the table anyway, which holds the basic
+/// properties read from the file system (see `derived_values`).
+///
+/// # Returns
+/// * The placeholder and its "Re-index" button, whose target is set to the subject when it is
+///   loaded.
+fn empty_properties_page(properties: &gtk::Stack) -> (adw::StatusPage, gtk::Button) {
+    let reindex = gtk::Button::builder()
+        .label("Re-index")
+        .action_name("win.reindex")
+        .build();
+    reindex.style_context().add_class("pill");
+    reindex.style_context().add_class("suggested-action");
+    let show_table = gtk::Button::with_label("Show Basic Information");
+    show_table.style_context().add_class("pill");
+    let properties = properties.clone();
+    show_table.connect_clicked(move |_| properties.set_visible_child_name("table"));
+
+    let buttons = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+    buttons.set_halign(gtk::Align::Center);
+    buttons.append(&reindex);
+    buttons.append(&show_table);
+    let page = adw::StatusPage::builder()
+        .icon_name("dialog-question-symbolic")
+        .title("No Information Available")
+        .description("This item may not be indexed.")
+        .child(&buttons)
+        .build();
+    (page, reindex)
+}
+
+/// Returns whether `rows` of a loaded subject hold nothing read from the store besides the
+/// identifier, i.e. at most the values derived from the file system.
+///
+/// # Returns
+/// * `false` as well if loading failed, i.e. there are no rows at all.
+fn nothing_indexed(rows: &[TableRow]) -> bool {
+    !rows.is_empty()
+        && rows
+            .iter()
+            .skip(1)
+            .all(|row| row.source == ValueSource::Derived)
+}
+
+/// Starts serving the metadata of a subject window over HTTP on a loopback port.
+///
+/// The root path serves an HTML rendering of the selected tab's table and `/json` serves the
+/// same JSON document that is passed to post-load hooks. Both always reflect the current
+/// contents. The server is stopped when the window is closed.
+///
+/// # Arguments
+/// * `win` - The subject window whose contents to serve and whose lifetime bounds the server.
+///
+/// # Returns
+/// * `Some(url)` with the URL of the HTML view, or `None` if the server could not be started.
+fn share_window(win: &SubjectWindow) -> Option<String> {
+    let win_clone = win.clone();
+    let responder: Rc<share::Responder> = Rc::new(move |path: &str| {
+        let view = current_view(&win_clone)?;
+        let uri = view.uri.borrow();
+        let rows = view.table_data.borrow();
+        let is_file_data_object = view.file_data_flag.get();
+        match path {
+            "/" => Some((
+                "text/html",
+                export::to_html(&uri, is_file_data_object, &rows),
+            )),
+            "/json" => Some((
+                "application/json",
+                export::to_json(&uri, is_file_data_object, &rows),
+            )),
+            _ => None,
+        }
+    });
+
+    let (service, port) = match share::serve(responder) {
+        Ok(s) => s,
+        Err(err) => {
+            log::warn!("Failed to start HTTP sharing: {err}");
+            return None;
+        }
+    };
+    // Stop listening as soon as the window goes away.
+    win.window.connect_close_request(move |_| {
+        service.stop();
+        service.close();
+        glib::Propagation::Proceed
+    });
+
+    Some(format!("http://127.0.0.1:{port}/"))
+}
+
+/// Reports a failure (or another message needing acknowledgement) in a dialog above `parent`.
+///
+/// # Arguments
+/// * `parent` - The window the message concerns, if any.
+/// * `heading` - What happened, e.g. "Failed to connect to Tracker".
+/// * `body` - The details, usually the error message.
+pub(crate) fn show_message(parent: Option<&gtk::Window>, heading: &str, body: &str) {
+    message_dialog(parent, heading, body).present();
+}
+
+/// Reports a failure like [`show_message`], offering to try again, e.g. after the Tracker
+/// miner has been started.
+///
+/// # Arguments
+/// * `parent` - The window the failure happened in, if any.
+/// * `heading` - What failed.
+/// * `body` - Why it failed, usually the error message.
+/// * `retry` - Called when "Retry" is chosen.
+fn show_message_with_retry(
+    parent: Option<&gtk::Window>,
+    heading: &str,
+    body: &str,
+    retry: impl Fn() + 'static,
+) {
+    let dialog = message_dialog(parent, heading, body);
+    dialog.add_response("retry", "Retry");
+    dialog.set_response_appearance("retry", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("retry"));
+    dialog.connect_response(Some("retry"), move |_, _| retry());
+    dialog.present();
+}
+
+/// Builds the dialog of [`show_message`], with a "Close" response.
+fn message_dialog(parent: Option<&gtk::Window>, heading: &str, body: &str) -> adw::MessageDialog {
+    let dialog = adw::MessageDialog::new(parent, Some(heading), Some(body));
+    dialog.add_response("close", "Close");
+    dialog.set_default_response(Some("close"));
+    dialog.set_close_response("close");
+    dialog
 }
 
-/// Adds actions for copying data to the clipboard and opening links externally such that these
-/// actions can be added to context menus.
+/// Adds actions for copying data to the clipboard, opening links externally and showing files in
+/// the file manager such that these actions can be added to context menus.
 ///
 /// # Arguments
 /// * `window` - The main application window to which the actions will be added.
@@ -327,6 +2570,7 @@ fn add_common_actions(window: &adw::ApplicationWindow) {
 
     // Register a handler for when the "copy-value" action is activated.
     // This closure receives the action object and the optional parameter (expected to be a string).
+    let win_for_copy = window.clone();
     copy_value.connect_activate(move |_action, param| {
         // Only proceed if a parameter was supplied.
         if let Some(v) = param {
@@ -338,6 +2582,8 @@ fn add_common_actions(window: &adw::ApplicationWindow) {
                     let clipboard = display.clipboard();
                     // Set the clipboard contents to the provided text.
                     clipboard.set_text(text);
+                    // Confirm the copy, which otherwise has no visible effect.
+                    announce::announce(&win_for_copy, "Copied to clipboard");
                 }
             }
         }
@@ -357,161 +2603,243 @@ fn add_common_actions(window: &adw::ApplicationWindow) {
         // Only proceed if a parameter (the URI) was supplied.
         if let Some(v) = param {
             if let Some(uri) = v.str() {
-                // Define a helper function to show an informational dialog with an error message.
-                // This will be used if the URI cannot be handled or if opening fails.
-                let report = |msg: String| {
-                    // Build a modal dialog attached to the main window with the error details.
-                    let dialog = gtk::MessageDialog::builder()
-                        .transient_for(&win_for_uri)
-                        .modal(true)
-                        .message_type(gtk::MessageType::Info)
-                        .buttons(gtk::ButtonsType::Ok)
-                        .text("Could not open URI")
-                        .secondary_text(&msg)
-                        .build();
-                    // Close the dialog when any response is received (e.g., user clicks OK).
-                    dialog.connect_response(|dlg, _| dlg.close());
-                    dialog.show();
-                };
-
                 // First, check if there is a handler registered for this URI scheme/type.
-                // If not, show a dialog to the user and exit early.
+                // If not, say so in the window's banner and exit early; trying again will not
+                // help until one is installed.
                 if let Err(msg) = uri_has_handler(uri) {
-                    report(msg);
+                    announce::report_problem(&win_for_uri, &msg, None);
                     return;
                 }
 
                 // Attempt to launch the URI using the system's default application.
-                // If this fails (e.g., no handler, launch error), report the error to the user.
+                // If this fails (e.g., the application did not start), offer to try again.
                 if let Err(err) =
                     gio::AppInfo::launch_default_for_uri(uri, None::<&gio::AppLaunchContext>)
                 {
-                    report(err.to_string());
+                    announce::report_problem(
+                        &win_for_uri,
+                        &format!("Could not open URI: {err}"),
+                        Some(("win.open-uri", Some(uri.to_variant()))),
+                    );
                 }
             }
         }
     });
     // Add the "open-uri" action to the window for use by UI elements or other parts of the code.
     window.add_action(&open_uri_action);
+
+    // ----- "Show in Files" Action -----
+    // Create an action that reveals a file (given as a `file:` URI) in the file manager.
+    let win_for_reveal = window.clone();
+    let show_in_files_action =
+        gio::SimpleAction::new("show-in-files", Some(glib::VariantTy::STRING));
+    show_in_files_action.connect_activate(move |_action, param| {
+        let Some(uri) = param.and_then(|v| v.str()).map(str::to_string) else {
+            return;
+        };
+        let window = win_for_reveal.clone();
+        glib::MainContext::default().spawn_local(async move {
+            if let Err(err) = show_in_files(&uri).await {
+                announce::report_problem(
+                    &window,
+                    &format!("Could not show file: {err}"),
+                    Some(("win.show-in-files", Some(uri.to_variant()))),
+                );
+            }
+        });
+    });
+    window.add_action(&show_in_files_action);
+
+    // ----- "Re-index" Action -----
+    // Create an action that asks the Tracker miner to index a file or folder (given as a `file:`
+    // URI) again. Open views of it are refreshed through the store's change notifications.
+    let win_for_reindex = window.clone();
+    let reindex_action = gio::SimpleAction::new("reindex", Some(glib::VariantTy::STRING));
+    reindex_action.connect_activate(move |_action, param| {
+        let Some(uri) = param.and_then(|v| v.str()).map(str::to_string) else {
+            return;
+        };
+        let window = win_for_reindex.clone();
+        glib::MainContext::default().spawn_local(async move {
+            if let Err(err) = reindex(&uri).await {
+                // The miner may just not have been running; retrying runs the action again.
+                announce::report_problem(
+                    &window,
+                    &format!("Could not re-index file: {err}"),
+                    Some(("win.reindex", Some(uri.to_variant()))),
+                );
+            }
+        });
+    });
+    window.add_action(&reindex_action);
+
+    // ----- "Provenance" Action -----
+    // Create an action that shows where a statement came from. Its argument is a pair of the
+    // statement's subject and the statement itself in N-Triples syntax.
+    let win_for_provenance = window.clone();
+    let provenance_action = gio::SimpleAction::new(
+        "show-provenance",
+        Some(glib::VariantTy::new("(ss)").expect("valid variant type")),
+    );
+    provenance_action.connect_activate(move |_action, param| {
+        let Some((subject, ntriples)) = param.and_then(|v| v.get::<(String, String)>()) else {
+            return;
+        };
+        let window = win_for_provenance.clone();
+        glib::MainContext::default().spawn_local(async move {
+            let details = match fetch_provenance(&subject, &ntriples).await {
+                Ok(details) => details,
+                Err(err) => format!("The store could not be queried: {err}"),
+            };
+            show_message(Some(window.upcast_ref()), "Provenance", &details);
+        });
+    });
+    window.add_action(&provenance_action);
+}
+
+/// Builds the query for the provenance of a statement: the named graphs containing it, and the
+/// data sources (`nie:dataSource`) of its subject.
+///
+/// # Arguments
+/// * `subject` - The subject IRI of the statement.
+/// * `ntriples` - The statement as a line of N-Triples, which is also a valid SPARQL triple
+///   pattern.
+fn provenance_query(subject: &str, ntriples: &str) -> String {
+    format!(
+        "SELECT DISTINCT ?g ?source WHERE {{ GRAPH ?g {{ {ntriples} }} \
+         OPTIONAL {{ {s} <{NIE_DATA_SOURCE}> ?source }} }}",
+        s = rdf::iri_ref(subject)
+    )
+}
+
+/// Looks up which named graphs hold a statement and which data source wrote its subject.
+///
+/// Tracker keeps the data of each miner (and of third-party applications) in separate graphs,
+/// e.g. `tracker:FileSystem` or `tracker:Documents`, so the graph tells who wrote a value.
+///
+/// # Returns
+/// * `Ok(details)` with a human-readable description, or the error of the query.
+async fn fetch_provenance(subject: &str, ntriples: &str) -> Result<String, glib::Error> {
+    let conn = store::client()?;
+    let cursor = conn
+        .query_async(&provenance_query(subject, ntriples))
+        .await?;
+    let mut graphs = Vec::new();
+    let mut sources = Vec::new();
+    while cursor.next_future().await? {
+        let compact = |iri: String| match rdf::compact(&iri) {
+            Some((prefix, local)) => format!("{prefix}:{local}"),
+            None => iri,
+        };
+        // A subject may have several data sources and the statement may be in several graphs,
+        // so collect the distinct values of each column.
+        for (column, seen) in [(0, &mut graphs), (1, &mut sources)] {
+            let value = cursor.string(column).map(|v| compact(v.to_string()));
+            if let Some(value) = value.filter(|v| !seen.contains(v)) {
+                seen.push(value);
+            }
+        }
+    }
+    if graphs.is_empty() {
+        return Ok("The statement is not stored in any named graph.".to_string());
+    }
+    let mut details = format!("Graph: {}", graphs.join(", "));
+    if !sources.is_empty() {
+        details.push_str(&format!("\nData source: {}", sources.join(", ")));
+    }
+    Ok(details)
+}
+
+/// Reveals a file in the file manager.
+///
+/// This asks the file manager to show and select the file through the
+/// `org.freedesktop.FileManager1.ShowItems` D-Bus method. If no file manager implements that
+/// interface, the folder containing the file is opened with its default handler instead.
+///
+/// # Arguments
+/// * `uri` - The `file:` URI of the file to reveal.
+///
+/// # Returns
+/// * `Ok(())` if the file or its folder was shown, or the error of the fallback otherwise.
+async fn show_in_files(uri: &str) -> Result<(), glib::Error> {
+    let params = (vec![uri.to_string()], "").to_variant();
+    let reveal = async {
+        let bus = gio::bus_get_future(gio::BusType::Session).await?;
+        bus.call_future(
+            Some("org.freedesktop.FileManager1"),
+            "/org/freedesktop/FileManager1",
+            "org.freedesktop.FileManager1",
+            "ShowItems",
+            Some(&params),
+            None,
+            gio::DBusCallFlags::NONE,
+            -1,
+        )
+        .await
+    };
+    if let Err(err) = reveal.await {
+        // Open the folder instead.
+        trace::event("show_items_failed", json!({ "error": err.to_string() }));
+        let file = gio::File::for_uri(uri);
+        let folder = file.parent().unwrap_or(file);
+        gio::AppInfo::launch_default_for_uri(&folder.uri(), None::<&gio::AppLaunchContext>)?;
+    }
+    Ok(())
+}
+
+/// Asks tracker-miner-fs to (re-)index a file or folder, through the `IndexLocation` method
+/// of its D-Bus control interface (as `tracker3 index --file` does).
+///
+/// # Arguments
+/// * `uri` - The `file:` URI of the file or folder.
+///
+/// # Returns
+/// * `Ok(())` once the miner has accepted the request, or the D-Bus error otherwise.
+async fn reindex(uri: &str) -> Result<(), glib::Error> {
+    let params = (uri, Vec::<String>::new(), Vec::<String>::new()).to_variant();
+    let bus = gio::bus_get_future(gio::BusType::Session).await?;
+    bus.call_future(
+        Some("org.freedesktop.Tracker3.Miner.Files.Control"),
+        "/org/freedesktop/Tracker3/Miner/Files/Index",
+        "org.freedesktop.Tracker3.Miner.Files.Index",
+        "IndexLocation",
+        Some(&params),
+        None,
+        gio::DBusCallFlags::NONE,
+        -1,
+    )
+    .await?;
+    Ok(())
 }
 
 /// Opens a new window displaying the backlinks (referencing nodes) for a given URI.
 ///
-/// This function creates a secondary application window styled and sized similarly to the main window,
-/// but focused on displaying "backlinks". It constructs the UI layout (header, grid, scrolling container,
-/// close button, etc.), adds all necessary actions, and asynchronously populates the grid with
-/// backlink data from Tracker.
+/// This function creates a `BacklinksWindow`, a secondary application window styled and sized
+/// similarly to the main window but focused on displaying "backlinks", and asynchronously
+/// populates its grid with backlink data from Tracker.
 ///
 /// # Arguments
 /// * `app` - Reference to the main application instance.
 /// * `parent` - The parent window to which this window will be transient (modal behavior).
 /// * `uri` - The URI of the object for which to display backlinks.
-/// * `debug` - If true, prints debug information during operation.
+/// * `ctx` - Shared application settings; `ctx.debug` enables debug output.
 fn open_object_window(
     app: &adw::Application,
     parent: &adw::ApplicationWindow,
     uri: String,
-    debug: bool,
+    ctx: &Rc<Context>,
 ) {
-    // ---- Window Construction ----
-
-    // Create a new GTK application window, sized and titled appropriately for backlinks.
-    // The window is set as transient for its parent for correct stacking and modality.
-    let window = adw::ApplicationWindow::builder()
-        .application(app)
-        .transient_for(parent)
-        .default_width(590)
-        .default_height(400)
-        .title("Backlinks")
-        .build();
-
-    // Add common actions (i.e., copy to clipboard, open URI) for context menus in this window.
-    add_common_actions(&window);
-
-    // ---- Header Bar ----
-
-    // Construct a header bar (title bar) with a title label.
-    let header = adw::HeaderBar::new();
-    header.set_show_end_title_buttons(true);
-    let header_label = gtk::Label::new(Some("Backlinks"));
-    header.set_title_widget(Some(&header_label));
-
-    // ---- Main Grid for Backlinks Data ----
-
-    // Create a GTK grid widget to display backlink entries.
-    let grid = gtk::Grid::builder()
-        .column_homogeneous(false)
-        .hexpand(true)
-        .vexpand(true)
-        .halign(gtk::Align::Fill)
-        .valign(gtk::Align::Fill)
-        .build();
-    grid.set_widget_name("data-grid"); // Set a name for styling via CSS.
-
-    // Embed the grid inside a viewport for scroll support (handles large data).
-    let viewport = gtk::Viewport::builder()
-        .scroll_to_focus(false)
-        .child(&grid)
-        .build();
-
-    // Wrap the viewport in a scrolled window, fixing the minimum content size.
-    let scroll = gtk::ScrolledWindow::builder()
-        .min_content_width(590)
-        .min_content_height(400)
-        .child(&viewport)
-        .build();
-
-    // ---- Toolbar and Bottom Bar ----
-
-    // Create a toolbar to host the header and bottom bar.
-    let toolbar = adw::ToolbarView::new();
-    toolbar.add_top_bar(&header);
-
-    // Create a "Close" button to allow the user to dismiss the window.
-    let close_button = gtk::Button::with_label("Close");
-    let win_clone = window.clone();
-    close_button.connect_clicked(move |_| {
-        win_clone.close();
-    });
-
-    // Layout the close button in a horizontal box, aligned to the end, with spacing and margins.
-    let bottom_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
-    bottom_box.set_spacing(5);
-    bottom_box.set_halign(gtk::Align::End);
-    bottom_box.set_margin_start(6);
-    bottom_box.set_margin_end(6);
-    bottom_box.set_margin_top(6);
-    bottom_box.set_margin_bottom(6);
-    bottom_box.append(&close_button);
-    toolbar.add_bottom_bar(&bottom_box);
-
-    // Set the scrolled grid as the main content of the toolbar, and set the toolbar as the window's content.
-    toolbar.set_content(Some(&scroll));
-    window.set_content(Some(&toolbar));
-
-    // Present (show) the window to the user.
-    window.present();
-
-    // ---- Asynchronous Population of Backlinks Data ----
-
-    // Clone references needed for the async block, since closures move their environment.
+    // The window is laid out by its template; subjects chosen in its search entry open in a
+    // subject window.
+    let window = backlinks::BacklinksWindow::new(app, parent, &uri, ctx);
     let app_clone = app.clone();
-    let window_clone = window.clone();
-    let grid_clone = grid.clone();
-    let uri_clone = uri.clone();
-    let debug_clone = debug;
+    let ctx_clone = ctx.clone();
+    window
+        .connect_open_subject(move |_, target| open_subject_window(&app_clone, target, &ctx_clone));
 
-    // Spawn an asynchronous task in the main context to populate the backlinks grid.
-    glib::MainContext::default().spawn_local(async move {
-        populate_backlinks_grid(
-            &app_clone,
-            &window_clone,
-            &grid_clone,
-            &uri_clone,
-            debug_clone,
-        )
-        .await;
-    });
+    // Present (show) the window to the user, then populate it asynchronously.
+    window.present();
+    window.load();
 }
 
 /// Asynchronously populates a GTK grid widget with backlinks—nodes that reference the given URI.
@@ -525,13 +2853,13 @@ fn open_object_window(
 /// * `window` - The parent window, used for modal dialogs.
 /// * `grid` - The GTK grid to populate with backlink data.
 /// * `uri` - The URI whose backlinks are to be listed.
-/// * `debug` - If true, emits diagnostic output during execution.
+/// * `ctx` - Shared application settings; `ctx.debug` enables diagnostic output.
 async fn populate_backlinks_grid(
     app: &adw::Application,
     window: &adw::ApplicationWindow,
     grid: &gtk::Grid,
     uri: &str,
-    debug: bool,
+    ctx: &Rc<Context>,
 ) {
     // ---- Clear Existing Grid Content ----
     // Remove all current children from the grid so we start with a blank slate.
@@ -540,67 +2868,93 @@ async fn populate_backlinks_grid(
     }
 
     // ---- Connect to Tracker and Handle Errors ----
-    let conn = match create_store_connection() {
+    let conn = match store::client() {
         Ok(c) => c,
         Err(err) => {
             // If connection fails, show an error dialog and return early.
-            if debug {
-                log::debug!("Failed to connect to Tracker: {err}");
-            }
-            let dialog = gtk::MessageDialog::builder()
-                .transient_for(window)
-                .modal(true)
-                .message_type(gtk::MessageType::Error)
-                .text("Failed to connect to Tracker")
-                .secondary_text(format!("{err}"))
-                .buttons(gtk::ButtonsType::Ok)
-                .build();
-            dialog.connect_response(|dlg, _| dlg.close());
-            dialog.show();
+            trace::event("connection_failed", json!({ "error": err.to_string() }));
+            // Retrying refreshes the window (or, in a subject window, its selected tab), like F5.
+            let window_clone = window.clone();
+            show_message_with_retry(
+                Some(window.upcast_ref()),
+                "Failed to connect to Tracker",
+                &err.to_string(),
+                move || ActionGroupExt::activate_action(&window_clone, "refresh", None),
+            );
+            announce::announce(window, "Loading backlinks failed");
             return;
         }
     };
 
-    // ---- Prepare and Run the SPARQL Query ----
-    // Query for all subject-predicate pairs where the object matches the given URI.
-    let sparql = format!("SELECT DISTINCT ?s ?p WHERE {{ ?s ?p <{uri}> }}", uri = uri);
-    if debug {
-        log::debug!("Running SPARQL query: {sparql}");
-    }
-    let cursor = match conn.query_future(&sparql).await {
-        Ok(c) => c,
+    // ---- Query the Backlinks ----
+    // Read all subject-predicate pairs where the object matches the given URI, along with the
+    // type and title of each referring subject, so it can be described by name.
+    trace::event("query_started", json!({ "query": "backlinks", "uri": uri }));
+    let started = Instant::now();
+    let results = match conn.backlinks(uri).await {
+        Ok(results) => {
+            trace::event(
+                "query_finished",
+                json!({
+                    "query": "backlinks",
+                    "uri": uri,
+                    "rows": results.len(),
+                    "duration_ms": trace::millis_since(started),
+                }),
+            );
+            results
+        }
         Err(err) => {
             // If query fails, show an error dialog and return early.
-            if debug {
-                log::debug!("SPARQL query error: {err}");
-            }
-            let dialog = gtk::MessageDialog::builder()
-                .transient_for(window)
-                .modal(true)
-                .message_type(gtk::MessageType::Error)
-                .text("SPARQL query error")
-                .secondary_text(format!("{err}"))
-                .buttons(gtk::ButtonsType::Ok)
-                .build();
-            dialog.connect_response(|dlg, _| dlg.close());
-            dialog.show();
+            trace::event(
+                "query_failed",
+                json!({ "query": "backlinks", "uri": uri, "error": err.to_string() }),
+            );
+            let window_clone = window.clone();
+            show_message_with_retry(
+                Some(window.upcast_ref()),
+                "SPARQL query error",
+                &err.to_string(),
+                move || ActionGroupExt::activate_action(&window_clone, "refresh", None),
+            );
+            announce::announce(window, "Loading backlinks failed");
             return;
         }
     };
 
-    // ---- Iterate Through Query Results and Populate the Grid ----
+    // Look up the ontology's labels of all predicates and types at once, for `friendly_label`.
+    labels::load(
+        results
+            .iter()
+            .flat_map(|(_, pred, subj_type, _)| [pred.as_str(), subj_type.as_str()]),
+    )
+    .await;
+    if ctx.compact_uris.get() {
+        prefixes::load().await;
+    }
+
+    // ---- Populate the Grid ----
     let mut row = 0;
-    while cursor.next_future().await.unwrap_or(false) {
-        // Extract the subject and predicate from the current result row.
-        let subj = cursor.string(0).unwrap_or_default().to_string();
-        let pred = cursor.string(1).unwrap_or_default().to_string();
+    for (subj, pred, subj_type, subj_title) in results {
+        // Describe the subject by its type and title where known, e.g. "Photo 'IMG_1234.jpg'".
+        let description = describe_subject(&subj, &subj_type, &subj_title);
+
+        // The statement that links the subject to this node, for copying in RDF syntaxes.
+        let triple = rdf::Triple {
+            subject: subj.clone(),
+            predicate: pred.clone(),
+            object: rdf::Object::Iri(uri.to_string()),
+        };
 
         // ---- Create a Widget for the Subject Node ----
         // If the subject looks like a URI, present it as a clickable link; otherwise, as plain text.
         let widget: gtk::Widget = if looks_like_uri(&subj) {
             let lbl_link = gtk::Label::new(None);
-            let escaped = glib::markup_escape_text(&subj);
-            lbl_link.set_markup(&format!("<a href=\"{0}\">{0}</a>", escaped));
+            lbl_link.set_markup(&format!(
+                "<a href=\"{}\">{}</a>",
+                glib::markup_escape_text(&subj),
+                glib::markup_escape_text(&description)
+            ));
             lbl_link.set_halign(gtk::Align::Start);
             lbl_link.set_margin_start(6);
             lbl_link.set_margin_top(4);
@@ -611,25 +2965,27 @@ async fn populate_backlinks_grid(
 
             // Make the link clickable: opens the subject in the UI.
             let app_clone = app.clone();
-            let debug_clone = debug;
+            let ctx_clone = ctx.clone();
             lbl_link.connect_activate_link(move |_lbl, uri| {
-                open_subject_window(&app_clone, uri.to_string(), debug_clone);
+                open_subject_window(&app_clone, uri.to_string(), &ctx_clone);
                 glib::Propagation::Stop
             });
 
             // Add a context menu for copying values.
             add_copy_menu(
                 &lbl_link,
-                &subj,
+                &description,
                 &subj,
                 "Copy Displayed Value",
                 "Copy Native Value",
+                Some(&triple),
+                None,
             );
 
             lbl_link.upcast()
         } else {
             // For plain text subjects, use a regular label.
-            let lbl_val = gtk::Label::new(Some(&subj));
+            let lbl_val = gtk::Label::new(Some(&description));
             lbl_val.set_halign(gtk::Align::Start);
             lbl_val.set_margin_start(6);
             lbl_val.set_margin_top(4);
@@ -640,10 +2996,12 @@ async fn populate_backlinks_grid(
 
             add_copy_menu(
                 &lbl_val,
-                &subj,
+                &description,
                 &subj,
                 "Copy Displayed Value",
                 "Copy Native Value",
+                Some(&triple),
+                None,
             );
 
             lbl_val.upcast()
@@ -656,7 +3014,7 @@ async fn populate_backlinks_grid(
 
         // ---- Create and Attach Predicate Label ----
         // Convert predicate URI to a friendly display label.
-        let pred_label = friendly_label(&pred);
+        let pred_label = predicate_label(&pred, ctx);
         let lbl_pred = gtk::Label::new(Some(&pred_label));
         lbl_pred.set_halign(gtk::Align::Start);
         lbl_pred.set_valign(gtk::Align::Start);
@@ -673,6 +3031,8 @@ async fn populate_backlinks_grid(
             &pred,
             "Copy Displayed Predicate",
             "Copy Native Predicate",
+            None,
+            Some(&pred),
         );
 
         // Attach the predicate label to the second column of the current row.
@@ -682,10 +3042,28 @@ async fn populate_backlinks_grid(
         row += 1;
     }
 
+    announce::announce(
+        window,
+        &format!(
+            "Loaded {row} {}",
+            if row == 1 { "backlink" } else { "backlinks" }
+        ),
+    );
+
     // ---- Final Debug Output ----
-    if debug {
-        log::debug!("Backlinks query returned {row} rows");
-    }
+    trace::event("backlinks_displayed", json!({ "uri": uri, "rows": row }));
+}
+
+/// What opening a URI externally would do: launch the application given (`Ok(Some(_))`), have
+/// GIO work it out (`Ok(None)`, for URIs that cannot be checked, such as ones without a scheme),
+/// or fail for lack of a handler (`Err` with a human-readable message).
+type Handler = Result<Option<gio::AppInfo>, String>;
+
+thread_local! {
+    /// The handlers looked up so far, by URI. They are shared by all windows, since finding the
+    /// MIME type of a file takes a query to Tracker and the same links are checked again in
+    /// every window.
+    static HANDLERS: RefCell<HashMap<String, Handler>> = RefCell::new(HashMap::new());
 }
 
 /// Determines whether the system has a registered application handler for a given URI.
@@ -697,90 +3075,179 @@ async fn populate_backlinks_grid(
 /// # Arguments
 /// * `uri` - The URI string to check (may be a file path, web link, etc.).
 ///
+/// The outcome is remembered for the lifetime of the application (see `HANDLERS`); a URI not
+/// looked up before is looked up while blocking, so windows resolve the handler of their
+/// subject beforehand with `resolve_handler`.
+///
 /// # Returns
 /// * `Ok(())` if a suitable handler exists for the URI's scheme or MIME type.
 /// * `Err(String)` with a descriptive message if no handler is found.
 fn uri_has_handler(uri: &str) -> Result<(), String> {
-    // Attempt to parse the URI using the Url crate to inspect its components.
-    if let Ok(url) = url::Url::parse(uri) {
-        // If the scheme is "file", handle as a local file.
-        if url.scheme() == "file" {
-            // Try to convert the file URI to a platform-native path.
-            if let Ok(path) = url.to_file_path() {
-                if let Some(p) = path.to_str() {
-                    // Attempt to determine the MIME type for the file.
-                    // First, try to use the indexed content type; if not found, guess based on filename.
-                    let mime = get_indexed_content_type(uri).unwrap_or_else(|| {
-                        let (guess, _) = gio::content_type_guess(Some(p), b"");
-                        guess.to_string()
-                    });
-                    // Check if there is a default application for this MIME type.
-                    // If not, return an error indicating the missing handler.
-                    if gio::AppInfo::default_for_type(&mime, false).is_none() {
-                        return Err(format!("No application available for type \"{}\".", mime));
-                    }
-                }
-            }
-        }
-        // For non-file URIs, check for a default application registered for the URI's scheme.
-        else if gio::AppInfo::default_for_uri_scheme(url.scheme()).is_none() {
-            return Err(format!(
-                "No application available for scheme \"{}\".",
-                url.scheme()
-            ));
-        }
+    if let Some(handler) = cached_handler(uri) {
+        return handler.map(|_| ());
     }
-    // If all checks pass, a handler exists; return success.
-    Ok(())
+    let mut content_type = None;
+    if file_url(uri).is_some()
+        && let Ok(conn) = store::client()
+        && let Ok(cursor) = conn.query(&content_type_query(uri))
+        && cursor.next(None::<&gio::Cancellable>).unwrap_or(false)
+    {
+        content_type = first_content_type(&cursor);
+    }
+    remember_handler(uri, find_handler(uri, content_type)).map(|_| ())
 }
 
-/// Creates a new connection to the Tracker store via D-Bus.
-///
-/// This helper wraps `tracker::SparqlConnection::bus_new` with the
-/// fixed service name used throughout the application.
-fn create_store_connection() -> Result<tracker::SparqlConnection, glib::Error> {
-    tracker::SparqlConnection::bus_new("org.freedesktop.Tracker3.Miner.Files", None, None)
+/// Looks up the handler of `uri` without blocking, unless it has been looked up before, so
+/// that `cached_handler` finds it.
+async fn resolve_handler(uri: &str) -> Handler {
+    if let Some(handler) = cached_handler(uri) {
+        return handler;
+    }
+    let mut content_type = None;
+    if file_url(uri).is_some()
+        && let Ok(conn) = store::client()
+        && let Ok(cursor) = conn.query_async(&content_type_query(uri)).await
+        && cursor.next_future().await.unwrap_or(false)
+    {
+        content_type = first_content_type(&cursor);
+    }
+    remember_handler(uri, find_handler(uri, content_type))
+}
+
+/// Returns the handler of `uri`, if it has been looked up before.
+fn cached_handler(uri: &str) -> Option<Handler> {
+    HANDLERS.with(|handlers| handlers.borrow().get(uri).cloned())
 }
 
-/// Queries the Tracker index for the MIME content type associated with a given URI, if available.
+/// Records `handler` as the handler of `uri` and returns it.
+fn remember_handler(uri: &str, handler: Handler) -> Handler {
+    HANDLERS.with(|handlers| {
+        let mut handlers = handlers.borrow_mut();
+        // Like the labels, the handlers start over when memory is short.
+        if memory::is_low() && handlers.len() >= HANDLER_CHECKS_LOW_MEMORY {
+            handlers.clear();
+        }
+        handlers.insert(uri.to_string(), handler.clone());
+    });
+    handler
+}
+
+/// Returns `uri` parsed, if it is a `file:` URI.
+fn file_url(uri: &str) -> Option<url::Url> {
+    url::Url::parse(uri)
+        .ok()
+        .filter(|url| url.scheme() == "file")
+}
+
+/// Finds the application that opening `uri` externally would launch, without consulting the
+/// handlers remembered in `HANDLERS`.
 ///
-/// This function attempts to determine the indexed content type (MIME type) for a file or resource
-/// by executing a SPARQL query against the Tracker database. If no content type can be found,
-/// it returns `None`.
+/// For files this is the default application for the file's content type (as indexed, or else
+/// guessed from its name); for other URIs it is the default handler of the URI scheme.
 ///
 /// # Arguments
-/// * `uri` - The URI of the file or resource whose content type should be queried.
+/// * `uri` - The URI to look up.
+/// * `indexed_type` - The MIME type Tracker has indexed for the file, if `uri` is a `file:` URI
+///   and it has one (see `content_type_query`).
+fn find_handler(uri: &str, indexed_type: Option<String>) -> Handler {
+    // URIs that cannot be parsed are left to GIO.
+    let Ok(url) = url::Url::parse(uri) else {
+        return Ok(None);
+    };
+    // For non-file URIs, check for a default application registered for the URI's scheme.
+    if url.scheme() != "file" {
+        return gio::AppInfo::default_for_uri_scheme(url.scheme())
+            .map(Some)
+            .ok_or_else(|| format!("No application available for scheme \"{}\".", url.scheme()));
+    }
+    let Ok(path) = url.to_file_path() else {
+        return Ok(None);
+    };
+    // Use the indexed content type; if there is none, guess based on the file name.
+    let mime = indexed_type.unwrap_or_else(|| {
+        let (guess, _) = gio::content_type_guess(Some(&path), b"");
+        guess.to_string()
+    });
+    gio::AppInfo::default_for_type(&mime, false)
+        .map(Some)
+        .ok_or_else(|| format!("No application available for type \"{mime}\"."))
+}
+
+/// Runs the user-specified post-load hook command, writing the exported JSON to its stdin.
 ///
-/// # Returns
-/// An `Option<String>` containing the MIME type (e.g., "application/pdf") if found, or `None` otherwise.
-fn get_indexed_content_type(uri: &str) -> Option<String> {
-    // Attempt to create a connection to the Tracker D-Bus service.
-    // If the service is unavailable or the connection fails, return None immediately.
-    let conn = create_store_connection().ok()?;
-
-    // Prepare a SPARQL query to fetch the indexed content type for the given URI.
-    // The query traverses from the file node to its "interpreted as" node, then retrieves its MIME type.
-    let sparql = format!(
-        "SELECT ?ct WHERE {{ <{uri}> <{interp}> ?o . ?o <{mime}> ?ct }} LIMIT 1",
-        uri = uri,
-        interp = NIE_INTERPRETED_AS,
-        mime = NIE_MIME_TYPE
-    );
+/// The command is interpreted by `sh -c`, so it may contain pipes and redirections. It runs
+/// asynchronously on the main context; failures to spawn it or a non-zero exit status are
+/// logged as warnings but otherwise do not affect the window.
+///
+/// # Arguments
+/// * `command` - The shell command line to execute.
+/// * `json` - The JSON document to feed to the command's standard input.
+fn run_post_load_hook(command: &str, json: String) {
+    let argv = ["sh", "-c", command].map(std::ffi::OsStr::new);
+    let process = match gio::Subprocess::newv(&argv, gio::SubprocessFlags::STDIN_PIPE) {
+        Ok(p) => p,
+        Err(err) => {
+            log::warn!("Failed to start post-load hook `{command}`: {err}");
+            return;
+        }
+    };
+    trace::event("hook_started", json!({ "command": command }));
+
+    let command = command.to_string();
+    glib::MainContext::default().spawn_local(async move {
+        // Write the JSON to the hook's stdin and close it, then wait for the hook to exit.
+        let result = match process.communicate_utf8_future(Some(json)).await {
+            Ok(_) => process.wait_check_future().await,
+            Err(err) => Err(err),
+        };
+        match result {
+            Ok(()) => trace::event("hook_finished", json!({ "command": command })),
+            Err(err) => log::warn!("Post-load hook `{command}` failed: {err}"),
+        }
+    });
+}
 
-    // Execute the SPARQL query on the Tracker service.
-    // If the query fails, return None.
-    let cursor = conn.query(&sparql, None::<&gio::Cancellable>).ok()?;
+/// Returns the query for the MIME type Tracker has indexed for the file `uri`: the type of the
+/// information element the file is interpreted as.
+fn content_type_query(uri: &str) -> String {
+    format!(
+        "SELECT ?ct WHERE {{ {uri} <{NIE_INTERPRETED_AS}> ?o . ?o <{NIE_MIME_TYPE}> ?ct }} LIMIT 1",
+        uri = rdf::iri_ref(uri)
+    )
+}
 
-    // If there is at least one result row, handle that one row.
-    if cursor.next(None::<&gio::Cancellable>).unwrap_or(false) {
-        // Extract the first string result (expected to be the content type).
-        let ct = cursor.string(0).unwrap_or_default().to_string();
-        // If the content type is an empty string, treat as not found.
-        if ct.is_empty() { None } else { Some(ct) }
-    } else {
-        // If the query returned no results, return None.
-        None
+/// Returns the MIME type in the current row of a cursor over `content_type_query`, unless it is
+/// empty. The cursor must have been moved to the row.
+fn first_content_type(cursor: &tracker::SparqlCursor) -> Option<String> {
+    Some(cursor.string(0).unwrap_or_default().to_string()).filter(|ct| !ct.is_empty())
+}
+
+/// The values of each predicate of a subject, as `(object value, datatype, language tag)`
+/// tuples.
+type PredicateValues = HashMap<String, Vec<(String, String, String)>>;
+
+/// Groups the statements about a subject by predicate.
+///
+/// # Returns
+/// * The predicates in order of appearance, which is rearranged for display later.
+/// * The `(object value, datatype, language tag)` values of each predicate.
+/// * Whether the subject is an `nfo:FileDataObject`.
+fn collect_statements(statements: Vec<source::Statement>) -> (Vec<String>, PredicateValues, bool) {
+    let mut order = Vec::new();
+    let mut map = PredicateValues::new();
+    let mut is_file_data_object = false;
+    for (pred, obj, dtype, lang) in statements {
+        // Check for a special RDF type indicating whether the node is a file data object.
+        if pred == RDF_TYPE && obj == FILEDATAOBJECT {
+            is_file_data_object = true;
+        }
+        // Track order of predicates as we see them.
+        if !map.contains_key(&pred) {
+            order.push(pred.clone());
+        }
+        map.entry(pred).or_default().push((obj, dtype, lang));
     }
+    (order, map, is_file_data_object)
 }
 
 /// Populates a GTK grid widget with metadata and properties for a given URI,
@@ -792,31 +3259,30 @@ fn get_indexed_content_type(uri: &str) -> Option<String> {
 /// and a vector of structured table rows for use elsewhere in the UI.
 ///
 /// # Arguments
-/// * `app` - Reference to the main application instance (used for launching sub-UIs).
+/// * `on_link` - Callback invoked when a link to another node is activated.
 /// * `window` - The application window owning the grid (used for modal dialogs).
 /// * `grid` - The GTK grid widget to populate with result rows.
 /// * `uri` - The URI to inspect and display information about.
-/// * `debug` - If true, prints diagnostic information to stderr during processing.
+/// * `ctx` - Shared application settings; `ctx.debug` enables diagnostic output.
+/// * `timings` - Receives how long connecting to the store and querying took.
 ///
 /// # Returns
 /// * `(bool, Vec<TableRow>)` - A tuple where the boolean indicates whether the URI
 ///   is a file data object, and the vector contains the table rows to display.
 async fn populate_grid(
-    app: &adw::Application,
+    on_link: &LinkHandler,
     window: &adw::ApplicationWindow,
     grid: &gtk::Grid,
     uri: &str,
-    debug: bool,
+    ctx: &Rc<Context>,
+    timings: &mut trace::Timings,
 ) -> (bool, Vec<TableRow>) {
     // Clear any existing children from the grid to prepare for new content.
     while let Some(child) = grid.first_child() {
         grid.remove(&child);
     }
 
-    // If debugging is enabled, print which URI we are processing.
-    if debug {
-        log::debug!("Fetching backlinks for {uri}");
-    }
+    trace::event("subject_loading", json!({ "uri": uri }));
 
     // Initialize a vector to collect all the table rows we generate.
     let mut rows_vec = Vec::new();
@@ -849,6 +3315,8 @@ async fn populate_grid(
         uri,
         "Copy Displayed Value",
         "Copy Native Value",
+        None,
+        None,
     );
 
     // Add a tooltip to the URI label, shortening the text if needed.
@@ -860,277 +3328,768 @@ async fn populate_grid(
     grid.attach(&uri_label, 1, 0, 1, 1);
 
     // Record this as the first table row for later copying/export.
-    rows_vec.push(TableRow {
-        display_predicate: "Identifier".to_string(),
-        native_predicate: "Identifier".to_string(),
-        display_value: uri.to_string(),
-        native_value: uri.to_string(),
-    });
+    rows_vec.push(table::identifier_row(uri));
 
     // ---- Query Tracker for Additional Metadata ----
 
-    if debug {
-        log::debug!("Connecting to Tracker database for metadata…");
-    }
-    // Try to connect to the Tracker D-Bus service for SPARQL queries.
-    let conn = match create_store_connection() {
-        Ok(c) => c,
+    // Try to connect to the store (normally the Tracker D-Bus service) for SPARQL queries.
+    let started = Instant::now();
+    let conn = match store::client() {
+        Ok(c) => {
+            timings.connection_ms = trace::millis_since(started);
+            trace::event(
+                "connection_ready",
+                json!({ "duration_ms": timings.connection_ms }),
+            );
+            c
+        }
         Err(err) => {
             // On error, show an error dialog and return empty result.
-            if debug {
-                log::debug!("Failed to connect to Tracker: {err}");
-            }
-            let dialog = gtk::MessageDialog::builder()
-                .transient_for(window)
-                .modal(true)
-                .message_type(gtk::MessageType::Error)
-                .text("Failed to connect to Tracker")
-                .secondary_text(format!("{err}"))
-                .buttons(gtk::ButtonsType::Ok)
-                .build();
-            dialog.connect_response(|dlg, _| dlg.close());
-            dialog.show();
+            trace::event("connection_failed", json!({ "error": err.to_string() }));
+            // Retrying reloads the subject, in every tab showing it.
+            let ctx_clone = ctx.clone();
+            let uri = uri.to_string();
+            show_message_with_retry(
+                Some(window.upcast_ref()),
+                "Failed to connect to Tracker",
+                &err.to_string(),
+                move || ctx_clone.changes.publish(&uri),
+            );
             return (false, Vec::new());
         }
     };
 
-    // Prepare a SPARQL query to get all direct predicates and objects for this node.
-    let sparql = format!(
-        r#"
-        SELECT DISTINCT ?pred ?obj (DATATYPE(?obj) AS ?dtype) WHERE {{
-            <{uri}> ?pred ?obj .
-        }}
-    "#,
-        uri = uri
+    // Read all direct predicates and objects for this node.
+    trace::event(
+        "query_started",
+        json!({ "query": "statements", "uri": uri }),
     );
-    if debug {
-        log::debug!("Running SPARQL query: {sparql}");
-    }
+    let started = Instant::now();
     // Run the query asynchronously; handle errors by reporting them to the user.
-    let cursor = match conn.query_future(&sparql).await {
-        Ok(c) => c,
+    let statements = match conn.statements(uri).await {
+        Ok(statements) => {
+            timings.query_ms = trace::millis_since(started);
+            trace::event(
+                "query_finished",
+                json!({
+                    "query": "statements",
+                    "uri": uri,
+                    "rows": statements.len(),
+                    "duration_ms": timings.query_ms,
+                }),
+            );
+            statements
+        }
         Err(err) => {
-            if debug {
-                log::debug!("SPARQL query error: {err}");
-            }
-            let dialog = gtk::MessageDialog::builder()
-                .transient_for(window)
-                .modal(true)
-                .message_type(gtk::MessageType::Error)
-                .text("SPARQL query error")
-                .secondary_text(format!("{err}"))
-                .buttons(gtk::ButtonsType::Ok)
-                .build();
-            dialog.connect_response(|dlg, _| dlg.close());
-            dialog.show();
+            trace::event(
+                "query_failed",
+                json!({ "query": "statements", "uri": uri, "error": err.to_string() }),
+            );
+            let ctx_clone = ctx.clone();
+            let uri = uri.to_string();
+            show_message_with_retry(
+                Some(window.upcast_ref()),
+                "SPARQL query error",
+                &err.to_string(),
+                move || ctx_clone.changes.publish(&uri),
+            );
             return (false, Vec::new());
         }
     };
 
-    // ---- Collect Results Into an Ordered Map ----
-
-    // Preserve the order in which predicates appear for display.
-    let mut order = Vec::new();
-    // Map each predicate to a list of (object value, datatype) pairs.
-    let mut map: HashMap<String, Vec<(String, String)>> = HashMap::new();
-
-    // Flag indicating if the node is a file data object.
-    let mut is_file_data_object = false;
-
-    // Iterate through all rows of the SPARQL result set.
-    while cursor.next_future().await.unwrap_or(false) {
-        let pred = cursor.string(0).unwrap_or_default().to_string();
-        let obj = cursor.string(1).unwrap_or_default().to_string();
-        let dtype = cursor.string(2).unwrap_or_default().to_string();
+    // ---- Compute the Rows ----
 
-        // Track order of predicates as we see them.
-        if !map.contains_key(&pred) {
-            order.push(pred.clone());
-            map.insert(pred.clone(), Vec::new());
-        }
-        map.get_mut(&pred)
-            .unwrap()
-            .push((obj.clone(), dtype.clone()));
+    let table = table::build(&*conn, uri, statements, ctx).await;
 
-        // Check for a special RDF type indicating whether the node is a file data object.
-        if pred == RDF_TYPE && obj == FILEDATAOBJECT {
-            is_file_data_object = true;
-        }
+    // Fetch the ontology's comments on all predicates, along with their domains and ranges, at
+    // once, to show in their tooltips. The table is still of use without them, so a failure is only reported in the banner.
+    if let Err(err) = labels::load_comments(table.predicates()).await {
+        trace::event(
+            "query_failed",
+            json!({ "query": "comments", "uri": uri, "error": err.to_string() }),
+        );
+        announce::report_problem(
+            window,
+            "Descriptions of the properties could not be loaded",
+            Some(("win.refresh", None)),
+        );
     }
 
-    // ---- Build Grid Rows for Each Predicate and Object ----
+    // ---- Build Grid Rows for Each Entry ----
 
     let mut row = 1; // Start from row 1 (row 0 is the identifier)
-    for pred in order {
-        if let Some(entries) = map.get(&pred) {
-            // Convert the raw predicate URI to a user-friendly label.
-            let label_text = friendly_label(&pred);
-
-            for (i, (obj, dtype)) in entries.iter().enumerate() {
-                // Only add the predicate label in the first row for multi-valued predicates.
-                if i == 0 {
-                    let lbl_key = gtk::Label::new(Some(&label_text));
-                    lbl_key.set_halign(gtk::Align::Start);
-                    lbl_key.set_valign(gtk::Align::Start);
-                    lbl_key.style_context().add_class("first-col");
-                    // Initially, use the raw native predicate URI as tooltip text.
-                    lbl_key.set_tooltip_text(Some(&pred));
-                    lbl_key.set_margin_start(6);
-                    lbl_key.set_margin_top(4);
-                    lbl_key.set_margin_bottom(4);
-
-                    // Add context menu for copying predicate names.
-                    add_copy_menu(
-                        &lbl_key,
-                        &label_text,
-                        &pred,
-                        "Copy Displayed Predicate",
-                        "Copy Native Predicate",
-                    );
+    for entry in &table.entries {
+        let entry_rows = table.entry_rows(entry, ctx);
+        match entry {
+            table::Entry::Predicate(pred) => {
+                let entries = &table.values[pred];
+                // Convert the raw predicate URI to a user-friendly label.
+                let label_text = predicate_label(pred, ctx);
+
+                // Of a long list of values, only the first few are shown until asked for the
+                // rest.
+                let collapsed = entries.len() > COLLAPSE_THRESHOLD;
+                let mut hidden: Vec<gtk::Widget> = Vec::new();
+
+                for (i, ((obj, dtype, lang), table_row)) in
+                    entries.iter().zip(&entry_rows).enumerate()
+                {
+                    // Only add the predicate label in the first row for multi-valued predicates.
+                    if i == 0 {
+                        let lbl_key = gtk::Label::new(Some(&label_text));
+                        lbl_key.set_halign(gtk::Align::Start);
+                        lbl_key.set_valign(gtk::Align::Start);
+                        lbl_key.style_context().add_class("first-col");
+                        if ctx.favorites.borrow().contains(pred) {
+                            lbl_key.style_context().add_class("favorite");
+                        }
+                        lbl_key.set_margin_start(6);
+                        lbl_key.set_margin_top(4);
+                        lbl_key.set_margin_bottom(4);
+
+                        // Add context menu for copying predicate names.
+                        add_copy_menu(
+                            &lbl_key,
+                            &label_text,
+                            pred,
+                            "Copy Displayed Predicate",
+                            "Copy Native Predicate",
+                            None,
+                            Some(pred),
+                        );
+
+                        // The tooltip presents the predicate's comment and its domain and
+                        // range, as far as the ontology declares them (all looked up above),
+                        // followed by its IRI.
+                        let (domains, ranges) = labels::signature(pred);
+                        let tip: Vec<String> = [
+                            labels::comment(pred)
+                                .map(|comment| ellipsize(&comment, COMMENT_TOOLTIP_MAX_CHARS)),
+                            describe_signature(&domains, &ranges),
+                            Some(pred.clone()),
+                        ]
+                        .into_iter()
+                        .flatten()
+                        .collect();
+                        lbl_key.set_tooltip_text(Some(&tip.join("\n\n")));
+
+                        // Attach the predicate label to the grid.
+                        grid.attach(&lbl_key, 0, row, 1, 1);
+                    }
 
-                    // If user clicks the predicate label, fetch description/comment for the
-                    // predicate from Tracker and update the tooltip to present it.
-                    let lbl_key_clone = lbl_key.clone();
-                    let pred_clone = pred.clone();
-                    let gesture = gtk::GestureClick::new();
-                    gesture.set_button(1);
-                    gesture.connect_pressed(move |_, _, _, _| {
-                        if let Some(comment) = fetch_comment(&pred_clone) {
-                            let tip = ellipsize(&comment, COMMENT_TOOLTIP_MAX_CHARS);
-                            lbl_key_clone.set_tooltip_text(Some(&tip));
-                            let lbl_ref = lbl_key_clone.clone();
-                            glib::idle_add_local_once(move || {
-                                lbl_ref.trigger_tooltip_query();
-                            });
+                    let displayed_str = table_row.display_value.clone();
+                    let native_str = obj.clone();
+
+                    // The statement this row represents, for copying in RDF syntaxes.
+                    let origin = table.interpreted.get(pred);
+                    let triple = rdf::Triple {
+                        subject: origin.map_or(uri, String::as_str).to_string(),
+                        predicate: pred.clone(),
+                        object: if dtype.is_empty() {
+                            rdf::Object::Iri(obj.clone())
+                        } else {
+                            rdf::Object::Literal {
+                                value: obj.clone(),
+                                datatype: dtype.clone(),
+                                lang: lang.clone(),
+                            }
+                        },
+                    };
+
+                    // Choose widget based on the object value datatype and contents.
+                    let widget: gtk::Widget = if dtype.is_empty() {
+                        // Untyped object values are assumed to be URIs representing RDF nodes that
+                        // should be rendered as links.
+                        let lbl_link = gtk::Label::new(None);
+                        // In prefixed-name mode, the link shows the abbreviated IRI and the tooltip
+                        // the full one.
+                        let compacted = if ctx.compact_uris.get() {
+                            prefixes::compact(obj)
+                        } else {
+                            None
+                        };
+                        // Blank nodes cannot be opened by their label, only unfolded below.
+                        let blank = tree::is_blank(obj);
+                        if blank {
+                            tree::blank_label(&lbl_link, obj);
+                        } else {
+                            lbl_link.set_markup(&format!(
+                                "<a href=\"{}\">{}</a>",
+                                glib::markup_escape_text(obj),
+                                glib::markup_escape_text(compacted.as_deref().unwrap_or(obj))
+                            ));
                         }
-                    });
-                    lbl_key.add_controller(gesture);
-
-                    // If mouse pointer leaves the predicate label, restore the original tooltip
-                    // text.
-                    let lbl_key_leave = lbl_key.clone();
-                    let pred_leave = pred.clone();
-                    let motion = gtk::EventControllerMotion::new();
-                    motion.connect_leave(move |_| {
-                        lbl_key_leave.set_tooltip_text(Some(&pred_leave));
-                    });
-                    lbl_key.add_controller(motion);
+                        if compacted.is_some() {
+                            lbl_link.set_tooltip_text(Some(obj));
+                        }
+                        lbl_link.set_halign(gtk::Align::Start);
+                        lbl_link.set_margin_start(6);
+                        lbl_link.set_margin_top(4);
+                        lbl_link.set_margin_bottom(4);
+
+                        // If such a link is clicked, the node in question should be shown, either in
+                        // this window or in a new one.
+                        let on_link_clone = on_link.clone();
+                        lbl_link.connect_activate_link(move |_lbl, uri| {
+                            on_link_clone(uri);
+                            glib::Propagation::Stop
+                        });
+
+                        lbl_link.set_wrap(true);
+                        lbl_link.set_wrap_mode(gtk::pango::WrapMode::WordChar);
+                        lbl_link.set_max_width_chars(80);
+
+                        // Add context menu for copying object values.
+                        add_copy_menu(
+                            &lbl_link,
+                            &displayed_str,
+                            &native_str,
+                            "Copy Displayed Value",
+                            "Copy Native Value",
+                            Some(&triple),
+                            None,
+                        );
+
+                        // An arrow before the link unfolds the linked node's own properties.
+                        let node = if blank {
+                            tree::Node::blank(tree::Node::Iri(triple.subject.clone()), pred, obj)
+                        } else {
+                            tree::Node::Iri(obj.clone())
+                        };
+                        tree::expandable(&lbl_link, node, ctx, on_link).upcast()
+                    } else if obj.contains('\n') {
+                        // For typed multi-line values, display in a non-editable text view.
+                        let txt = gtk::TextView::new();
+                        txt.set_editable(false);
+                        txt.set_cursor_visible(false);
+                        txt.style_context().add_class("bordered");
+                        txt.set_wrap_mode(gtk::WrapMode::Word);
+                        txt.set_margin_start(6);
+                        txt.set_margin_end(9);
+                        txt.set_margin_top(4);
+                        txt.set_margin_bottom(4);
+
+                        let buffer = txt.buffer();
+                        buffer.set_text(&displayed_str);
+                        let start = buffer.start_iter();
+                        buffer.place_cursor(&start);
+                        txt.upcast()
+                    } else {
+                        // For all other typed values, display in a standard label.
+                        let lbl_val = gtk::Label::new(Some(&displayed_str));
+                        lbl_val.set_halign(gtk::Align::Start);
+                        lbl_val.set_margin_start(6);
+                        lbl_val.set_margin_top(4);
+                        lbl_val.set_margin_bottom(4);
+                        lbl_val.set_wrap(true);
+                        lbl_val.set_wrap_mode(gtk::pango::WrapMode::WordChar);
+                        lbl_val.set_max_width_chars(80);
+
+                        add_copy_menu(
+                            &lbl_val,
+                            &displayed_str,
+                            &native_str,
+                            "Copy Displayed Value",
+                            "Copy Native Value",
+                            Some(&triple),
+                            None,
+                        );
+
+                        // Optionally show where a numeric value lies within the values of the same
+                        // property across the whole store.
+                        let value = obj.parse::<f64>().ok();
+                        match value.filter(|_| {
+                            ctx.value_ranges
+                                && !memory::is_low()
+                                && RANGED_PREDICATES.contains(&pred.as_str())
+                        }) {
+                            Some(value) => {
+                                let value_box = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+                                value_box.append(&lbl_val);
+                                value_box.append(&value_range_bar(&conn, pred, value));
+                                value_box.upcast()
+                            }
+                            // Long texts such as descriptions or lyrics are clamped to a few lines.
+                            None if is_long_value(&displayed_str) => {
+                                clamp_long_value(&lbl_val, &label_text, &displayed_str).upcast()
+                            }
+                            None => lbl_val.upcast(),
+                        }
+                    };
+
+                    // Literals in a given language carry its tag as a badge, e.g. "de" for a title
+                    // in German, so that values in several languages can be told apart.
+                    let widget: gtk::Widget = if lang.is_empty() {
+                        widget
+                    } else {
+                        let badge = gtk::Label::new(Some(lang));
+                        badge.style_context().add_class("badge");
+                        badge.set_valign(gtk::Align::Center);
+                        badge.set_tooltip_text(Some(&format!("Language: {lang}")));
+                        let value_box = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+                        value_box.append(&widget);
+                        value_box.append(&badge);
+                        value_box.upcast()
+                    };
+
+                    // Merged values of the interpreted element are marked with their origin.
+                    let widget: gtk::Widget = match origin {
+                        None => widget,
+                        Some(element) => {
+                            let badge = gtk::Label::new(Some("content"));
+                            badge.style_context().add_class("badge");
+                            badge.set_valign(gtk::Align::Center);
+                            badge.set_tooltip_text(Some(&format!(
+                                "From {element}, which this file is interpreted as"
+                            )));
+                            let value_box = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+                            value_box.append(&widget);
+                            value_box.append(&badge);
+                            value_box.upcast()
+                        }
+                    };
+
+                    // Set a tooltip for the native (raw) value. Dates additionally get calendar
+                    // details, which are too long to show in the grid itself.
+                    let mut tooltip_text = ellipsize(&native_str, TOOLTIP_MAX_CHARS);
+                    let now = glib::DateTime::now_local().ok();
+                    if let Some(details) = now.and_then(|now| date_details(obj, dtype, &now)) {
+                        tooltip_text = format!("{tooltip_text}\n{details}");
+                    }
+                    widget.set_tooltip_text(Some(&tooltip_text));
+
+                    // Attach the value widget to the grid.
+                    grid.attach(&widget, 1, row, 1, 1);
+
+                    // Optionally show the datatype of literals in a third column, as a prefixed
+                    // name such as "xsd:dateTime".
+                    if ctx.show_datatypes.get() && !dtype.is_empty() {
+                        let compacted = prefixes::compact(dtype);
+                        let lbl_type = gtk::Label::new(Some(compacted.as_deref().unwrap_or(dtype)));
+                        lbl_type.set_halign(gtk::Align::Start);
+                        lbl_type.set_valign(gtk::Align::Start);
+                        lbl_type.style_context().add_class("dim-label");
+                        lbl_type.set_tooltip_text(Some(dtype));
+                        lbl_type.set_margin_start(6);
+                        lbl_type.set_margin_end(6);
+                        lbl_type.set_margin_top(4);
+                        lbl_type.set_margin_bottom(4);
+                        grid.attach(&lbl_type, 2, row, 1, 1);
+                    }
 
-                    // Attach the predicate label to the grid.
-                    grid.attach(&lbl_key, 0, row, 1, 1);
+                    // Hide the row if it lies beyond the first values of a long list.
+                    if collapsed && i >= COLLAPSED_VALUES {
+                        for column in 1..=2 {
+                            if let Some(child) = grid.child_at(column, row) {
+                                child.set_visible(false);
+                                hidden.push(child);
+                            }
+                        }
+                    }
+                    row += 1;
                 }
 
-                // Displayed value uses a formatter if we know the datatype, else show raw object.
-                let displayed_str = if dtype.is_empty() {
-                    obj.clone()
-                } else {
-                    friendly_value(obj, dtype)
+                if collapsed {
+                    grid.attach(&show_all_expander(entries.len(), hidden), 1, row, 1, 1);
+                    row += 1;
+                }
+            }
+            // For files, tell whether and how current the indexed data is, or explain why there
+            // is none.
+            table::Entry::Indexing => {
+                let first_value = |pred: &str| {
+                    table
+                        .values
+                        .get(pred)
+                        .and_then(|v| v.first())
+                        .map(|v| v.0.as_str())
                 };
-                let native_str = obj.clone();
-
-                // Choose widget based on the object value datatype and contents.
-                let widget: gtk::Widget = if dtype.is_empty() {
-                    // Untyped object values are assumed to be URIs representing RDF nodes that
-                    // should be rendered as links.
-                    let lbl_link = gtk::Label::new(None);
-                    let escaped = glib::markup_escape_text(obj);
-                    lbl_link.set_markup(&format!("<a href=\"{0}\">{0}</a>", escaped));
-                    lbl_link.set_halign(gtk::Align::Start);
-                    lbl_link.set_margin_start(6);
-                    lbl_link.set_margin_top(4);
-                    lbl_link.set_margin_bottom(4);
-
-                    // If such a link is clicked, a new subject window should be opened for the
-                    // node in question.
-                    let app_clone = app.clone();
-                    let debug_clone = debug;
-                    lbl_link.connect_activate_link(move |_lbl, uri| {
-                        open_subject_window(&app_clone, uri.to_string(), debug_clone);
-                        glib::Propagation::Stop
-                    });
+                let status = indexing::status_widget(
+                    uri,
+                    table.is_file_data_object,
+                    first_value(NIE_DATA_SOURCE),
+                    first_value(NFO_FILE_LAST_MODIFIED),
+                )
+                .await;
+                let lbl_status = gtk::Label::new(Some("Indexing"));
+                lbl_status.set_halign(gtk::Align::Start);
+                lbl_status.set_valign(gtk::Align::Start);
+                lbl_status.style_context().add_class("first-col");
+                lbl_status.set_margin_start(6);
+                lbl_status.set_margin_top(4);
+                lbl_status.set_margin_bottom(4);
+                grid.attach(&lbl_status, 0, row, 1, 1);
+                grid.attach(&status, 1, row, 1, 1);
+                row += 1;
+            }
+            // The coordinates, in decimal degrees and with links to a map.
+            table::Entry::Location(coordinates) => {
+                grid.attach(&combined_key("Location", geo::SLO_LOCATION), 0, row, 1, 1);
+                grid.attach(&location_widget(coordinates), 1, row, 1, 1);
+                row += 1;
+            }
+            // The width and height, e.g. "1920 × 1080".
+            table::Entry::Resolution(displayed_str) => {
+                let tooltip = format!("{} × {}", media::NFO_WIDTH, media::NFO_HEIGHT);
+                grid.attach(&combined_key("Resolution", &tooltip), 0, row, 1, 1);
+                let native_str = displayed_str.replace(" × ", "x");
+                let lbl_val = gtk::Label::new(Some(displayed_str));
+                lbl_val.set_halign(gtk::Align::Start);
+                lbl_val.set_margin_start(6);
+                lbl_val.set_margin_top(4);
+                lbl_val.set_margin_bottom(4);
+                add_copy_menu(
+                    &lbl_val,
+                    displayed_str,
+                    &native_str,
+                    "Copy Displayed Value",
+                    "Copy Native Value",
+                    None,
+                    None,
+                );
+                grid.attach(&lbl_val, 1, row, 1, 1);
+                row += 1;
+            }
+            // Basic file properties the store does not have, filled in from the file system.
+            // They carry a badge so they are not mistaken for indexed facts.
+            table::Entry::Derived(pred, value, _) => {
+                let TableRow {
+                    display_predicate: label_text,
+                    display_value: displayed_str,
+                    ..
+                } = &entry_rows[0];
+                let lbl_key = gtk::Label::new(Some(label_text.as_str()));
+                lbl_key.set_halign(gtk::Align::Start);
+                lbl_key.set_valign(gtk::Align::Start);
+                lbl_key.style_context().add_class("first-col");
+                lbl_key.set_tooltip_text(Some(pred));
+                lbl_key.set_margin_start(6);
+                lbl_key.set_margin_top(4);
+                lbl_key.set_margin_bottom(4);
+                grid.attach(&lbl_key, 0, row, 1, 1);
+
+                let lbl_val = gtk::Label::new(Some(displayed_str.as_str()));
+                lbl_val.set_halign(gtk::Align::Start);
+                lbl_val.set_tooltip_text(Some(value));
+                add_copy_menu(
+                    &lbl_val,
+                    displayed_str,
+                    value,
+                    "Copy Displayed Value",
+                    "Copy Native Value",
+                    None,
+                    None,
+                );
+                let badge = gtk::Label::new(Some("derived"));
+                badge.style_context().add_class("badge");
+                badge.set_valign(gtk::Align::Center);
+                badge.set_tooltip_text(Some(
+                    "Computed by this application from the file system, not read from the store",
+                ));
+                let value_box = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+                value_box.set_margin_start(6);
+                value_box.set_margin_top(4);
+                value_box.set_margin_bottom(4);
+                value_box.append(&lbl_val);
+                value_box.append(&badge);
+                grid.attach(&value_box, 1, row, 1, 1);
+                row += 1;
+            }
+        }
+        // Record the rows for exporting or copying later.
+        rows_vec.extend(entry_rows);
+    }
+
+    // ---- Editable Tags Row ----
+
+    // Tags can be added and removed in place; this row is not part of the exported table.
+    let lbl_tags = gtk::Label::new(Some("Tags"));
+    lbl_tags.set_halign(gtk::Align::Start);
+    lbl_tags.set_valign(gtk::Align::Center);
+    lbl_tags.style_context().add_class("first-col");
+    lbl_tags.set_tooltip_text(Some("nao:hasTag"));
+    lbl_tags.set_margin_start(6);
+    lbl_tags.set_margin_top(4);
+    lbl_tags.set_margin_bottom(4);
+    grid.attach(&lbl_tags, 0, row, 1, 1);
+    grid.attach(&tags::editor(uri, ctx), 1, row, 1, 1);
+
+    // ---- Editable Note Row ----
+
+    // A free-form note, stored as `nao:description`; like the tags, it is edited in place.
+    let lbl_note = gtk::Label::new(Some("Note"));
+    lbl_note.set_halign(gtk::Align::Start);
+    lbl_note.set_valign(gtk::Align::Center);
+    lbl_note.style_context().add_class("first-col");
+    lbl_note.set_tooltip_text(Some("nao:description"));
+    lbl_note.set_margin_start(6);
+    lbl_note.set_margin_top(4);
+    lbl_note.set_margin_bottom(4);
+    grid.attach(&lbl_note, 0, row + 1, 1, 1);
+    grid.attach(&notes::editor(uri, ctx), 1, row + 1, 1, 1);
+
+    trace::event(
+        "table_filled",
+        json!({
+            "uri": uri,
+            "rows": rows_vec.len() - 1,
+            "file_data": table.is_file_data_object,
+        }),
+    );
+
+    // Return both the file data object flag and all collected rows.
+    (table.is_file_data_object, rows_vec)
+}
+
+/// Returns whether `text` is too long to be shown in full in the grid, i.e. longer than
+/// `LONG_VALUE_CHARS` characters or `CLAMPED_LINES` lines.
+fn is_long_value(text: &str) -> bool {
+    text.chars().count() > LONG_VALUE_CHARS || text.lines().count() > CLAMPED_LINES as usize
+}
+
+/// Clamps a label showing a long value (see `is_long_value`) to `CLAMPED_LINES` lines, and
+/// adds a "Show more" expander unclamping it and a button showing the whole value in a
+/// dialog (see `show_full_value`).
+///
+/// # Arguments
+/// * `lbl_val` - The label showing the value.
+/// * `title` - The label of the predicate, used as the title of the dialog.
+/// * `text` - The value as displayed.
+///
+/// # Returns
+/// * A box holding the label and the controls, to be placed in the grid instead of the label.
+fn clamp_long_value(lbl_val: &gtk::Label, title: &str, text: &str) -> gtk::Box {
+    lbl_val.set_lines(CLAMPED_LINES);
+    lbl_val.set_ellipsize(gtk::pango::EllipsizeMode::End);
+
+    let expander = gtk::Expander::new(Some("Show more"));
+    let lbl_clone = lbl_val.clone();
+    expander.connect_expanded_notify(move |expander| {
+        let expanded = expander.is_expanded();
+        lbl_clone.set_lines(if expanded { -1 } else { CLAMPED_LINES });
+        lbl_clone.set_ellipsize(if expanded {
+            gtk::pango::EllipsizeMode::None
+        } else {
+            gtk::pango::EllipsizeMode::End
+        });
+        expander.set_label(Some(if expanded { "Show less" } else { "Show more" }));
+    });
+
+    let view_button = gtk::Button::with_label("View Full Value…");
+    view_button.style_context().add_class("flat");
+    let title = title.to_string();
+    let text = text.to_string();
+    view_button.connect_clicked(move |button| show_full_value(button, &title, &text));
+
+    let controls = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+    controls.set_margin_start(6);
+    controls.append(&expander);
+    controls.append(&view_button);
+
+    let value_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+    value_box.append(lbl_val);
+    value_box.append(&controls);
+    value_box
+}
 
-                    lbl_link.set_wrap(true);
-                    lbl_link.set_wrap_mode(gtk::pango::WrapMode::WordChar);
-                    lbl_link.set_max_width_chars(80);
-
-                    // Add context menu for copying object values.
-                    add_copy_menu(
-                        &lbl_link,
-                        &displayed_str,
-                        &native_str,
-                        "Copy Displayed Value",
-                        "Copy Native Value",
-                    );
+/// Shows a long value in a dialog of its own, as selectable and scrollable text.
+///
+/// # Arguments
+/// * `parent` - A widget in the window the dialog belongs to.
+/// * `title` - The title of the dialog, i.e. the label of the predicate.
+/// * `text` - The value to show.
+fn show_full_value(parent: &impl IsA<gtk::Widget>, title: &str, text: &str) {
+    let text_view = gtk::TextView::builder()
+        .editable(false)
+        .cursor_visible(false)
+        .wrap_mode(gtk::WrapMode::WordChar)
+        .left_margin(12)
+        .right_margin(12)
+        .top_margin(12)
+        .bottom_margin(12)
+        .build();
+    text_view.buffer().set_text(text);
+    let scroll = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .vexpand(true)
+        .child(&text_view)
+        .build();
 
-                    lbl_link.upcast()
-                } else if obj.contains('\n') {
-                    // For typed multi-line values, display in a non-editable text view.
-                    let txt = gtk::TextView::new();
-                    txt.set_editable(false);
-                    txt.set_cursor_visible(false);
-                    txt.style_context().add_class("bordered");
-                    txt.set_wrap_mode(gtk::WrapMode::Word);
-                    txt.set_margin_start(6);
-                    txt.set_margin_end(9);
-                    txt.set_margin_top(4);
-                    txt.set_margin_bottom(4);
-
-                    let buffer = txt.buffer();
-                    buffer.set_text(&displayed_str);
-                    let start = buffer.start_iter();
-                    buffer.place_cursor(&start);
-                    txt.upcast()
-                } else {
-                    // For all other typed values, display in a standard label.
-                    let lbl_val = gtk::Label::new(Some(&displayed_str));
-                    lbl_val.set_halign(gtk::Align::Start);
-                    lbl_val.set_margin_start(6);
-                    lbl_val.set_margin_top(4);
-                    lbl_val.set_margin_bottom(4);
-                    lbl_val.set_wrap(true);
-                    lbl_val.set_wrap_mode(gtk::pango::WrapMode::WordChar);
-                    lbl_val.set_max_width_chars(80);
-
-                    add_copy_menu(
-                        &lbl_val,
-                        &displayed_str,
-                        &native_str,
-                        "Copy Displayed Value",
-                        "Copy Native Value",
-                    );
-                    lbl_val.upcast()
-                };
+    let header = adw::HeaderBar::new();
+    header.set_title_widget(Some(&gtk::Label::new(Some(title))));
+    let toolbar = adw::ToolbarView::new();
+    toolbar.add_top_bar(&header);
+    toolbar.set_content(Some(&scroll));
+
+    let dialog = adw::Window::builder()
+        .modal(true)
+        .default_width(560)
+        .default_height(420)
+        .title(title)
+        .content(&toolbar)
+        .build();
+    dialog.set_transient_for(parent.root().and_downcast_ref::<gtk::Window>());
+    dialog.present();
+}
 
-                // Set a tooltip for the native (raw) value.
-                let tooltip_text = ellipsize(&native_str, TOOLTIP_MAX_CHARS);
-                widget.set_tooltip_text(Some(&tooltip_text));
+/// Builds the expander below the first values of a predicate with many values (see
+/// `COLLAPSE_THRESHOLD`), which shows or hides the rows of the other values.
+///
+/// # Arguments
+/// * `total` - The number of values of the predicate.
+/// * `hidden` - The widgets of the rows that start out hidden.
+fn show_all_expander(total: usize, hidden: Vec<gtk::Widget>) -> gtk::Expander {
+    let collapsed_label = format!("Show all {total} values");
+    let expander = gtk::Expander::new(Some(&collapsed_label));
+    expander.set_halign(gtk::Align::Start);
+    expander.set_margin_start(6);
+    expander.set_margin_top(4);
+    expander.set_margin_bottom(4);
+    expander.connect_expanded_notify(move |expander| {
+        let expanded = expander.is_expanded();
+        for widget in &hidden {
+            widget.set_visible(expanded);
+        }
+        expander.set_label(Some(if expanded {
+            "Show fewer values"
+        } else {
+            &collapsed_label
+        }));
+    });
+    expander
+}
 
-                // Attach the value widget to the grid.
-                grid.attach(&widget, 1, row, 1, 1);
+/// Builds the label of a row combining several properties, such as "Location", styled like
+/// the predicate labels.
+///
+/// # Arguments
+/// * `text` - The label.
+/// * `tooltip` - The properties combined, shown as the tooltip.
+fn combined_key(text: &str, tooltip: &str) -> gtk::Label {
+    let lbl_key = gtk::Label::new(Some(text));
+    lbl_key.set_halign(gtk::Align::Start);
+    lbl_key.set_valign(gtk::Align::Start);
+    lbl_key.style_context().add_class("first-col");
+    lbl_key.set_tooltip_text(Some(tooltip));
+    lbl_key.set_margin_start(6);
+    lbl_key.set_margin_top(4);
+    lbl_key.set_margin_bottom(4);
+    lbl_key
+}
 
-                // Record the row for exporting or copying later.
-                rows_vec.push(TableRow {
-                    display_predicate: label_text.clone(),
-                    native_predicate: pred.clone(),
-                    display_value: displayed_str.clone(),
-                    native_value: native_str.clone(),
-                });
-                row += 1;
-            }
+/// Builds the value of the "Location" row: the coordinates, linked to their `geo:` URI for the
+/// map application, followed by a link to OpenStreetMap. Both links are opened with the
+/// window's `open-uri` action, which explains when no application handles them.
+fn location_widget(coordinates: &geo::Coordinates) -> gtk::Label {
+    let displayed = coordinates.display();
+    let geo_uri = coordinates.geo_uri();
+    let lbl_val = gtk::Label::new(None);
+    lbl_val.set_markup(&format!(
+        "<a href=\"{}\">{}</a>   <a href=\"{}\">OpenStreetMap</a>",
+        glib::markup_escape_text(&geo_uri),
+        glib::markup_escape_text(&displayed),
+        glib::markup_escape_text(&coordinates.map_url()),
+    ));
+    lbl_val.connect_activate_link(|lbl, uri| {
+        if let Err(err) = lbl.activate_action("win.open-uri", Some(&uri.to_variant())) {
+            log::warn!("Could not open {uri}: {err}");
         }
-    }
+        glib::Propagation::Stop
+    });
+    lbl_val.set_halign(gtk::Align::Start);
+    lbl_val.set_margin_start(6);
+    lbl_val.set_margin_top(4);
+    lbl_val.set_margin_bottom(4);
+    add_copy_menu(
+        &lbl_val,
+        &displayed,
+        &geo_uri,
+        "Copy Displayed Value",
+        "Copy Native Value",
+        None,
+        None,
+    );
+    lbl_val
+}
 
-    // Print summary of query results if debugging.
-    if debug {
-        log::debug!(
-            "query returned rows={} file_data={}",
-            rows_vec.len() - 1,
-            is_file_data_object
-        );
+/// Computes basic properties of a `file:` URI or a virtual location (see `gvfs::is_virtual`)
+/// with GIO, as a fallback for files that the store has no (or incomplete) data about. Files in
+/// the wastebasket also get where and when they were deleted.
+///
+/// The store keeps only some of the timestamps of a file, so its creation (birth), access and
+/// change times are read as well. GIO reads them with `statx`; file systems that do not record
+/// the birth time leave it out.
+///
+/// # Arguments
+/// * `uri` - The URI of the subject.
+///
+/// # Returns
+/// * `(predicate, value, datatype)` triples; empty for other URIs or files that cannot be read.
+async fn derived_values(uri: &str) -> Vec<(&'static str, String, &'static str)> {
+    let is_virtual = gvfs::is_virtual(uri);
+    if !uri.starts_with("file:") && !is_virtual {
+        return Vec::new();
+    }
+    let Ok(info) = gio::File::for_uri(uri)
+        .query_info_future(
+            "standard::size,standard::content-type,standard::display-name,trash::*,\
+             time::created,time::access,time::changed",
+            gio::FileQueryInfoFlags::NONE,
+            glib::Priority::DEFAULT,
+        )
+        .await
+    else {
+        return Vec::new();
+    };
+    let mut values = vec![(NFO_FILE_SIZE, info.size().to_string(), XSD_INTEGER)];
+    let mime = info
+        .content_type()
+        .and_then(|content_type| gio::content_type_get_mime_type(&content_type));
+    if let Some(mime) = mime {
+        values.push((NIE_MIME_TYPE, mime.to_string(), XSD_STRING));
+    }
+    // The store knows the names of local files, but not those of virtual locations, which may
+    // differ from the last segment of the URI (e.g. on phones).
+    if is_virtual {
+        values.push((NFO_FILE_NAME, info.display_name().to_string(), XSD_STRING));
+    }
+    if let Some(path) = info.attribute_byte_string(gio::FILE_ATTRIBUTE_TRASH_ORIG_PATH) {
+        let location = gio::File::for_path(path.as_str()).uri();
+        values.push((
+            gvfs::NFO_ORIGINAL_LOCATION,
+            location.to_string(),
+            XSD_STRING,
+        ));
+    }
+    // The deletion date is given in local time, without a time zone.
+    let deleted = info
+        .attribute_string(gio::FILE_ATTRIBUTE_TRASH_DELETION_DATE)
+        .and_then(|date| glib::DateTime::from_iso8601(&date, Some(&glib::TimeZone::local())).ok())
+        .and_then(|date| date.format_iso8601().ok());
+    if let Some(deleted) = deleted {
+        values.push((gvfs::NFO_DELETION_DATE, deleted.to_string(), XSD_DATETYPE));
     }
+    for (attribute, pred) in [
+        (gio::FILE_ATTRIBUTE_TIME_CREATED, NFO_FILE_CREATED),
+        (gio::FILE_ATTRIBUTE_TIME_ACCESS, NFO_FILE_LAST_ACCESSED),
+        (gio::FILE_ATTRIBUTE_TIME_CHANGED, FILE_STATUS_CHANGED),
+    ] {
+        let time = Some(attribute)
+            .filter(|attribute| info.has_attribute(attribute))
+            .and_then(|attribute| unix_time_value(info.attribute_uint64(attribute)));
+        if let Some(time) = time {
+            values.push((pred, time, XSD_DATETYPE));
+        }
+    }
+    values
+}
 
-    // Return both the file data object flag and all collected rows.
-    (is_file_data_object, rows_vec)
+/// Formats a time in seconds since the Unix epoch as an ISO 8601 date-time in UTC, as the
+/// store gives date-times.
+fn unix_time_value(seconds: u64) -> Option<String> {
+    let seconds = i64::try_from(seconds).ok()?;
+    let time = glib::DateTime::from_unix_utc(seconds).ok()?;
+    time.format_iso8601().ok().map(|time| time.to_string())
 }
 
 /// Attaches a right-click context menu to a GTK widget for copying its displayed and native values,
@@ -1139,7 +4098,20 @@ async fn populate_grid(
 /// When the user right-clicks on the widget, a popover menu appears offering:
 ///   - "Copy Displayed Value": Copies the value as displayed in the UI to the clipboard.
 ///   - "Copy Native Value": Copies the raw or underlying value to the clipboard.
+///   - "Pin Value" (only if a statement is given): Adds the displayed value to the pinboard.
 ///   - "Open Externally" (only if the native value is a URI and the system has a handler): Opens the URI using the system's default handler.
+///   - "Show in Files" (only if the native value is a `file:` URI): Reveals the file in the file
+///     manager.
+///   - "Re-index" (only if the native value is a `file:` URI): Asks the Tracker miner to index
+///     the file or folder again.
+///   - "Open in New Tab" (only if the native value is a URI and the widget is in a subject
+///     window): Shows the node in a new tab of the same window.
+///   - "Copy as N-Triples" / "Copy as Turtle" (only if a statement is given): Copies the whole
+///     statement the widget represents in the respective RDF syntax.
+///   - "Provenance" (only if a statement is given): Shows the named graphs holding the statement
+///     and the data source of its subject.
+///   - "Add to Favorites" / "Remove from Favorites" (only if a predicate is given): Marks the
+///     predicate as a favorite, which is listed first in every window, or unmarks it.
 ///
 /// # Arguments
 /// * `widget` - The widget to which the context menu will be attached. Must implement `gtk::Widget`.
@@ -1147,10 +4119,19 @@ async fn populate_grid(
 /// * `native` - The raw value, e.g., the underlying URI or identifier.
 /// * `disp_label` - Label for the displayed value copy menu item (e.g., "Copy Displayed Value").
 /// * `nat_label` - Label for the native value copy menu item (e.g., "Copy Native Value").
+/// * `triple` - The RDF statement the widget represents, if any.
+/// * `favorite` - The predicate the widget represents, if any, for marking it as a favorite.
 ///
 /// This function is generic and can be attached to any widget that implements `IsA<gtk::Widget>`.
-fn add_copy_menu<W>(widget: &W, displayed: &str, native: &str, disp_label: &str, nat_label: &str)
-where
+fn add_copy_menu<W>(
+    widget: &W,
+    displayed: &str,
+    native: &str,
+    disp_label: &str,
+    nat_label: &str,
+    triple: Option<&rdf::Triple>,
+    favorite: Option<&str>,
+) where
     W: IsA<gtk::Widget> + Clone + 'static,
 {
     // Create a GestureClick controller to detect right mouse button (button 3) presses.
@@ -1164,6 +4145,10 @@ where
     let native_clone = native.to_string();
     let disp_label_str = disp_label.to_string();
     let nat_label_str = nat_label.to_string();
+    let serialized = triple.map(|t| (t.subject.clone(), t.to_ntriples(), t.to_turtle()));
+    let pin_target =
+        triple.map(|t| (t.subject.as_str(), t.predicate.as_str(), displayed).to_variant());
+    let favorite = favorite.map(str::to_string);
     let widget_clone: gtk::Widget = widget.clone().upcast();
 
     // When the right-click gesture is pressed, build and show the popover menu.
@@ -1183,6 +4168,15 @@ where
         copy_nat_item.set_attribute_value("target", Some(&nat_variant));
         menu_model.append_item(&copy_nat_item);
 
+        // ---- Optional "Pin Value" Menu Item ----
+        // Only offered for statements, since a pinned value is labelled with its subject and
+        // predicate.
+        if let Some(pin_target) = &pin_target {
+            let pin_item = gio::MenuItem::new(Some("Pin Value"), Some("app.pin-value"));
+            pin_item.set_attribute_value("target", Some(pin_target));
+            menu_model.append_item(&pin_item);
+        }
+
         // ---- Optional "Open Externally" Menu Item ----
         // Only add this item if the native value looks like a URI and there is a handler for it.
         if looks_like_uri(&native_clone) && uri_has_handler(&native_clone).is_ok() {
@@ -1192,6 +4186,62 @@ where
             menu_model.append_item(&open_item);
         }
 
+        // ---- Optional "Show in Files" Menu Item ----
+        if native_clone.starts_with("file:") {
+            let show_item = gio::MenuItem::new(Some("Show in Files"), Some("win.show-in-files"));
+            let uri_variant = glib::Variant::from(native_clone.as_str());
+            show_item.set_attribute_value("target", Some(&uri_variant));
+            menu_model.append_item(&show_item);
+
+            let reindex_item = gio::MenuItem::new(Some("Re-index"), Some("win.reindex"));
+            reindex_item.set_attribute_value("target", Some(&uri_variant));
+            menu_model.append_item(&reindex_item);
+        }
+
+        // ---- Optional "Open in New Tab" Menu Item ----
+        // Only offered for URIs in windows that support tabs (i.e., subject windows).
+        let supports_tabs = widget_clone
+            .root()
+            .and_downcast::<gtk::ApplicationWindow>()
+            .is_some_and(|w| w.lookup_action("open-in-new-tab").is_some());
+        if supports_tabs && looks_like_uri(&native_clone) {
+            let tab_item = gio::MenuItem::new(Some("Open in New Tab"), Some("win.open-in-new-tab"));
+            let uri_variant = glib::Variant::from(native_clone.as_str());
+            tab_item.set_attribute_value("target", Some(&uri_variant));
+            menu_model.append_item(&tab_item);
+        }
+
+        // ---- Optional "Copy as N-Triples" and "Copy as Turtle" Menu Items ----
+        // These go into their own section, since they copy the whole statement rather than the
+        // value alone.
+        if let Some((subject, ntriples, turtle)) = &serialized {
+            let rdf_section = gio::Menu::new();
+            for (label, text) in [("Copy as N-Triples", ntriples), ("Copy as Turtle", turtle)] {
+                let item = gio::MenuItem::new(Some(label), Some("win.copy-value"));
+                item.set_attribute_value("target", Some(&glib::Variant::from(text.as_str())));
+                rdf_section.append_item(&item);
+            }
+            // "Provenance" looks up where the statement came from.
+            let item = gio::MenuItem::new(Some("Provenance"), Some("win.show-provenance"));
+            let target = (subject.as_str(), ntriples.as_str()).to_variant();
+            item.set_attribute_value("target", Some(&target));
+            rdf_section.append_item(&item);
+            menu_model.append_section(None, &rdf_section);
+        }
+
+        // ---- Optional "Add to Favorites" / "Remove from Favorites" Menu Item ----
+        if let Some(predicate) = &favorite {
+            let label =
+                if settings::preference_list(settings::FAVORITE_PREDICATES).contains(predicate) {
+                    "Remove from Favorites"
+                } else {
+                    "Add to Favorites"
+                };
+            let item = gio::MenuItem::new(Some(label), Some("app.toggle-favorite"));
+            item.set_attribute_value("target", Some(&predicate.to_variant()));
+            menu_model.append_item(&item);
+        }
+
         // Create a PopoverMenu from the menu model.
         let popover = gtk::PopoverMenu::from_model(Some(&menu_model));
 
@@ -1246,6 +4296,92 @@ fn looks_like_uri(s: &str) -> bool {
     url::Url::parse(s).is_ok()
 }
 
+/// Returns the URI of an item given on the command line: the subject of a deep link (see
+/// `deep_link_target`), the item itself with `--uri`, or the URI of the file at that path with
+/// `--path`.
+///
+/// Without either, items starting with a scheme (see `has_scheme`), such as `urn:uuid:…` or
+/// `https://…`, are taken as URIs, unless a file of that name exists. Virtual locations such
+/// as `recent:///…` are replaced by the file they stand for (see `gvfs::resolve`).
+fn item_uri(item: &str, opts: &options::Options) -> String {
+    if let Some(target) = deep_link_target(item) {
+        target
+    } else if opts.uri || (!opts.path && has_scheme(item) && !Path::new(item).exists()) {
+        gvfs::resolve(item)
+    } else {
+        gio::File::for_path(item).uri().to_string()
+    }
+}
+
+/// Tells whether `item` starts with a URI scheme and a colon, e.g. `urn:` or `file:`, rather
+/// than being a path. Schemes are a letter followed by letters, digits, `+`, `-` or `.`; a
+/// single letter is not taken as one.
+fn has_scheme(item: &str) -> bool {
+    item.split_once(':').is_some_and(|(scheme, _)| {
+        scheme.len() > 1
+            && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    })
+}
+
+/// Returns the subject a deep link (see `DEEP_LINK_SCHEME`) points to.
+///
+/// The subject IRI follows the scheme either verbatim (`x-fileinfo:urn:example:1`) or
+/// percent-encoded as a whole (`x-fileinfo:urn%3Aexample%3A1`), which keeps IRIs containing
+/// `#` intact in tools that would otherwise treat it as a fragment. A leading `//` is ignored.
+///
+/// # Returns
+/// * `Some(iri)` for deep links, or `None` if `uri` uses another scheme.
+fn deep_link_target(uri: &str) -> Option<String> {
+    let prefix = uri.get(..DEEP_LINK_SCHEME.len())?;
+    if !prefix.eq_ignore_ascii_case(DEEP_LINK_SCHEME) {
+        return None;
+    }
+    let rest = &uri[DEEP_LINK_SCHEME.len()..];
+    let rest = rest.strip_prefix("//").unwrap_or(rest);
+    if rest.contains(':') {
+        Some(rest.to_string())
+    } else {
+        glib::Uri::unescape_string(rest, None).map(|s| s.to_string())
+    }
+}
+
+/// Extracts the subject to inspect from pasted or typed text.
+///
+/// Only the first non-empty line is considered. IRIs may be wrapped in angle brackets, as in
+/// N-Triples or Turtle, absolute paths (including ones starting with `~`) are converted to
+/// `file:` URIs, and deep links (see `deep_link_target`) are resolved.
+///
+/// # Arguments
+/// * `text` - The text taken from the clipboard or typed by the user.
+///
+/// # Returns
+/// * `Some(uri)` with the URI to open, or `None` if the text is neither a URI nor a path.
+fn location_target(text: &str) -> Option<String> {
+    let line = text.lines().map(str::trim).find(|l| !l.is_empty())?;
+    let line = line
+        .strip_prefix('<')
+        .and_then(|l| l.strip_suffix('>'))
+        .unwrap_or(line);
+    if let Some(target) = deep_link_target(line) {
+        Some(target)
+    } else if line.starts_with('/') {
+        Some(gio::File::for_path(line).uri().to_string())
+    } else if let Some(rest) = line.strip_prefix("~/") {
+        Some(
+            gio::File::for_path(glib::home_dir().join(rest))
+                .uri()
+                .to_string(),
+        )
+    } else if looks_like_uri(line) {
+        Some(line.to_string())
+    } else {
+        None
+    }
+}
+
 /// Truncates a string to a maximum number of characters, appending an ellipsis if the string was cut off.
 ///
 /// This function iterates over the input string character by character, copying up to
@@ -1288,8 +4424,11 @@ fn ellipsize(s: &str, max_chars: usize) -> String {
     }
 }
 
-/// Converts a URI or predicate name into a more human-friendly label by extracting
-/// the last component and inserting spaces between words based on a camel-case interpretation.
+/// Converts a URI or predicate name into a more human-friendly label.
+///
+/// The `rdfs:label` the ontology gives the URI is used if it has been loaded (see
+/// `labels::load`). Otherwise the label is guessed by extracting the last component and
+/// inserting spaces between words based on a camel-case interpretation.
 ///
 /// # Arguments
 /// * `uri` - The full URI or identifier string to convert.
@@ -1297,6 +4436,10 @@ fn ellipsize(s: &str, max_chars: usize) -> String {
 /// # Returns
 /// * A `String` containing the label, e.g., "Date Modified" from "http://example.org/DateModified".
 fn friendly_label(uri: &str) -> String {
+    if let Some(label) = labels::lookup(uri) {
+        return label;
+    }
+
     // Remove any trailing '#' or '/' from the URI, to avoid empty components.
     let trimmed = uri.trim_end_matches(&['#', '/'][..]);
 
@@ -1341,27 +4484,118 @@ fn friendly_label(uri: &str) -> String {
         .join(" ") // Join the words with spaces for a human-friendly label.
 }
 
+/// Returns the label of the predicate `pred` in the first column of a grid.
+///
+/// This is the friendly label (see [`friendly_label`]), or the prefixed name (e.g.
+/// `nfo:fileName`, see `prefixes::compact`) if prefixed names have been chosen, falling back to
+/// the full IRI for predicates in namespaces without a prefix.
+fn predicate_label(pred: &str, ctx: &Context) -> String {
+    if ctx.compact_uris.get() {
+        prefixes::compact(pred).unwrap_or_else(|| pred.to_string())
+    } else {
+        friendly_label(pred)
+    }
+}
+
+/// Builds a human-readable description of an RDF subject from its type and title.
+///
+/// The type is converted with [`friendly_label`] and the title is quoted, giving descriptions
+/// such as "Photo 'IMG_1234.jpg'". If neither is known, the raw subject URI is returned.
+///
+/// # Arguments
+/// * `subject` - The subject URI, used as a fallback.
+/// * `type_uri` - The URI of the subject's most specific type, or an empty string.
+/// * `title` - The subject's title or file name, or an empty string.
+///
+/// # Returns
+/// * A `String` describing the subject.
+fn describe_subject(subject: &str, type_uri: &str, title: &str) -> String {
+    match (type_uri.is_empty(), title.is_empty()) {
+        (true, true) => subject.to_string(),
+        (true, false) => format!("'{title}'"),
+        (false, true) => format!("{} {}", friendly_label(type_uri), subject),
+        (false, false) => format!("{} '{}'", friendly_label(type_uri), title),
+    }
+}
+
+/// Creates a small bar that shows where `value` lies between the smallest and largest values of
+/// `predicate` in the store.
+///
+/// The bar stays hidden until the store-wide range has been queried asynchronously, and also if
+/// no usable range is found.
+///
+/// # Arguments
+/// * `conn` - The connection to the Tracker store.
+/// * `predicate` - The URI of the numeric property.
+/// * `value` - The value to place within the range.
+fn value_range_bar(conn: &Rc<dyn StoreClient>, predicate: &str, value: f64) -> gtk::LevelBar {
+    let bar = gtk::LevelBar::builder()
+        .min_value(0.0)
+        .max_value(1.0)
+        .width_request(60)
+        .valign(gtk::Align::Center)
+        .visible(false)
+        .build();
+
+    let sparql =
+        format!("SELECT (MIN(?v) AS ?min) (MAX(?v) AS ?max) WHERE {{ ?s <{predicate}> ?v }}");
+    let conn = conn.clone();
+    let bar_clone = bar.clone();
+    glib::MainContext::default().spawn_local(async move {
+        let Ok(cursor) = conn.query_async(&sparql).await else {
+            return;
+        };
+        if !cursor.next_future().await.unwrap_or(false) {
+            return;
+        }
+        let parse = |col| cursor.string(col).and_then(|s| s.parse::<f64>().ok());
+        let (Some(min), Some(max)) = (parse(0), parse(1)) else {
+            return;
+        };
+        if let Some(fraction) = range_fraction(value, min, max) {
+            bar_clone.set_value(fraction);
+            bar_clone.set_tooltip_text(Some(&format!("Range in the store: {min} – {max}")));
+            bar_clone.set_visible(true);
+        }
+    });
+    bar
+}
+
+/// Returns where `value` lies between `min` and `max`, as a fraction from 0 to 1.
+///
+/// # Returns
+/// * `Some(fraction)`, clamped to 0..=1, or `None` if the range is empty or not finite.
+fn range_fraction(value: f64, min: f64, max: f64) -> Option<f64> {
+    let span = max - min;
+    (span.is_finite() && span > 0.0).then(|| ((value - min) / span).clamp(0.0, 1.0))
+}
+
 /// Formats a native RDF literal value as a user-friendly string for display.
 ///
-/// Currently only translates ISO8601 date-times into "YYYY-MM-DD HH:MM:SS", while passing
-/// all other datatypes through as-is.
+/// Currently only translates ISO8601 date-times into local time (or UTC) formatted with
+/// `date_format` (by default "YYYY-MM-DD HH:MM:SS"), while passing all other datatypes through
+/// as-is.
 ///
 /// # Arguments
 /// * `obj` - The raw value as a string.
 /// * `dtype` - The datatype URI indicating how the value should be interpreted.
+/// * `date_format` - A `strftime`-style pattern, as understood by `glib::DateTime::format`.
+/// * `utc` - Whether to show date-times in UTC rather than in the local time zone, so that
+///   they compare equal across machines.
 ///
 /// # Returns
 /// * A `String` formatted for display.
-fn friendly_value(obj: &str, dtype: &str) -> String {
+fn friendly_value(obj: &str, dtype: &str, date_format: &str, utc: bool) -> String {
     // Check if the datatype corresponds to an ISO8601 date-time type.
     if dtype == XSD_DATETYPE {
         // Attempt to parse the value as an ISO8601 date-time using glib::DateTime.
-        // If successful, convert to local time and format as "YYYY-MM-DD HH:MM:SS".
+        // If successful, convert to the chosen time zone and format it with the configured
+        // pattern.
         if let Ok(dt) = glib::DateTime::from_iso8601(obj, None)
-            .and_then(|dt| dt.to_local())
-            .and_then(|ldt| ldt.format("%F %T"))
+            .and_then(|dt| if utc { dt.to_utc() } else { dt.to_local() })
+            .and_then(|ldt| ldt.format(date_format))
         {
-            // Return the formatted local date-time as a string.
+            // Return the formatted date-time as a string.
             return dt.to_string();
         }
     }
@@ -1369,41 +4603,132 @@ fn friendly_value(obj: &str, dtype: &str) -> String {
     obj.to_string()
 }
 
-/// Fetches the RDF comment (rdfs:comment) for a given predicate URI from the Tracker database, if available.
+/// Formats the value of `pred` for display: durations (see `DURATION_PREDICATES`) in hours,
+/// minutes and seconds, bit rates and sample rates in kbps and kHz (see `media::format`), and
+/// anything else with `friendly_value`.
+///
+/// # Arguments
+/// * `pred` - The predicate URI the value belongs to.
+/// * `obj` - The raw value as a string.
+/// * `dtype` - The datatype URI of the value.
+/// * `date_format` - A `strftime`-style pattern for date-times.
+/// * `utc` - Whether to show date-times in UTC rather than local time.
+fn display_value(pred: &str, obj: &str, dtype: &str, date_format: &str, utc: bool) -> String {
+    let special = if DURATION_PREDICATES.contains(&pred) {
+        format_duration(obj)
+    } else {
+        media::format(pred, obj)
+    };
+    special.unwrap_or_else(|| friendly_value(obj, dtype, date_format, utc))
+}
+
+/// Formats a number of seconds as hours, minutes and seconds, e.g. "1 h 23 m 45 s", leaving out
+/// leading units that are zero ("3 m 5 s", "42 s"). Fractions of a second are rounded.
+///
+/// # Returns
+/// * The formatted duration, or `None` if `seconds` is not a non-negative number.
+fn format_duration(seconds: &str) -> Option<String> {
+    let seconds = seconds.trim().parse::<f64>().ok()?;
+    if !seconds.is_finite() || seconds < 0.0 {
+        return None;
+    }
+    let total = seconds.round() as u64;
+    let (hours, minutes, seconds) = (total / 3600, total / 60 % 60, total % 60);
+    Some(if hours > 0 {
+        format!("{hours} h {minutes} m {seconds} s")
+    } else if minutes > 0 {
+        format!("{minutes} m {seconds} s")
+    } else {
+        format!("{seconds} s")
+    })
+}
+
+/// Describes where a date-time value falls in the calendar, for use in tooltips.
 ///
-/// This function performs a SPARQL query against the Tracker service to retrieve a human-readable
-/// comment or description associated with the specified predicate. It is used to provide
-/// contextual tooltips for RDF properties in the user interface.
+/// The description names the weekday, the ISO 8601 week and how many days lie between the
+/// value and `now`, e.g. "Tuesday, ISO week 23 of 2024, 3 days ago".
 ///
 /// # Arguments
-/// * `predicate` - The URI of the RDF property whose comment is to be fetched.
+/// * `obj` - The raw value.
+/// * `dtype` - The datatype URI of the value.
+/// * `now` - The point in time to count days from.
 ///
 /// # Returns
-/// * `Some(String)` containing the comment if found, or
-/// * `None` if the comment is not available or if any error occurs while querying.
-fn fetch_comment(predicate: &str) -> Option<String> {
-    // Attempt to establish a connection to the Tracker D-Bus SPARQL service.
-    // If the connection fails, return None immediately.
-    let conn = create_store_connection().ok()?;
-
-    // Prepare a SPARQL query that asks for the comment (rdfs:comment) of the predicate.
-    // The query is limited to return at most one comment string (?c).
-    let sparql = format!(
-        "SELECT ?c WHERE {{ <{pred}> <{comment}> ?c }} LIMIT 1",
-        pred = predicate,
-        comment = RDFS_COMMENT
-    );
+/// * `Some(String)` for valid date-time values, or `None` for anything else.
+fn date_details(obj: &str, dtype: &str, now: &glib::DateTime) -> Option<String> {
+    if dtype != XSD_DATETYPE {
+        return None;
+    }
+    let dt = glib::DateTime::from_iso8601(obj, None)
+        .and_then(|dt| dt.to_local())
+        .ok()?;
+    // %G is the ISO week-based year, which differs from the calendar year around New Year.
+    let calendar = dt.format("%A, ISO week %V of %G").ok()?;
+    Some(format!(
+        "{calendar}, {}",
+        relative_time(now.difference(&dt))
+    ))
+}
 
-    // Execute the query on the Tracker service. If querying fails, return None.
-    let cursor = conn.query(&sparql, None::<&gio::Cancellable>).ok()?;
+/// Describes how long ago a date-time value lies before `now`, e.g. "3 days ago", for showing
+/// next to the formatted date when `Context::relative_dates` is on.
+///
+/// # Returns
+/// * `Some(String)` for valid date-time values, or `None` for anything else.
+fn relative_date(obj: &str, dtype: &str, now: &glib::DateTime) -> Option<String> {
+    if dtype != XSD_DATETYPE {
+        return None;
+    }
+    let dt = glib::DateTime::from_iso8601(obj, None).ok()?;
+    Some(relative_time(now.difference(&dt)))
+}
 
-    // If there is a result, extract the comment string from the first column.
-    if cursor.next(None::<&gio::Cancellable>).unwrap_or(false) {
-        Some(cursor.string(0).unwrap_or_default().to_string())
+/// Phrases a time span in the largest whole unit it spans, e.g. "5 minutes ago", "2 hours ago"
+/// or "3 days ago"; spans into the future read "in 3 days", and spans under a minute "just
+/// now".
+fn relative_time(span: glib::TimeSpan) -> String {
+    let (count, unit) = if span.as_minutes().abs() < 1 {
+        return "just now".to_string();
+    } else if span.as_hours().abs() < 1 {
+        (span.as_minutes(), "minute")
+    } else if span.as_days().abs() < 1 {
+        (span.as_hours(), "hour")
     } else {
-        // If there are no results, return None to indicate that no comment was found.
-        None
+        (span.as_days(), "day")
+    };
+    let plural = if count.abs() == 1 { "" } else { "s" };
+    if count < 0 {
+        format!("in {} {unit}{plural}", -count)
+    } else {
+        format!("{count} {unit}{plural} ago")
+    }
+}
+
+/// Describes the classes a predicate applies to and the values it expects, abbreviating them
+/// with namespace prefixes where possible.
+///
+/// # Arguments
+/// * `domains` - The classes of the predicate's subjects (rdfs:domain).
+/// * `ranges` - The classes or datatypes of its values (rdfs:range).
+///
+/// # Returns
+/// * One line for each that is declared, e.g. "expects: xsd:dateTime", or `None` if neither is.
+fn describe_signature(domains: &[String], ranges: &[String]) -> Option<String> {
+    let names = |classes: &[String]| {
+        classes
+            .iter()
+            .map(|class| prefixes::compact(class).unwrap_or_else(|| class.clone()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let mut lines = Vec::new();
+    if !domains.is_empty() {
+        lines.push(format!("applies to: {}", names(domains)));
+    }
+    if !ranges.is_empty() {
+        lines.push(format!("expects: {}", names(ranges)));
     }
+    (!lines.is_empty()).then(|| lines.join("\n"))
 }
 
 #[cfg(test)]
@@ -1456,6 +4781,20 @@ mod tests {
         assert_eq!(friendly_label(uri), "Foo Bar Baz");
     }
 
+    #[test]
+    fn describe_subject_type_and_title() {
+        let ty = "http://tracker.api.gnome.org/ontology/v3/nmm#Photo";
+        assert_eq!(
+            describe_subject("urn:uuid:1", ty, "IMG_1234.jpg"),
+            "Photo 'IMG_1234.jpg'"
+        );
+    }
+
+    #[test]
+    fn describe_subject_falls_back_to_uri() {
+        assert_eq!(describe_subject("urn:uuid:1", "", ""), "urn:uuid:1");
+    }
+
     #[test]
     fn friendly_value_formats_date() {
         let raw = "2024-06-04T12:34:56Z";
@@ -1463,19 +4802,99 @@ mod tests {
             .and_then(|dt| dt.to_local())
             .and_then(|ldt| ldt.format("%F %T"))
             .unwrap();
-        assert_eq!(friendly_value(raw, XSD_DATETYPE), expected);
+        assert_eq!(
+            friendly_value(raw, XSD_DATETYPE, settings::DEFAULT_DATE_FORMAT, false),
+            expected
+        );
+    }
+
+    #[test]
+    fn friendly_value_custom_date_format() {
+        let raw = "2024-06-04T12:34:56Z";
+        let expected = glib::DateTime::from_iso8601(raw, None)
+            .and_then(|dt| dt.to_local())
+            .and_then(|ldt| ldt.format("%d.%m.%Y"))
+            .unwrap();
+        assert_eq!(
+            friendly_value(raw, XSD_DATETYPE, "%d.%m.%Y", false),
+            expected
+        );
+    }
+
+    #[test]
+    fn friendly_value_in_utc() {
+        let raw = "2024-06-04T12:34:56+02:00";
+        assert_eq!(
+            friendly_value(raw, XSD_DATETYPE, "%FT%T%:z", true),
+            "2024-06-04T10:34:56+00:00"
+        );
     }
 
     #[test]
     fn friendly_value_invalid_date() {
         let raw = "invalid";
-        assert_eq!(friendly_value(raw, XSD_DATETYPE), raw);
+        assert_eq!(
+            friendly_value(raw, XSD_DATETYPE, settings::DEFAULT_DATE_FORMAT, false),
+            raw
+        );
     }
 
     #[test]
     fn friendly_value_unrelated_type() {
         let raw = "hello";
-        assert_eq!(friendly_value(raw, "other"), raw);
+        assert_eq!(
+            friendly_value(raw, "other", settings::DEFAULT_DATE_FORMAT, false),
+            raw
+        );
+    }
+
+    #[test]
+    fn provenance_query_embeds_statement() {
+        let query = provenance_query("file:///a", "<file:///a> <urn:p> \"x\" .");
+        assert!(
+            query.contains("GRAPH ?g { <file:///a> <urn:p> \"x\" . }"),
+            "{query}"
+        );
+        assert!(query.contains(&format!("<file:///a> <{NIE_DATA_SOURCE}> ?source")));
+    }
+
+    #[test]
+    fn date_details_week_and_days_ago() {
+        let raw = "2024-06-04T12:00:00Z";
+        let now = glib::DateTime::from_iso8601("2024-06-07T12:00:00Z", None).unwrap();
+        let details = date_details(raw, XSD_DATETYPE, &now).unwrap();
+        assert!(details.contains("ISO week 23 of 2024"), "{details}");
+        assert!(details.ends_with(", 3 days ago"), "{details}");
+    }
+
+    #[test]
+    fn relative_time_uses_largest_unit() {
+        let now = glib::DateTime::from_iso8601("2024-06-07T12:00:00Z", None).unwrap();
+        let ago = |raw: &str| relative_date(raw, XSD_DATETYPE, &now).unwrap();
+        assert_eq!(ago("2024-06-07T11:59:30Z"), "just now");
+        assert_eq!(ago("2024-06-07T11:55:00Z"), "5 minutes ago");
+        assert_eq!(ago("2024-06-07T11:00:00Z"), "1 hour ago");
+        assert_eq!(ago("2024-06-04T12:00:00Z"), "3 days ago");
+        assert_eq!(ago("2024-06-08T12:00:00Z"), "in 1 day");
+        assert_eq!(relative_date("42", "other", &now), None);
+    }
+
+    #[test]
+    fn date_details_non_date() {
+        let now = glib::DateTime::now_local().unwrap();
+        assert_eq!(date_details("hello", "other", &now), None);
+        assert_eq!(date_details("invalid", XSD_DATETYPE, &now), None);
+    }
+
+    #[test]
+    fn range_fraction_within_range() {
+        assert_eq!(range_fraction(5.0, 0.0, 10.0), Some(0.5));
+        assert_eq!(range_fraction(20.0, 0.0, 10.0), Some(1.0));
+    }
+
+    #[test]
+    fn range_fraction_empty_range() {
+        assert_eq!(range_fraction(3.0, 3.0, 3.0), None);
     }
 
     #[test]
@@ -1493,6 +4912,64 @@ mod tests {
         assert!(!looks_like_uri("2024-06-04T12:34:56Z"));
     }
 
+    #[test]
+    fn unix_times_as_date_times() {
+        assert_eq!(
+            unix_time_value(86_400).as_deref(),
+            Some("1970-01-02T00:00:00Z")
+        );
+        assert_eq!(unix_time_value(u64::MAX), None);
+    }
+
+    #[test]
+    fn items_with_a_scheme_are_uris() {
+        assert!(has_scheme("urn:uuid:1234"));
+        assert!(has_scheme("file:///etc/hostname"));
+        assert!(has_scheme("x-fileinfo:urn:a"));
+        assert!(!has_scheme("/home/user/notes: draft.txt"));
+        assert!(!has_scheme("notes.txt"));
+        assert!(!has_scheme("c:photo.jpg"));
+    }
+
+    #[test]
+    fn deep_link_target_verbatim_and_encoded() {
+        assert_eq!(
+            deep_link_target("x-fileinfo:file:///etc/hostname").as_deref(),
+            Some("file:///etc/hostname")
+        );
+        assert_eq!(
+            deep_link_target("X-FileInfo://urn%3Aexample%231").as_deref(),
+            Some("urn:example#1")
+        );
+        assert_eq!(deep_link_target("file:///etc/hostname"), None);
+    }
+
+    #[test]
+    fn location_target_uri_and_path() {
+        assert_eq!(
+            location_target("  <https://example.com/a>\n").as_deref(),
+            Some("https://example.com/a")
+        );
+        assert_eq!(
+            location_target("/tmp/test").as_deref(),
+            Some("file:///tmp/test")
+        );
+    }
+
+    #[test]
+    fn location_target_deep_link() {
+        assert_eq!(
+            location_target("x-fileinfo:urn:example:1").as_deref(),
+            Some("urn:example:1")
+        );
+    }
+
+    #[test]
+    fn location_target_plain_text() {
+        assert_eq!(location_target("not a uri"), None);
+        assert_eq!(location_target("\n  \n"), None);
+    }
+
     #[test]
     fn ellipsize_zero_limit() {
         assert_eq!(ellipsize("hello", 0), "…");
@@ -1519,4 +4996,93 @@ mod tests {
         let uri = "nosuchscheme://foo";
         assert!(uri_has_handler(uri).is_err());
     }
+
+    #[test]
+    fn is_advanced_hides_technical_rows() {
+        let value = |obj: &str, dtype: &str| (obj.to_string(), dtype.to_string(), String::new());
+        assert!(is_advanced(RDF_TYPE, &[value(FILEDATAOBJECT, "")]));
+        assert!(is_advanced("urn:p", &[value("urn:bnode:1", "")]));
+        assert!(!is_advanced("urn:p", &[value("urn:bnode:1", XSD_STRING)]));
+        assert!(!is_advanced(NFO_FILE_NAME, &[value("a.txt", XSD_STRING)]));
+    }
+
+    #[test]
+    fn nothing_indexed_ignores_derived_values() {
+        let row = |source| TableRow {
+            source,
+            ..Default::default()
+        };
+        let identifier = row(ValueSource::Indexed);
+        assert!(!nothing_indexed(&[]));
+        assert!(nothing_indexed(std::slice::from_ref(&identifier)));
+        assert!(nothing_indexed(&[
+            identifier.clone(),
+            row(ValueSource::Derived)
+        ]));
+        assert!(!nothing_indexed(&[identifier, row(ValueSource::Indexed)]));
+    }
+
+    #[test]
+    fn page_query_names_the_subject() {
+        let uri = "file:///home/user/notes.txt";
+        assert_eq!(page_query("properties", uri), source::statements_query(uri));
+        assert_eq!(page_query("preview", uri), source::statements_query(uri));
+        let backlinks = page_query("backlinks", uri);
+        assert!(
+            backlinks.contains(&format!("?s ?p <{uri}> .")),
+            "{backlinks}"
+        );
+    }
+
+    #[test]
+    fn long_values_by_length_or_lines() {
+        assert!(!is_long_value("short"));
+        assert!(is_long_value(&"x".repeat(LONG_VALUE_CHARS + 1)));
+        assert!(is_long_value("one\ntwo\nthree\nfour"));
+        assert!(!is_long_value("one\ntwo\nthree"));
+    }
+
+    #[test]
+    fn format_duration_uses_largest_units() {
+        assert_eq!(format_duration("5025").as_deref(), Some("1 h 23 m 45 s"));
+        assert_eq!(format_duration("185.6").as_deref(), Some("3 m 6 s"));
+        assert_eq!(format_duration("42").as_deref(), Some("42 s"));
+        assert_eq!(format_duration("-1"), None);
+        assert_eq!(
+            display_value(DURATION_PREDICATES[0], "3600", XSD_INTEGER, "%F", false),
+            "1 h 0 m 0 s"
+        );
+    }
+
+    #[test]
+    fn collect_statements_from_a_mock_source() {
+        let source = source::MockSource::default()
+            .literal("file:///a.txt", NFO_FILE_NAME, "a.txt", XSD_STRING)
+            .link("file:///a.txt", RDF_TYPE, FILEDATAOBJECT)
+            .link("file:///a.txt", RDF_TYPE, "urn:Document")
+            .literal("file:///b.txt", NFO_FILE_NAME, "b.txt", XSD_STRING);
+        let statements = glib::MainContext::default()
+            .block_on(source.statements("file:///a.txt"))
+            .unwrap();
+        let (order, map, is_file_data_object) = collect_statements(statements);
+        assert!(is_file_data_object);
+        assert_eq!(order, [NFO_FILE_NAME, RDF_TYPE]);
+        assert_eq!(map[RDF_TYPE].len(), 2);
+        assert_eq!(map[NFO_FILE_NAME][0].0, "a.txt");
+    }
+
+    #[test]
+    fn describe_signature_abbreviates_classes() {
+        let range = vec!["http://www.w3.org/2001/XMLSchema#dateTime".to_string()];
+        assert_eq!(
+            describe_signature(&[], &range).as_deref(),
+            Some("expects: xsd:dateTime")
+        );
+        let domain = vec!["urn:example:Thing".to_string()];
+        assert_eq!(
+            describe_signature(&domain, &range).as_deref(),
+            Some("applies to: urn:example:Thing\nexpects: xsd:dateTime")
+        );
+        assert_eq!(describe_signature(&[], &[]), None);
+    }
 }
@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+
+pub const NFO_AVERAGE_BITRATE: &str = "http://tracker.api.gnome.org/ontology/v3/nfo#averageBitrate";
+pub const NFO_SAMPLE_RATE: &str = "http://tracker.api.gnome.org/ontology/v3/nfo#sampleRate";
+pub const NFO_WIDTH: &str = "http://tracker.api.gnome.org/ontology/v3/nfo#width";
+pub const NFO_HEIGHT: &str = "http://tracker.api.gnome.org/ontology/v3/nfo#height";
+
+/// Formats the value of an audio or video property in the unit it is usually given in: bit
+/// rates (stored in bits per second) in kbps and sample rates (stored in Hz) in kHz.
+///
+/// # Returns
+/// * The formatted value, or `None` for other properties and for values that are not numbers.
+pub fn format(pred: &str, obj: &str) -> Option<String> {
+    let value = obj.trim().parse::<f64>().ok().filter(|v| v.is_finite())?;
+    match pred {
+        NFO_AVERAGE_BITRATE => Some(format!("{} kbps", (value / 1000.0).round())),
+        NFO_SAMPLE_RATE => Some(format!("{} kHz", trim_decimals(value / 1000.0))),
+        _ => None,
+    }
+}
+
+/// Formats `value` with up to two decimals, leaving out trailing zeros, e.g. "44.1" or "48".
+fn trim_decimals(value: f64) -> String {
+    let text = format!("{value:.2}");
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// Pairs `nfo:width` and `nfo:height` into a resolution, e.g. "1920 × 1080".
+///
+/// # Arguments
+/// * `map` - The values of each predicate, as `(value, datatype, language)` tuples.
+///
+/// # Returns
+/// * The resolution and the predicates it combines, whose rows the "Resolution" row replaces;
+///   `None` unless the subject has both a width and a height.
+pub fn resolution(
+    map: &HashMap<String, Vec<(String, String, String)>>,
+) -> Option<(String, Vec<String>)> {
+    let first = |pred: &str| map.get(pred).and_then(|v| v.first()).map(|v| v.0.trim());
+    let width = first(NFO_WIDTH)?.parse::<u64>().ok()?;
+    let height = first(NFO_HEIGHT)?.parse::<u64>().ok()?;
+    Some((
+        format!("{width} × {height}"),
+        vec![NFO_WIDTH.to_string(), NFO_HEIGHT.to_string()],
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn media_values_read_naturally() {
+        assert_eq!(
+            format(NFO_AVERAGE_BITRATE, "320000").as_deref(),
+            Some("320 kbps")
+        );
+        assert_eq!(
+            format(NFO_SAMPLE_RATE, "44100").as_deref(),
+            Some("44.1 kHz")
+        );
+        assert_eq!(format(NFO_SAMPLE_RATE, "48000").as_deref(), Some("48 kHz"));
+        assert_eq!(format(NFO_SAMPLE_RATE, "fast"), None);
+        assert_eq!(format(NFO_WIDTH, "1920"), None);
+
+        let integer = "http://www.w3.org/2001/XMLSchema#integer".to_string();
+        let mut map = HashMap::new();
+        map.insert(
+            NFO_WIDTH.to_string(),
+            vec![("1920".to_string(), integer.clone(), String::new())],
+        );
+        assert_eq!(resolution(&map), None);
+        map.insert(
+            NFO_HEIGHT.to_string(),
+            vec![("1080".to_string(), integer, String::new())],
+        );
+        let (text, replaced) = resolution(&map).unwrap();
+        assert_eq!(text, "1920 × 1080");
+        assert_eq!(replaced, [NFO_WIDTH, NFO_HEIGHT]);
+    }
+}
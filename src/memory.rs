@@ -0,0 +1,74 @@
+use crate::trace;
+use serde_json::json;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Machines with at most this much memory run in low-memory mode unless told otherwise.
+const LOW_MEMORY_THRESHOLD: u64 = 1024 * 1024 * 1024;
+
+/// The factor by which result limits are reduced in low-memory mode.
+const LIMIT_DIVISOR: usize = 4;
+
+/// Whether low-memory mode is on (see [`init`]).
+static LOW_MEMORY: AtomicBool = AtomicBool::new(false);
+
+/// Turns low-memory mode on if `forced` (i.e. `--low-memory` was given) or if the machine has
+/// little memory.
+///
+/// In low-memory mode previews and thumbnails are not loaded, caches of ontology data are kept
+/// small or not filled at all, value range bars are left out and queries return fewer results
+/// (see [`limit`]), so the application stays usable on small single-board computers running
+/// Tracker.
+pub fn init(forced: bool) {
+    let low = forced || total_memory().is_some_and(|total| total <= LOW_MEMORY_THRESHOLD);
+    if low {
+        trace::event("low_memory_mode", json!({}));
+    }
+    LOW_MEMORY.store(low, Ordering::Relaxed);
+}
+
+/// Returns whether low-memory mode is on.
+pub fn is_low() -> bool {
+    LOW_MEMORY.load(Ordering::Relaxed)
+}
+
+/// Returns the number of results to ask for instead of `normal`: a fraction of it in
+/// low-memory mode, and `normal` itself otherwise.
+pub fn limit(normal: usize) -> usize {
+    if is_low() {
+        (normal / LIMIT_DIVISOR).max(1)
+    } else {
+        normal
+    }
+}
+
+/// Returns the total memory of the machine in bytes, read from `/proc/meminfo`.
+///
+/// The total rather than the available memory (`MemAvailable`) is used: the mode is decided
+/// once per process, and the available memory at that moment mostly reflects what else happens
+/// to be running, so the mode would depend on when the application was started rather than on
+/// the machine.
+fn total_memory() -> Option<u64> {
+    parse_mem_total(&std::fs::read_to_string("/proc/meminfo").ok()?)
+}
+
+/// Parses the `MemTotal` line of `/proc/meminfo`, which is given in KiB.
+///
+/// # Returns
+/// * The total memory in bytes, or `None` if there is no such line.
+fn parse_mem_total(meminfo: &str) -> Option<u64> {
+    let line = meminfo.lines().find(|line| line.starts_with("MemTotal:"))?;
+    let kib = line.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+    Some(kib * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_mem_total_reads_kib() {
+        let meminfo = "MemTotal:         948304 kB\nMemFree:          123456 kB\n";
+        assert_eq!(parse_mem_total(meminfo), Some(948304 * 1024));
+        assert_eq!(parse_mem_total("MemFree: 1 kB\n"), None);
+    }
+}
@@ -0,0 +1,103 @@
+use crate::context::Context;
+use crate::rdf;
+use crate::store;
+use crate::tags;
+use gtk::prelude::*;
+use std::rc::Rc;
+use tracker::prelude::*;
+
+const NAO_DESCRIPTION: &str = "http://tracker.api.gnome.org/ontology/v3/nao#description";
+
+/// Returns a query for the note attached to `subject`, as a single `?note` row (if any).
+pub fn note_query(subject: &str) -> String {
+    format!(
+        "SELECT ?note WHERE {{ {s} <{NAO_DESCRIPTION}> ?note }} LIMIT 1",
+        s = rdf::iri_ref(subject)
+    )
+}
+
+/// Returns an update replacing the note attached to `subject` with `note`.
+///
+/// An empty note removes the existing one.
+pub fn set_note_update(subject: &str, note: &str) -> String {
+    let s = rdf::iri_ref(subject);
+    let mut sparql = format!(
+        "DELETE {{ {s} <{NAO_DESCRIPTION}> ?note }} WHERE {{ {s} <{NAO_DESCRIPTION}> ?note }}"
+    );
+    if !note.is_empty() {
+        sparql.push_str(&format!(
+            " ;\nINSERT DATA {{ {s} <{NAO_DESCRIPTION}> \"{}\" }}",
+            rdf::escape_string(note)
+        ));
+    }
+    sparql
+}
+
+/// Builds the editable value of the "Note" row for `subject`: an entry holding the free-form
+/// note stored as `nao:description`, saved when Enter is pressed. Like the tags, notes are kept
+/// in the application's own store (see `store::annotations`).
+///
+/// # Arguments
+/// * `subject` - The URI of the resource the note is attached to.
+/// * `ctx` - Shared application settings.
+pub fn editor(subject: &str, ctx: &Rc<Context>) -> gtk::Widget {
+    let entry = gtk::Entry::builder()
+        .placeholder_text("Add a note…")
+        .tooltip_text("Press Enter to save the note")
+        .hexpand(true)
+        .margin_start(6)
+        .margin_end(9)
+        .margin_top(4)
+        .margin_bottom(4)
+        .build();
+
+    let subject_clone = subject.to_string();
+    let ctx_clone = ctx.clone();
+    entry.connect_activate(move |entry| {
+        let sparql = set_note_update(&subject_clone, entry.text().trim());
+        tags::apply(
+            entry.upcast_ref(),
+            &subject_clone,
+            sparql,
+            "Could not save note",
+            &ctx_clone,
+        );
+    });
+
+    // Show the stored note once it has been queried.
+    let subject = subject.to_string();
+    let entry_clone = entry.clone();
+    glib::MainContext::default().spawn_local(async move {
+        let Ok(conn) = store::annotations() else {
+            return;
+        };
+        let Ok(cursor) = conn.query_async(&note_query(&subject)).await else {
+            return;
+        };
+        if cursor.next_future().await.unwrap_or(false) {
+            entry_clone.set_text(&cursor.string(0).unwrap_or_default());
+        }
+    });
+
+    entry.upcast()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_note_replaces_existing_note() {
+        let sparql = set_note_update("file:///a", "line\none");
+        assert!(sparql.starts_with(&format!(
+            "DELETE {{ <file:///a> <{NAO_DESCRIPTION}> ?note }}"
+        )));
+        assert!(sparql.contains("INSERT DATA { <file:///a>"), "{sparql}");
+        assert!(sparql.contains("\"line\\none\""), "{sparql}");
+    }
+
+    #[test]
+    fn empty_note_only_deletes() {
+        assert!(!set_note_update("file:///a", "").contains("INSERT"));
+    }
+}
@@ -1,17 +1,129 @@
+use crate::export::ExportFormat;
+use crate::headless::ReportFormat;
+use crate::start::StartPage;
+use crate::trace::DebugFormat;
 use clap::Parser;
 
 /// Command line interface definition using clap.
 #[derive(Parser, Debug)]
 #[command(author, version, about)]
 pub struct Options {
-    /// Interpret the input argument as a URI
+    /// Interpret the input argument as a URI (the default if it starts with a scheme such as urn: or file:)
     #[arg(short, long)]
     pub uri: bool,
 
+    /// Interpret the input argument as a file path, even if it looks like a URI
+    #[arg(long, conflicts_with = "uri")]
+    pub path: bool,
+
     /// Enable debug output
     #[arg(short, long)]
     pub debug: bool,
 
-    /// File path or URI to open
-    pub item: String,
+    /// Format of the debug output: readable text, or one JSON object per event and line
+    #[arg(long, value_enum, default_value_t, value_name = "FORMAT")]
+    pub debug_format: DebugFormat,
+
+    /// With --debug, write a PNG snapshot of the window to this file once the loaded metadata has been painted
+    #[arg(long, value_name = "PNG_FILE", requires = "debug")]
+    pub screenshot: Option<std::path::PathBuf>,
+
+    /// Shell command to run after metadata has loaded; it receives the metadata as JSON on stdin
+    #[arg(long, value_name = "COMMAND")]
+    pub post_load_hook: Option<String>,
+
+    /// Serve each window's metadata as HTML and JSON on a random localhost port
+    #[arg(long)]
+    pub share: bool,
+
+    /// Follow links in the current window, with back/forward navigation, instead of opening new windows
+    #[arg(long, conflicts_with = "new_window")]
+    pub same_window: bool,
+
+    /// Follow links by opening new windows, overriding the stored preference
+    #[arg(long)]
+    pub new_window: bool,
+
+    /// strftime-style pattern for displaying date-times (default: "%F %T")
+    #[arg(long, value_name = "FORMAT")]
+    pub date_format: Option<String>,
+
+    /// Display date-times in UTC instead of the local time zone, overriding the stored preference
+    #[arg(long)]
+    pub utc: bool,
+
+    /// Show where numeric values such as file sizes and durations lie within the range found across the store
+    #[arg(long)]
+    pub value_ranges: bool,
+
+    /// Show how long connecting, querying and rendering took in a status bar below each subject
+    #[arg(long)]
+    pub timings: bool,
+
+    /// Save memory: no previews or caches, fewer results (the default on machines with little memory)
+    #[arg(long)]
+    pub low_memory: bool,
+
+    /// Keep running and show windows requested over D-Bus via ShowInformation(uri)
+    #[arg(long)]
+    pub service: bool,
+
+    /// With --service, also answer "show properties" requests made to the file manager over D-Bus (org.freedesktop.FileManager1)
+    #[arg(long, requires = "service")]
+    pub file_manager: bool,
+
+    /// Serve all queries from an in-memory store loaded with the triples of this Turtle file instead of Tracker
+    #[arg(long, value_name = "TURTLE_FILE")]
+    pub fixture: Option<std::path::PathBuf>,
+
+    /// What to show when no file is given, overriding the stored preference
+    #[arg(long, value_name = "PAGE")]
+    pub start_page: Option<StartPage>,
+
+    /// Print the metadata as JSON on stdout instead of showing a window
+    #[arg(long, conflicts_with = "service", requires = "item")]
+    pub json: bool,
+
+    /// With --json, keep running and print the metadata again whenever it changes in the store
+    #[arg(long, requires = "json")]
+    pub watch: bool,
+
+    /// With --json, only print the values of this property, as a prefixed name (nfo:fileSize) or full IRI; may be repeated
+    #[arg(long = "predicate", value_name = "PREDICATE", requires = "json")]
+    pub predicates: Vec<String>,
+
+    /// Print the resources matching a collection saved in the query builder instead of showing a window
+    #[arg(long, value_name = "NAME", conflicts_with_all = ["service", "json", "item"])]
+    pub run_saved: Option<String>,
+
+    /// Output format of --run-saved
+    #[arg(long, value_enum, default_value_t, requires = "run_saved")]
+    pub format: ReportFormat,
+
+    /// Write the metadata of each item to a file in this directory instead of showing a window
+    #[arg(long, value_name = "DIR", requires = "item", conflicts_with_all = ["service", "json", "run_saved"])]
+    pub export_dir: Option<std::path::PathBuf>,
+
+    /// Format of the files written by --export-dir, overriding the stored preference
+    #[arg(long, value_enum, value_name = "FORMAT", requires = "export_dir")]
+    pub export_format: Option<ExportFormat>,
+
+    /// With --export-dir, write one JSON array holding every item to metadata.json instead of a file per item
+    #[arg(long, requires = "export_dir", conflicts_with = "export_format")]
+    pub combined: bool,
+
+    /// Open a window searching the content of the indexed files for TERM
+    #[arg(long, value_name = "TERM", conflicts_with_all = ["json", "run_saved", "item"])]
+    pub search: Option<String>,
+
+    /// Register this executable with the desktop (desktop entry, x-fileinfo: links, D-Bus service) and exit
+    #[arg(long, conflicts_with_all = ["service", "json", "run_saved", "item"])]
+    pub install_handlers: bool,
+
+    /// File path or URI to open; without one, the start page is shown
+    pub item: Option<String>,
+
+    /// More files or URIs to export, with --export-dir
+    #[arg(requires = "export_dir")]
+    pub more_items: Vec<String>,
 }
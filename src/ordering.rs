@@ -0,0 +1,203 @@
+use crate::memory;
+use crate::settings;
+use crate::store;
+use gio::prelude::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use tracker::prelude::*;
+
+const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+const RDF_PROPERTY: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#Property";
+const NIE: &str = "http://tracker.api.gnome.org/ontology/v3/nie#";
+const NFO: &str = "http://tracker.api.gnome.org/ontology/v3/nfo#";
+
+/// The properties listed first when rows are ordered by importance: names, then sizes and
+/// types, then dates. Given as `(namespace, local name)` pairs.
+const IMPORTANT: &[(&str, &str)] = &[
+    (NFO, "fileName"),
+    (NIE, "title"),
+    (NFO, "fileSize"),
+    (NIE, "byteSize"),
+    (NIE, "mimeType"),
+    (NFO, "fileLastModified"),
+    (NFO, "fileCreated"),
+    (NIE, "contentCreated"),
+    (NIE, "contentLastModified"),
+    (NFO, "fileLastAccessed"),
+];
+
+thread_local! {
+    /// The position of each property in the ontology, once the ontology has been loaded.
+    static DEFINITIONS: RefCell<Option<HashMap<String, usize>>> = const { RefCell::new(None) };
+}
+
+/// How the rows of the property table are ordered.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RowOrder {
+    /// In the order the properties are defined in the ontology.
+    #[default]
+    Ontology,
+    /// Alphabetically by the displayed label.
+    Alphabetical,
+    /// Names, sizes and dates first (see `IMPORTANT`), then the rest alphabetically.
+    Importance,
+}
+
+impl RowOrder {
+    /// All orders, in the order they are offered.
+    pub const ALL: [RowOrder; 3] = [
+        RowOrder::Ontology,
+        RowOrder::Alphabetical,
+        RowOrder::Importance,
+    ];
+
+    /// Returns the name of the order, as used in the preferences.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RowOrder::Ontology => "ontology",
+            RowOrder::Alphabetical => "alphabetical",
+            RowOrder::Importance => "importance",
+        }
+    }
+
+    /// Returns the label of the order in the window menu.
+    pub fn label(self) -> &'static str {
+        match self {
+            RowOrder::Ontology => "Ontology Order",
+            RowOrder::Alphabetical => "Alphabetically",
+            RowOrder::Importance => "By Importance",
+        }
+    }
+
+    /// Returns the order named `name` (see [`RowOrder::as_str`]), if there is one.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|order| order.as_str() == name)
+    }
+
+    /// Returns the order chosen in the preferences, or the default one.
+    pub fn from_settings() -> Self {
+        Self::from_name(&settings::preferences().string(settings::ROW_ORDER)).unwrap_or_default()
+    }
+}
+
+/// Loads the order in which the properties are defined in the ontology, unless that has been
+/// done already.
+///
+/// Tracker lists ontology resources in the order they were loaded, which follows the
+/// ontology files. If the store cannot be queried, the properties keep the order in which
+/// the store returned them, and loading is tried again next time. In low-memory mode the
+/// ontology is not loaded at all, with the same result.
+pub async fn load() {
+    if memory::is_low() || DEFINITIONS.with(|definitions| definitions.borrow().is_some()) {
+        return;
+    }
+    let Ok(conn) = store::client() else {
+        return;
+    };
+    let sparql = format!("SELECT ?p WHERE {{ ?p <{RDF_TYPE}> <{RDF_PROPERTY}> }}");
+    let Ok(cursor) = conn.query_async(&sparql).await else {
+        return;
+    };
+    let mut definitions = HashMap::new();
+    while cursor.next_future().await.unwrap_or(false) {
+        let property = cursor.string(0).unwrap_or_default().to_string();
+        let position = definitions.len();
+        definitions.entry(property).or_insert(position);
+    }
+    DEFINITIONS.with(|loaded| loaded.replace(Some(definitions)));
+}
+
+/// Sorts `predicates` by `order`.
+///
+/// The sort is stable, so properties the ontology does not know keep the order in which the
+/// store returned them, after the known ones.
+///
+/// # Arguments
+/// * `predicates` - The predicate IRIs, in the order the store returned them.
+/// * `order` - How to order them. `RowOrder::Ontology` needs [`load`] to have run.
+/// * `label` - Returns the displayed label of a predicate, for alphabetical ordering.
+pub fn sort(predicates: &mut [String], order: RowOrder, label: impl Fn(&str) -> String) {
+    match order {
+        RowOrder::Ontology => DEFINITIONS.with(|definitions| {
+            if let Some(definitions) = definitions.borrow().as_ref() {
+                predicates.sort_by_key(|p| definitions.get(p).copied().unwrap_or(usize::MAX));
+            }
+        }),
+        RowOrder::Alphabetical => {
+            predicates.sort_by_cached_key(|p| label(p).to_lowercase());
+        }
+        RowOrder::Importance => {
+            predicates.sort_by_cached_key(|p| (importance(p), label(p).to_lowercase()));
+        }
+    }
+}
+
+/// Moves the predicates among `favorites` to the front, in the order of `favorites`; the
+/// others keep their order.
+pub fn favorites_first(predicates: &mut [String], favorites: &[String]) {
+    predicates.sort_by_key(|p| favorites.iter().position(|f| f == p).unwrap_or(usize::MAX));
+}
+
+/// Returns `favorites` with `predicate` removed if it is among them, or else appended.
+pub fn toggle(favorites: &[String], predicate: &str) -> Vec<String> {
+    let mut toggled: Vec<String> = favorites
+        .iter()
+        .filter(|f| *f != predicate)
+        .cloned()
+        .collect();
+    if toggled.len() == favorites.len() {
+        toggled.push(predicate.to_string());
+    }
+    toggled
+}
+
+/// Returns the position of `predicate` in `IMPORTANT`, or `usize::MAX` if it is not listed.
+fn importance(predicate: &str) -> usize {
+    IMPORTANT
+        .iter()
+        .position(|(namespace, local)| predicate.strip_prefix(namespace) == Some(local))
+        .unwrap_or(usize::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sort_puts_important_properties_first() {
+        let mut predicates = vec![
+            "urn:b".to_string(),
+            format!("{NFO}fileLastModified"),
+            "urn:A".to_string(),
+            format!("{NFO}fileName"),
+        ];
+        sort(&mut predicates, RowOrder::Importance, |p| p.to_string());
+        assert_eq!(
+            predicates,
+            [
+                format!("{NFO}fileName"),
+                format!("{NFO}fileLastModified"),
+                "urn:A".to_string(),
+                "urn:b".to_string(),
+            ]
+        );
+
+        sort(&mut predicates, RowOrder::Alphabetical, |p| {
+            p.rsplit(['#', ':']).next().unwrap_or(p).to_string()
+        });
+        assert_eq!(predicates[0], "urn:A");
+        assert_eq!(predicates[1], "urn:b");
+        assert_eq!(predicates[2], format!("{NFO}fileLastModified"));
+    }
+
+    #[test]
+    fn favorites_come_first_in_their_own_order() {
+        let mut predicates: Vec<String> = ["urn:a", "urn:b", "urn:c", "urn:d"]
+            .map(String::from)
+            .to_vec();
+        let favorites = toggle(&toggle(&[], "urn:d"), "urn:b");
+        favorites_first(&mut predicates, &favorites);
+        assert_eq!(predicates, ["urn:d", "urn:b", "urn:a", "urn:c"]);
+        assert_eq!(toggle(&favorites, "urn:d"), ["urn:b"]);
+    }
+}
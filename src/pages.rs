@@ -0,0 +1,199 @@
+use crate::memory;
+use crate::rdf;
+use crate::store;
+use adw::prelude::*;
+use tracker::prelude::*;
+
+/// Largest number of bytes of a text file shown in the preview.
+const PREVIEW_MAX_BYTES: usize = 64 * 1024;
+
+/// Shows a preview of the subject `uri` in `container`.
+///
+/// Images are shown as they are and text files as (the start of) their text. For other files
+/// the thumbnail the desktop has generated is shown, if there is one. Nothing is loaded in
+/// low-memory mode (see `memory::init`).
+///
+/// # Arguments
+/// * `container` - The preview page of a subject tab.
+/// * `uri` - The URI of the subject.
+pub async fn load_preview(container: &adw::Bin, uri: &str) {
+    if memory::is_low() {
+        container.set_child(Some(&no_preview(
+            "Previews are turned off in low-memory mode.",
+        )));
+        return;
+    }
+    let file = gio::File::for_uri(uri);
+    let info = if uri.starts_with("file:") {
+        file.query_info_future(
+            "standard::content-type,thumbnail::path",
+            gio::FileQueryInfoFlags::NONE,
+            glib::Priority::DEFAULT,
+        )
+        .await
+        .ok()
+    } else {
+        None
+    };
+    let Some(info) = info else {
+        container.set_child(Some(&no_preview("Only files can be previewed.")));
+        return;
+    };
+
+    let content_type = info.content_type().unwrap_or_default();
+    let thumbnail = info.attribute_byte_string("thumbnail::path");
+    let child: gtk::Widget = if gio::content_type_is_a(&content_type, "image/*") {
+        picture(&gtk::Picture::for_file(&file))
+    } else if gio::content_type_is_a(&content_type, "text/plain") {
+        match read_text(&file).await {
+            Ok(text) => {
+                let buffer = gtk::TextBuffer::new(None);
+                buffer.set_text(&text);
+                text_view(&buffer)
+            }
+            Err(err) => no_preview(&format!("The file could not be read: {err}")),
+        }
+    } else if let Some(path) = thumbnail {
+        picture(&gtk::Picture::for_filename(path.as_str()))
+    } else {
+        no_preview("There is no preview for this type of file.")
+    };
+    container.set_child(Some(&child));
+}
+
+/// Reads the start of a text file, up to `PREVIEW_MAX_BYTES`.
+async fn read_text(file: &gio::File) -> Result<String, glib::Error> {
+    let stream = file.read_future(glib::Priority::DEFAULT).await?;
+    let bytes = stream
+        .read_bytes_future(PREVIEW_MAX_BYTES, glib::Priority::DEFAULT)
+        .await?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Shows the statements about the subject `uri` as N-Triples in `buffer`.
+///
+/// # Arguments
+/// * `buffer` - The text buffer of the raw page of a subject tab.
+/// * `uri` - The URI of the subject.
+pub async fn load_raw(buffer: &gtk::TextBuffer, uri: &str) {
+    let text = match subject_triples(uri).await {
+        Ok(triples) => triples
+            .iter()
+            .map(rdf::Triple::to_ntriples)
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Err(err) => format!("# The store could not be queried: {err}"),
+    };
+    buffer.set_text(&text);
+}
+
+/// Returns the query for all statements with the subject `uri`, as `?pred ?obj ?dtype ?lang`
+/// rows.
+pub fn raw_query(uri: &str) -> String {
+    format!(
+        "SELECT ?pred ?obj (DATATYPE(?obj) AS ?dtype) (LANG(?obj) AS ?lang) \
+         WHERE {{ {s} ?pred ?obj }}",
+        s = rdf::iri_ref(uri)
+    )
+}
+
+/// Queries all statements with the subject `uri`.
+pub async fn subject_triples(uri: &str) -> Result<Vec<rdf::Triple>, glib::Error> {
+    let conn = store::client()?;
+    let cursor = conn.query_async(&raw_query(uri)).await?;
+    let mut triples = Vec::new();
+    while cursor.next_future().await? {
+        let obj = cursor.string(1).unwrap_or_default().to_string();
+        let dtype = cursor.string(2).unwrap_or_default().to_string();
+        let lang = cursor.string(3).unwrap_or_default().to_string();
+        triples.push(rdf::Triple {
+            subject: uri.to_string(),
+            predicate: cursor.string(0).unwrap_or_default().to_string(),
+            // Like in the grid, values without a datatype are references to other nodes.
+            object: if dtype.is_empty() {
+                rdf::Object::Iri(obj)
+            } else {
+                rdf::Object::Literal {
+                    value: obj,
+                    datatype: dtype,
+                    lang,
+                }
+            },
+        });
+    }
+    Ok(triples)
+}
+
+/// Wraps a picture for the preview page, scaled down to fit the page.
+fn picture(picture: &gtk::Picture) -> gtk::Widget {
+    picture.set_can_shrink(true);
+    picture.set_margin_start(6);
+    picture.set_margin_end(6);
+    picture.set_margin_top(6);
+    picture.set_margin_bottom(6);
+    picture.clone().upcast()
+}
+
+/// Builds a read-only, scrollable text view showing `buffer`.
+pub fn text_view(buffer: &gtk::TextBuffer) -> gtk::Widget {
+    let view = gtk::TextView::builder()
+        .buffer(buffer)
+        .editable(false)
+        .cursor_visible(false)
+        .monospace(true)
+        .wrap_mode(gtk::WrapMode::WordChar)
+        .left_margin(6)
+        .right_margin(6)
+        .top_margin(4)
+        .bottom_margin(4)
+        .build();
+    gtk::ScrolledWindow::builder()
+        .child(&view)
+        .vexpand(true)
+        .build()
+        .upcast()
+}
+
+/// Builds a page of a subject tab holding an initially empty list of subjects, such as the
+/// contents of a folder, that scrolls.
+///
+/// # Arguments
+/// * `placeholder` - The text shown while the list is empty.
+///
+/// # Returns
+/// * The page and its list.
+pub fn list_page(placeholder: &str) -> (gtk::Widget, gtk::ListBox) {
+    let list = gtk::ListBox::builder()
+        .selection_mode(gtk::SelectionMode::None)
+        .valign(gtk::Align::Start)
+        .margin_start(12)
+        .margin_end(12)
+        .margin_top(12)
+        .margin_bottom(12)
+        .build();
+    list.style_context().add_class("boxed-list");
+    list.set_placeholder(Some(&gtk::Label::new(Some(placeholder))));
+    let scroll = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .vexpand(true)
+        .child(&list)
+        .build();
+    (scroll.upcast(), list)
+}
+
+/// Removes all rows from `list`, e.g. before a page built by [`list_page`] is reloaded.
+pub fn clear_list(list: &gtk::ListBox) {
+    while let Some(row) = list.row_at_index(0) {
+        list.remove(&row);
+    }
+}
+
+/// Builds the empty state shown when there is nothing to preview.
+fn no_preview(description: &str) -> gtk::Widget {
+    adw::StatusPage::builder()
+        .icon_name("image-missing-symbolic")
+        .title("No Preview")
+        .description(description)
+        .build()
+        .upcast()
+}
@@ -0,0 +1,187 @@
+use crate::{TableRow, tab_title};
+use gtk::pango;
+use std::path::Path;
+
+/// The size of an A4 page, in points.
+const PAGE_WIDTH: f64 = 595.28;
+const PAGE_HEIGHT: f64 = 841.89;
+
+/// The empty space around the content of each page, in points.
+const MARGIN: f64 = 48.0;
+
+/// The width of the column of predicates and the space between the columns, in points.
+const PREDICATE_WIDTH: f64 = 150.0;
+const COLUMN_GAP: f64 = 12.0;
+
+/// The size of the text of the table and the distance between its lines, in points.
+const FONT_SIZE: f64 = 9.5;
+const LINE_HEIGHT: f64 = 13.0;
+
+/// The size of the title above the table, in points.
+const TITLE_SIZE: f64 = 15.0;
+
+/// The font of the text. Pango falls back to other fonts for characters it lacks, such as CJK
+/// or emoji, and shapes right-to-left text.
+const FONT_FAMILY: &str = "Sans";
+
+/// Writes the table of a subject tab to a PDF file, spread across as many A4 pages as needed.
+///
+/// The title of the subject and its URI head the first page, followed by a row for each entry
+/// of `rows` with its displayed predicate and value, wrapped to fit the columns.
+///
+/// # Arguments
+/// * `path` - The file to write.
+/// * `uri` - The URI of the subject.
+/// * `rows` - The rows of the table, as shown.
+pub fn write(path: &Path, uri: &str, rows: &[TableRow]) -> Result<(), cairo::Error> {
+    let surface = cairo::PdfSurface::new(PAGE_WIDTH, PAGE_HEIGHT, path)?;
+    let cr = cairo::Context::new(&surface)?;
+    let value_x = MARGIN + PREDICATE_WIDTH + COLUMN_GAP;
+    let value_width = PAGE_WIDTH - MARGIN - value_x;
+    let bottom = PAGE_HEIGHT - MARGIN;
+
+    cr.set_source_rgb(0.0, 0.0, 0.0);
+    let title = lay_out(
+        &cr,
+        &tab_title(uri),
+        TITLE_SIZE,
+        pango::Weight::Bold,
+        PAGE_WIDTH - 2.0 * MARGIN,
+    );
+    let mut y = MARGIN + TITLE_SIZE;
+    for line in title.lines_readonly() {
+        show_line(&cr, MARGIN, y, &line);
+        y += TITLE_SIZE * 1.3;
+    }
+    cr.set_source_rgb(0.35, 0.35, 0.35);
+    let subject = lay_out(
+        &cr,
+        uri,
+        FONT_SIZE,
+        pango::Weight::Normal,
+        PAGE_WIDTH - 2.0 * MARGIN,
+    );
+    for line in subject.lines_readonly() {
+        show_line(&cr, MARGIN, y, &line);
+        y += LINE_HEIGHT;
+    }
+    y += LINE_HEIGHT;
+
+    for row in rows {
+        let predicate = lay_out(
+            &cr,
+            &row.display_predicate,
+            FONT_SIZE,
+            pango::Weight::Bold,
+            PREDICATE_WIDTH,
+        );
+        let value = lay_out(
+            &cr,
+            &row.display_value,
+            FONT_SIZE,
+            pango::Weight::Normal,
+            value_width,
+        );
+        let line_count = predicate.line_count().max(value.line_count());
+        let height = f64::from(line_count) * LINE_HEIGHT;
+        // Rows start on a new page rather than being split, unless they are longer than a page.
+        if y + height > bottom && y > MARGIN + LINE_HEIGHT {
+            cr.show_page()?;
+            y = MARGIN + LINE_HEIGHT;
+        }
+
+        cr.set_source_rgb(0.0, 0.0, 0.0);
+        let mut line_y = y;
+        for index in 0..line_count {
+            if line_y > bottom {
+                cr.show_page()?;
+                line_y = MARGIN + LINE_HEIGHT;
+            }
+            if let Some(line) = predicate.line_readonly(index) {
+                show_line(&cr, MARGIN, line_y, &line);
+            }
+            if let Some(line) = value.line_readonly(index) {
+                show_line(&cr, value_x, line_y, &line);
+            }
+            line_y += LINE_HEIGHT;
+        }
+        y = line_y + LINE_HEIGHT * 0.25;
+
+        // A thin rule separates the rows.
+        cr.set_source_rgb(0.8, 0.8, 0.8);
+        cr.set_line_width(0.5);
+        cr.move_to(MARGIN, y - LINE_HEIGHT * 0.6);
+        cr.line_to(PAGE_WIDTH - MARGIN, y - LINE_HEIGHT * 0.6);
+        cr.stroke()?;
+        y += LINE_HEIGHT * 0.25;
+    }
+
+    cr.show_page()?;
+    surface.finish();
+    Ok(())
+}
+
+/// Lays `text` out for drawing on `cr`, in lines no wider than `width`. Lines are broken at
+/// word boundaries where possible and between characters otherwise, as for long URIs; line
+/// breaks in `text` are kept.
+///
+/// # Arguments
+/// * `cr` - The context the text is to be drawn on.
+/// * `text` - The text.
+/// * `size` - The size of the font, in points.
+/// * `weight` - The weight of the font.
+/// * `width` - The width available, in points.
+fn lay_out(
+    cr: &cairo::Context,
+    text: &str,
+    size: f64,
+    weight: pango::Weight,
+    width: f64,
+) -> pango::Layout {
+    let mut font = pango::FontDescription::new();
+    font.set_family(FONT_FAMILY);
+    font.set_weight(weight);
+    // The size is given in device units, i.e. points on a PDF surface, regardless of the
+    // resolution Pango assumes.
+    font.set_absolute_size(size * f64::from(pango::SCALE));
+    let layout = pangocairo::functions::create_layout(cr);
+    layout.set_font_description(Some(&font));
+    layout.set_width((width * f64::from(pango::SCALE)) as i32);
+    layout.set_wrap(pango::WrapMode::WordChar);
+    layout.set_text(text);
+    layout
+}
+
+/// Draws one line of a layout with its baseline at `y`.
+fn show_line(cr: &cairo::Context, x: f64, y: f64, line: &pango::LayoutLine) {
+    cr.move_to(x, y);
+    pangocairo::functions::show_layout_line(cr, line);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lay_out_wraps_to_the_width() {
+        let surface = cairo::ImageSurface::create(cairo::Format::ARgb32, 200, 200).unwrap();
+        let cr = cairo::Context::new(&surface).unwrap();
+        let width = 60.0;
+        let layout = lay_out(
+            &cr,
+            "a short text\nfile:///home/user/a/rather/long/path",
+            FONT_SIZE,
+            pango::Weight::Normal,
+            width,
+        );
+        assert!(layout.line_count() > 2);
+        for line in layout.lines_readonly() {
+            let (_, logical) = line.pixel_extents();
+            assert!(f64::from(logical.width()) <= width + 1.0);
+        }
+        assert_eq!(
+            lay_out(&cr, "", FONT_SIZE, pango::Weight::Normal, width).line_count(),
+            1
+        );
+    }
+}
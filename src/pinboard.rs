@@ -0,0 +1,272 @@
+use crate::METADATA_WINDOW_CLASS;
+use crate::announce;
+use crate::context::Context;
+use crate::dnd;
+use crate::events::EventBus;
+use crate::geometry;
+use crate::open_subject_window;
+use adw::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Widget name of the pinboard window, by which it is found among the application's windows.
+const WINDOW_NAME: &str = "pinboard";
+
+/// A value pinned from one of the subject windows.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PinnedValue {
+    /// The URI of the subject the value belongs to.
+    pub subject: String,
+    /// The label of the property, as displayed in the subject window.
+    pub property: String,
+    /// The value, as displayed in the subject window.
+    pub value: String,
+}
+
+/// Values collected from any number of windows, e.g. for assembling facts about several files
+/// into a report. There is one pinboard per application instance (see `Context`).
+#[derive(Debug, Default)]
+pub struct Pinboard {
+    values: RefCell<Vec<PinnedValue>>,
+    /// Notified with the subject of each value pinned or removed, or with an empty string when
+    /// the pinboard is cleared.
+    pub changes: EventBus,
+}
+
+impl Pinboard {
+    /// Adds a value to the end of the pinboard, unless it has been pinned already.
+    ///
+    /// # Returns
+    /// * Whether the value was added.
+    pub fn pin(&self, value: PinnedValue) -> bool {
+        if self.values.borrow().contains(&value) {
+            return false;
+        }
+        let subject = value.subject.clone();
+        self.values.borrow_mut().push(value);
+        self.changes.publish(&subject);
+        true
+    }
+
+    /// Removes the value at `index`, if there is one.
+    pub fn remove(&self, index: usize) {
+        let removed = {
+            let mut values = self.values.borrow_mut();
+            (index < values.len()).then(|| values.remove(index))
+        };
+        if let Some(removed) = removed {
+            self.changes.publish(&removed.subject);
+        }
+    }
+
+    /// Removes all values.
+    pub fn clear(&self) {
+        self.values.borrow_mut().clear();
+        self.changes.publish("");
+    }
+
+    /// Returns the pinned values, in the order they were pinned.
+    pub fn values(&self) -> Vec<PinnedValue> {
+        self.values.borrow().clone()
+    }
+
+    /// Formats the pinned values as a plain-text block, grouped by subject:
+    ///
+    /// ```text
+    /// file:///home/user/a.jpg
+    ///   Width: 640
+    ///   Height: 480
+    ///
+    /// file:///home/user/b.jpg
+    ///   Width: 800
+    /// ```
+    ///
+    /// Subjects appear in the order their first value was pinned.
+    pub fn to_text(&self) -> String {
+        let values = self.values.borrow();
+        let mut subjects: Vec<&str> = Vec::new();
+        for value in values.iter() {
+            if !subjects.contains(&value.subject.as_str()) {
+                subjects.push(&value.subject);
+            }
+        }
+        let blocks: Vec<String> = subjects
+            .into_iter()
+            .map(|subject| {
+                let mut block = subject.to_string();
+                for value in values.iter().filter(|v| v.subject == subject) {
+                    block.push_str(&format!("\n  {}: {}", value.property, value.value));
+                }
+                block
+            })
+            .collect();
+        blocks.join("\n\n")
+    }
+}
+
+/// Shows the pinboard window, creating it if it is not open yet.
+///
+/// The window lists the pinned values with buttons for removing them, and has header bar
+/// buttons for copying the whole pinboard (see [`Pinboard::to_text`]) and for clearing it. It
+/// follows changes to the pinboard made from any window.
+///
+/// # Arguments
+/// * `app` - The application instance.
+/// * `ctx` - Shared application settings, holding the pinboard.
+pub fn open_window(app: &adw::Application, ctx: &Rc<Context>) {
+    if let Some(window) = app
+        .windows()
+        .into_iter()
+        .find(|w| w.widget_name() == WINDOW_NAME)
+    {
+        window.present();
+        return;
+    }
+
+    let content = adw::Bin::new();
+    let copy_button = gtk::Button::from_icon_name("edit-copy-symbolic");
+    copy_button.set_tooltip_text(Some("Copy All"));
+    let ctx_clone = ctx.clone();
+    copy_button.connect_clicked(move |button| {
+        if let Some(display) = gdk4::Display::default() {
+            display.clipboard().set_text(&ctx_clone.pinboard.to_text());
+            announce::announce(button, "Copied pinboard");
+        }
+    });
+    let clear_button = gtk::Button::from_icon_name("edit-clear-all-symbolic");
+    clear_button.set_tooltip_text(Some("Clear"));
+    let ctx_clone = ctx.clone();
+    clear_button.connect_clicked(move |_| ctx_clone.pinboard.clear());
+
+    let header = adw::HeaderBar::new();
+    header.set_title_widget(Some(&gtk::Label::new(Some("Pinboard"))));
+    header.pack_start(&copy_button);
+    header.pack_start(&clear_button);
+    let toolbar = adw::ToolbarView::new();
+    toolbar.add_top_bar(&header);
+    toolbar.set_content(Some(&content));
+
+    let window = adw::ApplicationWindow::builder()
+        .application(app)
+        .default_width(360)
+        .default_height(320)
+        .title("Pinboard")
+        .content(&announce::wrap(&toolbar))
+        .build();
+    window.set_widget_name(WINDOW_NAME);
+    window.style_context().add_class(METADATA_WINDOW_CLASS);
+    geometry::remember(&window, "pinboard");
+    // Files dropped onto the window open in subject windows.
+    let app_clone = app.clone();
+    let ctx_clone = ctx.clone();
+    dnd::accept(&window, move |target| {
+        open_subject_window(&app_clone, target, &ctx_clone)
+    });
+
+    // Rebuild the list whenever a value is pinned or removed, in whichever window.
+    let refresh = {
+        let ctx = ctx.clone();
+        let content = content.clone();
+        let buttons = [copy_button.clone(), clear_button.clone()];
+        move || {
+            let values = ctx.pinboard.values();
+            for button in &buttons {
+                button.set_sensitive(!values.is_empty());
+            }
+            content.set_child(Some(&values_widget(&values, &ctx)));
+        }
+    };
+    refresh();
+    let subscription = ctx.pinboard.changes.subscribe(move |_| refresh());
+    let ctx_clone = ctx.clone();
+    window.connect_destroy(move |_| ctx_clone.pinboard.changes.unsubscribe(subscription));
+    window.present();
+}
+
+/// Builds the list of pinned values, or a placeholder explaining how to pin values.
+fn values_widget(values: &[PinnedValue], ctx: &Rc<Context>) -> gtk::Widget {
+    if values.is_empty() {
+        return adw::StatusPage::builder()
+            .icon_name("view-pin-symbolic")
+            .title("No Pinned Values")
+            .description("Right-click a value and choose “Pin Value” to collect it here.")
+            .build()
+            .upcast();
+    }
+
+    let list = gtk::ListBox::builder()
+        .selection_mode(gtk::SelectionMode::None)
+        .build();
+    for (index, value) in values.iter().enumerate() {
+        let property = gtk::Label::new(Some(&value.property));
+        property.set_halign(gtk::Align::Start);
+        property.style_context().add_class("first-col");
+        let text = gtk::Label::new(Some(&value.value));
+        text.set_halign(gtk::Align::Start);
+        text.set_xalign(0.0);
+        text.set_wrap(true);
+        text.set_wrap_mode(gtk::pango::WrapMode::WordChar);
+        text.set_selectable(true);
+        let subject = gtk::Label::new(Some(&value.subject));
+        subject.set_halign(gtk::Align::Start);
+        subject.set_ellipsize(gtk::pango::EllipsizeMode::Middle);
+        subject.style_context().add_class("dim-label");
+
+        let labels = gtk::Box::new(gtk::Orientation::Vertical, 2);
+        labels.set_hexpand(true);
+        labels.append(&property);
+        labels.append(&text);
+        labels.append(&subject);
+
+        let remove = gtk::Button::builder()
+            .icon_name("window-close-symbolic")
+            .tooltip_text("Remove")
+            .has_frame(false)
+            .valign(gtk::Align::Start)
+            .build();
+        let ctx = ctx.clone();
+        remove.connect_clicked(move |_| ctx.pinboard.remove(index));
+
+        let row = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        row.set_margin_start(6);
+        row.set_margin_end(6);
+        row.set_margin_top(4);
+        row.set_margin_bottom(4);
+        row.append(&labels);
+        row.append(&remove);
+        list.append(&row);
+    }
+    gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .child(&list)
+        .build()
+        .upcast()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pinned(subject: &str, property: &str, value: &str) -> PinnedValue {
+        PinnedValue {
+            subject: subject.to_string(),
+            property: property.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn to_text_groups_values_by_subject() {
+        let board = Pinboard::default();
+        assert!(board.pin(pinned("urn:a", "Width", "640")));
+        assert!(board.pin(pinned("urn:b", "Width", "800")));
+        assert!(board.pin(pinned("urn:a", "Height", "480")));
+        assert!(!board.pin(pinned("urn:a", "Width", "640")));
+        assert_eq!(
+            board.to_text(),
+            "urn:a\n  Width: 640\n  Height: 480\n\nurn:b\n  Width: 800"
+        );
+        board.remove(1);
+        assert_eq!(board.to_text(), "urn:a\n  Width: 640\n  Height: 480");
+    }
+}
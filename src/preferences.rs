@@ -0,0 +1,335 @@
+use crate::context::Context;
+use crate::export::ExportFormat;
+use crate::prefixes;
+use crate::settings;
+use crate::{is_valid_date_format, looks_like_uri};
+use adw::prelude::*;
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+/// Widget name of the preferences window, by which it is found among the application's windows.
+const WINDOW_NAME: &str = "preferences";
+
+/// Opens the preferences window, or brings it to the front if it is already open.
+///
+/// The window gathers the behavior that can be changed at runtime: how links are followed,
+/// predicates that are never shown, how dates are displayed and the format of copied tables.
+/// Its rows are bound to the user's preferences, so every change is stored at once and applies
+/// through [`follow`].
+///
+/// # Arguments
+/// * `app` - The application instance.
+pub fn open_window(app: &adw::Application) {
+    if let Some(window) = app
+        .windows()
+        .into_iter()
+        .find(|w| w.widget_name() == WINDOW_NAME)
+    {
+        window.present();
+        return;
+    }
+
+    let settings = settings::preferences();
+    let page = adw::PreferencesPage::new();
+    page.add(&links_group(&settings));
+    page.add(&dates_group(&settings));
+    page.add(&export_group(&settings));
+    page.add(&hidden_group());
+
+    let window = adw::PreferencesWindow::builder()
+        .application(app)
+        .title("Preferences")
+        .search_enabled(false)
+        .build();
+    if let Some(parent) = app.active_window() {
+        window.set_transient_for(Some(&parent));
+    }
+    window.set_widget_name(WINDOW_NAME);
+    window.add(&page);
+    window.present();
+}
+
+thread_local! {
+    /// The contexts following the preferences (see [`follow`]). They are held weakly, since a
+    /// context is dropped with the last window using it.
+    static FOLLOWERS: RefCell<Vec<Weak<Context>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Makes `ctx` follow the preferences edited in the preferences window, whichever window or
+/// tool changes them (see [`connect_changes`]).
+///
+/// # Arguments
+/// * `ctx` - Shared application settings, which follow the preferences.
+pub fn follow(ctx: &Rc<Context>) {
+    FOLLOWERS.with(|followers| {
+        let mut followers = followers.borrow_mut();
+        followers.retain(|follower| follower.strong_count() > 0);
+        if !followers
+            .iter()
+            .any(|follower| follower.as_ptr() == Rc::as_ptr(ctx))
+        {
+            followers.push(Rc::downgrade(ctx));
+        }
+    });
+}
+
+/// Calls `f` with each context following the preferences.
+fn for_each_follower(f: impl Fn(&Context)) {
+    let followers: Vec<Rc<Context>> = FOLLOWERS.with(|followers| {
+        followers
+            .borrow()
+            .iter()
+            .filter_map(Weak::upgrade)
+            .collect()
+    });
+    for ctx in &followers {
+        f(ctx);
+    }
+}
+
+/// Reloads every subject open with `ctx`, after a change of how they are shown.
+fn reload_open_subjects(ctx: &Context) {
+    let uris = ctx.open_subjects.borrow().uris();
+    for uri in uris {
+        ctx.changes.publish(&uri);
+    }
+}
+
+/// Applies changes of the preferences to the contexts following them (see [`follow`]), and
+/// keeps the state of the `app.same-window` action of the window menu in step.
+///
+/// The handlers are connected once, when the application starts, however many times it is
+/// invoked afterwards.
+///
+/// # Arguments
+/// * `app` - The application instance.
+pub fn connect_changes(app: &adw::Application) {
+    let settings = settings::preferences();
+
+    let app_clone = app.clone();
+    settings.connect_changed(Some(settings::SAME_WINDOW), move |settings, key| {
+        let enabled = settings.boolean(key);
+        for_each_follower(|ctx| ctx.same_window.set(enabled));
+        // Setting the state directly does not write the preference back.
+        if let Some(action) = app_clone
+            .lookup_action("same-window")
+            .and_downcast::<gio::SimpleAction>()
+        {
+            action.set_state(&enabled.to_variant());
+        }
+    });
+
+    // Changing how dates are shown reloads every open subject.
+    settings.connect_changed(Some(settings::DATE_FORMAT), move |settings, key| {
+        let format = settings.string(key);
+        let format = if is_valid_date_format(&format) {
+            format.to_string()
+        } else {
+            settings::DEFAULT_DATE_FORMAT.to_string()
+        };
+        for_each_follower(|ctx| {
+            if *ctx.date_format.borrow() != format {
+                ctx.date_format.replace(format.clone());
+                reload_open_subjects(ctx);
+            }
+        });
+    });
+
+    settings.connect_changed(Some(settings::UTC_DATES), move |settings, key| {
+        let utc = settings.boolean(key);
+        for_each_follower(|ctx| {
+            if ctx.utc_dates.replace(utc) != utc {
+                reload_open_subjects(ctx);
+            }
+        });
+    });
+
+    settings.connect_changed(Some(settings::EXPORT_FORMAT), move |settings, key| {
+        let format = ExportFormat::from_name(&settings.string(key)).unwrap_or_default();
+        for_each_follower(|ctx| ctx.export_format.set(format));
+    });
+
+    // Hiding or showing a predicate reloads every open subject.
+    settings.connect_changed(Some(settings::HIDDEN_PREDICATES), move |_, key| {
+        let hidden = settings::preference_list(key);
+        for_each_follower(|ctx| {
+            if *ctx.hidden_predicates.borrow() != hidden {
+                ctx.hidden_predicates.replace(hidden.clone());
+                reload_open_subjects(ctx);
+            }
+        });
+    });
+}
+
+/// Builds the group for link handling.
+fn links_group(settings: &gio::Settings) -> adw::PreferencesGroup {
+    let same_window = adw::SwitchRow::builder()
+        .title("Open Links in Same Window")
+        .subtitle("Follow links in the current tab instead of opening a new window")
+        .build();
+    settings
+        .bind(settings::SAME_WINDOW, &same_window, "active")
+        .build();
+
+    let group = adw::PreferencesGroup::builder().title("Links").build();
+    group.add(&same_window);
+    group
+}
+
+/// Builds the group for the date-time pattern and time zone, like the "Date Format" dialog.
+/// Changes apply to subjects loaded from then on.
+fn dates_group(settings: &gio::Settings) -> adw::PreferencesGroup {
+    let format = adw::EntryRow::builder()
+        .title("Date Format")
+        .show_apply_button(true)
+        .build();
+    // The entry shows the stored pattern, but only patterns that can actually be used are
+    // stored, when applied.
+    settings
+        .bind(settings::DATE_FORMAT, &format, "text")
+        .get()
+        .build();
+    format.connect_apply(move |row| {
+        let text = row.text().to_string();
+        if !is_valid_date_format(&text) {
+            row.style_context().add_class("error");
+            return;
+        }
+        row.style_context().remove_class("error");
+        settings::set_preference(settings::DATE_FORMAT, text.as_str());
+    });
+
+    let utc = adw::SwitchRow::builder()
+        .title("Show in UTC")
+        .subtitle("Makes times comparable across machines")
+        .build();
+    settings.bind(settings::UTC_DATES, &utc, "active").build();
+
+    let group = adw::PreferencesGroup::builder()
+        .title("Dates")
+        .description("A strftime-style pattern, e.g. \"%F %T\" or \"%x %X\"")
+        .build();
+    group.add(&format);
+    group.add(&utc);
+    group
+}
+
+/// Builds the group choosing the format of tables copied with the "Copy" button.
+fn export_group(settings: &gio::Settings) -> adw::PreferencesGroup {
+    let labels: Vec<&str> = ExportFormat::ALL.iter().map(|f| f.label()).collect();
+    let format = adw::ComboRow::builder()
+        .title("Copy Format")
+        .model(&gtk::StringList::new(&labels))
+        .build();
+    // The preference holds the name of the format, the row its position.
+    settings
+        .bind(settings::EXPORT_FORMAT, &format, "selected")
+        .mapping(|variant, _| {
+            let format = ExportFormat::from_name(variant.str()?)?;
+            let position = ExportFormat::ALL.iter().position(|f| *f == format)?;
+            Some((position as u32).to_value())
+        })
+        .set_mapping(|value, _| {
+            let format = ExportFormat::ALL.get(value.get::<u32>().ok()? as usize)?;
+            Some(format.as_str().to_variant())
+        })
+        .build();
+
+    let group = adw::PreferencesGroup::builder().title("Export").build();
+    group.add(&format);
+    group
+}
+
+/// Builds the group listing the predicates that are never shown, with an entry for adding one
+/// and a button on each for showing it again.
+fn hidden_group() -> adw::PreferencesGroup {
+    let group = adw::PreferencesGroup::builder()
+        .title("Hidden Properties")
+        .description("Properties never shown in subject windows")
+        .build();
+    let add = adw::EntryRow::builder()
+        .title("Add Property (IRI or prefixed name)")
+        .show_apply_button(true)
+        .build();
+    group.add(&add);
+
+    let rows = Rc::new(RefCell::new(Vec::new()));
+    show_hidden(&group, &rows);
+
+    let group_clone = group.clone();
+    add.connect_apply(move |row| {
+        let Some(predicate) = parse_predicate(&row.text()) else {
+            row.style_context().add_class("error");
+            return;
+        };
+        row.style_context().remove_class("error");
+        row.set_text("");
+        let mut hidden = settings::preference_list(settings::HIDDEN_PREDICATES);
+        if !hidden.contains(&predicate) {
+            hidden.push(predicate);
+            settings::set_preference(settings::HIDDEN_PREDICATES, hidden);
+            show_hidden(&group_clone, &rows);
+        }
+    });
+    group
+}
+
+/// Replaces the rows of `group` listing hidden predicates (kept in `rows`) with rows for the
+/// current ones.
+fn show_hidden(group: &adw::PreferencesGroup, rows: &Rc<RefCell<Vec<adw::ActionRow>>>) {
+    for row in rows.borrow_mut().drain(..) {
+        group.remove(&row);
+    }
+    for predicate in settings::preference_list(settings::HIDDEN_PREDICATES) {
+        let row = adw::ActionRow::builder()
+            .title(prefixes::compact(&predicate).unwrap_or_else(|| predicate.clone()))
+            .subtitle(&predicate)
+            .build();
+        let remove = gtk::Button::from_icon_name("user-trash-symbolic");
+        remove.set_tooltip_text(Some("Show Again"));
+        remove.set_valign(gtk::Align::Center);
+        remove.style_context().add_class("flat");
+        let group_clone = group.clone();
+        let rows_clone = rows.clone();
+        remove.connect_clicked(move |_| {
+            let mut hidden = settings::preference_list(settings::HIDDEN_PREDICATES);
+            hidden.retain(|p| *p != predicate);
+            settings::set_preference(settings::HIDDEN_PREDICATES, hidden);
+            // Rebuilding removes this button's row, so it happens once the handler is done.
+            let group = group_clone.clone();
+            let rows = rows_clone.clone();
+            glib::idle_add_local_once(move || show_hidden(&group, &rows));
+        });
+        row.add_suffix(&remove);
+        group.add(&row);
+        rows.borrow_mut().push(row);
+    }
+}
+
+/// Reads a predicate given as a prefixed name such as `nfo:fileName` (see `prefixes::expand`)
+/// or as a full IRI.
+///
+/// # Returns
+/// * The IRI of the predicate, or `None` if `text` is neither.
+fn parse_predicate(text: &str) -> Option<String> {
+    let text = text.trim();
+    prefixes::expand(text).or_else(|| looks_like_uri(text).then(|| text.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn predicates_by_prefixed_name_or_iri() {
+        assert_eq!(
+            parse_predicate(" nfo:fileName ").as_deref(),
+            Some("http://tracker.api.gnome.org/ontology/v3/nfo#fileName")
+        );
+        assert_eq!(
+            parse_predicate("http://example.org/p").as_deref(),
+            Some("http://example.org/p")
+        );
+        assert_eq!(parse_predicate("file name"), None);
+    }
+}
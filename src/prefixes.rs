@@ -0,0 +1,144 @@
+use crate::rdf;
+use crate::store;
+use std::cell::RefCell;
+use tracker::prelude::*;
+
+const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+const NRL_NAMESPACE: &str = "http://tracker.api.gnome.org/ontology/v3/nrl#Namespace";
+const NRL_PREFIX: &str = "http://tracker.api.gnome.org/ontology/v3/nrl#prefix";
+
+thread_local! {
+    /// The `(prefix, namespace)` pairs defined in the store, once they have been loaded.
+    static LOADED: RefCell<Option<Vec<(String, String)>>> = const { RefCell::new(None) };
+}
+
+/// Returns a query for the namespace prefixes defined in the store, as `?namespace ?prefix`
+/// rows.
+pub fn prefixes_query() -> String {
+    format!(
+        "SELECT ?namespace ?prefix WHERE {{ ?namespace <{RDF_TYPE}> <{NRL_NAMESPACE}> ; <{NRL_PREFIX}> ?prefix }}"
+    )
+}
+
+/// Loads the namespace prefixes defined in the store, unless that has been done already.
+///
+/// If the store cannot be queried, only the well-known prefixes (see `rdf::PREFIXES`) are used,
+/// and loading is tried again next time.
+pub async fn load() {
+    if LOADED.with(|loaded| loaded.borrow().is_some()) {
+        return;
+    }
+    let Ok(conn) = store::client() else {
+        return;
+    };
+    let Ok(cursor) = conn.query_async(&prefixes_query()).await else {
+        return;
+    };
+    let mut prefixes = Vec::new();
+    while cursor.next_future().await.unwrap_or(false) {
+        let namespace = cursor.string(0).unwrap_or_default().to_string();
+        let prefix = cursor.string(1).unwrap_or_default().to_string();
+        if !namespace.is_empty() && !prefix.is_empty() {
+            prefixes.push((prefix, namespace));
+        }
+    }
+    LOADED.with(|loaded| loaded.replace(Some(prefixes)));
+}
+
+/// Like [`load`], but blocks until the prefixes have been loaded.
+pub fn load_blocking() {
+    glib::MainContext::default().block_on(load());
+}
+
+/// Abbreviates `iri` as `prefix:localName`, e.g. `nfo:fileName`, using the prefixes defined in
+/// the store (see [`load`]) or else the well-known ones.
+///
+/// # Returns
+/// * The abbreviated IRI, or `None` if no prefix applies.
+pub fn compact(iri: &str) -> Option<String> {
+    let loaded = LOADED.with(|loaded| compact_with(iri, loaded.borrow().as_deref().unwrap_or(&[])));
+    loaded.or_else(|| rdf::compact(iri).map(|(prefix, local)| format!("{prefix}:{local}")))
+}
+
+/// Expands a prefixed name such as `nfo:fileName` into the full IRI, using the prefixes defined
+/// in the store (see [`load`]) or else the well-known ones.
+///
+/// # Returns
+/// * The full IRI, or `None` if `name` is not a prefixed name with a known prefix.
+pub fn expand(name: &str) -> Option<String> {
+    let (prefix, local) = name.split_once(':')?;
+    if !rdf::is_local_name(local) {
+        return None;
+    }
+    let namespace = LOADED
+        .with(|loaded| {
+            loaded
+                .borrow()
+                .iter()
+                .flatten()
+                .find(|(p, _)| p == prefix)
+                .map(|(_, namespace)| namespace.clone())
+        })
+        .or_else(|| {
+            rdf::PREFIXES
+                .iter()
+                .find(|(p, _)| *p == prefix)
+                .map(|(_, namespace)| namespace.to_string())
+        })?;
+    Some(format!("{namespace}{local}"))
+}
+
+/// Abbreviates `iri` with the longest matching namespace among `prefixes`, given as
+/// `(prefix, namespace)` pairs.
+fn compact_with(iri: &str, prefixes: &[(String, String)]) -> Option<String> {
+    prefixes
+        .iter()
+        .filter_map(|(prefix, namespace)| {
+            iri.strip_prefix(namespace.as_str())
+                .filter(|local| rdf::is_local_name(local))
+                .map(|local| (namespace.len(), format!("{prefix}:{local}")))
+        })
+        .max_by_key(|(len, _)| *len)
+        .map(|(_, compacted)| compacted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compact_with_prefers_longest_namespace() {
+        let prefixes = vec![
+            ("ex".to_string(), "http://example.org/".to_string()),
+            ("exv".to_string(), "http://example.org/vocab#".to_string()),
+        ];
+        assert_eq!(
+            compact_with("http://example.org/vocab#size", &prefixes).as_deref(),
+            Some("exv:size")
+        );
+        assert_eq!(
+            compact_with("http://example.org/thing", &prefixes).as_deref(),
+            Some("ex:thing")
+        );
+        // Local names with characters Turtle does not allow stay unabbreviated.
+        assert_eq!(compact_with("http://example.org/a/b", &prefixes), None);
+    }
+
+    #[test]
+    fn expand_uses_well_known_prefixes() {
+        assert_eq!(
+            expand("nie:mimeType").as_deref(),
+            Some("http://tracker.api.gnome.org/ontology/v3/nie#mimeType")
+        );
+        assert_eq!(expand("unknown:thing"), None);
+        assert_eq!(expand("file:///tmp/a"), None);
+    }
+
+    #[test]
+    fn compact_falls_back_to_well_known_prefixes() {
+        assert_eq!(
+            compact("http://tracker.api.gnome.org/ontology/v3/nfo#fileName").as_deref(),
+            Some("nfo:fileName")
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+
+/// Number of subjects in one batch that are opened without asking first.
+pub const CONFIRM_THRESHOLD: usize = 10;
+
+/// Subjects waiting to be opened in windows.
+///
+/// Requests to open subjects (from a file manager's selection or over D-Bus) are queued here and
+/// opened one at a time, so that hundreds of requests arriving at once do not spawn as many
+/// windows and store connections simultaneously. All subjects queued while the queue is being
+/// drained form one batch; a large batch has to be confirmed before it is opened.
+#[derive(Debug, Default)]
+pub struct OpenQueue {
+    pending: RefCell<VecDeque<String>>,
+    /// Number of subjects queued since the queue was last empty.
+    batch: Cell<usize>,
+    /// Whether the queue is being drained (or waiting for confirmation).
+    draining: Cell<bool>,
+    /// Whether the user agreed to open the current batch.
+    confirmed: Cell<bool>,
+}
+
+impl OpenQueue {
+    /// Adds subjects to the end of the queue.
+    pub fn push(&self, uris: impl IntoIterator<Item = String>) {
+        let mut pending = self.pending.borrow_mut();
+        let before = pending.len();
+        pending.extend(uris);
+        self.batch.set(self.batch.get() + pending.len() - before);
+    }
+
+    /// Marks the queue as being drained.
+    ///
+    /// # Returns
+    /// * `true` if the caller should start draining, or `false` if that is already happening.
+    pub fn start(&self) -> bool {
+        !self.draining.replace(true)
+    }
+
+    /// Takes the next subject to open. Once the queue is empty, the batch is over and draining
+    /// stops.
+    pub fn pop(&self) -> Option<String> {
+        let next = self.pending.borrow_mut().pop_front();
+        if next.is_none() {
+            self.clear();
+        }
+        next
+    }
+
+    /// Drops all pending subjects and ends the batch.
+    pub fn clear(&self) {
+        self.pending.borrow_mut().clear();
+        self.batch.set(0);
+        self.draining.set(false);
+        self.confirmed.set(false);
+    }
+
+    /// Whether the current batch is too large to open without asking.
+    pub fn needs_confirmation(&self) -> bool {
+        !self.confirmed.get() && self.batch.get() > CONFIRM_THRESHOLD
+    }
+
+    /// Records that the user agreed to open the current batch, including subjects added to it
+    /// later.
+    pub fn confirm(&self) {
+        self.confirmed.set(true);
+    }
+
+    /// Whether the current batch has been confirmed.
+    pub fn confirmed(&self) -> bool {
+        self.confirmed.get()
+    }
+
+    /// Returns the number of subjects of the current batch opened so far, and the size of the
+    /// batch.
+    pub fn progress(&self) -> (usize, usize) {
+        let total = self.batch.get();
+        (total - self.pending.borrow().len(), total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uris(n: usize) -> Vec<String> {
+        (0..n).map(|i| format!("file:///{i}")).collect()
+    }
+
+    #[test]
+    fn large_batch_needs_confirmation_once() {
+        let queue = OpenQueue::default();
+        queue.push(uris(CONFIRM_THRESHOLD));
+        assert!(queue.start());
+        assert!(!queue.start());
+        assert!(!queue.needs_confirmation());
+        queue.push(uris(1));
+        assert!(queue.needs_confirmation());
+        queue.confirm();
+        queue.push(uris(5));
+        assert!(!queue.needs_confirmation());
+        assert_eq!(queue.pop().as_deref(), Some("file:///0"));
+        assert_eq!(queue.progress(), (1, CONFIRM_THRESHOLD + 6));
+    }
+
+    #[test]
+    fn empty_queue_ends_batch() {
+        let queue = OpenQueue::default();
+        queue.push(uris(CONFIRM_THRESHOLD + 1));
+        queue.start();
+        queue.confirm();
+        while queue.pop().is_some() {}
+        assert!(!queue.confirmed());
+        assert_eq!(queue.progress(), (0, 0));
+        assert!(queue.start());
+    }
+}
@@ -0,0 +1,264 @@
+/// Well-known namespace prefixes, used to abbreviate IRIs.
+pub const PREFIXES: &[(&str, &str)] = &[
+    ("rdf", "http://www.w3.org/1999/02/22-rdf-syntax-ns#"),
+    ("rdfs", "http://www.w3.org/2000/01/rdf-schema#"),
+    ("xsd", "http://www.w3.org/2001/XMLSchema#"),
+    ("dc", "http://purl.org/dc/elements/1.1/"),
+    ("nrl", "http://tracker.api.gnome.org/ontology/v3/nrl#"),
+    ("nie", "http://tracker.api.gnome.org/ontology/v3/nie#"),
+    ("nfo", "http://tracker.api.gnome.org/ontology/v3/nfo#"),
+    ("nco", "http://tracker.api.gnome.org/ontology/v3/nco#"),
+    ("nmm", "http://tracker.api.gnome.org/ontology/v3/nmm#"),
+    ("nao", "http://tracker.api.gnome.org/ontology/v3/nao#"),
+    ("slo", "http://tracker.api.gnome.org/ontology/v3/slo#"),
+    ("osinfo", "http://tracker.api.gnome.org/ontology/v3/osinfo#"),
+    ("mfo", "http://tracker.api.gnome.org/ontology/v3/mfo#"),
+    (
+        "tracker",
+        "http://tracker.api.gnome.org/ontology/v3/tracker#",
+    ),
+];
+
+const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+const XSD_STRING: &str = "http://www.w3.org/2001/XMLSchema#string";
+const XSD_INTEGER: &str = "http://www.w3.org/2001/XMLSchema#integer";
+const XSD_BOOLEAN: &str = "http://www.w3.org/2001/XMLSchema#boolean";
+const RDF_LANG_STRING: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#langString";
+
+/// The object of an RDF statement.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Object {
+    /// A reference to another node.
+    Iri(String),
+    /// A literal value with its datatype IRI and language tag (either may be empty).
+    Literal {
+        value: String,
+        datatype: String,
+        lang: String,
+    },
+}
+
+/// A single RDF statement, as shown in one row of a subject window.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Triple {
+    pub subject: String,
+    pub predicate: String,
+    pub object: Object,
+}
+
+impl Triple {
+    /// Serializes the statement as one line of N-Triples (without a trailing newline).
+    pub fn to_ntriples(&self) -> String {
+        let object = match &self.object {
+            Object::Iri(iri) => iri_ref(iri),
+            Object::Literal {
+                value,
+                datatype,
+                lang,
+            } => {
+                let quoted = format!("\"{}\"", escape_string(value));
+                if !lang.is_empty() {
+                    format!("{quoted}@{lang}")
+                } else if datatype.is_empty() || datatype == XSD_STRING {
+                    quoted
+                } else {
+                    format!("{quoted}^^{}", iri_ref(datatype))
+                }
+            }
+        };
+        format!(
+            "{} {} {} .",
+            iri_ref(&self.subject),
+            iri_ref(&self.predicate),
+            object
+        )
+    }
+
+    /// Serializes the statement as a Turtle document.
+    ///
+    /// Unlike N-Triples, IRIs in well-known namespaces are abbreviated (with the required
+    /// `@prefix` declarations emitted first), `rdf:type` is written as `a`, and integer and
+    /// boolean literals use their bare forms.
+    pub fn to_turtle(&self) -> String {
+        let mut used = Vec::new();
+        let subject = turtle_iri(&self.subject, &mut used);
+        let predicate = if self.predicate == RDF_TYPE {
+            "a".to_string()
+        } else {
+            turtle_iri(&self.predicate, &mut used)
+        };
+        let object = match &self.object {
+            Object::Iri(iri) => turtle_iri(iri, &mut used),
+            Object::Literal {
+                value,
+                datatype,
+                lang,
+            } => {
+                let quoted = format!("\"{}\"", escape_string(value));
+                if !lang.is_empty() {
+                    format!("{quoted}@{lang}")
+                } else if (datatype == XSD_INTEGER && is_integer(value))
+                    || (datatype == XSD_BOOLEAN && (value == "true" || value == "false"))
+                {
+                    value.clone()
+                } else if datatype.is_empty()
+                    || datatype == XSD_STRING
+                    || datatype == RDF_LANG_STRING
+                {
+                    quoted
+                } else {
+                    format!("{quoted}^^{}", turtle_iri(datatype, &mut used))
+                }
+            }
+        };
+
+        let mut doc = String::new();
+        for (prefix, namespace) in PREFIXES.iter().filter(|(p, _)| used.contains(p)) {
+            doc.push_str(&format!("@prefix {prefix}: <{namespace}> .\n"));
+        }
+        if !doc.is_empty() {
+            doc.push('\n');
+        }
+        doc.push_str(&format!("{subject} {predicate} {object} ."));
+        doc
+    }
+}
+
+/// Splits an IRI into a well-known prefix and local name, if it belongs to one of the
+/// namespaces in [`PREFIXES`].
+pub fn compact(iri: &str) -> Option<(&'static str, &str)> {
+    PREFIXES.iter().find_map(|(prefix, namespace)| {
+        iri.strip_prefix(namespace)
+            .filter(|local| is_local_name(local))
+            .map(|local| (*prefix, local))
+    })
+}
+
+/// Writes an IRI for Turtle, abbreviating it if possible and recording the prefix used.
+fn turtle_iri(iri: &str, used: &mut Vec<&'static str>) -> String {
+    match compact(iri) {
+        Some((prefix, local)) => {
+            if !used.contains(&prefix) {
+                used.push(prefix);
+            }
+            format!("{prefix}:{local}")
+        }
+        None => iri_ref(iri),
+    }
+}
+
+/// Writes an IRI in angle brackets, escaping characters that are not allowed in an IRIREF (in
+/// N-Triples, Turtle and SPARQL alike).
+pub fn iri_ref(iri: &str) -> String {
+    let mut out = String::from("<");
+    for c in iri.chars() {
+        match c {
+            '\u{0}'..='\u{20}' | '<' | '>' | '"' | '{' | '}' | '|' | '^' | '`' | '\\' => {
+                out.push_str(&format!("\\u{:04X}", c as u32))
+            }
+            _ => out.push(c),
+        }
+    }
+    out.push('>');
+    out
+}
+
+/// Escapes a string for use inside a double-quoted N-Triples, Turtle or SPARQL literal.
+pub fn escape_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Whether `s` can be written as a Turtle prefixed-name local part without escaping.
+pub fn is_local_name(s: &str) -> bool {
+    !s.is_empty()
+        && !s.ends_with('.')
+        && s.chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '.')
+}
+
+/// Whether `s` is a valid bare Turtle integer.
+fn is_integer(s: &str) -> bool {
+    let digits = s.strip_prefix(['+', '-']).unwrap_or(s);
+    !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triple(object: Object) -> Triple {
+        Triple {
+            subject: "file:///tmp/a%20b.txt".to_string(),
+            predicate: "http://tracker.api.gnome.org/ontology/v3/nfo#fileName".to_string(),
+            object,
+        }
+    }
+
+    #[test]
+    fn ntriples_typed_literal() {
+        let t = triple(Object::Literal {
+            value: "42".to_string(),
+            datatype: XSD_INTEGER.to_string(),
+            lang: String::new(),
+        });
+        assert_eq!(
+            t.to_ntriples(),
+            "<file:///tmp/a%20b.txt> <http://tracker.api.gnome.org/ontology/v3/nfo#fileName> \
+             \"42\"^^<http://www.w3.org/2001/XMLSchema#integer> ."
+        );
+    }
+
+    #[test]
+    fn ntriples_lang_literal_escapes() {
+        let t = triple(Object::Literal {
+            value: "a \"b\"\nc".to_string(),
+            datatype: RDF_LANG_STRING.to_string(),
+            lang: "de".to_string(),
+        });
+        assert!(t.to_ntriples().ends_with(" \"a \\\"b\\\"\\nc\"@de ."));
+    }
+
+    #[test]
+    fn turtle_uses_prefixes() {
+        let t = Triple {
+            subject: "urn:uuid:1".to_string(),
+            predicate: RDF_TYPE.to_string(),
+            object: Object::Iri("http://tracker.api.gnome.org/ontology/v3/nfo#Image".to_string()),
+        };
+        assert_eq!(
+            t.to_turtle(),
+            "@prefix nfo: <http://tracker.api.gnome.org/ontology/v3/nfo#> .\n\n\
+             <urn:uuid:1> a nfo:Image ."
+        );
+    }
+
+    #[test]
+    fn turtle_bare_integer() {
+        let t = triple(Object::Literal {
+            value: "-7".to_string(),
+            datatype: XSD_INTEGER.to_string(),
+            lang: String::new(),
+        });
+        assert!(t.to_turtle().ends_with("nfo:fileName -7 ."));
+    }
+
+    #[test]
+    fn compact_known_namespace() {
+        assert_eq!(
+            compact("http://tracker.api.gnome.org/ontology/v3/nie#mimeType"),
+            Some(("nie", "mimeType"))
+        );
+        assert_eq!(compact("http://example.com/x"), None);
+    }
+}
@@ -0,0 +1,79 @@
+use crate::context::Context;
+use crate::settings::{self, Settings};
+use crate::tab_title;
+use adw::prelude::*;
+
+/// How many of the recently inspected subjects the "Recent" menu lists.
+const MENU_ITEMS: usize = 10;
+
+/// Records `uri` as the subject inspected most recently, for the "Recent" menu and the recent
+/// start page (see `start::StartPage::Recent`).
+///
+/// # Arguments
+/// * `ctx` - Shared application settings, whose menu of recent subjects is updated.
+/// * `uri` - The subject just shown.
+pub fn record(ctx: &Context, uri: &str) {
+    let settings = Settings::load();
+    settings.push_recent(settings::RECENT_SUBJECTS, uri);
+    fill_menu(
+        &ctx.recent_menu,
+        &settings.string_list(settings::RECENT_SUBJECTS),
+    );
+}
+
+/// Forgets the recently inspected subjects.
+pub fn clear(ctx: &Context) {
+    Settings::load().set_string(settings::RECENT_SUBJECTS, "");
+    fill_menu(&ctx.recent_menu, &[]);
+}
+
+/// Returns the menu of recently inspected subjects, filled from the user's preferences if this is
+/// the first time it is asked for. All windows share it, so that it is kept up to date in each.
+pub fn menu(ctx: &Context) -> gio::Menu {
+    if ctx.recent_menu.n_items() == 0 {
+        fill_menu(
+            &ctx.recent_menu,
+            &Settings::load().string_list(settings::RECENT_SUBJECTS),
+        );
+    }
+    ctx.recent_menu.clone()
+}
+
+/// Replaces the items of `menu` with one for each of the first `MENU_ITEMS` of `recent`, which
+/// opens the subject in a new tab, followed by one for clearing the list.
+fn fill_menu(menu: &gio::Menu, recent: &[String]) {
+    menu.remove_all();
+    let subjects = gio::Menu::new();
+    for uri in recent.iter().take(MENU_ITEMS) {
+        let item = gio::MenuItem::new(Some(&tab_title(uri)), None);
+        item.set_action_and_target_value(Some("win.open-in-new-tab"), Some(&uri.to_variant()));
+        subjects.append_item(&item);
+    }
+    menu.append_section(None, &subjects);
+    let clear = gio::Menu::new();
+    clear.append(Some("Clear List"), Some("app.clear-recent"));
+    menu.append_section(None, &clear);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn menu_lists_the_most_recent_subjects() {
+        let recent: Vec<String> = (0..MENU_ITEMS + 5)
+            .map(|i| format!("file:///{i}.txt"))
+            .collect();
+        let menu = gio::Menu::new();
+        fill_menu(&menu, &recent);
+        let subjects = menu
+            .item_link(0, gio::MENU_LINK_SECTION)
+            .expect("a section of subjects");
+        assert_eq!(subjects.n_items(), MENU_ITEMS as i32);
+        let label = subjects.item_attribute_value(0, gio::MENU_ATTRIBUTE_LABEL, None);
+        assert_eq!(
+            label.and_then(|l| l.get::<String>()).as_deref(),
+            Some("0.txt")
+        );
+    }
+}
@@ -0,0 +1,72 @@
+/// Registry of the subjects that are currently shown, and where they are shown.
+///
+/// Each place (e.g. a tab) shows at most one subject at a time, so registering a place under a
+/// new URI replaces its previous entry.
+#[derive(Debug)]
+pub struct Registry<T> {
+    entries: Vec<(String, T)>,
+}
+
+impl<T> Default for Registry<T> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<T: Clone + PartialEq> Registry<T> {
+    /// Records that `place` now shows the subject `uri`.
+    pub fn register(&mut self, uri: &str, place: &T) {
+        self.unregister(place);
+        self.entries.push((uri.to_string(), place.clone()));
+    }
+
+    /// Forgets `place`, e.g. because it was closed.
+    pub fn unregister(&mut self, place: &T) {
+        self.entries.retain(|(_, p)| p != place);
+    }
+
+    /// Returns the URIs of all subjects shown somewhere, without duplicates.
+    pub fn uris(&self) -> Vec<String> {
+        let mut uris: Vec<String> = Vec::new();
+        for (uri, _) in &self.entries {
+            if !uris.contains(uri) {
+                uris.push(uri.clone());
+            }
+        }
+        uris
+    }
+
+    /// Returns a place showing the subject `uri`, if there is one. If several places show it,
+    /// the most recently registered one is returned.
+    pub fn find(&self, uri: &str) -> Option<T> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|(u, _)| u == uri)
+            .map(|(_, p)| p.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn register_and_find() {
+        let mut r = Registry::default();
+        r.register("a", &1);
+        r.register("b", &2);
+        assert_eq!(r.find("a"), Some(1));
+        assert_eq!(r.find("b"), Some(2));
+        assert_eq!(r.find("c"), None);
+        // Place 1 navigates to "b"; it no longer shows "a".
+        r.register("b", &1);
+        assert_eq!(r.find("a"), None);
+        assert_eq!(r.find("b"), Some(1));
+        assert_eq!(r.uris(), ["b"]);
+        r.unregister(&1);
+        assert_eq!(r.find("b"), Some(2));
+    }
+}
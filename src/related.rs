@@ -0,0 +1,172 @@
+use crate::announce;
+use crate::pages;
+use crate::rdf;
+use crate::store::{self, StoreClient};
+use crate::tags::{NAO_HAS_TAG, NAO_PREF_LABEL};
+use crate::trace;
+use crate::{LinkHandler, NIE_INTERPRETED_AS, NIE_IS_STORED_AS, NIE_TITLE, tab_title};
+use adw::prelude::*;
+use serde_json::json;
+use std::rc::Rc;
+use tracker::prelude::*;
+
+const NCO_CREATOR: &str = "http://tracker.api.gnome.org/ontology/v3/nco#creator";
+const NCO_FULLNAME: &str = "http://tracker.api.gnome.org/ontology/v3/nco#fullname";
+const NMM_MUSIC_ALBUM: &str = "http://tracker.api.gnome.org/ontology/v3/nmm#musicAlbum";
+
+/// The properties whose values are compared, along with how sharing one is described.
+const SHARED_PROPERTIES: &[(&str, &str)] = &[
+    (NAO_HAS_TAG, "Same tag"),
+    (NCO_CREATOR, "Same creator"),
+    (NMM_MUSIC_ALBUM, "Same album"),
+];
+
+/// Largest number of related items listed.
+const MAX_RELATED: usize = 200;
+
+/// Another subject that shares a value of one of the `SHARED_PROPERTIES` with the subject.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Relation {
+    /// The related subject: the file storing the related resource, if there is one.
+    pub related: String,
+    /// The property both have the value of.
+    pub predicate: String,
+    /// The label of the shared value (e.g. the name of a tag), or else its IRI.
+    pub value: String,
+}
+
+impl Relation {
+    /// Describes what the subjects have in common, e.g. "Same tag: Holidays".
+    pub fn reason(&self) -> String {
+        let reason = SHARED_PROPERTIES
+            .iter()
+            .find(|(predicate, _)| *predicate == self.predicate)
+            .map_or("Same value", |(_, reason)| reason);
+        format!("{reason}: {}", self.value)
+    }
+}
+
+/// Returns the query for the subjects sharing a value of one of the `SHARED_PROPERTIES` with
+/// `uri`, as `?related ?pred ?label` rows.
+pub fn related_query(uri: &str) -> String {
+    let subject = rdf::iri_ref(uri);
+    let predicates = SHARED_PROPERTIES
+        .iter()
+        .map(|(predicate, _)| format!("<{predicate}>"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        "SELECT DISTINCT ?related ?pred ?label WHERE {{ \
+         VALUES ?pred {{ {predicates} }} \
+         {{ BIND({subject} AS ?self) }} UNION {{ {subject} <{NIE_INTERPRETED_AS}> ?self }} \
+         ?self ?pred ?value . \
+         ?other ?pred ?value . \
+         OPTIONAL {{ ?other <{NIE_IS_STORED_AS}> ?file }} \
+         BIND(COALESCE(?file, ?other) AS ?related) \
+         FILTER(?other != ?self && ?related != {subject}) \
+         OPTIONAL {{ ?value <{NAO_PREF_LABEL}> ?tagLabel }} \
+         OPTIONAL {{ ?value <{NCO_FULLNAME}> ?name }} \
+         OPTIONAL {{ ?value <{NIE_TITLE}> ?title }} \
+         BIND(COALESCE(?tagLabel, ?name, ?title, STR(?value)) AS ?label) \
+         }} ORDER BY ?pred ?label ?related LIMIT {MAX_RELATED}"
+    )
+}
+
+/// Queries the subjects sharing a tag, creator or album with `uri`, or with the resource it is
+/// interpreted as (so that a music file finds the other pieces of its album).
+///
+/// The tags added in the application are kept apart from the miner's data (see
+/// `store::annotations`), so they are compared there as well.
+pub async fn related(uri: &str) -> Result<Vec<Relation>, glib::Error> {
+    let conn = store::client()?;
+    let mut relations = read_relations(conn.as_ref(), uri).await?;
+    // Without a separate store of annotations, e.g. with `--fixture`, it is the same store.
+    if let Some(annotations) = store::annotations().ok().filter(|a| !Rc::ptr_eq(a, &conn)) {
+        // Tags that cannot be compared leave the relations found in the miner's data.
+        relations.extend(
+            read_relations(annotations.as_ref(), uri)
+                .await
+                .unwrap_or_default(),
+        );
+        relations.sort_by(|a, b| {
+            (&a.predicate, &a.value, &a.related).cmp(&(&b.predicate, &b.value, &b.related))
+        });
+        relations.dedup();
+        relations.truncate(MAX_RELATED);
+    }
+    Ok(relations)
+}
+
+/// Runs [`related_query`] on `conn`.
+async fn read_relations(conn: &dyn StoreClient, uri: &str) -> Result<Vec<Relation>, glib::Error> {
+    let cursor = conn.query_async(&related_query(uri)).await?;
+    let mut relations = Vec::new();
+    while cursor.next_future().await? {
+        relations.push(Relation {
+            related: cursor.string(0).unwrap_or_default().to_string(),
+            predicate: cursor.string(1).unwrap_or_default().to_string(),
+            value: cursor.string(2).unwrap_or_default().to_string(),
+        });
+    }
+    Ok(relations)
+}
+
+/// Lists the subjects related to `uri` in `list`, replacing those shown before, each with what
+/// it has in common with the subject.
+///
+/// A failed query is reported in the window's banner, offering to refresh the tab.
+///
+/// # Arguments
+/// * `list` - The list of the related page of a subject tab.
+/// * `uri` - The URI of the subject.
+/// * `on_link` - Called with the URI of a related subject that is activated.
+pub async fn load(list: &gtk::ListBox, uri: &str, on_link: &LinkHandler) {
+    pages::clear_list(list);
+    let relations = match related(uri).await {
+        Ok(relations) => relations,
+        Err(err) => {
+            trace::event(
+                "query_failed",
+                json!({ "query": "related", "uri": uri, "error": err.to_string() }),
+            );
+            announce::report_problem(
+                list,
+                "The related items could not be loaded.",
+                Some(("win.refresh", None)),
+            );
+            return;
+        }
+    };
+    for relation in relations {
+        let row = adw::ActionRow::builder()
+            .title(glib::markup_escape_text(&tab_title(&relation.related)))
+            .subtitle(glib::markup_escape_text(&relation.reason()))
+            .tooltip_text(&relation.related)
+            .activatable(true)
+            .build();
+        row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+        let on_link = on_link.clone();
+        row.connect_activated(move |_| on_link(&relation.related));
+        list.append(&row);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relations_say_what_is_shared() {
+        let relation = Relation {
+            related: "file:///music/02.ogg".to_string(),
+            predicate: NMM_MUSIC_ALBUM.to_string(),
+            value: "Blue".to_string(),
+        };
+        assert_eq!(relation.reason(), "Same album: Blue");
+        let relation = Relation {
+            predicate: "urn:other".to_string(),
+            ..relation
+        };
+        assert_eq!(relation.reason(), "Same value: Blue");
+    }
+}
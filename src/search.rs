@@ -0,0 +1,221 @@
+use crate::memory;
+use crate::rdf;
+use crate::store;
+use adw::prelude::*;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use tracker::prelude::*;
+
+pub const FTS_MATCH: &str = "http://tracker.api.gnome.org/ontology/v3/fts#match";
+const NIE_IS_STORED_AS: &str = "http://tracker.api.gnome.org/ontology/v3/nie#isStoredAs";
+const NIE_URL: &str = "http://tracker.api.gnome.org/ontology/v3/nie#url";
+const NFO_FILE_NAME: &str = "http://tracker.api.gnome.org/ontology/v3/nfo#fileName";
+
+/// Largest number of matches listed below the search entry.
+const MAX_MATCHES: usize = 20;
+
+/// Returns a query for indexed files matching `text`, as `?url ?name` rows ordered by name.
+///
+/// A file matches if the full-text index of its content holds `text` as a phrase (its last word
+/// as a prefix, so that matches show up while typing), or if its name contains `text`.
+pub fn search_query(text: &str) -> String {
+    let text = text.trim();
+    let phrase = fts_phrase(text);
+    format!(
+        "SELECT DISTINCT ?url ?name WHERE {{ \
+         {{ ?ie <{FTS_MATCH}> \"{phrase}\" . ?ie <{NIE_IS_STORED_AS}> ?file }} \
+         UNION {{ ?file <{NFO_FILE_NAME}> ?n . FILTER (CONTAINS(LCASE(?n), \"{lowered}\")) }} \
+         ?file <{NIE_URL}> ?url ; <{NFO_FILE_NAME}> ?name \
+         }} ORDER BY ?name LIMIT {limit}",
+        limit = memory::limit(MAX_MATCHES),
+        phrase = rdf::escape_string(&phrase),
+        lowered = rdf::escape_string(&text.to_lowercase()),
+    )
+}
+
+/// Quotes `text` as a full-text search phrase whose last word may be a prefix, so that
+/// characters with a meaning in the full-text query syntax (e.g. `-` or `:`) are searched for
+/// literally. The result still has to be escaped for a SPARQL literal.
+pub fn fts_phrase(text: &str) -> String {
+    format!("\"{}\"*", text.trim().replace('"', "\"\""))
+}
+
+/// Queries the store for the files matching `text` (see [`search_query`]).
+///
+/// # Returns
+/// The URL and file name of each match.
+async fn find_files(text: &str) -> Result<Vec<(String, String)>, glib::Error> {
+    let conn = store::client()?;
+    let cursor = conn.query_async(&search_query(text)).await?;
+    let mut matches = Vec::new();
+    while cursor.next_future().await? {
+        let url = cursor.string(0).unwrap_or_default().to_string();
+        let name = cursor.string(1).unwrap_or_default().to_string();
+        matches.push((url, name));
+    }
+    Ok(matches)
+}
+
+/// Builds a search entry for the header bar of a window, which lists the indexed files matching
+/// the entered text in a dropdown.
+///
+/// Activating a match (or the entry itself, for the first match) calls `on_select` with the
+/// URL of the file and clears the entry.
+///
+/// # Arguments
+/// * `on_select` - Called with the URL of the chosen file, e.g. to open it in a new tab.
+pub fn entry(on_select: impl Fn(String) + 'static) -> gtk::SearchEntry {
+    let entry = gtk::SearchEntry::builder()
+        .placeholder_text("Search Files")
+        .width_chars(18)
+        .build();
+
+    let list = gtk::ListBox::builder()
+        .selection_mode(gtk::SelectionMode::None)
+        .activate_on_single_click(true)
+        .build();
+    let scroll = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .propagate_natural_height(true)
+        .max_content_height(360)
+        .min_content_width(320)
+        .child(&list)
+        .build();
+    // The dropdown does not hide itself on outside clicks, since that would take the keyboard
+    // focus away from the entry while typing.
+    let popover = gtk::Popover::builder()
+        .child(&scroll)
+        .autohide(false)
+        .has_arrow(false)
+        .position(gtk::PositionType::Bottom)
+        .build();
+    popover.set_parent(&entry);
+    let popover_clone = popover.clone();
+    entry.connect_destroy(move |_| popover_clone.unparent());
+
+    // The URLs of the listed matches, in the order of the rows.
+    let urls: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+    let on_select: Rc<dyn Fn(String)> = Rc::new(on_select);
+    let select = {
+        let entry = entry.clone();
+        let popover = popover.clone();
+        let urls = urls.clone();
+        move |index: usize| {
+            let Some(url) = urls.borrow().get(index).cloned() else {
+                return;
+            };
+            popover.popdown();
+            entry.set_text("");
+            on_select(url);
+        }
+    };
+    let select = Rc::new(select);
+
+    let select_clone = select.clone();
+    list.connect_row_activated(move |_, row| select_clone(row.index() as usize));
+    entry.connect_activate(move |_| select(0));
+    let popover_clone = popover.clone();
+    entry.connect_stop_search(move |_| popover_clone.popdown());
+
+    // Each change of the text starts a new query; results of queries that have been superseded
+    // in the meantime are dropped.
+    let generation = Rc::new(Cell::new(0u32));
+    entry.connect_search_changed(move |entry| {
+        generation.set(generation.get().wrapping_add(1));
+        let current = generation.get();
+        let text = entry.text().trim().to_string();
+        if text.is_empty() {
+            urls.borrow_mut().clear();
+            popover.popdown();
+            return;
+        }
+        let generation = generation.clone();
+        let list = list.clone();
+        let popover = popover.clone();
+        let urls = urls.clone();
+        glib::MainContext::default().spawn_local(async move {
+            let result = find_files(&text).await;
+            if generation.get() != current {
+                return;
+            }
+            while let Some(child) = list.first_child() {
+                list.remove(&child);
+            }
+            urls.borrow_mut().clear();
+            match result {
+                Ok(matches) if matches.is_empty() => list.append(&message_row("No matching files")),
+                Ok(matches) => {
+                    for (url, name) in matches {
+                        list.append(&match_row(&url, &name));
+                        urls.borrow_mut().push(url);
+                    }
+                }
+                Err(err) => list.append(&message_row(&format!("Search failed: {err}"))),
+            }
+            popover.popup();
+        });
+    });
+
+    entry
+}
+
+/// Builds the row listing one matching file: its name above its location.
+fn match_row(url: &str, name: &str) -> gtk::ListBoxRow {
+    let location = gio::File::for_uri(url)
+        .parent()
+        .and_then(|parent| parent.path())
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|| url.to_string());
+
+    let name_label = gtk::Label::new(Some(name));
+    name_label.set_halign(gtk::Align::Start);
+    name_label.set_ellipsize(gtk::pango::EllipsizeMode::End);
+    let location_label = gtk::Label::new(Some(&location));
+    location_label.set_halign(gtk::Align::Start);
+    location_label.set_ellipsize(gtk::pango::EllipsizeMode::Middle);
+    location_label.style_context().add_class("dim-label");
+
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 2);
+    content.set_margin_top(4);
+    content.set_margin_bottom(4);
+    content.append(&name_label);
+    content.append(&location_label);
+    let row = gtk::ListBoxRow::builder().child(&content).build();
+    row.set_tooltip_text(Some(url));
+    row
+}
+
+/// Builds a row that cannot be activated, e.g. saying that nothing matched.
+fn message_row(text: &str) -> gtk::ListBoxRow {
+    let label = gtk::Label::new(Some(text));
+    label.set_margin_top(4);
+    label.set_margin_bottom(4);
+    label.set_wrap(true);
+    label.style_context().add_class("dim-label");
+    gtk::ListBoxRow::builder()
+        .child(&label)
+        .activatable(false)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_query_quotes_text() {
+        let sparql = search_query(" Say \"Hi\" ");
+        // The FTS phrase doubles the quote, which is then escaped for the SPARQL literal.
+        assert!(
+            sparql.contains(&format!(
+                "<{FTS_MATCH}> \"\\\"Say \\\"\\\"Hi\\\"\\\"\\\"*\""
+            )),
+            "{sparql}"
+        );
+        assert!(
+            sparql.contains("CONTAINS(LCASE(?n), \"say \\\"hi\\\"\")"),
+            "{sparql}"
+        );
+        assert!(sparql.ends_with(&format!("LIMIT {MAX_MATCHES}")));
+    }
+}
@@ -0,0 +1,202 @@
+use crate::trace;
+use gio::prelude::*;
+use serde_json::json;
+use std::rc::Rc;
+
+/// Well-known bus name claimed in service mode. D-Bus activation starts the application under
+/// this name (see `resources/com.example.DesktopFileInformation.service`).
+pub const BUS_NAME: &str = "com.example.DesktopFileInformation";
+
+/// Object path at which the interface is exported.
+pub const OBJECT_PATH: &str = "/com/example/DesktopFileInformation";
+
+/// Name of the exported interface.
+pub const INTERFACE_NAME: &str = "com.example.DesktopFileInformation";
+
+/// Introspection data of the exported interface.
+const INTERFACE_XML: &str = r#"
+<node>
+  <interface name="com.example.DesktopFileInformation">
+    <method name="ShowInformation">
+      <arg type="s" name="uri" direction="in"/>
+    </method>
+  </interface>
+</node>
+"#;
+
+/// Well-known bus name of the file manager interface that desktop components call to show the
+/// properties of files.
+pub const FILE_MANAGER_BUS_NAME: &str = "org.freedesktop.FileManager1";
+
+/// Object path at which the file manager interface is exported.
+const FILE_MANAGER_PATH: &str = "/org/freedesktop/FileManager1";
+
+/// Introspection data of the file manager interface.
+const FILE_MANAGER_XML: &str = r#"
+<node>
+  <interface name="org.freedesktop.FileManager1">
+    <method name="ShowFolders">
+      <arg type="as" name="uris" direction="in"/>
+      <arg type="s" name="startup_id" direction="in"/>
+    </method>
+    <method name="ShowItems">
+      <arg type="as" name="uris" direction="in"/>
+      <arg type="s" name="startup_id" direction="in"/>
+    </method>
+    <method name="ShowItemProperties">
+      <arg type="as" name="uris" direction="in"/>
+      <arg type="s" name="startup_id" direction="in"/>
+    </method>
+  </interface>
+</node>
+"#;
+
+/// Claims `BUS_NAME` on the session bus and exports the service interface.
+///
+/// The interface has a single method, `ShowInformation(s uri)`, which passes the URI (or
+/// absolute path) to `on_show`. Calls are handled on the main context, so the callback may
+/// freely open windows.
+///
+/// # Arguments
+/// * `on_show` - Callback invoked with the URI of each requested subject.
+/// * `on_name_lost` - Callback invoked if the bus name could not be claimed or was lost later,
+///   e.g. because another instance already provides the service.
+///
+/// # Returns
+/// * The owner ID, which keeps the name claimed until it is passed to `gio::bus_unown_name`.
+pub fn export(on_show: Rc<dyn Fn(&str)>, on_name_lost: impl Fn() + 'static) -> gio::OwnerId {
+    gio::bus_own_name(
+        gio::BusType::Session,
+        BUS_NAME,
+        gio::BusNameOwnerFlags::NONE,
+        move |connection, _name| {
+            if let Err(err) = register(&connection, on_show.clone()) {
+                log::warn!("D-Bus service: failed to export {INTERFACE_NAME}: {err}");
+            }
+        },
+        |_connection, name| trace::event("bus_name_acquired", json!({ "name": name })),
+        move |_connection, name| {
+            log::warn!("D-Bus service: could not own {name}");
+            on_name_lost();
+        },
+    )
+}
+
+/// Registers the service object on `connection`.
+fn register(
+    connection: &gio::DBusConnection,
+    on_show: Rc<dyn Fn(&str)>,
+) -> Result<(), glib::Error> {
+    let node = gio::DBusNodeInfo::for_xml(INTERFACE_XML)?;
+    let interface = node
+        .lookup_interface(INTERFACE_NAME)
+        .expect("interface is declared in INTERFACE_XML");
+    connection
+        .register_object(OBJECT_PATH, &interface)
+        .method_call(
+            move |_conn, _sender, _path, _interface, method, params, invocation| {
+                let uri = params.get::<(String,)>().map(|(uri,)| uri);
+                match (method, uri) {
+                    ("ShowInformation", Some(uri)) => {
+                        on_show(&uri);
+                        invocation.return_value(None);
+                    }
+                    _ => invocation.return_dbus_error(
+                        "org.freedesktop.DBus.Error.UnknownMethod",
+                        &format!("Unknown method {method}"),
+                    ),
+                }
+            },
+        )
+        .build()?;
+    Ok(())
+}
+
+/// Claims `FILE_MANAGER_BUS_NAME` on the session bus and exports the file manager interface,
+/// so that desktop components asking the file manager to show the properties of files open
+/// them here instead.
+///
+/// Only `ShowItemProperties` is handled by this application: each URI is passed to `on_show`.
+/// `ShowFolders` and `ShowItems` are passed on to the default handler of the folders (or of
+/// the folders containing the items), which is the actual file manager. The name is not taken
+/// over from a file manager that already owns it.
+///
+/// # Arguments
+/// * `on_show` - Callback invoked with the URI of each item whose properties are requested.
+///
+/// # Returns
+/// * The owner ID, which keeps the name claimed until it is passed to `gio::bus_unown_name`.
+pub fn export_file_manager(on_show: Rc<dyn Fn(&str)>) -> gio::OwnerId {
+    gio::bus_own_name(
+        gio::BusType::Session,
+        FILE_MANAGER_BUS_NAME,
+        gio::BusNameOwnerFlags::NONE,
+        move |connection, _name| {
+            if let Err(err) = register_file_manager(&connection, on_show.clone()) {
+                log::warn!("D-Bus service: failed to export {FILE_MANAGER_BUS_NAME}: {err}");
+            }
+        },
+        |_connection, name| trace::event("bus_name_acquired", json!({ "name": name })),
+        |_connection, name| log::warn!("D-Bus service: could not own {name}"),
+    )
+}
+
+/// Registers the file manager object on `connection`.
+fn register_file_manager(
+    connection: &gio::DBusConnection,
+    on_show: Rc<dyn Fn(&str)>,
+) -> Result<(), glib::Error> {
+    let node = gio::DBusNodeInfo::for_xml(FILE_MANAGER_XML)?;
+    let interface = node
+        .lookup_interface(FILE_MANAGER_BUS_NAME)
+        .expect("interface is declared in FILE_MANAGER_XML");
+    connection
+        .register_object(FILE_MANAGER_PATH, &interface)
+        .method_call(
+            move |_conn, _sender, _path, _interface, method, params, invocation| {
+                // The startup ID is not needed, since windows are presented anyway.
+                let uris = params.get::<(Vec<String>, String)>().map(|(uris, _)| uris);
+                match (method, uris) {
+                    ("ShowItemProperties", Some(uris)) => {
+                        for uri in &uris {
+                            on_show(uri);
+                        }
+                        invocation.return_value(None);
+                    }
+                    ("ShowFolders", Some(uris)) => {
+                        show_folders(&uris);
+                        invocation.return_value(None);
+                    }
+                    ("ShowItems", Some(uris)) => {
+                        let folders: Vec<String> =
+                            uris.iter().filter_map(|uri| parent_uri(uri)).collect();
+                        show_folders(&folders);
+                        invocation.return_value(None);
+                    }
+                    _ => invocation.return_dbus_error(
+                        "org.freedesktop.DBus.Error.UnknownMethod",
+                        &format!("Unknown method {method}"),
+                    ),
+                }
+            },
+        )
+        .build()?;
+    Ok(())
+}
+
+/// Opens each of `uris` with its default handler, logging (but otherwise ignoring) failures.
+fn show_folders(uris: &[String]) {
+    for uri in uris {
+        if let Err(err) = gio::AppInfo::launch_default_for_uri(uri, None::<&gio::AppLaunchContext>)
+        {
+            log::warn!("D-Bus service: failed to show {uri}: {err}");
+        }
+    }
+}
+
+/// Returns the URI of the folder containing the file at `uri`, if it has one.
+fn parent_uri(uri: &str) -> Option<String> {
+    gio::File::for_uri(uri)
+        .parent()
+        .map(|parent| parent.uri().to_string())
+}
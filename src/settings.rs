@@ -0,0 +1,219 @@
+use gio::prelude::*;
+use std::path::PathBuf;
+
+/// Key file group holding all state.
+const GROUP: &str = "Preferences";
+
+/// The GSettings schema holding the preferences, installed as
+/// `/usr/share/glib-2.0/schemas/com.example.DesktopFileInformation.gschema.xml`.
+pub const SCHEMA_ID: &str = "com.example.DesktopFileInformation";
+
+// The keys of the preferences, in the schema `SCHEMA_ID`.
+
+/// Whether links are followed in the current window rather than in a new one.
+pub const SAME_WINDOW: &str = "open-links-in-same-window";
+
+/// Whether IRIs are shown abbreviated with namespace prefixes, e.g. `nfo:fileName`.
+pub const COMPACT_URIS: &str = "compact-uris";
+
+/// Whether technical rows such as `rdf:type` and `nie:dataSource` are shown.
+pub const SHOW_ADVANCED: &str = "show-advanced-properties";
+
+/// Whether the datatype of literals is shown in a column of its own.
+pub const SHOW_DATATYPES: &str = "show-datatypes";
+
+/// Whether the properties of the information element a file is interpreted as are shown
+/// along with the file's own.
+pub const MERGE_INTERPRETED: &str = "merge-interpreted";
+
+/// Whether dates are followed by how long ago they were, e.g. "(3 days ago)".
+pub const RELATIVE_DATES: &str = "relative-dates";
+
+/// The `strftime`-style pattern used to display date-times.
+pub const DATE_FORMAT: &str = "date-format";
+
+/// Whether date-times are shown in UTC rather than in the local time zone.
+pub const UTC_DATES: &str = "utc-dates";
+
+/// Predicates whose rows are never shown, as a list of IRIs.
+pub const HIDDEN_PREDICATES: &str = "hidden-predicates";
+
+/// The format the "Copy" button puts the table in (see `export::ExportFormat`).
+pub const EXPORT_FORMAT: &str = "export-format";
+
+/// How the rows of the property table are ordered (see `ordering::RowOrder`).
+pub const ROW_ORDER: &str = "row-order";
+
+/// What a launch without a file to inspect shows (see `start::StartPage`).
+pub const START_PAGE: &str = "start-page";
+
+/// Predicates listed at the top of every window, in the order they were marked as favorites.
+pub const FAVORITE_PREDICATES: &str = "favorite-predicates";
+
+/// The date-time pattern used when none has been configured, as in the schema.
+pub const DEFAULT_DATE_FORMAT: &str = "%F %T";
+
+// The keys of the state kept in the key file (see `Settings`).
+
+/// Locations recently opened through the "Open Location" dialog, most recent first.
+pub const RECENT_LOCATIONS: &str = "recent-locations";
+
+/// Bookmarked subjects, one URI per line, in the order they were bookmarked.
+pub const BOOKMARKS: &str = "bookmarks";
+
+/// Subjects recently shown in a subject window, most recent first (see `recent::record`).
+pub const RECENT_SUBJECTS: &str = "recent-subjects";
+
+/// Whether registering the application with the desktop has been offered (see
+/// `handlers::offer`).
+pub const HANDLERS_OFFERED: &str = "handlers-offered";
+
+/// The number of entries kept in lists of recent items.
+const MAX_RECENT: usize = 20;
+
+thread_local! {
+    /// The preferences, shared so that the handlers connected to their changes stay alive.
+    static PREFERENCES: gio::Settings =
+        gio::Settings::new_full(&schema(SCHEMA_ID), None::<&gio::SettingsBackend>, None);
+}
+
+/// Returns the user preferences, stored with GSettings in the schema `SCHEMA_ID`.
+pub fn preferences() -> gio::Settings {
+    PREFERENCES.with(Clone::clone)
+}
+
+/// Changes the preference `key`, logging (but otherwise ignoring) a failure, which happens when
+/// an administrator has locked the key.
+pub fn set_preference(key: &str, value: impl Into<glib::Variant>) {
+    if let Err(err) = preferences().set(key, value) {
+        log::warn!("Failed to change the preference {key}: {err}");
+    }
+}
+
+/// Returns the list preference `key`.
+pub fn preference_list(key: &str) -> Vec<String> {
+    preferences()
+        .strv(key)
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+/// Looks up the schema `id` among the installed ones, or else among those compiled along with
+/// the application (see `build.rs`), so that it also runs from the source tree.
+///
+/// # Panics
+/// If the schema is in neither place, since the application cannot run without its
+/// preferences.
+pub fn schema(id: &str) -> gio::SettingsSchema {
+    let installed = gio::SettingsSchemaSource::default();
+    installed
+        .as_ref()
+        .and_then(|source| source.lookup(id, true))
+        .or_else(|| {
+            gio::SettingsSchemaSource::from_directory(env!("SCHEMA_DIR"), installed.as_ref(), false)
+                .ok()?
+                .lookup(id, true)
+        })
+        .unwrap_or_else(|| panic!("The GSettings schema {id} is not installed"))
+}
+
+/// State kept between runs, such as recent locations and bookmarks, persisted as a key file in
+/// the user's configuration directory. Preferences are kept with GSettings instead (see
+/// [`preferences`]).
+///
+/// Missing or unreadable files are treated as empty, so every lookup takes a default value.
+pub struct Settings {
+    keyfile: glib::KeyFile,
+    path: PathBuf,
+}
+
+impl Settings {
+    /// Loads the state from `$XDG_CONFIG_HOME/file-information/settings.ini`.
+    pub fn load() -> Self {
+        let path = glib::user_config_dir()
+            .join("file-information")
+            .join("settings.ini");
+        let keyfile = glib::KeyFile::new();
+        // A missing file simply means that nothing has been kept yet.
+        let _ = keyfile.load_from_file(&path, glib::KeyFileFlags::KEEP_COMMENTS);
+        Self { keyfile, path }
+    }
+
+    /// Returns the boolean entry `key`, or `default` if it is unset or invalid.
+    pub fn boolean(&self, key: &str, default: bool) -> bool {
+        self.keyfile.boolean(GROUP, key).unwrap_or(default)
+    }
+
+    /// Returns the string entry `key`, or `None` if it is unset.
+    pub fn string(&self, key: &str) -> Option<String> {
+        self.keyfile.string(GROUP, key).ok().map(|s| s.to_string())
+    }
+
+    /// Returns the list entry `key`, which is empty if it is unset.
+    ///
+    /// Lists are stored as a single string with one item per line, since items such as URIs
+    /// may contain the key file's usual list separator.
+    pub fn string_list(&self, key: &str) -> Vec<String> {
+        self.string(key)
+            .map(|s| s.lines().map(str::to_string).collect())
+            .unwrap_or_default()
+    }
+
+    /// Moves `value` to the front of the list entry `key`, dropping the oldest items if
+    /// the list grows too long, and writes the state back to disk.
+    pub fn push_recent(&self, key: &str, value: &str) {
+        let list = with_recent(self.string_list(key), value);
+        self.set_string(key, &list.join("\n"));
+    }
+
+    /// Sets the boolean entry `key` and writes the state back to disk.
+    pub fn set_boolean(&self, key: &str, value: bool) {
+        self.keyfile.set_boolean(GROUP, key, value);
+        self.save();
+    }
+
+    /// Sets the string entry `key` and writes the state back to disk.
+    pub fn set_string(&self, key: &str, value: &str) {
+        self.keyfile.set_string(GROUP, key, value);
+        self.save();
+    }
+
+    /// Writes the state to disk, logging (but otherwise ignoring) any failure.
+    fn save(&self) {
+        if let Err(err) = self.path.parent().map_or(Ok(()), std::fs::create_dir_all) {
+            log::warn!("Failed to create the configuration directory: {err}");
+            return;
+        }
+        if let Err(err) = self.keyfile.save_to_file(&self.path) {
+            log::warn!("Failed to save {}: {err}", self.path.display());
+        }
+    }
+}
+
+/// Returns `list` with `value` moved (or added) to the front, limited to `MAX_RECENT` items.
+fn with_recent(mut list: Vec<String>, value: &str) -> Vec<String> {
+    list.retain(|item| item != value);
+    list.insert(0, value.to_string());
+    list.truncate(MAX_RECENT);
+    list
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn with_recent_moves_to_front() {
+        let list = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        assert_eq!(with_recent(list, "b"), ["b", "a", "c"]);
+    }
+
+    #[test]
+    fn with_recent_limits_length() {
+        let list = (0..MAX_RECENT).map(|i| i.to_string()).collect();
+        let list = with_recent(list, "new");
+        assert_eq!(list.len(), MAX_RECENT);
+        assert_eq!(list[0], "new");
+    }
+}
@@ -0,0 +1,143 @@
+use gio::prelude::*;
+use std::rc::Rc;
+
+/// Produces the body of a response for a request path, as `(content type, body)`, or `None` if
+/// nothing is served at that path.
+pub type Responder = dyn Fn(&str) -> Option<(&'static str, String)>;
+
+/// Starts a minimal HTTP server on a random loopback port.
+///
+/// The server only understands `GET` requests and answers every request with the content
+/// produced by `responder` before closing the connection. Connections are handled on the main
+/// context, so the responder may freely access GTK-side state. The server keeps running until
+/// the returned service is stopped.
+///
+/// # Arguments
+/// * `responder` - Callback producing the content for a request path.
+///
+/// # Returns
+/// * `Ok((service, port))` with the running socket service and the port it listens on.
+/// * `Err(glib::Error)` if no listening socket could be created.
+pub fn serve(responder: Rc<Responder>) -> Result<(gio::SocketService, u16), glib::Error> {
+    let service = gio::SocketService::new();
+
+    // Bind to the IPv4 loopback address only, letting the system pick a free port. Remote
+    // access is possible only through explicit means such as SSH port forwarding.
+    let loopback = gio::InetAddress::new_loopback(gio::SocketFamily::Ipv4);
+    let address = gio::InetSocketAddress::new(&loopback, 0);
+    let effective = service.add_address(
+        &address,
+        gio::SocketType::Stream,
+        gio::SocketProtocol::Tcp,
+        None::<&glib::Object>,
+    )?;
+    let port = effective
+        .downcast::<gio::InetSocketAddress>()
+        .map(|a| a.port())
+        .unwrap_or(0);
+
+    service.connect_incoming(move |_service, connection, _source| {
+        let connection = connection.clone();
+        let responder = responder.clone();
+        glib::MainContext::default().spawn_local(async move {
+            if let Err(err) = handle_connection(&connection, &*responder).await {
+                log::warn!("HTTP sharing: failed to answer request: {err}");
+            }
+        });
+        // Let other handlers (there are none) see the connection too.
+        false
+    });
+    service.start();
+
+    Ok((service, port))
+}
+
+/// Reads a single request from `connection`, writes the response and closes the connection.
+async fn handle_connection(
+    connection: &gio::SocketConnection,
+    responder: &Responder,
+) -> Result<(), glib::Error> {
+    // Requests for this server are tiny; the request line is all that is needed.
+    let (buffer, len) = connection
+        .input_stream()
+        .read_future(vec![0u8; 4096], glib::Priority::DEFAULT)
+        .await
+        .map_err(|(_, err)| err)?;
+    let request = String::from_utf8_lossy(&buffer[..len]);
+    let response = build_response(&request, responder);
+
+    connection
+        .output_stream()
+        .write_all_future(response.into_bytes(), glib::Priority::DEFAULT)
+        .await
+        .map_err(|(_, err)| err)?;
+    connection.close_future(glib::Priority::DEFAULT).await
+}
+
+/// Builds the complete HTTP response for a raw request.
+fn build_response(request: &str, responder: &Responder) -> String {
+    let request_line = request.lines().next().unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    // Ignore any query string; only the path selects the content.
+    let path = parts
+        .next()
+        .unwrap_or_default()
+        .split('?')
+        .next()
+        .unwrap_or_default();
+
+    if method != "GET" {
+        return http_response(
+            "405 Method Not Allowed",
+            "text/plain",
+            "Method not allowed\n",
+        );
+    }
+    match responder(path) {
+        Some((content_type, body)) => http_response("200 OK", content_type, &body),
+        None => http_response("404 Not Found", "text/plain", "Not found\n"),
+    }
+}
+
+/// Formats an HTTP/1.0 response with the given status line, content type and body.
+fn http_response(status: &str, content_type: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.0 {status}\r\n\
+         Content-Type: {content_type}; charset=utf-8\r\n\
+         Content-Length: {len}\r\n\
+         Connection: close\r\n\
+         \r\n\
+         {body}",
+        len = body.len()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn responder(path: &str) -> Option<(&'static str, String)> {
+        (path == "/json").then(|| ("application/json", "{}".to_string()))
+    }
+
+    #[test]
+    fn build_response_known_path() {
+        let response = build_response("GET /json?x=1 HTTP/1.1\r\nHost: a\r\n\r\n", &responder);
+        assert!(response.starts_with("HTTP/1.0 200 OK\r\n"));
+        assert!(response.contains("Content-Length: 2\r\n"));
+        assert!(response.ends_with("\r\n\r\n{}"));
+    }
+
+    #[test]
+    fn build_response_unknown_path() {
+        let response = build_response("GET /nope HTTP/1.1\r\n\r\n", &responder);
+        assert!(response.starts_with("HTTP/1.0 404 Not Found\r\n"));
+    }
+
+    #[test]
+    fn build_response_rejects_post() {
+        let response = build_response("POST /json HTTP/1.1\r\n\r\n", &responder);
+        assert!(response.starts_with("HTTP/1.0 405 "));
+    }
+}
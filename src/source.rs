@@ -0,0 +1,291 @@
+use crate::labels;
+use crate::memory;
+use crate::rdf;
+use crate::store::StoreClient;
+use crate::{BACKLINKS_LOW_MEMORY_LIMIT, NFO_FILE_NAME, NIE_TITLE, RDF_TYPE, RDFS_SUB_CLASS_OF};
+use std::future::Future;
+use std::pin::Pin;
+use tracker::prelude::*;
+
+/// A statement about a subject, as `(predicate, object, datatype, language)`. The datatype is
+/// empty for nodes, and the language is empty for values without a language tag.
+pub type Statement = (String, String, String, String);
+
+/// A node linking to a subject, as `(subject, predicate, type, title)`: the node, the predicate
+/// it links with, its most specific type and its title or file name (both empty if unknown).
+pub type Backlink = (String, String, String, String);
+
+/// A text the ontology gives a property or class in some language, as `(iri, text, language)`.
+pub type Text = (String, String, String);
+
+/// What the ontology says about a property or class, as `(iri, property, value, language)`:
+/// the property is `rdfs:comment`, `rdfs:domain` or `rdfs:range`, and the language is empty for
+/// values without a language tag.
+pub type Description = (String, String, String, String);
+
+/// Result of a request to a [`MetadataSource`].
+pub type SourceFuture<T> = Pin<Box<dyn Future<Output = Result<T, glib::Error>>>>;
+
+/// The metadata the windows show, independent of how it is stored.
+///
+/// Every [`StoreClient`], and thereby the Tracker miner, is a source. Tests use an in-memory
+/// list of statements instead, so that the code turning metadata into rows runs without a
+/// Tracker daemon or a display.
+pub trait MetadataSource {
+    /// Reads the statements about `uri`.
+    fn statements(&self, uri: &str) -> SourceFuture<Vec<Statement>>;
+
+    /// Reads the nodes linking to `uri`.
+    fn backlinks(&self, uri: &str) -> SourceFuture<Vec<Backlink>>;
+
+    /// Reads the labels (`rdfs:label`) of `iris`, in all languages available.
+    fn labels(&self, iris: &[String]) -> SourceFuture<Vec<Text>>;
+
+    /// Reads the comments (`rdfs:comment`) of `iris`, in all languages available, along with
+    /// their domains and ranges.
+    fn descriptions(&self, iris: &[String]) -> SourceFuture<Vec<Description>>;
+}
+
+impl<S: StoreClient + ?Sized> MetadataSource for S {
+    fn statements(&self, uri: &str) -> SourceFuture<Vec<Statement>> {
+        rows(self.query_async(&statements_query(uri)))
+    }
+
+    fn backlinks(&self, uri: &str) -> SourceFuture<Vec<Backlink>> {
+        rows(self.query_async(&backlinks_query(uri)))
+    }
+
+    fn labels(&self, iris: &[String]) -> SourceFuture<Vec<Text>> {
+        texts(self.query_async(&labels::labels_query(iris)))
+    }
+
+    fn descriptions(&self, iris: &[String]) -> SourceFuture<Vec<Description>> {
+        rows(self.query_async(&labels::comments_query(iris)))
+    }
+}
+
+/// Reads the `?iri ?text ?lang` rows of a query for labels or comments.
+fn texts(query: crate::store::QueryFuture) -> SourceFuture<Vec<Text>> {
+    Box::pin(async move {
+        let cursor = query.await?;
+        let mut texts = Vec::new();
+        while cursor.next_future().await? {
+            texts.push((
+                cursor.string(0).unwrap_or_default().to_string(),
+                cursor.string(1).unwrap_or_default().to_string(),
+                cursor.string(2).unwrap_or_default().to_string(),
+            ));
+        }
+        Ok(texts)
+    })
+}
+
+/// Reads the four columns of each row a query returns.
+fn rows(query: crate::store::QueryFuture) -> SourceFuture<Vec<(String, String, String, String)>> {
+    Box::pin(async move {
+        let cursor = query.await?;
+        let mut rows = Vec::new();
+        while cursor.next_future().await? {
+            rows.push((
+                cursor.string(0).unwrap_or_default().to_string(),
+                cursor.string(1).unwrap_or_default().to_string(),
+                cursor.string(2).unwrap_or_default().to_string(),
+                cursor.string(3).unwrap_or_default().to_string(),
+            ));
+        }
+        Ok(rows)
+    })
+}
+
+/// Returns the query for the statements about `uri` (see [`Statement`]).
+pub fn statements_query(uri: &str) -> String {
+    format!(
+        "SELECT DISTINCT ?pred ?obj (DATATYPE(?obj) AS ?dtype) (LANG(?obj) AS ?lang) \
+         WHERE {{ {s} ?pred ?obj }}",
+        s = rdf::iri_ref(uri)
+    )
+}
+
+/// Returns the query for the nodes linking to `uri` (see [`Backlink`]).
+///
+/// Of the types of a node, the most specific one is taken: a type none of whose subclasses is
+/// also a type of the node.
+pub fn backlinks_query(uri: &str) -> String {
+    format!(
+        r#"
+        SELECT ?s ?p (SAMPLE(?type) AS ?stype) (SAMPLE(COALESCE(?title, ?name)) AS ?stitle) WHERE {{
+            ?s ?p {uri} .
+            OPTIONAL {{
+                ?s <{RDF_TYPE}> ?type .
+                FILTER NOT EXISTS {{
+                    ?s <{RDF_TYPE}> ?sub .
+                    ?sub <{RDFS_SUB_CLASS_OF}> ?type .
+                    FILTER (?sub != ?type)
+                }}
+            }}
+            OPTIONAL {{ ?s <{NIE_TITLE}> ?title }}
+            OPTIONAL {{ ?s <{NFO_FILE_NAME}> ?name }}
+        }}
+        GROUP BY ?s ?p
+        {limit}
+    "#,
+        uri = rdf::iri_ref(uri),
+        limit = if memory::is_low() {
+            format!("LIMIT {BACKLINKS_LOW_MEMORY_LIMIT}")
+        } else {
+            String::new()
+        }
+    )
+}
+
+/// A source holding `(subject, predicate, object, datatype, language)` statements in memory,
+/// for tests.
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub struct MockSource {
+    pub statements: Vec<(String, String, String, String, String)>,
+}
+
+#[cfg(test)]
+impl MockSource {
+    /// Adds a statement linking `subject` to the node `object`.
+    pub fn link(mut self, subject: &str, predicate: &str, object: &str) -> Self {
+        self.statements.push((
+            subject.to_string(),
+            predicate.to_string(),
+            object.to_string(),
+            String::new(),
+            String::new(),
+        ));
+        self
+    }
+
+    /// Adds a statement giving `subject` the literal `value` of type `datatype`.
+    pub fn literal(mut self, subject: &str, predicate: &str, value: &str, datatype: &str) -> Self {
+        self.statements.push((
+            subject.to_string(),
+            predicate.to_string(),
+            value.to_string(),
+            datatype.to_string(),
+            String::new(),
+        ));
+        self
+    }
+
+    /// Returns the values of `predicate` of those of `iris` that have any, as texts.
+    fn texts(&self, predicate: &str, iris: &[String]) -> SourceFuture<Vec<Text>> {
+        let texts = self
+            .statements
+            .iter()
+            .filter(|(s, p, _, _, _)| iris.contains(s) && p == predicate)
+            .map(|(s, _, o, _, lang)| (s.clone(), o.clone(), lang.clone()))
+            .collect();
+        Box::pin(async move { Ok(texts) })
+    }
+
+    /// Returns the first object of `subject` and `predicate`, or an empty string.
+    fn first(&self, subject: &str, predicate: &str) -> String {
+        self.statements
+            .iter()
+            .find(|(s, p, _, _, _)| s == subject && p == predicate)
+            .map(|(_, _, o, _, _)| o.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+impl MetadataSource for MockSource {
+    fn statements(&self, uri: &str) -> SourceFuture<Vec<Statement>> {
+        let statements = self
+            .statements
+            .iter()
+            .filter(|(s, _, _, _, _)| s == uri)
+            .map(|(_, p, o, dtype, lang)| (p.clone(), o.clone(), dtype.clone(), lang.clone()))
+            .collect();
+        Box::pin(async move { Ok(statements) })
+    }
+
+    fn backlinks(&self, uri: &str) -> SourceFuture<Vec<Backlink>> {
+        let backlinks = self
+            .statements
+            .iter()
+            .filter(|(_, _, o, dtype, _)| o == uri && dtype.is_empty())
+            .map(|(s, p, _, _, _)| {
+                let title = Some(self.first(s, NIE_TITLE))
+                    .filter(|title| !title.is_empty())
+                    .unwrap_or_else(|| self.first(s, NFO_FILE_NAME));
+                (s.clone(), p.clone(), self.first(s, RDF_TYPE), title)
+            })
+            .collect();
+        Box::pin(async move { Ok(backlinks) })
+    }
+
+    fn labels(&self, iris: &[String]) -> SourceFuture<Vec<Text>> {
+        self.texts("http://www.w3.org/2000/01/rdf-schema#label", iris)
+    }
+
+    fn descriptions(&self, iris: &[String]) -> SourceFuture<Vec<Description>> {
+        let properties = [
+            "http://www.w3.org/2000/01/rdf-schema#comment",
+            "http://www.w3.org/2000/01/rdf-schema#domain",
+            "http://www.w3.org/2000/01/rdf-schema#range",
+        ];
+        let descriptions = self
+            .statements
+            .iter()
+            .filter(|(s, p, _, _, _)| iris.contains(s) && properties.contains(&p.as_str()))
+            .map(|(s, p, o, _, lang)| (s.clone(), p.clone(), o.clone(), lang.clone()))
+            .collect();
+        Box::pin(async move { Ok(descriptions) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backlinks_query_names_the_node() {
+        let query = backlinks_query("file:///a b");
+        assert!(query.contains("?s ?p <file:///a\\u0020b> ."), "{query}");
+    }
+
+    #[test]
+    fn mock_source_answers_like_the_store() {
+        let source = MockSource::default()
+            .link("urn:playlist", "urn:entry", "file:///a.ogg")
+            .link("urn:playlist", RDF_TYPE, "urn:Playlist")
+            .literal("urn:playlist", NIE_TITLE, "Favorites", "urn:string")
+            .literal("file:///a.ogg", NFO_FILE_NAME, "a.ogg", "urn:string");
+        let context = glib::MainContext::default();
+
+        let statements = context
+            .block_on(source.statements("file:///a.ogg"))
+            .unwrap();
+        assert_eq!(
+            statements,
+            [(
+                NFO_FILE_NAME.to_string(),
+                "a.ogg".to_string(),
+                "urn:string".to_string(),
+                String::new()
+            )]
+        );
+        let backlinks = context.block_on(source.backlinks("file:///a.ogg")).unwrap();
+        assert_eq!(
+            backlinks,
+            [(
+                "urn:playlist".to_string(),
+                "urn:entry".to_string(),
+                "urn:Playlist".to_string(),
+                "Favorites".to_string()
+            )]
+        );
+        assert!(
+            context
+                .block_on(source.backlinks("Favorites"))
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,202 @@
+use crate::context::Context;
+use crate::dnd;
+use crate::geometry;
+use crate::settings::{self, Settings};
+use crate::{open_subject_window, search, tab_title};
+use adw::prelude::*;
+use clap::ValueEnum;
+use std::rc::Rc;
+
+/// What a launch without a file to inspect shows.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum StartPage {
+    /// A file chooser for picking the file to inspect.
+    #[default]
+    FileChooser,
+    /// The subjects inspected recently (see `settings::RECENT_SUBJECTS`).
+    Recent,
+    /// A search entry for finding an indexed file (see `search::entry`).
+    Search,
+    /// Nothing; the application exits unless it has something else to do, e.g. as a service.
+    #[value(name = "none")]
+    Nothing,
+}
+
+impl StartPage {
+    /// Returns the name of the start page, as used on the command line and in the preferences.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StartPage::FileChooser => "file-chooser",
+            StartPage::Recent => "recent",
+            StartPage::Search => "search",
+            StartPage::Nothing => "none",
+        }
+    }
+
+    /// Returns the start page named `name` (see [`StartPage::as_str`]), if there is one.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::value_variants()
+            .iter()
+            .copied()
+            .find(|page| page.as_str() == name)
+    }
+
+    /// Returns the start page chosen in the preferences, or the default one.
+    pub fn from_settings() -> Self {
+        Self::from_name(&settings::preferences().string(settings::START_PAGE)).unwrap_or_default()
+    }
+}
+
+/// Shows the start page `page`.
+///
+/// # Arguments
+/// * `app` - The application instance.
+/// * `page` - The start page to show.
+/// * `ctx` - Shared application settings, passed on to the subject window that is opened.
+pub fn show(app: &adw::Application, page: StartPage, ctx: &Rc<Context>) {
+    match page {
+        StartPage::FileChooser => choose_file(app, ctx),
+        StartPage::Recent => start_window(app, "Recent", &recent_list(app, ctx), ctx),
+        StartPage::Search => start_window(app, "Search", &search_page(app, ctx), ctx),
+        StartPage::Nothing => {}
+    }
+}
+
+/// Shows a file chooser and opens a subject window for the chosen file.
+fn choose_file(app: &adw::Application, ctx: &Rc<Context>) {
+    let chooser = gtk::FileChooserNative::new(
+        Some("Choose a File to Inspect"),
+        None::<&gtk::Window>,
+        gtk::FileChooserAction::Open,
+        Some("Inspect"),
+        None,
+    );
+    // There is no window yet, so keep the application running while the chooser is shown.
+    let hold = std::cell::RefCell::new(Some(app.hold()));
+    let app = app.clone();
+    let ctx = ctx.clone();
+    chooser.connect_response(move |chooser, response| {
+        let file = (response == gtk::ResponseType::Accept)
+            .then(|| chooser.file())
+            .flatten();
+        if let Some(file) = file {
+            open_subject_window(&app, file.uri().to_string(), &ctx);
+        }
+        hold.borrow_mut().take();
+    });
+    chooser.show();
+}
+
+/// Shows `content` in a small window titled `title`, which closes once a subject window has
+/// been opened from it, or a file has been dropped onto it.
+fn start_window(app: &adw::Application, title: &str, content: &gtk::Widget, ctx: &Rc<Context>) {
+    let header = adw::HeaderBar::new();
+    header.set_title_widget(Some(&gtk::Label::new(Some(title))));
+    let toolbar = adw::ToolbarView::new();
+    toolbar.add_top_bar(&header);
+    toolbar.set_content(Some(content));
+    let window = adw::ApplicationWindow::builder()
+        .application(app)
+        .default_width(420)
+        .default_height(360)
+        .title(title)
+        .content(&toolbar)
+        .build();
+    geometry::remember(&window, "start");
+    let app = app.clone();
+    let ctx = ctx.clone();
+    let weak = window.downgrade();
+    dnd::accept(&window, move |target| {
+        if let Some(window) = weak.upgrade() {
+            open_from(window.upcast_ref(), &app, target, &ctx);
+        }
+    });
+    window.present();
+}
+
+/// Opens a subject window for `uri` and closes the start window holding `widget`.
+fn open_from(widget: &gtk::Widget, app: &adw::Application, uri: String, ctx: &Rc<Context>) {
+    let start = widget.root().and_downcast::<gtk::Window>();
+    open_subject_window(app, uri, ctx);
+    if let Some(start) = start {
+        start.close();
+    }
+}
+
+/// Builds the list of recently inspected subjects, or a placeholder if there are none.
+fn recent_list(app: &adw::Application, ctx: &Rc<Context>) -> gtk::Widget {
+    let recent = Settings::load().string_list(settings::RECENT_SUBJECTS);
+    if recent.is_empty() {
+        return adw::StatusPage::builder()
+            .icon_name("document-open-recent-symbolic")
+            .title("Nothing Inspected Yet")
+            .description("Files and other subjects you inspect are listed here.")
+            .build()
+            .upcast();
+    }
+
+    let list = gtk::ListBox::builder()
+        .selection_mode(gtk::SelectionMode::None)
+        .activate_on_single_click(true)
+        .build();
+    for location in &recent {
+        let title = gtk::Label::new(Some(&tab_title(location)));
+        title.set_halign(gtk::Align::Start);
+        let uri = gtk::Label::new(Some(location));
+        uri.set_halign(gtk::Align::Start);
+        uri.set_ellipsize(gtk::pango::EllipsizeMode::Middle);
+        uri.style_context().add_class("dim-label");
+        let content = gtk::Box::new(gtk::Orientation::Vertical, 2);
+        content.set_margin_start(6);
+        content.set_margin_end(6);
+        content.set_margin_top(4);
+        content.set_margin_bottom(4);
+        content.append(&title);
+        content.append(&uri);
+        list.append(&content);
+    }
+    let app = app.clone();
+    let ctx = ctx.clone();
+    list.connect_row_activated(move |list, row| {
+        if let Some(location) = recent.get(row.index() as usize) {
+            open_from(list.upcast_ref(), &app, location.clone(), &ctx);
+        }
+    });
+    gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .child(&list)
+        .build()
+        .upcast()
+}
+
+/// Builds a page centred around a search entry for indexed files.
+fn search_page(app: &adw::Application, ctx: &Rc<Context>) -> gtk::Widget {
+    let page = adw::StatusPage::builder()
+        .icon_name("system-search-symbolic")
+        .title("Search Indexed Files")
+        .description("Type part of a file name or of its contents.")
+        .build();
+    let app = app.clone();
+    let ctx = ctx.clone();
+    let page_clone = page.clone();
+    let entry = search::entry(move |uri| open_from(page_clone.upcast_ref(), &app, uri, &ctx));
+    entry.set_halign(gtk::Align::Center);
+    entry.set_width_chars(30);
+    page.set_child(Some(&entry));
+    page.upcast()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_match_command_line_values() {
+        for page in StartPage::value_variants() {
+            let value = page.to_possible_value().unwrap();
+            assert_eq!(value.get_name(), page.as_str());
+            assert_eq!(StartPage::from_name(page.as_str()), Some(*page));
+        }
+        assert_eq!(StartPage::from_name("bogus"), None);
+    }
+}
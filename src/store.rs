@@ -0,0 +1,300 @@
+use gio::prelude::*;
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use std::rc::Rc;
+use tracker::prelude::*;
+
+/// D-Bus name of the Tracker miner whose store holds the file metadata.
+const MINER_FS: &str = "org.freedesktop.Tracker3.Miner.Files";
+
+/// The Nepomuk ontology bundled with libtracker-sparql, i.e. the file returned by
+/// `tracker_sparql_get_ontology_nepomuk()`. It is the ontology used by tracker-miner-fs.
+const NEPOMUK_ONTOLOGY: &str = "resource:///org/freedesktop/tracker/ontology";
+
+/// Result of an asynchronous query.
+pub type QueryFuture = Pin<Box<dyn Future<Output = Result<tracker::SparqlCursor, glib::Error>>>>;
+
+/// Result of an asynchronous update.
+pub type UpdateFuture = Pin<Box<dyn Future<Output = Result<(), glib::Error>>>>;
+
+/// Access to a SPARQL store holding file metadata.
+///
+/// All code talks to the store through this trait (obtained from [`client`]), so that the
+/// Tracker miner can be replaced by deterministic data, e.g. with `--fixture`.
+pub trait StoreClient {
+    /// Runs a query, blocking until the results are available.
+    fn query(&self, sparql: &str) -> Result<tracker::SparqlCursor, glib::Error>;
+
+    /// Runs a query asynchronously on the main context.
+    fn query_async(&self, sparql: &str) -> QueryFuture;
+
+    /// Runs an update, blocking until it has been applied.
+    fn update(&self, sparql: &str) -> Result<(), glib::Error>;
+
+    /// Runs an update asynchronously on the main context.
+    fn update_async(&self, sparql: &str) -> UpdateFuture;
+
+    /// Returns a notifier reporting changes to the resources in the store, if the store
+    /// supports it.
+    fn notifier(&self) -> Option<tracker::Notifier>;
+}
+
+/// The store of tracker-miner-fs, reached over D-Bus.
+pub struct BusStore {
+    conn: tracker::SparqlConnection,
+}
+
+impl BusStore {
+    /// Connects to the miner's SPARQL endpoint on the session bus.
+    pub fn connect() -> Result<Self, glib::Error> {
+        let conn = tracker::SparqlConnection::bus_new(MINER_FS, None, None)?;
+        Ok(Self { conn })
+    }
+}
+
+impl StoreClient for BusStore {
+    fn query(&self, sparql: &str) -> Result<tracker::SparqlCursor, glib::Error> {
+        self.conn.query(sparql, None::<&gio::Cancellable>)
+    }
+
+    fn query_async(&self, sparql: &str) -> QueryFuture {
+        self.conn.query_future(sparql)
+    }
+
+    fn update(&self, sparql: &str) -> Result<(), glib::Error> {
+        self.conn.update(sparql, None::<&gio::Cancellable>)
+    }
+
+    fn update_async(&self, sparql: &str) -> UpdateFuture {
+        self.conn.update_future(sparql)
+    }
+
+    fn notifier(&self) -> Option<tracker::Notifier> {
+        self.conn.create_notifier()
+    }
+}
+
+/// A private store held in memory, using the same ontology as tracker-miner-fs.
+pub struct MemoryStore {
+    conn: tracker::SparqlConnection,
+}
+
+impl MemoryStore {
+    /// Creates an empty store.
+    pub fn new() -> Result<Self, glib::Error> {
+        let ontology = gio::File::for_uri(NEPOMUK_ONTOLOGY);
+        let conn = tracker::SparqlConnection::new(
+            tracker::SparqlConnectionFlags::NONE,
+            None::<&gio::File>,
+            Some(&ontology),
+            None::<&gio::Cancellable>,
+        )?;
+        Ok(Self { conn })
+    }
+
+    /// Adds the triples of a Turtle file to the store.
+    pub fn load_turtle(&self, path: &Path) -> Result<(), glib::Error> {
+        let uri = gio::File::for_path(path).uri();
+        self.update(&format!("LOAD <{uri}>"))
+    }
+}
+
+/// A private store kept in a directory, using the same ontology as tracker-miner-fs. It holds
+/// what the user adds in the application, such as tags and notes, since the miner's endpoint
+/// does not accept updates.
+pub struct LocalStore {
+    conn: tracker::SparqlConnection,
+}
+
+impl LocalStore {
+    /// Opens the store in `dir`, creating it if needed.
+    pub fn open(dir: &Path) -> Result<Self, glib::Error> {
+        if let Err(err) = std::fs::create_dir_all(dir) {
+            return Err(glib::Error::new(
+                gio::IOErrorEnum::Failed,
+                &format!("Could not create {}: {err}", dir.display()),
+            ));
+        }
+        let ontology = gio::File::for_uri(NEPOMUK_ONTOLOGY);
+        let conn = tracker::SparqlConnection::new(
+            tracker::SparqlConnectionFlags::NONE,
+            Some(&gio::File::for_path(dir)),
+            Some(&ontology),
+            None::<&gio::Cancellable>,
+        )?;
+        Ok(Self { conn })
+    }
+}
+
+impl StoreClient for LocalStore {
+    fn query(&self, sparql: &str) -> Result<tracker::SparqlCursor, glib::Error> {
+        self.conn.query(sparql, None::<&gio::Cancellable>)
+    }
+
+    fn query_async(&self, sparql: &str) -> QueryFuture {
+        self.conn.query_future(sparql)
+    }
+
+    fn update(&self, sparql: &str) -> Result<(), glib::Error> {
+        self.conn.update(sparql, None::<&gio::Cancellable>)
+    }
+
+    fn update_async(&self, sparql: &str) -> UpdateFuture {
+        self.conn.update_future(sparql)
+    }
+
+    fn notifier(&self) -> Option<tracker::Notifier> {
+        self.conn.create_notifier()
+    }
+}
+
+impl StoreClient for MemoryStore {
+    fn query(&self, sparql: &str) -> Result<tracker::SparqlCursor, glib::Error> {
+        self.conn.query(sparql, None::<&gio::Cancellable>)
+    }
+
+    fn query_async(&self, sparql: &str) -> QueryFuture {
+        self.conn.query_future(sparql)
+    }
+
+    fn update(&self, sparql: &str) -> Result<(), glib::Error> {
+        self.conn.update(sparql, None::<&gio::Cancellable>)
+    }
+
+    fn update_async(&self, sparql: &str) -> UpdateFuture {
+        self.conn.update_future(sparql)
+    }
+
+    fn notifier(&self) -> Option<tracker::Notifier> {
+        self.conn.create_notifier()
+    }
+}
+
+thread_local! {
+    /// The store installed with `install`, if any.
+    static INSTALLED: RefCell<Option<Rc<dyn StoreClient>>> = const { RefCell::new(None) };
+    /// The store opened by `annotations`, which is kept open for the lifetime of the
+    /// application.
+    static ANNOTATIONS: RefCell<Option<Rc<dyn StoreClient>>> = const { RefCell::new(None) };
+}
+
+/// Makes `store` the store returned by all subsequent calls to [`client`].
+pub fn install(store: Rc<dyn StoreClient>) {
+    INSTALLED.with(|installed| installed.replace(Some(store)));
+}
+
+/// Returns the store to query: the one given to [`install`], or else a new connection to the
+/// Tracker miner.
+pub fn client() -> Result<Rc<dyn StoreClient>, glib::Error> {
+    if let Some(store) = INSTALLED.with(|installed| installed.borrow().clone()) {
+        return Ok(store);
+    }
+    Ok(Rc::new(BusStore::connect()?))
+}
+
+/// Returns the store for the tags and notes added in the application: the one given to
+/// [`install`], which accepts updates, or else a [`LocalStore`] in
+/// `$XDG_DATA_HOME/file-information/annotations`.
+pub fn annotations() -> Result<Rc<dyn StoreClient>, glib::Error> {
+    if let Some(store) = INSTALLED.with(|installed| installed.borrow().clone()) {
+        return Ok(store);
+    }
+    if let Some(store) = ANNOTATIONS.with(|annotations| annotations.borrow().clone()) {
+        return Ok(store);
+    }
+    let dir = glib::user_data_dir()
+        .join("file-information")
+        .join("annotations");
+    let store: Rc<dyn StoreClient> = Rc::new(LocalStore::open(&dir)?);
+    ANNOTATIONS.with(|annotations| annotations.replace(Some(store.clone())));
+    Ok(store)
+}
+
+/// Watches the Tracker miner on the session bus and calls `on_restart` whenever it comes back
+/// after having gone away, e.g. after `tracker3 daemon -t` or a crash.
+///
+/// Queries connect anew each time (see [`client`]), so they reach the new instance by
+/// themselves; `on_restart` is for state tied to the old one, such as notifiers, and for
+/// re-running queries that failed while the miner was gone.
+///
+/// The watch lasts as long as the application.
+///
+/// # Returns
+/// * Whether the miner is watched; it is not if a store was given to [`install`], which cannot
+///   go away.
+pub fn watch_service(on_restart: impl Fn() + 'static) -> bool {
+    if INSTALLED.with(|installed| installed.borrow().is_some()) {
+        return false;
+    }
+    // The name is reported as vanished right away if the miner is not running yet. Only an
+    // appearance after the miner was seen running counts as a restart, so that activating it
+    // with the first query does not reload everything. `None` means it was never seen.
+    let running: Rc<Cell<Option<bool>>> = Rc::new(Cell::new(None));
+    let running_clone = running.clone();
+    let _watch = gio::bus_watch_name(
+        gio::BusType::Session,
+        MINER_FS,
+        gio::BusNameWatcherFlags::NONE,
+        move |_conn, _name, owner| {
+            if running.replace(Some(true)) == Some(false) {
+                log::info!("{MINER_FS} is back as {owner}; reconnecting");
+                on_restart();
+            }
+        },
+        move |_conn, _name| {
+            if running_clone.get() == Some(true) {
+                running_clone.set(Some(false));
+                log::warn!("{MINER_FS} went away; waiting for it to restart");
+            }
+        },
+    );
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A store without any data, which fails every request.
+    struct NullStore;
+
+    impl StoreClient for NullStore {
+        fn query(&self, _sparql: &str) -> Result<tracker::SparqlCursor, glib::Error> {
+            Err(glib::Error::new(
+                gio::IOErrorEnum::NotSupported,
+                "null store",
+            ))
+        }
+
+        fn query_async(&self, sparql: &str) -> QueryFuture {
+            let result = self.query(sparql);
+            Box::pin(async move { result })
+        }
+
+        fn update(&self, _sparql: &str) -> Result<(), glib::Error> {
+            Err(glib::Error::new(
+                gio::IOErrorEnum::NotSupported,
+                "null store",
+            ))
+        }
+
+        fn update_async(&self, sparql: &str) -> UpdateFuture {
+            let result = self.update(sparql);
+            Box::pin(async move { result })
+        }
+
+        fn notifier(&self) -> Option<tracker::Notifier> {
+            None
+        }
+    }
+
+    #[test]
+    fn client_returns_installed_store() {
+        let store: Rc<dyn StoreClient> = Rc::new(NullStore);
+        install(store.clone());
+        assert!(Rc::ptr_eq(&client().unwrap(), &store));
+        assert!(client().unwrap().query("SELECT 1 {}").is_err());
+    }
+}
@@ -0,0 +1,360 @@
+use crate::context::Context;
+use crate::source::{MetadataSource, Statement};
+use crate::{
+    NIE_INTERPRETED_AS, NIE_IS_STORED_AS, PredicateValues, TableRow, ValueSource,
+    collect_statements, derived_values, display_value, geo, is_advanced, labels, media, ordering,
+    predicate_label, prefixes, relative_date,
+};
+use std::collections::HashMap;
+
+/// A row of the table of a subject, or the rows of one predicate, in the order they are shown.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Entry {
+    /// The values of a predicate, as found in `SubjectTable::values`.
+    Predicate(String),
+    /// Whether and how current the indexed data of a file is. It is only shown in the window,
+    /// so it has no table row.
+    Indexing,
+    /// Coordinates combined into a single "Location" row (see `geo::find`).
+    Location(geo::Coordinates),
+    /// Width and height combined into a single "Resolution" row, e.g. "1920 × 1080". It takes
+    /// the place of `nfo:width` in the order.
+    Resolution(String),
+    /// A value computed from the file system, as `(predicate, value, datatype)` (see
+    /// `derived_values`).
+    Derived(&'static str, String, &'static str),
+}
+
+/// The contents of the table of a subject, computed from a [`MetadataSource`] without building
+/// any widgets, so that windows, the command line and tests get the same rows.
+#[derive(Debug, Default)]
+pub struct SubjectTable {
+    /// Whether the subject is an `nfo:FileDataObject`.
+    pub is_file_data_object: bool,
+    /// The `(object value, datatype, language tag)` values of each predicate, including those
+    /// merged from the interpreted elements.
+    pub values: PredicateValues,
+    /// The element each merged predicate was read from (see `Context::merge_interpreted`).
+    pub interpreted: HashMap<String, String>,
+    /// What is shown, in order; the identifier row comes first and is not included.
+    pub entries: Vec<Entry>,
+}
+
+impl SubjectTable {
+    /// Returns the predicates whose values are shown, in order.
+    pub fn predicates(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().filter_map(|entry| match entry {
+            Entry::Predicate(pred) => Some(pred.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Returns the table rows of `entry`: one per value for predicates, none for the indexing
+    /// status, those of the width and height for the resolution, and one otherwise.
+    pub fn entry_rows(&self, entry: &Entry, ctx: &Context) -> Vec<TableRow> {
+        match entry {
+            Entry::Predicate(pred) => {
+                let label = predicate_label(pred, ctx);
+                let source = if self.interpreted.contains_key(pred) {
+                    ValueSource::Interpreted
+                } else {
+                    ValueSource::Indexed
+                };
+                let now = glib::DateTime::now_local()
+                    .ok()
+                    .filter(|_| ctx.relative_dates.get());
+                let values = self.values.get(pred).map(Vec::as_slice).unwrap_or_default();
+                values
+                    .iter()
+                    .map(|(obj, dtype, lang)| {
+                        // Displayed values use a formatter if the datatype is known.
+                        let mut displayed = if dtype.is_empty() {
+                            obj.clone()
+                        } else {
+                            display_value(
+                                pred,
+                                obj,
+                                dtype,
+                                &ctx.date_format.borrow(),
+                                ctx.utc_dates.get(),
+                            )
+                        };
+                        // Optionally follow dates with how long ago they were.
+                        if let Some(relative) =
+                            now.as_ref().and_then(|now| relative_date(obj, dtype, now))
+                        {
+                            displayed = format!("{displayed} ({relative})");
+                        }
+                        TableRow {
+                            display_predicate: label.clone(),
+                            native_predicate: pred.clone(),
+                            display_value: displayed,
+                            native_value: obj.clone(),
+                            language: lang.clone(),
+                            source,
+                        }
+                    })
+                    .collect()
+            }
+            Entry::Indexing => Vec::new(),
+            Entry::Location(coordinates) => vec![TableRow {
+                display_predicate: "Location".to_string(),
+                native_predicate: geo::SLO_LOCATION.to_string(),
+                display_value: coordinates.display(),
+                native_value: coordinates.geo_uri(),
+                language: String::new(),
+                source: ValueSource::Indexed,
+            }],
+            // The rows of the combined properties keep their own predicates, so that exports and
+            // `--predicate` see the properties actually stored.
+            Entry::Resolution(_) => [media::NFO_WIDTH, media::NFO_HEIGHT]
+                .iter()
+                .flat_map(|pred| self.entry_rows(&Entry::Predicate(pred.to_string()), ctx))
+                .collect(),
+            Entry::Derived(pred, value, dtype) => vec![TableRow {
+                display_predicate: predicate_label(pred, ctx),
+                native_predicate: pred.to_string(),
+                display_value: display_value(
+                    pred,
+                    value,
+                    dtype,
+                    &ctx.date_format.borrow(),
+                    ctx.utc_dates.get(),
+                ),
+                native_value: value.clone(),
+                language: String::new(),
+                source: ValueSource::Derived,
+            }],
+        }
+    }
+
+    /// Returns all rows of the table of `uri`, starting with the identifier, for copying and
+    /// exporting.
+    pub fn rows(&self, uri: &str, ctx: &Context) -> Vec<TableRow> {
+        let mut rows = vec![identifier_row(uri)];
+        for entry in &self.entries {
+            rows.extend(self.entry_rows(entry, ctx));
+        }
+        rows
+    }
+}
+
+/// Returns the first row of every table, which holds the URI of the subject.
+pub fn identifier_row(uri: &str) -> TableRow {
+    TableRow {
+        display_predicate: "Identifier".to_string(),
+        native_predicate: "Identifier".to_string(),
+        display_value: uri.to_string(),
+        native_value: uri.to_string(),
+        language: String::new(),
+        source: ValueSource::Indexed,
+    }
+}
+
+/// Computes what the table of a subject shows, as chosen in `ctx`: the values of its
+/// predicates (merged with those of its interpreted elements if asked for), ordered and
+/// filtered, the combined rows and the values derived from the file system.
+///
+/// The labels of the predicates are looked up in `source` along the way, so that the rows can
+/// be labelled and sorted by them.
+///
+/// # Arguments
+/// * `source` - Where to read further statements and labels from.
+/// * `uri` - The URI of the subject.
+/// * `statements` - The statements about the subject, already read from `source`.
+/// * `ctx` - Shared application settings.
+pub async fn build<S: MetadataSource + ?Sized>(
+    source: &S,
+    uri: &str,
+    statements: Vec<Statement>,
+    ctx: &Context,
+) -> SubjectTable {
+    let (mut order, mut map, is_file_data_object) = collect_statements(statements);
+
+    // Optionally merge in the properties of the information elements the subject is interpreted
+    // as, where files keep their title, author, dimensions and so on. Properties the subject has
+    // itself take precedence. Each merged predicate maps to the element it was read from.
+    let mut interpreted: HashMap<String, String> = HashMap::new();
+    if ctx.merge_interpreted.get() {
+        let elements: Vec<String> = map
+            .get(NIE_INTERPRETED_AS)
+            .map(|values| values.iter().map(|(obj, _, _)| obj.clone()).collect())
+            .unwrap_or_default();
+        for element in elements {
+            // An element that cannot be read adds nothing.
+            let statements = source.statements(&element).await.unwrap_or_default();
+            for (pred, obj, dtype, lang) in statements {
+                if pred == NIE_IS_STORED_AS
+                    || interpreted
+                        .get(&pred)
+                        .map_or(map.contains_key(&pred), |e| *e != element)
+                {
+                    continue;
+                }
+                if !map.contains_key(&pred) {
+                    order.push(pred.clone());
+                    interpreted.insert(pred.clone(), element.clone());
+                }
+                map.entry(pred).or_default().push((obj, dtype, lang));
+            }
+        }
+    }
+
+    // Look up the ontology's labels of all predicates at once, for `friendly_label`, or the
+    // namespace prefixes when prefixed names are shown instead.
+    if ctx.compact_uris.get() {
+        prefixes::load().await;
+    } else {
+        labels::load_from(source, order.iter().map(String::as_str)).await;
+    }
+
+    // Arrange the predicates as chosen in the "Sort Properties" menu.
+    if ctx.row_order.get() == ordering::RowOrder::Ontology {
+        ordering::load().await;
+    }
+    ordering::sort(&mut order, ctx.row_order.get(), |pred| {
+        predicate_label(pred, ctx)
+    });
+    ordering::favorites_first(&mut order, &ctx.favorites.borrow());
+
+    // Technical rows are only shown when asked for in the window menu, or when marked as
+    // favorites.
+    if !ctx.show_advanced.get() {
+        let favorites = ctx.favorites.borrow();
+        order.retain(|pred| {
+            favorites.contains(pred) || !map.get(pred).is_some_and(|v| is_advanced(pred, v))
+        });
+    }
+
+    // Predicates hidden in the preferences are never shown.
+    {
+        let hidden = ctx.hidden_predicates.borrow();
+        order.retain(|pred| !hidden.contains(pred));
+    }
+
+    // Coordinates, given as latitude and longitude or as a geo: URI, are combined into a single
+    // "Location" row. Files only link to their location, so it is looked up separately.
+    let mut location = geo::find(&map);
+    if location.is_none() {
+        location = geo::linked(source, &map)
+            .await
+            .map(|coordinates| (coordinates, Vec::new()));
+    }
+    if let Some((_, replaced)) = &location {
+        order.retain(|pred| !replaced.contains(pred));
+    }
+    // Likewise, width and height are combined into a "Resolution" row, which is placed where the
+    // width would be (or the height, if the width is not shown).
+    let resolution = media::resolution(&map);
+    let anchor = resolution
+        .as_ref()
+        .and_then(|(_, replaced)| replaced.iter().find(|pred| order.contains(pred)).cloned());
+    let mut entries: Vec<Entry> = Vec::new();
+    for pred in order {
+        match &resolution {
+            Some((displayed, _)) if anchor.as_ref() == Some(&pred) => {
+                entries.push(Entry::Resolution(displayed.clone()));
+            }
+            Some((_, replaced)) if replaced.contains(&pred) => {}
+            _ => entries.push(Entry::Predicate(pred)),
+        }
+    }
+    // For files, the window tells whether and how current the indexed data is.
+    if uri.starts_with("file:") {
+        entries.push(Entry::Indexing);
+    }
+    entries.extend(location.map(|(coordinates, _)| Entry::Location(coordinates)));
+
+    // Basic file properties the store does not have (e.g. because the file is not indexed) are
+    // filled in from the file system.
+    let derived: Vec<_> = derived_values(uri)
+        .await
+        .into_iter()
+        .filter(|(pred, _, _)| !map.contains_key(*pred))
+        .collect();
+    labels::load_from(source, derived.iter().map(|(pred, _, _)| *pred)).await;
+    entries.extend(
+        derived
+            .into_iter()
+            .map(|(pred, value, dtype)| Entry::Derived(pred, value, dtype)),
+    );
+
+    SubjectTable {
+        is_file_data_object,
+        values: map,
+        interpreted,
+        entries,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::MockSource;
+    use crate::{FILEDATAOBJECT, NFO_FILE_NAME, NIE_TITLE, RDF_TYPE, XSD_INTEGER, XSD_STRING};
+
+    #[test]
+    fn table_from_a_mock_source() {
+        let file = "file:///nonexistent/sunset.jpg";
+        let source = MockSource::default()
+            .literal(file, NFO_FILE_NAME, "sunset.jpg", XSD_STRING)
+            .link(file, RDF_TYPE, FILEDATAOBJECT)
+            .link(file, NIE_INTERPRETED_AS, "urn:image")
+            .literal("urn:image", NIE_TITLE, "Sunset", XSD_STRING)
+            .literal("urn:image", media::NFO_WIDTH, "1920", XSD_INTEGER)
+            .literal("urn:image", media::NFO_HEIGHT, "1080", XSD_INTEGER)
+            .link("urn:image", NIE_IS_STORED_AS, file)
+            .literal(
+                NIE_TITLE,
+                "http://www.w3.org/2000/01/rdf-schema#label",
+                "Title",
+                "",
+            );
+        let ctx = Context::default();
+        ctx.row_order.set(ordering::RowOrder::Alphabetical);
+        ctx.merge_interpreted.set(true);
+        ctx.favorites
+            .replace(vec![NIE_TITLE.to_string(), media::NFO_WIDTH.to_string()]);
+
+        let context = glib::MainContext::default();
+        let statements = context.block_on(source.statements(file)).unwrap();
+        let table = context.block_on(build(&source, file, statements, &ctx));
+        assert!(table.is_file_data_object);
+        assert_eq!(table.interpreted[NIE_TITLE], "urn:image");
+        // The favorites come first; the type is technical, and the width and height are
+        // combined in the place of the width.
+        assert_eq!(
+            table.entries,
+            [
+                Entry::Predicate(NIE_TITLE.to_string()),
+                Entry::Resolution("1920 × 1080".to_string()),
+                Entry::Predicate(NFO_FILE_NAME.to_string()),
+                Entry::Predicate(NIE_INTERPRETED_AS.to_string()),
+                Entry::Indexing,
+            ]
+        );
+
+        let rows = table.rows(file, &ctx);
+        let summary: Vec<_> = rows
+            .iter()
+            .map(|r| {
+                (
+                    r.display_predicate.as_str(),
+                    r.display_value.as_str(),
+                    r.source,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("Identifier", file, ValueSource::Indexed),
+                ("Title", "Sunset", ValueSource::Interpreted),
+                ("Width", "1920", ValueSource::Interpreted),
+                ("Height", "1080", ValueSource::Interpreted),
+                ("File Name", "sunset.jpg", ValueSource::Indexed),
+                ("Interpreted As", "urn:image", ValueSource::Indexed),
+            ]
+        );
+    }
+}
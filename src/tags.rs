@@ -0,0 +1,191 @@
+use crate::context::Context;
+use crate::rdf;
+use crate::show_message;
+use crate::store;
+use gtk::prelude::*;
+use std::rc::Rc;
+use tracker::prelude::*;
+
+pub const NAO_HAS_TAG: &str = "http://tracker.api.gnome.org/ontology/v3/nao#hasTag";
+const NAO_TAG: &str = "http://tracker.api.gnome.org/ontology/v3/nao#Tag";
+pub const NAO_PREF_LABEL: &str = "http://tracker.api.gnome.org/ontology/v3/nao#prefLabel";
+const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+
+/// Returns a query for the tags of `subject`, as `?tag ?label` rows ordered by label.
+pub fn tags_query(subject: &str) -> String {
+    format!(
+        "SELECT ?tag ?label WHERE {{ {s} <{NAO_HAS_TAG}> ?tag . \
+         OPTIONAL {{ ?tag <{NAO_PREF_LABEL}> ?label }} }} ORDER BY ?label",
+        s = rdf::iri_ref(subject)
+    )
+}
+
+/// Returns an update tagging `subject` with the tag labelled `label`.
+///
+/// The tag resource is created first unless a tag with that label already exists, so that tags
+/// are shared between all resources that carry them.
+pub fn add_tag_update(subject: &str, label: &str) -> String {
+    let label = rdf::escape_string(label);
+    format!(
+        "INSERT {{ _:tag <{RDF_TYPE}> <{NAO_TAG}> ; <{NAO_PREF_LABEL}> \"{label}\" }} \
+         WHERE {{ FILTER NOT EXISTS {{ ?t <{RDF_TYPE}> <{NAO_TAG}> ; <{NAO_PREF_LABEL}> \"{label}\" }} }} ;\n\
+         INSERT {{ {s} <{NAO_HAS_TAG}> ?t }} \
+         WHERE {{ ?t <{RDF_TYPE}> <{NAO_TAG}> ; <{NAO_PREF_LABEL}> \"{label}\" }}",
+        s = rdf::iri_ref(subject)
+    )
+}
+
+/// Returns an update removing the tag `tag` (a tag resource IRI) from `subject`.
+pub fn remove_tag_update(subject: &str, tag: &str) -> String {
+    format!(
+        "DELETE DATA {{ {s} <{NAO_HAS_TAG}> {t} }}",
+        s = rdf::iri_ref(subject),
+        t = rdf::iri_ref(tag)
+    )
+}
+
+/// Builds the editable value of the "Tags" row for `subject`.
+///
+/// Each tag is shown with a button removing it, followed by an entry for adding a tag by label.
+/// The tags are kept in the application's own store (see `store::annotations`), as the miner's
+/// is read-only. Changes are written there and then published on `ctx.changes`, which reloads
+/// every view of the subject (including the one holding this editor).
+///
+/// # Arguments
+/// * `subject` - The URI of the resource whose tags are edited.
+/// * `ctx` - Shared application settings.
+pub fn editor(subject: &str, ctx: &Rc<Context>) -> gtk::Widget {
+    let flow = gtk::FlowBox::builder()
+        .selection_mode(gtk::SelectionMode::None)
+        .column_spacing(4)
+        .row_spacing(4)
+        .margin_start(6)
+        .margin_top(4)
+        .margin_bottom(4)
+        .build();
+
+    let entry = gtk::Entry::builder()
+        .placeholder_text("Add tag…")
+        .width_chars(12)
+        .build();
+    let subject_clone = subject.to_string();
+    let ctx_clone = ctx.clone();
+    entry.connect_activate(move |entry| {
+        let label = entry.text().trim().to_string();
+        if !label.is_empty() {
+            let sparql = add_tag_update(&subject_clone, &label);
+            apply(
+                entry.upcast_ref(),
+                &subject_clone,
+                sparql,
+                "Could not change tags",
+                &ctx_clone,
+            );
+        }
+    });
+    flow.insert(&entry, -1);
+
+    // Fill in the existing tags in front of the entry once they have been queried.
+    let subject = subject.to_string();
+    let ctx = ctx.clone();
+    let flow_clone = flow.clone();
+    glib::MainContext::default().spawn_local(async move {
+        let Ok(conn) = store::annotations() else {
+            return;
+        };
+        let Ok(cursor) = conn.query_async(&tags_query(&subject)).await else {
+            return;
+        };
+        let mut position = 0;
+        while cursor.next_future().await.unwrap_or(false) {
+            let tag = cursor.string(0).unwrap_or_default().to_string();
+            let label = cursor
+                .string(1)
+                .map(|l| l.to_string())
+                .unwrap_or_else(|| tag.clone());
+            let chip = tag_chip(&subject, &tag, &label, &ctx);
+            flow_clone.insert(&chip, position);
+            position += 1;
+        }
+    });
+
+    flow.upcast()
+}
+
+/// Builds the widget for a single tag: its label and a button removing it from `subject`.
+fn tag_chip(subject: &str, tag: &str, label: &str, ctx: &Rc<Context>) -> gtk::Widget {
+    let chip = gtk::Box::new(gtk::Orientation::Horizontal, 2);
+    chip.append(&gtk::Label::new(Some(label)));
+    let remove = gtk::Button::builder()
+        .icon_name("window-close-symbolic")
+        .tooltip_text("Remove Tag")
+        .has_frame(false)
+        .build();
+    let subject = subject.to_string();
+    let tag = tag.to_string();
+    let ctx = ctx.clone();
+    remove.connect_clicked(move |button| {
+        let sparql = remove_tag_update(&subject, &tag);
+        apply(
+            button.upcast_ref(),
+            &subject,
+            sparql,
+            "Could not change tags",
+            &ctx,
+        );
+    });
+    chip.append(&remove);
+    chip.upcast()
+}
+
+/// Runs an update of `subject` on the store of tags and notes (see `store::annotations`) and
+/// announces the change, or reports the failure in a dialog attached to the window holding
+/// `widget`.
+///
+/// # Arguments
+/// * `widget` - The widget the user edited.
+/// * `subject` - The URI of the changed resource.
+/// * `sparql` - The update to run.
+/// * `failure` - The heading of the error dialog.
+/// * `ctx` - Shared application settings.
+pub fn apply(
+    widget: &gtk::Widget,
+    subject: &str,
+    sparql: String,
+    failure: &'static str,
+    ctx: &Rc<Context>,
+) {
+    let window = widget.root().and_downcast::<gtk::Window>();
+    let subject = subject.to_string();
+    let ctx = ctx.clone();
+    glib::MainContext::default().spawn_local(async move {
+        let result = match store::annotations() {
+            Ok(conn) => conn.update_async(&sparql).await,
+            Err(err) => Err(err),
+        };
+        match result {
+            Ok(()) => ctx.changes.publish(&subject),
+            Err(err) => show_message(window.as_ref(), failure, &err.to_string()),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_tag_escapes_label() {
+        let sparql = add_tag_update("file:///a", "say \"hi\"");
+        assert!(sparql.contains("\"say \\\"hi\\\"\""), "{sparql}");
+        assert!(sparql.contains(&format!("<file:///a> <{NAO_HAS_TAG}> ?t")));
+    }
+
+    #[test]
+    fn remove_tag_deletes_statement() {
+        assert_eq!(
+            remove_tag_update("file:///a", "urn:tag:1"),
+            format!("DELETE DATA {{ <file:///a> <{NAO_HAS_TAG}> <urn:tag:1> }}")
+        );
+    }
+}
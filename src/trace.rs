@@ -0,0 +1,145 @@
+use clap::ValueEnum;
+use serde_json::{Map, Value, json};
+use std::io::Write;
+use std::sync::OnceLock;
+
+/// The log target of events, by which the JSON formatter tells them from other messages.
+const EVENT_TARGET: &str = "event";
+
+/// The format of the diagnostic output of `--debug`, chosen once at startup.
+static FORMAT: OnceLock<DebugFormat> = OnceLock::new();
+
+/// The formats of the diagnostic output of `--debug`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum DebugFormat {
+    /// One line per message, readable by people, e.g.
+    /// `query_finished query=statements rows=12 duration_ms=8`.
+    #[default]
+    Text,
+    /// One JSON object per line, for tests and tooling, e.g.
+    /// `{"event":"query_finished","query":"statements","rows":12,"duration_ms":8,…}`.
+    Json,
+}
+
+/// Sets up the logger writing diagnostic output to stderr: debug messages if `debug` is set,
+/// otherwise only warnings, in the given format.
+pub fn init(debug: bool, format: DebugFormat) {
+    let _ = FORMAT.set(format);
+    let mut builder = env_logger::Builder::new();
+    builder.filter_level(if debug {
+        log::LevelFilter::Debug
+    } else {
+        log::LevelFilter::Warn
+    });
+    if format == DebugFormat::Json {
+        builder.format(|buf, record| {
+            let mut object = if record.target() == EVENT_TARGET {
+                serde_json::from_str(&record.args().to_string()).unwrap_or_default()
+            } else {
+                // Other messages become "message" events.
+                event_object("message", json!({ "message": record.args().to_string() }))
+            };
+            object.insert("level".to_string(), json!(record.level().as_str()));
+            object.insert("time_ms".to_string(), json!(glib::real_time() / 1000));
+            writeln!(buf, "{}", Value::Object(object))
+        });
+    }
+    builder.init();
+}
+
+/// Reports an event, such as a query being started or finished, with details given as the
+/// members of the JSON object `fields`. Nothing is written unless debug output is enabled.
+///
+/// # Arguments
+/// * `name` - What happened, in snake case, e.g. `"query_finished"`.
+/// * `fields` - Details such as the subject, a row count or a duration in milliseconds.
+pub fn event(name: &str, fields: Value) {
+    if !log::log_enabled!(target: EVENT_TARGET, log::Level::Debug) {
+        return;
+    }
+    let line = match FORMAT.get() {
+        Some(DebugFormat::Json) => Value::Object(event_object(name, fields)).to_string(),
+        _ => text_line(name, &fields),
+    };
+    log::debug!(target: EVENT_TARGET, "{line}");
+}
+
+/// Returns `fields` with the name of the event added as `"event"`.
+fn event_object(name: &str, fields: Value) -> Map<String, Value> {
+    let mut object = match fields {
+        Value::Object(object) => object,
+        _ => Map::new(),
+    };
+    object.insert("event".to_string(), json!(name));
+    object
+}
+
+/// Formats an event as its name followed by `key=value` pairs, with strings unquoted.
+fn text_line(name: &str, fields: &Value) -> String {
+    let mut line = name.to_string();
+    for (key, value) in fields.as_object().into_iter().flatten() {
+        match value {
+            Value::String(text) => line.push_str(&format!(" {key}={text}")),
+            value => line.push_str(&format!(" {key}={value}")),
+        }
+    }
+    line
+}
+
+/// How long the stages of loading a subject took, in milliseconds.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Timings {
+    /// Connecting to the store.
+    pub connection_ms: u64,
+    /// Running the query for the statements about the subject.
+    pub query_ms: u64,
+    /// Filling the table with the results and painting it.
+    pub rendering_ms: u64,
+}
+
+impl Timings {
+    /// Describes the timings for the status bar, e.g.
+    /// "Connected in 2 ms · Queried in 35 ms · Rendered in 18 ms".
+    pub fn summary(&self) -> String {
+        format!(
+            "Connected in {} ms · Queried in {} ms · Rendered in {} ms",
+            self.connection_ms, self.query_ms, self.rendering_ms
+        )
+    }
+}
+
+/// Returns the milliseconds elapsed since `start`, for the durations reported in events.
+pub fn millis_since(start: std::time::Instant) -> u64 {
+    start.elapsed().as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_as_text_or_json() {
+        let fields = json!({ "query": "statements", "rows": 3 });
+        assert_eq!(
+            text_line("query_finished", &fields),
+            "query_finished query=statements rows=3"
+        );
+        assert_eq!(
+            Value::Object(event_object("query_finished", fields)),
+            json!({ "event": "query_finished", "query": "statements", "rows": 3 })
+        );
+    }
+
+    #[test]
+    fn timings_summary() {
+        let timings = Timings {
+            connection_ms: 2,
+            query_ms: 35,
+            rendering_ms: 18,
+        };
+        assert_eq!(
+            timings.summary(),
+            "Connected in 2 ms · Queried in 35 ms · Rendered in 18 ms"
+        );
+    }
+}
@@ -0,0 +1,440 @@
+use crate::context::Context;
+use crate::labels;
+use crate::memory;
+use crate::prefixes;
+use crate::rdf;
+use crate::store;
+use crate::{LinkHandler, display_value, predicate_label};
+use adw::prelude::*;
+use std::rc::Rc;
+use tracker::prelude::*;
+
+/// The largest number of properties listed for a nested node.
+const NESTED_LIMIT: usize = 200;
+
+const RDF_FIRST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#first";
+const RDF_REST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#rest";
+const RDF_NIL: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#nil";
+
+/// The common prefix of the container membership properties `rdf:_1`, `rdf:_2`, and so on.
+const RDF_MEMBER_PREFIX: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#_";
+
+/// A node whose properties can be unfolded.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Node {
+    /// A node named by an IRI, which can be queried directly.
+    Iri(String),
+    /// A blank node, which cannot be queried by its label, but only through the statement
+    /// linking to it: it is the blank node labelled `label` that `parent` links to with
+    /// `predicate`.
+    Blank {
+        parent: Rc<Node>,
+        predicate: String,
+        label: String,
+    },
+}
+
+impl Node {
+    /// Returns the blank node labelled `label` that `parent` links to with `predicate`.
+    pub fn blank(parent: Node, predicate: &str, label: &str) -> Self {
+        Node::Blank {
+            parent: Rc::new(parent),
+            predicate: predicate.to_string(),
+            label: label.to_string(),
+        }
+    }
+
+    /// Returns the number of blank nodes on the path to this node.
+    fn depth(&self) -> usize {
+        match self {
+            Node::Iri(_) => 0,
+            Node::Blank { parent, .. } => parent.depth() + 1,
+        }
+    }
+
+    /// Returns a SPARQL group pattern reaching the node, and the term standing for it in the
+    /// pattern: the IRI itself, or a variable bound by following the statements from the
+    /// nearest named ancestor.
+    ///
+    /// Blank nodes are told apart by their label where the store keeps it stable across
+    /// queries (`urn:bnode:` labels). Labels such as `_:b0` only hold within one query, so
+    /// all blank nodes linked with the same predicate are reached instead.
+    fn pattern(&self) -> (String, String) {
+        match self {
+            Node::Iri(iri) => (String::new(), rdf::iri_ref(iri)),
+            Node::Blank {
+                parent,
+                predicate,
+                label,
+            } => {
+                let (parent_pattern, parent_term) = parent.pattern();
+                let var = format!("?blank{}", self.depth());
+                let label_filter = if label.starts_with("_:") {
+                    String::new()
+                } else {
+                    format!(" && STR({var}) = \"{}\"", rdf::escape_string(label))
+                };
+                let pattern = format!(
+                    "{parent_pattern}{parent_term} {} {var} . FILTER(isBlank({var}){label_filter}) ",
+                    rdf::iri_ref(predicate)
+                );
+                (pattern, var)
+            }
+        }
+    }
+}
+
+/// Returns whether the object value `obj` is a blank node rather than a named resource.
+pub fn is_blank(obj: &str) -> bool {
+    obj.starts_with("_:") || obj.starts_with("urn:bnode:")
+}
+
+/// Adds an arrow before the link `lbl_link` to `node`, which shows the node's own properties
+/// right beneath the row, so that simple graphs can be explored without opening new windows.
+/// For blank nodes, which cannot be opened in a window of their own, this is the only way to
+/// reach their properties.
+///
+/// The properties are fetched when the arrow is first opened. Links among them get arrows of
+/// their own, so the graph unfolds one level at a time.
+///
+/// # Arguments
+/// * `lbl_link` - The label linking to the node, or describing a blank node.
+/// * `node` - The node.
+/// * `ctx` - The shared context, for labels and date formatting.
+/// * `on_link` - Called when a link among the nested properties is activated.
+///
+/// # Returns
+/// * A box holding the arrow, the link and the (initially hidden) nested properties, to be
+///   placed in the grid instead of the link.
+pub fn expandable(
+    lbl_link: &gtk::Label,
+    node: Node,
+    ctx: &Rc<Context>,
+    on_link: &LinkHandler,
+) -> gtk::Box {
+    let arrow = gtk::ToggleButton::builder()
+        .icon_name("pan-end-symbolic")
+        .valign(gtk::Align::Start)
+        .tooltip_text("Show the properties of the linked node")
+        .build();
+    arrow.style_context().add_class("flat");
+
+    let revealer = gtk::Revealer::new();
+    revealer.set_transition_type(gtk::RevealerTransitionType::SlideDown);
+
+    let ctx = ctx.clone();
+    let on_link = on_link.clone();
+    let revealer_clone = revealer.clone();
+    arrow.connect_toggled(move |arrow| {
+        let open = arrow.is_active();
+        arrow.set_icon_name(if open {
+            "pan-down-symbolic"
+        } else {
+            "pan-end-symbolic"
+        });
+        revealer_clone.set_reveal_child(open);
+        if !open || revealer_clone.child().is_some() {
+            return;
+        }
+        // Fetch the properties on first opening only; they stay until the view is reloaded.
+        let loading = gtk::Label::new(Some("Loading…"));
+        loading.style_context().add_class("dim-label");
+        loading.set_halign(gtk::Align::Start);
+        revealer_clone.set_child(Some(&loading));
+        let (node, ctx, on_link) = (node.clone(), ctx.clone(), on_link.clone());
+        let revealer = revealer_clone.clone();
+        glib::spawn_future_local(async move {
+            let statements = properties(&node).await;
+            let entries = entries(&node, statements, &ctx).await;
+            revealer.set_child(Some(&nested_grid(&entries, &ctx, &on_link)));
+        });
+    });
+
+    let row = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    row.append(&arrow);
+    row.append(lbl_link);
+    let node_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+    node_box.append(&row);
+    node_box.append(&revealer);
+    node_box
+}
+
+/// A value shown in the grid of a nested node.
+struct Entry {
+    /// The label of the row: the predicate, or the position of a list member.
+    key: String,
+    /// The node the value belongs to, for reaching blank nodes among the values.
+    parent: Node,
+    /// The predicate linking `parent` to the value.
+    predicate: String,
+    /// The value, and its datatype (empty for nodes).
+    obj: String,
+    dtype: String,
+}
+
+/// Reads the `(predicate, object, datatype)` statements about `node` from the store.
+///
+/// # Returns
+/// * The statements, or none if the store cannot be queried.
+async fn properties(node: &Node) -> Vec<(String, String, String)> {
+    let Ok(conn) = store::client() else {
+        return Vec::new();
+    };
+    let sparql = properties_query(node);
+    let Ok(cursor) = conn.query_async(&sparql).await else {
+        return Vec::new();
+    };
+    let mut statements = Vec::new();
+    while cursor.next_future().await.unwrap_or(false) {
+        statements.push((
+            cursor.string(0).unwrap_or_default().to_string(),
+            cursor.string(1).unwrap_or_default().to_string(),
+            cursor.string(2).unwrap_or_default().to_string(),
+        ));
+    }
+    statements
+}
+
+/// Returns the query for the properties of `node` (see [`Node::pattern`]).
+fn properties_query(node: &Node) -> String {
+    let (pattern, term) = node.pattern();
+    format!(
+        "SELECT DISTINCT ?pred ?obj (DATATYPE(?obj) AS ?dtype) WHERE {{ {pattern}{term} ?pred ?obj }} LIMIT {limit}",
+        limit = memory::limit(NESTED_LIMIT)
+    )
+}
+
+/// Returns the values to list beneath `node`: the members of an RDF collection or container in
+/// order, numbered "1.", "2.", …, or else its properties.
+///
+/// # Arguments
+/// * `node` - The node being unfolded.
+/// * `statements` - Its `(predicate, object, datatype)` statements.
+/// * `ctx` - The shared context, for labelling predicates.
+async fn entries(
+    node: &Node,
+    statements: Vec<(String, String, String)>,
+    ctx: &Context,
+) -> Vec<Entry> {
+    let members = if first_and_rest(&statements).is_some() {
+        list_members(node, statements.clone()).await
+    } else {
+        container_members(node, &statements)
+    };
+    if !members.is_empty() {
+        return (1..)
+            .zip(members)
+            .map(|(position, (parent, predicate, obj, dtype))| Entry {
+                key: format!("{position}."),
+                parent,
+                predicate,
+                obj,
+                dtype,
+            })
+            .collect();
+    }
+
+    if ctx.compact_uris.get() {
+        prefixes::load().await;
+    } else {
+        labels::load(statements.iter().map(|(pred, _, _)| pred.as_str())).await;
+    }
+    statements
+        .into_iter()
+        .map(|(pred, obj, dtype)| Entry {
+            key: predicate_label(&pred, ctx),
+            parent: node.clone(),
+            predicate: pred,
+            obj,
+            dtype,
+        })
+        .collect()
+}
+
+/// Returns the `rdf:first` value, with its datatype, and the `rdf:rest` of a cell of an RDF
+/// collection, if the statements describe one.
+fn first_and_rest(statements: &[(String, String, String)]) -> Option<((String, String), String)> {
+    let value = |pred: &str| statements.iter().find(|(p, _, _)| p == pred);
+    let (_, first, dtype) = value(RDF_FIRST)?;
+    let (_, rest, _) = value(RDF_REST)?;
+    Some(((first.clone(), dtype.clone()), rest.clone()))
+}
+
+/// Follows the `rdf:rest` chain of the RDF collection (`rdf:List`) starting at `head`, whose
+/// statements are given, and collects the `rdf:first` of each cell.
+///
+/// Each cell takes a query of its own, so at most `NESTED_LIMIT` members are collected.
+///
+/// # Returns
+/// * The members in order, each with the cell it was read from, as
+///   `(cell, predicate, object, datatype)`.
+async fn list_members(
+    head: &Node,
+    statements: Vec<(String, String, String)>,
+) -> Vec<(Node, String, String, String)> {
+    let mut members = Vec::new();
+    let mut cell = head.clone();
+    let mut statements = statements;
+    while let Some(((first, dtype), rest)) = first_and_rest(&statements) {
+        members.push((cell.clone(), RDF_FIRST.to_string(), first, dtype));
+        if rest == RDF_NIL || members.len() >= memory::limit(NESTED_LIMIT) {
+            break;
+        }
+        cell = if is_blank(&rest) {
+            Node::blank(cell, RDF_REST, &rest)
+        } else {
+            Node::Iri(rest)
+        };
+        statements = properties(&cell).await;
+    }
+    members
+}
+
+/// Collects the members of an RDF container (`rdf:Seq`, `rdf:Bag` or `rdf:Alt`), which are
+/// linked with the membership properties `rdf:_1`, `rdf:_2`, and so on.
+///
+/// # Arguments
+/// * `node` - The container.
+/// * `statements` - Its `(predicate, object, datatype)` statements.
+///
+/// # Returns
+/// * The members ordered by their membership property, as for [`list_members`]; none if the
+///   statements do not describe a container.
+fn container_members(
+    node: &Node,
+    statements: &[(String, String, String)],
+) -> Vec<(Node, String, String, String)> {
+    let mut members: Vec<(usize, &(String, String, String))> = statements
+        .iter()
+        .filter_map(|statement| {
+            let index = statement.0.strip_prefix(RDF_MEMBER_PREFIX)?.parse().ok()?;
+            Some((index, statement))
+        })
+        .collect();
+    members.sort_by_key(|(index, _)| *index);
+    members
+        .into_iter()
+        .map(|(_, (pred, obj, dtype))| (node.clone(), pred.clone(), obj.clone(), dtype.clone()))
+        .collect()
+}
+
+/// Lays out the values of a nested node (see [`entries`]) as a two-column grid, indented below
+/// the link.
+fn nested_grid(entries: &[Entry], ctx: &Rc<Context>, on_link: &LinkHandler) -> gtk::Widget {
+    if entries.is_empty() {
+        let empty = gtk::Label::new(Some("No properties"));
+        empty.style_context().add_class("dim-label");
+        empty.set_halign(gtk::Align::Start);
+        empty.set_margin_start(30);
+        return empty.upcast();
+    }
+
+    let grid = gtk::Grid::new();
+    grid.set_column_spacing(12);
+    grid.set_margin_start(30);
+    grid.set_margin_bottom(4);
+    for (row, entry) in (0..).zip(entries) {
+        let (obj, dtype) = (&entry.obj, &entry.dtype);
+        let lbl_key = gtk::Label::new(Some(&entry.key));
+        lbl_key.set_halign(gtk::Align::Start);
+        lbl_key.set_valign(gtk::Align::Start);
+        lbl_key.style_context().add_class("dim-label");
+        lbl_key.set_tooltip_text(Some(&entry.predicate));
+        lbl_key.set_margin_top(2);
+        lbl_key.set_margin_bottom(2);
+        grid.attach(&lbl_key, 0, row, 1, 1);
+
+        let lbl_val = gtk::Label::new(None);
+        lbl_val.set_halign(gtk::Align::Start);
+        lbl_val.set_margin_top(2);
+        lbl_val.set_margin_bottom(2);
+        lbl_val.set_wrap(true);
+        lbl_val.set_wrap_mode(gtk::pango::WrapMode::WordChar);
+        lbl_val.set_max_width_chars(60);
+        let widget: gtk::Widget = if dtype.is_empty() && is_blank(obj) {
+            // Blank nodes can only be unfolded.
+            blank_label(&lbl_val, obj);
+            let node = Node::blank(entry.parent.clone(), &entry.predicate, obj);
+            expandable(&lbl_val, node, ctx, on_link).upcast()
+        } else if dtype.is_empty() {
+            // Links to further nodes can be followed, or unfolded in turn.
+            lbl_val.set_markup(&format!(
+                "<a href=\"{0}\">{0}</a>",
+                glib::markup_escape_text(obj)
+            ));
+            let on_link_clone = on_link.clone();
+            lbl_val.connect_activate_link(move |_, uri| {
+                on_link_clone(uri);
+                glib::Propagation::Stop
+            });
+            expandable(&lbl_val, Node::Iri(obj.clone()), ctx, on_link).upcast()
+        } else {
+            lbl_val.set_text(&display_value(
+                &entry.predicate,
+                obj,
+                dtype,
+                &ctx.date_format.borrow(),
+                ctx.utc_dates.get(),
+            ));
+            lbl_val.set_tooltip_text(Some(obj));
+            lbl_val.upcast()
+        };
+        grid.attach(&widget, 1, row, 1, 1);
+    }
+    grid.upcast()
+}
+
+/// Makes `label` describe the blank node `obj` instead of linking to it, since a blank node
+/// cannot be opened by its label.
+pub fn blank_label(label: &gtk::Label, obj: &str) {
+    label.set_text("Blank node");
+    label.style_context().add_class("dim-label");
+    label.set_tooltip_text(Some(obj));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blank_nodes_are_reached_through_their_parent() {
+        let file = Node::Iri("file:///a.jpg".to_string());
+        let exif = Node::blank(file, "urn:p", "urn:bnode:1");
+        let query = properties_query(&Node::blank(exif, "urn:q", "_:b0"));
+        assert!(
+            query.contains(
+                "<file:///a.jpg> <urn:p> ?blank1 . FILTER(isBlank(?blank1) && STR(?blank1) = \"urn:bnode:1\")"
+            ),
+            "{query}"
+        );
+        assert!(
+            query.contains("?blank1 <urn:q> ?blank2 . FILTER(isBlank(?blank2)) ?blank2 ?pred ?obj"),
+            "{query}"
+        );
+        assert!(is_blank("_:b0") && !is_blank("urn:uuid:1"));
+    }
+
+    #[test]
+    fn container_members_follow_their_index() {
+        let statement = |pred: &str, obj: &str| (pred.to_string(), obj.to_string(), String::new());
+        let member = |n: usize| format!("{RDF_MEMBER_PREFIX}{n}");
+        let playlist = Node::Iri("urn:playlist".to_string());
+        let statements = [
+            statement(&member(10), "urn:c"),
+            statement("http://www.w3.org/1999/02/22-rdf-syntax-ns#type", "urn:Seq"),
+            statement(&member(2), "urn:b"),
+            statement(&member(1), "urn:a"),
+        ];
+        let members: Vec<String> = container_members(&playlist, &statements)
+            .into_iter()
+            .map(|(_, _, obj, _)| obj)
+            .collect();
+        assert_eq!(members, ["urn:a", "urn:b", "urn:c"]);
+        assert!(container_members(&playlist, &statements[1..2]).is_empty());
+
+        let cell = [statement(RDF_FIRST, "urn:a"), statement(RDF_REST, RDF_NIL)];
+        let ((first, _), rest) = first_and_rest(&cell).unwrap();
+        assert_eq!((first.as_str(), rest.as_str()), ("urn:a", RDF_NIL));
+        assert_eq!(first_and_rest(&cell[..1]), None);
+    }
+}
@@ -0,0 +1,264 @@
+use crate::context::Context;
+use crate::geometry;
+use crate::{open_subject_window, tab_title};
+use adw::prelude::*;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+/// How many visits are kept; older ones are dropped when a new one is recorded.
+const MAX_VISITS: usize = 1000;
+
+/// Widget name of the history window, by which it is found among the application's windows.
+const WINDOW_NAME: &str = "history";
+
+/// A subject shown in a subject window at some time, across sessions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Visit {
+    /// When the subject was shown, in seconds since the Unix epoch.
+    pub time: i64,
+    pub uri: String,
+}
+
+impl Visit {
+    /// Returns whether the URI or the title (see `tab_title`) of the visited subject contains
+    /// `query`, ignoring case. Every visit matches an empty query.
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        self.uri.to_lowercase().contains(&query)
+            || tab_title(&self.uri).to_lowercase().contains(&query)
+    }
+}
+
+/// Returns the path of the file holding the visits,
+/// `$XDG_DATA_HOME/file-information/history.tsv`.
+fn path() -> PathBuf {
+    glib::user_data_dir()
+        .join("file-information")
+        .join("history.tsv")
+}
+
+/// Returns the recorded visits, most recent first.
+///
+/// A missing or unreadable file is treated as empty, and lines that cannot be parsed are
+/// skipped.
+pub fn load() -> Vec<Visit> {
+    std::fs::read_to_string(path())
+        .map(|text| from_text(&text))
+        .unwrap_or_default()
+}
+
+/// Records that `uri` is being shown now, keeping the `MAX_VISITS` most recent visits.
+///
+/// Failures to write the file are logged; the history is a convenience only.
+pub fn record(uri: &str) {
+    let mut visits = load();
+    visits.insert(
+        0,
+        Visit {
+            time: glib::real_time() / 1_000_000,
+            uri: uri.to_string(),
+        },
+    );
+    visits.truncate(MAX_VISITS);
+    if let Err(err) = save(&visits) {
+        log::warn!("Could not save the history: {err}");
+    }
+}
+
+/// Forgets all visits.
+pub fn clear() -> std::io::Result<()> {
+    save(&[])
+}
+
+/// Writes `visits` to the history file.
+fn save(visits: &[Visit]) -> std::io::Result<()> {
+    let path = path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, to_text(visits))
+}
+
+/// Serializes `visits` as one `time<TAB>uri` line each. URIs contain no tabs or line breaks.
+fn to_text(visits: &[Visit]) -> String {
+    visits
+        .iter()
+        .map(|visit| format!("{}\t{}\n", visit.time, visit.uri))
+        .collect()
+}
+
+/// Parses the lines written by [`to_text`].
+fn from_text(text: &str) -> Vec<Visit> {
+    text.lines()
+        .filter_map(|line| {
+            let (time, uri) = line.split_once('\t')?;
+            Some(Visit {
+                time: time.parse().ok()?,
+                uri: uri.to_string(),
+            })
+            .filter(|visit| !visit.uri.is_empty())
+        })
+        .collect()
+}
+
+/// Opens the window listing the history, or brings it to the front if it is already open.
+///
+/// The list can be narrowed down by searching for part of a title or URI, and activating an
+/// entry opens its subject.
+///
+/// # Arguments
+/// * `app` - The application instance.
+/// * `ctx` - Shared application settings, for the date format and the subject windows opened.
+pub fn open_window(app: &adw::Application, ctx: &Rc<Context>) {
+    if let Some(window) = app
+        .windows()
+        .into_iter()
+        .find(|w| w.widget_name() == WINDOW_NAME)
+    {
+        window.present();
+        return;
+    }
+
+    let visits = Rc::new(load());
+    let list = gtk::ListBox::builder()
+        .selection_mode(gtk::SelectionMode::None)
+        .activate_on_single_click(true)
+        .build();
+    list.style_context().add_class("boxed-list");
+    list.set_placeholder(Some(&gtk::Label::new(Some("No subjects found"))));
+    for visit in visits.iter() {
+        let row = adw::ActionRow::builder()
+            .title(glib::markup_escape_text(&tab_title(&visit.uri)))
+            .subtitle(glib::markup_escape_text(&visit.uri))
+            .activatable(true)
+            .build();
+        let time = format_time(visit.time, ctx);
+        let time = gtk::Label::new(Some(&time));
+        time.style_context().add_class("dim-label");
+        row.add_suffix(&time);
+        list.append(&row);
+    }
+
+    let search = gtk::SearchEntry::builder()
+        .placeholder_text("Search title or URI")
+        .build();
+    let search_clone = search.clone();
+    let visits_clone = visits.clone();
+    list.set_filter_func(move |row| {
+        visits_clone
+            .get(row.index() as usize)
+            .is_some_and(|visit| visit.matches(&search_clone.text()))
+    });
+    let list_clone = list.clone();
+    search.connect_search_changed(move |_| list_clone.invalidate_filter());
+
+    let app_clone = app.clone();
+    let ctx_clone = ctx.clone();
+    let visits_clone = visits.clone();
+    list.connect_row_activated(move |_, row| {
+        if let Some(visit) = visits_clone.get(row.index() as usize) {
+            open_subject_window(&app_clone, visit.uri.clone(), &ctx_clone);
+        }
+    });
+
+    let clear_button = gtk::Button::with_label("Clear");
+    clear_button.set_tooltip_text(Some("Forget All Subjects Shown Before"));
+    let list_clone = list.clone();
+    clear_button.connect_clicked(move |_| {
+        if let Err(err) = clear() {
+            log::warn!("Could not clear the history: {err}");
+            return;
+        }
+        while let Some(row) = list_clone.row_at_index(0) {
+            list_clone.remove(&row);
+        }
+    });
+
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+    content.append(&search);
+    content.append(&list);
+    let scroll = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .vexpand(true)
+        .child(&content)
+        .build();
+
+    let header = adw::HeaderBar::new();
+    header.pack_start(&clear_button);
+    let toolbar = adw::ToolbarView::new();
+    toolbar.add_top_bar(&header);
+    toolbar.set_content(Some(&scroll));
+
+    let window = adw::ApplicationWindow::builder()
+        .application(app)
+        .default_width(520)
+        .default_height(560)
+        .title("History")
+        .content(&toolbar)
+        .build();
+    window.set_widget_name(WINDOW_NAME);
+    geometry::remember(&window, "visits");
+    if let Some(parent) = app.active_window() {
+        window.set_transient_for(Some(&parent));
+    }
+    // Typing anywhere in the window searches.
+    search.set_key_capture_widget(Some(&window));
+    window.present();
+}
+
+/// Formats the time of a visit with the configured date format and time zone.
+fn format_time(time: i64, ctx: &Context) -> String {
+    let datetime = if ctx.utc_dates.get() {
+        glib::DateTime::from_unix_utc(time)
+    } else {
+        glib::DateTime::from_unix_local(time)
+    };
+    datetime
+        .ok()
+        .and_then(|datetime| datetime.format(&ctx.date_format.borrow()).ok())
+        .map(|text| text.to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn visits_survive_a_round_trip() {
+        let visits = vec![
+            Visit {
+                time: 1_700_000_100,
+                uri: "urn:tag:holidays".to_string(),
+            },
+            Visit {
+                time: 1_700_000_000,
+                uri: "file:///home/user/notes.txt".to_string(),
+            },
+        ];
+        assert_eq!(from_text(&to_text(&visits)), visits);
+        assert_eq!(
+            from_text("garbage\n1\t\nnot-a-time\tfile:///a\n5\turn:x\n"),
+            [Visit {
+                time: 5,
+                uri: "urn:x".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn visits_match_title_or_uri() {
+        let visit = Visit {
+            time: 0,
+            uri: "file:///home/user/Notes.txt".to_string(),
+        };
+        assert!(visit.matches("notes"));
+        assert!(visit.matches(" /HOME/ "));
+        assert!(visit.matches(""));
+        assert!(!visit.matches("photos"));
+    }
+}
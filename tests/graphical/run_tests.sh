@@ -14,6 +14,20 @@ TEST_DIR="$HOME/tmp"
 TEST_FILE="$TEST_DIR/testfile.txt"
 XVFB_LOG="/tmp/xvfb.log"
 APP_LOG="/tmp/file_information_app.log"
+FIXTURE_FILE="/tmp/file_information_fixture.ttl"
+
+# Regions of the screenshots whose contents vary between runs (sizes, dates, the value
+# column), blacked out before comparing with the stored references. They follow the layout
+# of the windows, so check them whenever the references are regenerated.
+MAIN_MASKS=(
+    -fill black -draw "rectangle 176,130 310,143"
+    -fill black -draw "rectangle 176,180 310,193"
+    -fill black -draw "rectangle 176,205 183,218"
+    -fill black -draw "rectangle 11,230 568,445"
+)
+BACKLINKS_MASKS=(
+    -fill black -draw "rectangle 11,57 310,70"
+)
 
 # ANSI color codes for log messages.
 GREEN="\033[1;32m"
@@ -23,6 +37,8 @@ RESET="\033[0m"
 
 app_pid=""
 xvfb_pid=""
+# Set when a screenshot does not match its reference; the script then exits with status 1.
+mismatch=false
 
 cleanup() {
     if [ -n "${app_pid:-}" ]; then
@@ -57,12 +73,20 @@ run_and_time() {
     log "Command '$*' took ${duration} ms."
 }
 
+# --release tests the release build; --fixture serves the metadata from a generated Turtle
+# fixture instead of a live Tracker miner, which makes the test independent of indexing.
+# --update-references replaces the stored reference screenshots with the ones captured,
+# after a deliberate change to the layout; use it together with --fixture, so that the
+# references do not depend on what the miner extracted.
 release=false
+fixture=false
+update_references=false
 for arg in "$@"; do
-    if [ "$arg" = "--release" ]; then
-        release=true
-        break
-    fi
+    case "$arg" in
+        --release) release=true ;;
+        --fixture) fixture=true ;;
+        --update-references) update_references=true ;;
+    esac
 done
 
 if $release; then
@@ -133,31 +157,54 @@ if [ -z "${DBUS_SESSION_BUS_ADDRESS:-}" ]; then
     export DBUS_SESSION_BUS_ADDRESS="$addr"
 fi
 
-log "Initiating Tracker indexing of $TEST_DIR..."
-# Let Tracker index the test directory.
-tracker3 daemon -s >/dev/null
-tracker3 index --add "$TEST_DIR" >/dev/null
-
-# Wait for the test file to be indexed before launching the application.
-log "Waiting up to 60 seconds for Tracker to index $TEST_FILE..."
-for i in {1..600}; do
-    if ! tracker3 info "$TEST_FILE" 2>&1 | grep -q "No metadata available"; then
-        break
+app_args=(--debug --debug-format json)
+if $fixture; then
+    # Describe the test file the way tracker-miner-fs would.
+    log "Writing metadata fixture for $TEST_FILE to $FIXTURE_FILE..."
+    test_uri="file://$TEST_FILE"
+    cat <<EOS >"$FIXTURE_FILE"
+@prefix nie: <http://tracker.api.gnome.org/ontology/v3/nie#> .
+@prefix nfo: <http://tracker.api.gnome.org/ontology/v3/nfo#> .
+@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+
+<$test_uri> a nfo:FileDataObject ;
+    nfo:fileName "$(basename "$TEST_FILE")" ;
+    nfo:fileSize $(stat -c %s "$TEST_FILE") ;
+    nfo:fileLastModified "$(date -u -r "$TEST_FILE" +%Y-%m-%dT%H:%M:%SZ)"^^xsd:dateTime ;
+    nie:interpretedAs <urn:fixture:testfile> .
+
+<urn:fixture:testfile> a nfo:PlainTextDocument ;
+    nie:mimeType "text/plain" ;
+    nie:isStoredAs <$test_uri> .
+EOS
+    app_args+=(--fixture "$FIXTURE_FILE")
+else
+    log "Initiating Tracker indexing of $TEST_DIR..."
+    # Let Tracker index the test directory.
+    tracker3 daemon -s >/dev/null
+    tracker3 index --add "$TEST_DIR" >/dev/null
+
+    # Wait for the test file to be indexed before launching the application.
+    log "Waiting up to 60 seconds for Tracker to index $TEST_FILE..."
+    for i in {1..600}; do
+        if ! tracker3 info "$TEST_FILE" 2>&1 | grep -q "No metadata available"; then
+            break
+        fi
+        sleep 0.1
+    done
+    if tracker3 info "$TEST_FILE" 2>&1 | grep -q "No metadata available"; then
+        error "Timed out waiting for Tracker to index $TEST_FILE."
+        exit 1
     fi
-    sleep 0.1
-done
-if tracker3 info "$TEST_FILE" 2>&1 | grep -q "No metadata available"; then
-    error "Timed out waiting for Tracker to index $TEST_FILE."
-    exit 1
-fi
 
-# Query Tracker for metadata about the file to be shown in the application.
-log "Tracker metadata for $TEST_FILE:"
-(tracker3 info "$TEST_FILE" || true) | head -n 5
+    # Query Tracker for metadata about the file to be shown in the application.
+    log "Tracker metadata for $TEST_FILE:"
+    (tracker3 info "$TEST_FILE" || true) | head -n 5
+fi
 
 
 rm -f "$APP_LOG"
-"$app_path" --debug "$TEST_FILE" >"$APP_LOG" 2>&1 &
+"$app_path" "${app_args[@]}" "$TEST_FILE" >"$APP_LOG" 2>&1 &
 app_pid=$!
 log "Application started with PID $app_pid; logging to $APP_LOG."
 
@@ -195,7 +242,7 @@ log "Waiting up to 10 seconds for query results to be displayed..."
 ready=false
 # Poll for the structured debug message that indicates results are visible.
 for i in {1..100}; do
-    if grep -q "DEBUG: results displayed" "$APP_LOG"; then
+    if grep -q '"event":"results_displayed"' "$APP_LOG"; then
         ready=true
         break
     fi
@@ -212,30 +259,26 @@ log "Main window screenshot saved to $MAIN_SCREENSHOT."
 
 # Mask known variable regions that can affect the MD5 digest by overlaying black
 # rectangles on the captured image.
-convert "$MAIN_SCREENSHOT" \
-    -fill black -draw "rectangle 176,130 310,143" \
-    -fill black -draw "rectangle 176,180 310,193" \
-    -fill black -draw "rectangle 176,205 183,218" \
-    -fill black -draw "rectangle 11,230 568,445" \
-    "$MAIN_SCREENSHOT_MASKED"
+convert "$MAIN_SCREENSHOT" "${MAIN_MASKS[@]}" "$MAIN_SCREENSHOT_MASKED"
 
 # Compute and log the MD5 digest of the raw screenshot so it can be compared
 # against known values.
 main_window_digest=$(convert "$MAIN_SCREENSHOT_MASKED" rgba:- | md5sum | awk '{print $1}')
 log "Masked main window screenshot MD5 digest: $main_window_digest."
 
+if $update_references; then
+    cp "$MAIN_SCREENSHOT" "$MAIN_SCREENSHOT_STORED"
+    log "Stored reference replaced with $MAIN_SCREENSHOT."
+fi
+
 # Compute MD5 digest of the stored reference screenshot for comparison.
-convert "$MAIN_SCREENSHOT_STORED" \
-    -fill black -draw "rectangle 176,130 310,143" \
-    -fill black -draw "rectangle 176,180 310,193" \
-    -fill black -draw "rectangle 176,205 183,218" \
-    -fill black -draw "rectangle 11,230 568,445" \
-    "$MAIN_SCREENSHOT_STORED_MASKED"
+convert "$MAIN_SCREENSHOT_STORED" "${MAIN_MASKS[@]}" "$MAIN_SCREENSHOT_STORED_MASKED"
 main_window_stored_digest=$(convert "$MAIN_SCREENSHOT_STORED_MASKED" rgba:- | md5sum | awk '{print $1}')
 if [ "$main_window_digest" = "$main_window_stored_digest" ]; then
     log "Masked main window screenshot matches the stored reference."
 else
     error "Masked main window screenshot does not match the stored reference."
+    mismatch=true
 fi
 
 # Print geometry using the captured window ID.
@@ -293,7 +336,7 @@ log "Backlinks window ID acquired: $backlinks_window_id."
 log "Waiting up to 20 seconds for query results to be displayed..."
 back_ready=false
 for i in {1..200}; do
-    if grep -q "Backlinks query returned" "$APP_LOG"; then
+    if grep -q '"event":"backlinks_displayed"' "$APP_LOG"; then
         back_ready=true
         break
     fi
@@ -309,21 +352,23 @@ import -display "$XVFB_DISPLAY" -window "$backlinks_window_id" "$BACKLINKS_SCREE
 log "\"Backlinks\" window screenshot saved to $BACKLINKS_SCREENSHOT."
 
 # Mask known variable regions in the "Backlinks" window screenshot.
-convert "$BACKLINKS_SCREENSHOT" \
-    -fill black -draw "rectangle 11,57 310,70" \
-    "$BACKLINKS_SCREENSHOT_MASKED"
+convert "$BACKLINKS_SCREENSHOT" "${BACKLINKS_MASKS[@]}" "$BACKLINKS_SCREENSHOT_MASKED"
 
 backlinks_window_digest=$(convert "$BACKLINKS_SCREENSHOT_MASKED" rgba:- | md5sum | awk '{print $1}')
 log "Masked \"Backlinks\" window screenshot MD5 digest: $backlinks_window_digest."
 
-convert "$BACKLINKS_SCREENSHOT_STORED" \
-    -fill black -draw "rectangle 11,57 310,70" \
-    "$BACKLINKS_SCREENSHOT_STORED_MASKED"
+if $update_references; then
+    cp "$BACKLINKS_SCREENSHOT" "$BACKLINKS_SCREENSHOT_STORED"
+    log "Stored reference replaced with $BACKLINKS_SCREENSHOT."
+fi
+
+convert "$BACKLINKS_SCREENSHOT_STORED" "${BACKLINKS_MASKS[@]}" "$BACKLINKS_SCREENSHOT_STORED_MASKED"
 backlinks_window_stored_digest=$(convert "$BACKLINKS_SCREENSHOT_STORED_MASKED" rgba:- | md5sum | awk '{print $1}')
 if [ "$backlinks_window_digest" = "$backlinks_window_stored_digest" ]; then
     log "Masked \"Backlinks\" window screenshot matches the stored reference."
 else
     error "Masked \"Backlinks\" window screenshot does not match the stored reference."
+    mismatch=true
 fi
 
 log "Acquiring window geometry for window $backlinks_window_id..."
@@ -378,4 +423,8 @@ else
     error "Main window failed to close."
 fi
 
+if $mismatch; then
+    error "Screenshots differ from the references in tests/graphical/images."
+    exit 1
+fi
 exit 0